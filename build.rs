@@ -2,6 +2,15 @@ use std::env;
 use std::process::Command;
 
 fn main() {
+    // build time for the About dialog. Honor SOURCE_DATE_EPOCH for reproducible builds
+    let build_time = env::var("SOURCE_DATE_EPOCH").ok().unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs().to_string())
+            .unwrap_or_default()
+    });
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_time);
+
     // if .git doesnt exist then we are not in a git repo
     // it may happen in container builds. do not set GIT_VERSION
     if !std::path::Path::new(".git").exists() {
@@ -0,0 +1,395 @@
+// Every action the UI produces passes the bus before the application handles
+// it. Cross-cutting behavior lives in middlewares instead of being repeated in
+// the handlers of the pages
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use log::info;
+
+#[cfg(feature = "efi")]
+use crate::ipc::eve_types::EfiBootOrderUpdate;
+use crate::{
+    actions::MonActions,
+    model::{connectivity::PortSummary, model::MonitorModel, sanitize::sanitize_line},
+    ui::action::{Action, UiActions},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    // refused, the reason is shown to the user
+    Block(String),
+    // ask the user first, the action comes back wrapped in UiActions::Confirmed
+    Confirm { title: String, message: String },
+}
+
+pub trait ActionMiddleware {
+    fn inspect(&mut self, action: &Action, model: &mut MonitorModel) -> Verdict;
+}
+
+/// Handled and refused actions by kind for the debug page
+#[derive(Debug, Default, Clone)]
+pub struct ActionStats {
+    pub handled: BTreeMap<String, u64>,
+    pub blocked: u64,
+}
+
+impl ActionStats {
+    pub fn total(&self) -> u64 {
+        self.handled.values().sum()
+    }
+}
+
+// "AppAction(AppCommand(Restart, ..))" -> "AppAction"
+fn action_kind(action: &UiActions) -> String {
+    let debug = format!("{:?}", action);
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+pub struct ActionBus {
+    middlewares: Vec<Box<dyn ActionMiddleware>>,
+}
+
+impl ActionBus {
+    pub fn new() -> Self {
+        Self {
+            middlewares: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, middleware: impl ActionMiddleware + 'static) -> Self {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    /// The default chain. Everything that changes the device is refused while
+    /// the model is read-only or locked
+    pub fn standard() -> Self {
+        Self::new()
+            .with(LogActions)
+            .with(CountActions)
+            .with(Locked)
+            .with(ReadOnly)
+            .with(ConfirmDangerous)
+    }
+
+    /// Run the middlewares in order, the first one that does not pass decides
+    pub fn dispatch(&mut self, action: &Action, model: &mut MonitorModel) -> Verdict {
+        for middleware in self.middlewares.iter_mut() {
+            let verdict = middleware.inspect(action, model);
+            if verdict != Verdict::Pass {
+                if let Verdict::Block(reason) = &verdict {
+                    info!("Action {:?} refused: {}", action.action, reason);
+                    model.action_stats.blocked += 1;
+                }
+                return verdict;
+            }
+        }
+        Verdict::Pass
+    }
+}
+
+pub struct LogActions;
+
+impl ActionMiddleware for LogActions {
+    fn inspect(&mut self, action: &Action, _model: &mut MonitorModel) -> Verdict {
+        // redraws are far too frequent to log
+        if action.action != UiActions::Redraw {
+            info!("Action from {}: {:?}", action.source, action.action);
+        }
+        Verdict::Pass
+    }
+}
+
+pub struct CountActions;
+
+impl ActionMiddleware for CountActions {
+    fn inspect(&mut self, action: &Action, model: &mut MonitorModel) -> Verdict {
+        if action.action == UiActions::Redraw {
+            return Verdict::Pass;
+        }
+        *model
+            .action_stats
+            .handled
+            .entry(action_kind(&action.action))
+            .or_default() += 1;
+        Verdict::Pass
+    }
+}
+
+// actions that change the device or write to it. The configuration dialogs
+// open for viewing, their results come back as AppAction
+fn changes_device(action: &UiActions) -> bool {
+    match action {
+        UiActions::Confirmed(action) => changes_device(action),
+        UiActions::AppAction(_)
+        | UiActions::SupportBundle
+        | UiActions::RetryAttestation
+        | UiActions::AttachConsole(_)
+        | UiActions::RollbackDpc(_, _)
+        | UiActions::AppCommand(_, _) => true,
+        _ => false,
+    }
+}
+
+pub struct ReadOnly;
+
+impl ActionMiddleware for ReadOnly {
+    fn inspect(&mut self, action: &Action, model: &mut MonitorModel) -> Verdict {
+        match model.read_only_reason() {
+            Some(reason) if changes_device(&action.action) => Verdict::Block(format!(
+                "The monitor runs in read-only mode ({}), {} is not allowed",
+                reason,
+                action_kind(&action.action)
+            )),
+            _ => Verdict::Pass,
+        }
+    }
+}
+
+// the lock screen takes all keys, this catches actions that arrive some other way
+pub struct Locked;
+
+impl ActionMiddleware for Locked {
+    fn inspect(&mut self, action: &Action, model: &mut MonitorModel) -> Verdict {
+        if model.lock_screen.is_some() && changes_device(&action.action) {
+            Verdict::Block(format!(
+                "The monitor is locked, {} is not allowed",
+                action_kind(&action.action)
+            ))
+        } else {
+            Verdict::Pass
+        }
+    }
+}
+
+pub struct ConfirmDangerous;
+
+impl ActionMiddleware for ConfirmDangerous {
+    fn inspect(&mut self, action: &Action, model: &mut MonitorModel) -> Verdict {
+        if let UiActions::RollbackDpc(key, time_priority) = &action.action {
+            return confirm_rollback(key, time_priority, model);
+        }
+        #[cfg(feature = "efi")]
+        if let UiActions::AppAction(MonActions::SetBootOrder { update, previous }) = &action.action
+        {
+            return confirm_boot_order(update, previous);
+        }
+        let UiActions::AppAction(MonActions::NetworkInterfaceUpdated(old, new)) = &action.action
+        else {
+            return Verdict::Pass;
+        };
+        if old == new {
+            return Verdict::Pass;
+        }
+        // a mistake on the last working management port cuts off the controller
+        let ports = PortSummary::new(&model.network);
+        let last_port = ports.up == 1
+            && model
+                .network
+                .iter()
+                .any(|p| p.name == new.iface_name && p.is_mgmt && p.up);
+        if !last_port {
+            return Verdict::Pass;
+        }
+        Verdict::Confirm {
+            title: "Network".to_string(),
+            message: format!(
+                "{} is the only working management port. A wrong configuration disconnects the device from the controller. Apply it?",
+                new.iface_name
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "efi")]
+fn boot_numbers(numbers: impl Iterator<Item = u16>) -> String {
+    numbers
+        .map(|n| format!("{:04X}", n))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// a wrong boot order may leave the device unable to boot, recovery needs someone on site
+#[cfg(feature = "efi")]
+fn confirm_boot_order(update: &EfiBootOrderUpdate, previous: &[u16]) -> Verdict {
+    let order = update
+        .boot_order
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]));
+    let mut message = format!("Write the boot order {}?", boot_numbers(order));
+    if !update.inactive.is_empty() {
+        message.push_str(&format!(
+            " Entries {} are disabled.",
+            boot_numbers(update.inactive.iter().copied())
+        ));
+    }
+    message.push_str(&format!(
+        " WARNING: if the device does not come back, restore the previous order {} in the firmware setup on site",
+        boot_numbers(previous.iter().copied())
+    ));
+    Verdict::Confirm {
+        title: "Boot order".to_string(),
+        message,
+    }
+}
+
+// replacing the network configuration is always confirmed, louder if it did not work
+fn confirm_rollback(key: &str, time_priority: &DateTime<Utc>, model: &MonitorModel) -> Verdict {
+    let Some(dpc) = model
+        .get_dpc_list()
+        .and_then(|list| list.get_dpc(key, time_priority))
+    else {
+        return Verdict::Block(format!(
+            "Configuration '{}' is no longer in the history of EVE",
+            key
+        ));
+    };
+    let when = time_priority.format("%Y-%m-%d %H:%M:%S");
+    let mut message = if model.get_current_dpc() == Some(dpc) {
+        format!(
+            "Apply configuration '{}' from {} again? EVE tests it again",
+            key, when
+        )
+    } else {
+        format!(
+            "Apply configuration '{}' from {} as the local configuration?",
+            key, when
+        )
+    };
+    if dpc.last_test_failed() {
+        let error = &dpc.test_results.last_error;
+        message = format!(
+            "WARNING: the last test of this configuration failed{}. The device may lose the connection to the controller. {}",
+            if error.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", sanitize_line(error))
+            },
+            message
+        );
+    }
+    Verdict::Confirm {
+        title: "Network configuration".to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        actions::AppCommand,
+        ipc::eve_types::{
+            DPCState, DevicePortConfig, DevicePortConfigList, MonitorPolicy, TestResults,
+        },
+        lock::LockScreen,
+        ui::export::ExportFormat,
+    };
+
+    #[test]
+    fn test_action_bus() {
+        let mut model = MonitorModel::default();
+        let restart = Action::new(
+            "apps",
+            UiActions::AppCommand(AppCommand::Restart, uuid::Uuid::nil()),
+        );
+        let export = Action::new("user", UiActions::ExportView(ExportFormat::Csv));
+
+        let mut bus = ActionBus::standard();
+        assert_eq!(bus.dispatch(&restart, &mut model), Verdict::Pass);
+
+        model.read_only_config = true;
+        assert!(matches!(
+            bus.dispatch(&restart, &mut model),
+            Verdict::Block(reason) if reason.contains("AppCommand")
+        ));
+        // confirming does not get around read-only
+        let confirmed = Action::new(
+            "user",
+            UiActions::Confirmed(Box::new(restart.action.clone())),
+        );
+        assert!(matches!(
+            bus.dispatch(&confirmed, &mut model),
+            Verdict::Block(_)
+        ));
+        assert_eq!(bus.dispatch(&export, &mut model), Verdict::Pass);
+
+        assert_eq!(model.action_stats.total(), 4);
+        assert_eq!(model.action_stats.handled["AppCommand"], 2);
+        assert_eq!(model.action_stats.blocked, 2);
+
+        // the controller policy wins over the config file
+        model.policy = Some(MonitorPolicy {
+            read_only: true,
+            reason: "kiosk".to_string(),
+        });
+        assert!(matches!(
+            bus.dispatch(&restart, &mut model),
+            Verdict::Block(reason) if reason.contains("kiosk")
+        ));
+        model.read_only_config = false;
+        model.policy = None;
+        model.lock_screen = Some(LockScreen {
+            typed: 0,
+            message: None,
+        });
+        assert!(matches!(
+            bus.dispatch(&restart, &mut model),
+            Verdict::Block(reason) if reason.contains("locked")
+        ));
+        assert_eq!(bus.dispatch(&export, &mut model), Verdict::Pass);
+    }
+
+    fn dpc(key: &str, time_priority: DateTime<Utc>, ok: bool) -> DevicePortConfig {
+        let tested = time_priority + chrono::Duration::minutes(1);
+        DevicePortConfig {
+            version: 1,
+            key: key.to_string(),
+            time_priority,
+            state: DPCState::Success,
+            sha_file: String::new(),
+            sha_value: None,
+            test_results: TestResults {
+                last_failed: if ok { DateTime::default() } else { tested },
+                last_succeeded: if ok { tested } else { DateTime::default() },
+                last_error: String::new(),
+            },
+            last_ip_and_dns: DateTime::default(),
+            ports: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_confirm_rollback() {
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::days(1);
+        let mut model = MonitorModel::default();
+        model.dpc_key = Some("zedagent".to_string());
+        model.dpc_list = Some(DevicePortConfigList {
+            current_index: 0,
+            port_config_list: Some(vec![
+                dpc("zedagent", now, true),
+                dpc("manual", earlier, false),
+            ]),
+        });
+        let mut bus = ActionBus::standard();
+        let rollback =
+            |key: &str, time| Action::new("dpc", UiActions::RollbackDpc(key.into(), time));
+
+        let retest = bus.dispatch(&rollback("zedagent", now), &mut model);
+        assert!(matches!(retest, Verdict::Confirm { message, .. } if message.contains("again")));
+        let failed = bus.dispatch(&rollback("manual", earlier), &mut model);
+        assert!(
+            matches!(failed, Verdict::Confirm { message, .. } if message.starts_with("WARNING"))
+        );
+        // keys repeat, the time picks the entry
+        let gone = bus.dispatch(&rollback("manual", now), &mut model);
+        assert!(matches!(gone, Verdict::Block(_)));
+    }
+}
@@ -1,7 +1,47 @@
-use crate::ui::ipdialog::InterfaceState;
+use uuid::Uuid;
+
+#[cfg(feature = "efi")]
+use crate::ipc::eve_types::EfiBootOrderUpdate;
+use crate::{ipc::message::Request, ui::ipdialog::InterfaceState};
+
+// lifecycle commands for an app instance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppCommand {
+    Restart,
+    // restart with the volumes recreated from their images
+    Purge,
+    PowerOff,
+}
+
+impl AppCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppCommand::Restart => "restart",
+            AppCommand::Purge => "purge",
+            AppCommand::PowerOff => "power off",
+        }
+    }
+
+    pub fn request(&self, uuid: Uuid) -> Request {
+        match self {
+            AppCommand::Restart => Request::RestartApp(uuid),
+            AppCommand::Purge => Request::PurgeApp(uuid),
+            AppCommand::PowerOff => Request::PowerOffApp(uuid),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MonActions {
     NetworkInterfaceUpdated(InterfaceState, InterfaceState),
     ServerUpdated(String),
+    UploadSupportBundle(String),
+    RetryAttestation,
+    AppCommand(AppCommand, Uuid),
+    // `previous` is the BootOrder to restore if the device does not boot
+    #[cfg(feature = "efi")]
+    SetBootOrder {
+        update: EfiBootOrderUpdate,
+        previous: Vec<u16>,
+    },
 }
@@ -1,18 +1,63 @@
-use crate::actions::MonActions;
+use crate::action_bus::{ActionBus, Verdict};
+use crate::actions::{AppCommand, MonActions};
+use crate::audit::{audit_file, read_audit_records, spawn_audit_writer};
+use crate::bundler::{spawn_bundler, BundleEvent, BundleSources};
+use crate::collectors::ipc::{task_restart_delay, IpcCollector, IpcCrash};
+#[cfg(feature = "dmesg")]
+use crate::collectors::kmsg::KmsgCollector;
+use crate::collectors::metrics::MetricsCollector;
+use crate::collectors::netlink::NetlinkCollector;
+use crate::collectors::netstats::NetStatsCollector;
+use crate::collectors::sensors::SensorsCollector;
+use crate::collectors::timer::TimerCollector;
+use crate::collectors::zpool::ZpoolCollector;
+use crate::collectors::{Collector, CollectorHealth};
+use crate::config::{MonitorConfig, RuntimeSettings};
+use crate::diagnostics::{spawn_diagnostics, DiagnosticsEvent, DiagnosticsTarget};
+use crate::error::MonitorError;
 use crate::events::Event;
+use crate::file_io::{spawn_write_file, FileIoEvent};
+use crate::guest_console::{is_detach_key, key_to_bytes, AttachedConsole, DETACH_KEY};
+use crate::idle::IdleTracker;
+use crate::lock::SessionLock;
+use crate::model::alerts::AlertSeverity;
+#[cfg(feature = "dmesg")]
+use crate::model::alerts::{AlertLink, DmesgAlertMatcher};
+use crate::model::app_logs::AppLogs;
+use crate::model::audit::AuditRecord;
+use crate::model::bundle::BundleProgress;
+use crate::model::device::config_partition::GRUB_CFG;
+#[cfg(feature = "dmesg")]
+use crate::model::device::dmesg::dmesg_text;
+#[cfg(feature = "efi")]
+use crate::model::device::efi::{boot_order_text, parse_boot_order};
+use crate::model::device::identity::{certificates_to_pem, CertInfo};
+use crate::model::device::zfs::ZfsSource;
+use crate::model::diagnostics::DiagnosticsReport;
+use crate::model::model::HistoryLimits;
+use crate::model::model::IpcConnectionState;
 use crate::model::model::Model;
 use crate::model::model::MonitorModel;
+use crate::model::model_snapshot::{snapshot_file, ModelSnapshot};
+use crate::model::snapshot::Snapshot;
+use crate::selftest::run_self_tests;
+use crate::ui::debug_page::unknown_values_text;
+use crate::ui::export::ExportFormat;
 use crate::ui::ipdialog::InterfaceState;
+use crate::ui::theme::Theme;
 use crate::ui::ui::Ui;
 
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::net::IpAddr;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::result::Result::Ok;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
-use anyhow::Result;
-use ipnet::IpNet;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use log::error;
 use log::{debug, info, trace, warn};
 
@@ -20,76 +65,554 @@ use tokio::sync::mpsc;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::UnboundedSender;
 
-use futures::{FutureExt, SinkExt, StreamExt};
+use futures::{FutureExt, StreamExt};
 
 use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
-use crate::ipc::ipc_client::IpcClient;
-use crate::ipc::message::{IpcMessage, Request};
+use crate::ipc::eve_types::{ConsoleHandoffState, SupportBundleState, SupportBundleStatus};
+use crate::ipc::ipc_client::{IpcClient, Reply};
+use crate::ipc::message::{IpcMessage, RequestId};
+use crate::ipc::pending::{PendingRequest, PendingRequests, RequestError};
+use crate::ipc::protocol::{ProtocolState, PROTOCOL_VERSION};
+use crate::ipc::replay::{spawn_playback, IpcRecorder};
 use crate::terminal::TerminalWrapper;
 use crate::ui::action::{Action, UiActions};
 
+// max 10 fps for redraws caused by data updates. User input is drawn immediately
+const FRAME_BUDGET: Duration = Duration::from_millis(100);
+
+// the resource history holds one sample per period
+const METRICS_PERIOD: Duration = Duration::from_secs(1);
+// temperatures change slowly
+const SENSORS_PERIOD: Duration = Duration::from_secs(5);
+// zpool status is not free, pools rarely change state
+const ZPOOL_PERIOD: Duration = Duration::from_secs(30);
+
+// CA bundles are a few hundred KiB at most
+const MAX_CERT_FILE_SIZE: u64 = 1024 * 1024;
+
+// snapshots, bundles, exports and blobs, next to the config
+const MONITOR_DATA_DIR_EVE: &str = "/persist/monitor";
+const MONITOR_DATA_DIR: &str = "./persist/monitor";
+
+#[cfg(feature = "dmesg")]
+type DmesgEntry = rmesg::entry::Entry;
+// nothing reads kernel messages, the select! branch never fires
+#[cfg(not(feature = "dmesg"))]
+type DmesgEntry = std::convert::Infallible;
+
+// tokio::select! branches cannot be cfg'd, so the kmsg one waits forever instead
+#[cfg(feature = "dmesg")]
+macro_rules! recv_dmesg {
+    ($app:expr) => {
+        $app.kmsg.recv()
+    };
+}
+#[cfg(not(feature = "dmesg"))]
+macro_rules! recv_dmesg {
+    ($app:expr) => {
+        std::future::pending::<Option<DmesgEntry>>()
+    };
+}
+
+/// State shown instead of the one of EVE
+pub enum Replay {
+    // the last state messages, see --replay
+    Snapshot(ModelSnapshot),
+    // messages with the milliseconds since the recording started, see --playback
+    Recording(Vec<(u64, IpcMessage)>),
+}
+
+// what to do when EVE answers a request
+struct ResponseHandler {
+    on_ok: Box<dyn Fn(&mut Application)>,
+    on_error: Box<dyn Fn(&mut Application, &RequestError)>,
+}
+
 pub struct Application {
     terminal_rx: UnboundedReceiver<Event>,
     terminal_tx: UnboundedSender<Event>,
     action_rx: UnboundedReceiver<Action>,
     action_tx: UnboundedSender<Action>,
-    ipc_tx: Option<UnboundedSender<IpcMessage>>,
+    action_bus: ActionBus,
+    ipc: IpcCollector,
+    #[cfg(feature = "dmesg")]
+    kmsg: KmsgCollector,
+    netlink: NetlinkCollector,
+    netstats: NetStatsCollector,
+    metrics: MetricsCollector,
+    sensors: SensorsCollector,
+    zpool: ZpoolCollector,
+    timer: TimerCollector,
+    file_io_rx: UnboundedReceiver<FileIoEvent>,
+    file_io_tx: UnboundedSender<FileIoEvent>,
+    diagnostics_rx: UnboundedReceiver<DiagnosticsEvent>,
+    diagnostics_tx: UnboundedSender<DiagnosticsEvent>,
+    // cancels the running connectivity checks
+    diagnostics_cancel: Option<CancellationToken>,
+    bundle_rx: UnboundedReceiver<BundleEvent>,
+    bundle_tx: UnboundedSender<BundleEvent>,
+    // the bundler task, one bundle at a time
+    bundle_task: Option<JoinHandle<()>>,
+    // shown instead of the state of EVE
+    replay: Option<Replay>,
+    // recorded messages on their original schedule
+    playback_rx: UnboundedReceiver<IpcMessage>,
+    playback_tx: UnboundedSender<IpcMessage>,
+    // messages from EVE are appended to a file, see --record
+    recorder: Option<IpcRecorder>,
+    // records for the audit file, see audited_ipc_call
+    audit_tx: UnboundedSender<AuditRecord>,
     ui: Ui,
+    idle: IdleTracker,
+    lock: SessionLock,
+    // shared with the terminal task to silence errors while a guest owns the console
+    console_handed_off: Arc<AtomicBool>,
+    #[cfg(feature = "dmesg")]
+    dmesg_alerts: DmesgAlertMatcher,
     // this is our model :)
     model: Rc<Model>,
-    // pending requests
-    pending_requests: HashMap<u64, Rc<dyn Fn(&mut Application)>>,
+    // pending requests by call id
+    pending_requests: PendingRequests<ResponseHandler>,
+    next_call_id: RequestId,
+    // answers of the IPC client, see ipc_call
+    reply_rx: UnboundedReceiver<(RequestId, Reply)>,
+    reply_tx: UnboundedSender<(RequestId, Reply)>,
+    // failed connection attempts in a row
+    ipc_failures: u32,
+    // whether we had a connection before, the model is stale after a reconnect
+    ipc_was_connected: bool,
+    // custom colors from the config, they apply to every theme
+    theme_colors: BTreeMap<String, String>,
+    // the IPC task ended unexpectedly, reported to the user once it is back
+    ipc_crash: Option<IpcCrash>,
 }
 
 impl Application {
-    pub fn new() -> Result<Self> {
+    pub fn new(replay: Option<Replay>, record: Option<PathBuf>) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel::<Action>();
         let (terminal_tx, terminal_rx) = mpsc::unbounded_channel::<Event>();
+        let (file_io_tx, file_io_rx) = mpsc::unbounded_channel::<FileIoEvent>();
+        let (diagnostics_tx, diagnostics_rx) = mpsc::unbounded_channel::<DiagnosticsEvent>();
+        let (bundle_tx, bundle_rx) = mpsc::unbounded_channel::<BundleEvent>();
+        let (playback_tx, playback_rx) = mpsc::unbounded_channel::<IpcMessage>();
+        let (reply_tx, reply_rx) = mpsc::unbounded_channel::<(RequestId, Reply)>();
+        let (audit_tx, audit_rx) = mpsc::unbounded_channel::<AuditRecord>();
+        let config = MonitorConfig::load();
+        let low_memory = config.is_low_memory();
+        let mut limits = if low_memory {
+            info!("Running in low-memory mode");
+            HistoryLimits::low_memory()
+        } else {
+            HistoryLimits::default()
+        };
+        if config.dmesg_lines > 0 {
+            limits.dmesg = config.dmesg_lines;
+        }
         let terminal = TerminalWrapper::open_terminal()?;
-        let mut ui = Ui::new(action_tx.clone(), terminal)?;
-        let model = Rc::new(RefCell::new(MonitorModel::default()));
-        let pending_requests = HashMap::new();
+        let mut ui = Ui::new(action_tx.clone(), terminal, &config)?;
+        let model = Rc::new(RefCell::new(MonitorModel::new(limits)));
+        let pending_requests = PendingRequests::default();
 
-        ui.init();
+        ui.init(low_memory);
+
+        // there is no EVE to test against when replaying
+        if replay.is_none() {
+            let terminal_size = ui.terminal.size().ok().map(|s| (s.width, s.height));
+            let results = run_self_tests(&Application::get_socket_path(), terminal_size);
+            ui.show_self_test_results(results);
+        }
+
+        let settings = config.settings();
+        log::set_max_level(settings.log_level);
+        let idle = IdleTracker::new(settings.idle_duration());
+        #[cfg(feature = "dmesg")]
+        let dmesg_alerts = DmesgAlertMatcher::new(&config.dmesg_alerts);
+
+        let ipc = IpcCollector::new(
+            Application::get_socket_path(),
+            config.ipc_max_message_kb * 1024,
+        );
+        #[cfg(feature = "dmesg")]
+        let kmsg = KmsgCollector::new(Application::is_desktop());
+        let netlink = NetlinkCollector::new();
+        let netstats =
+            NetStatsCollector::new(Duration::from_millis(settings.tick_ms), idle.subscribe());
+        let metrics = MetricsCollector::new(METRICS_PERIOD, idle.subscribe());
+        let sensors = SensorsCollector::new(SENSORS_PERIOD);
+        let zpool = ZpoolCollector::new(ZPOOL_PERIOD);
+        let timer = TimerCollector::new(Duration::from_millis(settings.tick_ms), idle.subscribe());
+        model.borrow_mut().theme = Theme::new(settings.theme, &config.theme_colors);
+        model.borrow_mut().app_thresholds = config.app_thresholds;
+        model.borrow_mut().sensor_thresholds = config.sensor_thresholds.clone();
+        model.borrow_mut().alerts_config = config.alerts.clone();
+        if config.read_only {
+            info!("Running in read-only mode");
+        }
+        let action_bus = ActionBus::standard();
+        model.borrow_mut().read_only_config = config.read_only;
+        model.borrow_mut().settings = settings;
+        match audit_file() {
+            Some(path) => {
+                let records = read_audit_records(&path);
+                let skip = records.len().saturating_sub(model.borrow().limits.audit);
+                for record in records.into_iter().skip(skip) {
+                    model.borrow_mut().push_audit(record);
+                }
+                spawn_audit_writer(path, audit_rx);
+            }
+            // the records are only shown on the Audit page
+            None => warn!("Logging is not initialized, changes are not written to the audit file"),
+        }
 
         Ok(Self {
             terminal_rx,
             terminal_tx,
             action_rx,
             action_tx,
+            action_bus,
             ui,
-            ipc_tx: None,
+            idle,
+            lock: SessionLock::new(&config.lock),
+            console_handed_off: Arc::new(AtomicBool::new(false)),
+            #[cfg(feature = "dmesg")]
+            dmesg_alerts,
+            ipc,
+            #[cfg(feature = "dmesg")]
+            kmsg,
+            netlink,
+            netstats,
+            metrics,
+            sensors,
+            zpool,
+            timer,
+            file_io_rx,
+            file_io_tx,
+            diagnostics_rx,
+            diagnostics_tx,
+            diagnostics_cancel: None,
+            bundle_rx,
+            bundle_tx,
+            bundle_task: None,
+            replay,
+            playback_rx,
+            playback_tx,
+            recorder: record.map(IpcRecorder::start),
+            audit_tx,
             model,
             pending_requests,
+            next_call_id: 1,
+            reply_rx,
+            reply_tx,
+            ipc_failures: 0,
+            ipc_was_connected: false,
+            ipc_crash: None,
+            theme_colors: config.theme_colors.clone(),
         })
     }
-    pub fn send_ipc_message<F>(&mut self, msg: IpcMessage, handle_response: F)
+    /// Send a request with the IPC client, `on_ok` runs when EVE accepts it.
+    /// Errors are reported to the user
+    pub fn ipc_call<C, R, F>(&mut self, name: &str, call: C, on_ok: F)
     where
-        F: Fn(&mut Application) -> () + 'static,
+        C: FnOnce(&IpcClient) -> R,
+        R: Future<Output = Reply> + Send + 'static,
+        F: Fn(&mut Application) + 'static,
     {
-        if let Some(ipc_tx) = &self.ipc_tx {
-            match &msg {
-                IpcMessage::Request { request, id } => {
-                    debug!("Pending response for: {:?}", request);
-                    self.pending_requests.insert(*id, Rc::new(handle_response));
-                }
-                _ => {}
+        let failed = name.to_string();
+        self.ipc_call_or_else(name, call, on_ok, move |app, e| {
+            app.report_request_error(&failed, e)
+        });
+    }
+
+    /// Like ipc_call but `on_error` is called if EVE rejects the request or
+    /// does not answer in time. Returns the id of the call, None if the IPC
+    /// collector is not running
+    pub fn ipc_call_or_else<C, R, F, E>(
+        &mut self,
+        name: &str,
+        call: C,
+        on_ok: F,
+        on_error: E,
+    ) -> Option<RequestId>
+    where
+        C: FnOnce(&IpcClient) -> R,
+        R: Future<Output = Reply> + Send + 'static,
+        F: Fn(&mut Application) + 'static,
+        E: Fn(&mut Application, &RequestError) + 'static,
+    {
+        let client = self.ipc.client()?;
+        // sent right away, the task only waits for the answer
+        let reply = call(&client);
+        let id = self.next_call_id;
+        self.next_call_id += 1;
+        debug!("Pending response for: {}", name);
+        let handler = ResponseHandler {
+            on_ok: Box::new(on_ok),
+            on_error: Box::new(on_error),
+        };
+        self.pending_requests
+            .insert(id, PendingRequest::new(name, handler));
+        self.model.borrow_mut().pending_requests = self.pending_requests.len();
+        let reply_tx = self.reply_tx.clone();
+        tokio::spawn(async move {
+            // the application is gone
+            let _ = reply_tx.send((id, reply.await));
+        });
+        Some(id)
+    }
+
+    /// Like ipc_call_or_else for requests that change the device. `record`
+    /// goes to the audit log with the result of the request
+    pub fn audited_ipc_call<C, R, F, E>(
+        &mut self,
+        record: AuditRecord,
+        call: C,
+        on_ok: F,
+        on_error: E,
+    ) where
+        C: FnOnce(&IpcClient) -> R,
+        R: Future<Output = Reply> + Send + 'static,
+        F: Fn(&mut Application) + 'static,
+        E: Fn(&mut Application, &RequestError) + 'static,
+    {
+        // the action bus refuses these already, the policy may have changed since
+        let reason = self.model.borrow().read_only_reason();
+        if let Some(reason) = reason {
+            warn!("{} refused, read-only mode ({})", record.action, reason);
+            self.ui.message_box(
+                "Not allowed",
+                &format!(
+                    "The monitor runs in read-only mode ({}), {} is not sent",
+                    reason, record.action
+                ),
+            );
+            return;
+        }
+        let name = record.action.clone();
+        let accepted = record.clone();
+        let failed = record.clone();
+        let id = self.ipc_call_or_else(
+            &name,
+            call,
+            move |app| {
+                app.audit(accepted.clone());
+                on_ok(app);
+            },
+            move |app, e| {
+                app.audit(AuditRecord {
+                    error: Some(e.to_string()),
+                    ..failed.clone()
+                });
+                on_error(app, e);
+            },
+        );
+        if id.is_none() {
+            self.audit(AuditRecord {
+                error: Some(RequestError::Disconnected.to_string()),
+                ..record
+            });
+        }
+    }
+
+    fn audit(&mut self, record: AuditRecord) {
+        info!(
+            "Audit: {} {} {:?} -> {:?}: {}",
+            record.action,
+            record.target,
+            record.old,
+            record.new,
+            record.result()
+        );
+        // the writer is not running without a log directory
+        let _ = self.audit_tx.send(record.clone());
+        self.model.borrow_mut().push_audit(record);
+    }
+
+    fn handle_reply(&mut self, id: RequestId, reply: Reply) {
+        let pending = self.pending_requests.remove(id);
+        self.model.borrow_mut().pending_requests = self.pending_requests.len();
+        match (reply, pending) {
+            (Ok(()), Some(pending)) => {
+                debug!("Request {} OK", pending.name);
+                (pending.handler.on_ok)(self);
+            }
+            (Err(e), Some(pending)) => {
+                warn!("Request {} failed: {}", pending.name, e);
+                (pending.handler.on_error)(self, &e);
             }
+            // dropped after the connection was lost
+            (_, None) => debug!("Reply to call {} that is not pending", id),
+        }
+    }
+
+    fn report_request_error(&mut self, name: &str, e: &RequestError) {
+        match e {
+            RequestError::Rejected(e) => {
+                MonitorError::Ipc(anyhow!("Request {} failed: {}", name, e)).log()
+            }
+            RequestError::TimedOut => self.ui.message_box(
+                "ERROR",
+                &format!("EVE did not answer the {} request. Try again later", name),
+            ),
+            RequestError::Disconnected => self.ui.message_box(
+                "ERROR",
+                &format!(
+                    "The {} request was lost, the monitor is not connected to EVE",
+                    name
+                ),
+            ),
+            // the monitor is shutting down
+            RequestError::Cancelled => debug!("Request {} cancelled", name),
+        }
+    }
+
+    /// Write a file without blocking the UI. The result is reported by handle_file_io_event
+    pub fn write_file(&self, path: PathBuf, data: Vec<u8>) {
+        spawn_write_file(path, data, self.file_io_tx.clone());
+    }
+
+    fn handle_file_io_event(&mut self, event: FileIoEvent) {
+        match event {
+            FileIoEvent::Progress {
+                path,
+                written,
+                total,
+            } => {
+                trace!("Writing {}: {}/{} bytes", path.display(), written, total);
+            }
+            FileIoEvent::Completed { path } => {
+                info!("File saved: {}", path.display());
+                self.ui
+                    .message_box("Saved", &format!("File saved to {}", path.display()));
+            }
+            FileIoEvent::Failed { path, error } => {
+                warn!("Failed to write {}", path.display());
+                error.log();
+                self.ui.message_box("ERROR", &error.user_message());
+            }
+        }
+    }
 
-            match ipc_tx.send(msg) {
-                Ok(_) => {
-                    debug!("Sent IPC message");
+    /// Run the connectivity checks of `iface` and show their report. Checks
+    /// that are still running are cancelled
+    fn start_diagnostics(&mut self, iface: &str) {
+        let target = {
+            let model = self.model.borrow();
+            let Some(status) = model.network.iter().find(|n| n.name == iface) else {
+                return;
+            };
+            DiagnosticsTarget {
+                iface: iface.to_string(),
+                gateway: status.gw,
+                dns: status.dns.clone().unwrap_or_default(),
+                server: model.node_status.server.clone(),
+            }
+        };
+        self.stop_diagnostics();
+        // the dialog is open while there is a report
+        let dialog_open = self.model.borrow().diagnostics.is_some();
+        self.model.borrow_mut().diagnostics = Some(DiagnosticsReport::new(target.step_names()));
+        if !dialog_open {
+            self.ui.show_diagnostics(iface);
+        }
+        let token = CancellationToken::new();
+        spawn_diagnostics(target, self.diagnostics_tx.clone(), token.clone());
+        self.diagnostics_cancel = Some(token);
+    }
+
+    /// Create a support bundle without EVE and show its progress
+    fn start_bundle(&mut self) {
+        if self
+            .bundle_task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            if self.model.borrow().bundle.is_none() {
+                // hidden while running, show it again
+                self.ui.show_bundle_dialog();
+                self.model.borrow_mut().bundle = Some(BundleProgress::Running {
+                    step: "Creating the bundle",
+                    done: 0,
+                    total: 1,
+                });
+            }
+            return;
+        }
+        #[cfg(feature = "dmesg")]
+        let dmesg = Some(dmesg_text(&self.model.borrow().dmesg));
+        #[cfg(not(feature = "dmesg"))]
+        let dmesg = None;
+        let sources = BundleSources::capture(
+            &self.model.borrow(),
+            dmesg,
+            MonitorConfig::get_config_path(),
+        );
+        let dialog_open = self.model.borrow().bundle.is_some();
+        self.model.borrow_mut().bundle = Some(BundleProgress::Running {
+            step: "Starting",
+            done: 0,
+            total: 1,
+        });
+        if !dialog_open {
+            self.ui.show_bundle_dialog();
+        }
+        self.bundle_task = Some(spawn_bundler(sources, self.bundle_tx.clone()));
+    }
+
+    fn handle_bundle_event(&mut self, event: BundleEvent) {
+        let dialog_open = self.model.borrow().bundle.is_some();
+        let progress = match event {
+            BundleEvent::Progress { step, done, total } => {
+                BundleProgress::Running { step, done, total }
+            }
+            BundleEvent::Completed { path } => {
+                if !dialog_open {
+                    self.ui.message_box(
+                        "Support bundle",
+                        &format!("Support bundle saved to {}", path.display()),
+                    );
                 }
-                Err(e) => {
-                    error!("Error sending IPC message: {:?}", e);
+                BundleProgress::Completed(path)
+            }
+            BundleEvent::Failed { error } => {
+                error.log();
+                if !dialog_open {
+                    self.ui.message_box("ERROR", &error.user_message());
                 }
+                BundleProgress::Failed(error.user_message())
             }
+        };
+        if dialog_open {
+            self.model.borrow_mut().bundle = Some(progress);
         }
     }
 
-    fn get_socket_path() -> String {
+    fn stop_diagnostics(&mut self) {
+        if let Some(token) = self.diagnostics_cancel.take() {
+            token.cancel();
+        }
+        // events of the cancelled run are still queued
+        while self.diagnostics_rx.try_recv().is_ok() {}
+    }
+
+    #[cfg(feature = "dmesg")]
+    fn save_dmesg(&self) {
+        let Ok(log_dir) = std::env::var("EVE_MONITOR_LOG_DIR") else {
+            warn!("EVE_MONITOR_LOG_DIR is not set, cannot save dmesg");
+            return;
+        };
+        let data = dmesg_text(&self.model.borrow().dmesg);
+        let file_name = format!(
+            "dmesg-{}.log",
+            chrono::Local::now().format("%Y-%m-%d-%H-%M-%S")
+        );
+        self.write_file(PathBuf::from(log_dir).join(file_name), data.into_bytes());
+    }
+
+    pub fn get_socket_path() -> String {
         // try to get XDG_RUNTIME_DIR first if we run a standalone app on development host
         if let Ok(xdg_runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
             return format!("{}/monitor.sock", xdg_runtime_dir);
@@ -99,96 +622,516 @@ impl Application {
         }
     }
 
+    /// <monitor data dir>/<sub>, created by whoever writes there
+    pub fn monitor_data_dir(sub: &str) -> PathBuf {
+        // same trick as for the config: XDG_RUNTIME_DIR is only set on desktop linux
+        let dir = if std::env::var("XDG_RUNTIME_DIR").is_ok() {
+            MONITOR_DATA_DIR
+        } else {
+            MONITOR_DATA_DIR_EVE
+        };
+        PathBuf::from(dir).join(sub)
+    }
+
+    #[cfg(feature = "dmesg")]
     fn is_desktop() -> bool {
         //std::env::var("XDG_RUNTIME_DIR").is_ok()
         false
     }
 
     pub fn handle_ipc_message(&mut self, msg: IpcMessage) {
+        if let IpcMessage::Version(version) = &msg {
+            self.ipc.set_compression(version.accepts_zlib());
+        }
+        // state updates are handled by the model itself
+        let Some(msg) = self.model.borrow_mut().apply_ipc_message(msg) else {
+            return;
+        };
         match msg {
-            IpcMessage::Response { result, id } => {
-                debug!("Got response: {:?}", result);
-                match result {
-                    Ok(_) => {
-                        debug!("Response OK");
-                        if let Some(handle_response) = self.pending_requests.remove(&id) {
-                            handle_response(self);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Response error: {:?}", e);
-                        // remove pending request
-                        self.pending_requests.remove(&id);
-                    }
-                }
+            IpcMessage::Connecting => {
+                let mut model = self.model.borrow_mut();
+                model.ipc_state = IpcConnectionState::Connecting;
+                // EVE may have been updated while we were disconnected
+                model.protocol = ProtocolState::Unknown;
             }
-
-            IpcMessage::DPCList(cfg) => {
-                debug!("Got DPC list");
-                self.model.borrow_mut().set_dpc_list(cfg);
+            IpcMessage::Ready => {
+                self.model.borrow_mut().ipc_state = IpcConnectionState::Connected;
+                self.ipc_failures = 0;
+                self.send_hello();
+                if self.ipc_was_connected {
+                    // whatever changed while we were away was not seen
+                    info!("IPC reconnected, requesting full state");
+                    self.ipc_call("ResendState", |ipc| ipc.resend_state(), |_| {});
+                }
+                self.ipc_was_connected = true;
+                // the stream of the log viewer died with the connection
+                self.start_app_logs_stream();
+                if let Some(crash) = self.ipc_crash.take() {
+                    self.ui.confirm_dialog(
+                        "Connection to EVE recovered",
+                        &format!(
+                            "The connection to EVE failed: {}. Generate a support bundle to report the problem?",
+                            crash.error
+                        ),
+                        UiActions::SupportBundle,
+                    );
+                }
             }
-            IpcMessage::NetworkStatus(cfg) => {
-                debug!("Got Network status");
-                self.model.borrow_mut().update_network_status(cfg);
+            IpcMessage::Disconnected {
+                reason,
+                retry_in_ms,
+            } => {
+                info!("IPC disconnected: {}", reason);
+                // reconnect attempts disconnect again, tell only once
+                if self.ipc_failures == 0 {
+                    self.model.borrow_mut().raise_alert(
+                        AlertSeverity::Warning,
+                        "ipc",
+                        format!("Connection to EVE lost: {}", reason),
+                        None,
+                    );
+                }
+                self.ipc_failures += 1;
+                self.drop_pending_requests();
+                self.detach_console(Some("connection to EVE lost"));
+                self.model.borrow_mut().ipc_state = IpcConnectionState::Reconnecting {
+                    attempt: self.ipc_failures,
+                    retry_at: Instant::now().into_std() + Duration::from_millis(retry_in_ms),
+                };
             }
-            IpcMessage::AppStatus(app) => {
-                debug!("Got AppStatus");
-                self.model.borrow_mut().update_app_status(app);
+            // the IPC client routes the answers to its requests, e.g. this one
+            // arrived after the request timed out
+            IpcMessage::Response { result, id } => {
+                debug!("Response to request {} nobody waits for: {:?}", id, result);
             }
 
-            IpcMessage::DownloaderStatus(dnl) => {
-                debug!("Got DownloaderStatus");
-                self.model.borrow_mut().update_downloader_status(dnl);
+            IpcMessage::ConsoleHandoff(handoff) => {
+                info!(
+                    "Console handoff to {}: {:?}",
+                    handoff.app_name, handoff.state
+                );
+                let was_handed_off = self.model.borrow().is_console_handed_off();
+                self.model.borrow_mut().console_handoff = Some(handoff);
+                let handed_off = self.model.borrow().is_console_handed_off();
+                self.console_handed_off.store(handed_off, Ordering::Relaxed);
+                // the guest left garbage on the screen
+                if was_handed_off && !handed_off {
+                    self.ui.invalidate_screen();
+                }
             }
 
-            // this event is guaranteed to be sent before periodic events
-            IpcMessage::AppSummary(summary) => {
-                debug!("Got AppSummary");
-                self.model.borrow_mut().update_app_summary(summary);
+            IpcMessage::ConsoleOutput(output) => {
+                let attached = self
+                    .model
+                    .borrow()
+                    .attached_console
+                    .as_ref()
+                    .is_some_and(|c| c.uuid == output.app_uuid);
+                // output of a console we have detached from may still be on the way
+                if attached {
+                    if let Err(e) = self.ui.terminal.write_raw(&output.data) {
+                        warn!("Cannot write console output: {}", e);
+                    }
+                }
             }
 
-            // this event is guaranteed to be sent before periodic events
-            IpcMessage::NodeStatus(node_status) => {
-                debug!("Got NodeStatus");
-                self.model.borrow_mut().update_node_status(node_status);
+            IpcMessage::SupportBundleStatus(status) => {
+                debug!("Got SupportBundleStatus: {:?}", status.state);
+                self.handle_support_bundle_status(status);
             }
 
-            IpcMessage::OnboardingStatus(o_status) => {
-                debug!("Got OnboardingStatus");
-                self.model.borrow_mut().update_onboarding_status(o_status);
+            _ => {
+                warn!("Unhandled IPC message: {:?}", msg);
             }
+        }
+    }
 
-            IpcMessage::VaultStatus(status) => {
-                debug!("Got VaultStatus");
-                self.model.borrow_mut().update_vault_status(status);
-            }
+    // give the terminal to the serial console of a guest until DETACH_KEY
+    fn attach_console(&mut self, uuid: Uuid) {
+        let app = self
+            .model
+            .borrow()
+            .apps
+            .get(&uuid)
+            .map(|a| (a.name.clone(), a.has_serial_console()));
+        let Some((app_name, has_serial_console)) = app else {
+            return;
+        };
+        if !has_serial_console {
+            self.ui.message_box(
+                "Console",
+                &format!("{} has no serial console, only HVM guests do", app_name),
+            );
+            return;
+        }
+        if self.model.borrow().ipc_state != IpcConnectionState::Connected {
+            self.ui
+                .message_box("Console", "Not connected to EVE, try again later");
+            return;
+        }
+        info!("Attaching console of {}", app_name);
+        let banner = format!(
+            "\x1b[2J\x1b[HConnected to the console of {}. Press {} to detach\r\n",
+            app_name, DETACH_KEY
+        );
+        if let Err(e) = self.ui.terminal.show_cursor() {
+            warn!("Cannot show cursor: {}", e);
+        }
+        if let Err(e) = self.ui.terminal.write_raw(banner.as_bytes()) {
+            warn!("Cannot write to terminal: {}", e);
+        }
+        self.model.borrow_mut().attached_console = Some(AttachedConsole { uuid, app_name });
+        self.ipc_call_or_else(
+            "AttachConsole",
+            |ipc| ipc.attach_console(uuid),
+            |_| {},
+            |app, e| app.detach_console(Some(&e.to_string())),
+        );
+    }
 
-            IpcMessage::LedBlinkCounter(_led) => {
-                debug!("Got LedBlinkCounter");
-            }
+    fn detach_console(&mut self, reason: Option<&str>) {
+        let Some(console) = self.model.borrow_mut().attached_console.take() else {
+            return;
+        };
+        info!("Detaching console of {}", console.app_name);
+        if let Err(e) = self.ui.terminal.hide_cursor() {
+            warn!("Cannot hide cursor: {}", e);
+        }
+        self.ui.invalidate_screen();
+        match reason {
+            Some(reason) => self.ui.message_box(
+                "Console",
+                &format!("Console of {} was detached: {}", console.app_name, reason),
+            ),
+            None => self.ipc_call(
+                "DetachConsole",
+                |ipc| ipc.detach_console(console.uuid),
+                |_| {},
+            ),
+        }
+    }
 
-            // this event is guaranteed to be sent before periodic events
-            IpcMessage::AppsList(app_list) => {
-                debug!("Got AppsList");
-                self.model.borrow_mut().update_app_list(app_list);
-            }
+    fn handle_console_key(&mut self, key: crossterm::event::KeyEvent) {
+        if is_detach_key(&key) {
+            self.detach_console(None);
+            self.invalidate();
+            return;
+        }
+        if let Some(data) = key_to_bytes(&key) {
+            self.send_console_input(data);
+        }
+    }
+
+    fn send_console_input(&mut self, data: Vec<u8>) {
+        let Some(app_uuid) = self
+            .model
+            .borrow()
+            .attached_console
+            .as_ref()
+            .map(|c| c.uuid)
+        else {
+            return;
+        };
+        // a lost key is not worth a dialog over the console
+        self.ipc_call_or_else(
+            "ConsoleInput",
+            |ipc| ipc.console_input(app_uuid, data),
+            |_| {},
+            |_, e| debug!("Console input failed: {}", e),
+        );
+    }
+
+    fn open_app_logs(&mut self, uuid: Uuid) {
+        let Some(name) = self.model.borrow().apps.get(&uuid).map(|a| a.name.clone()) else {
+            return;
+        };
+        if self.model.borrow().ipc_state != IpcConnectionState::Connected {
+            self.ui
+                .message_box("App logs", "Not connected to EVE, try again later");
+            return;
+        }
+        self.model.borrow_mut().app_logs = Some(AppLogs::new(uuid, name));
+        self.start_app_logs_stream();
+        self.ui.show_app_logs();
+    }
+
+    // (re)start streaming logs of the app open in the viewer
+    fn start_app_logs_stream(&mut self) {
+        let Some(uuid) = self.model.borrow().app_logs.as_ref().map(|logs| logs.uuid) else {
+            return;
+        };
+        // the id is known once the request is sent, the answer comes later
+        let sent = Rc::new(Cell::new(None));
+        let sent_id = sent.clone();
+        let id = self.ipc_call_or_else(
+            "StartAppLogs",
+            |ipc| ipc.start_app_logs(uuid),
+            |_| {},
+            // e.g. the app has no console or EVE cannot stream logs
+            move |app, e| {
+                info!("App logs are not available: {}", e);
+                let mut model = app.model.borrow_mut();
+                if let Some(logs) = model.app_logs.as_mut() {
+                    if logs.request_id.is_some() && logs.request_id == sent_id.get() {
+                        logs.error = Some(format!("Logs are not available: {}", e));
+                    }
+                }
+            },
+        );
+        sent.set(id);
+        if let Some(logs) = self.model.borrow_mut().app_logs.as_mut() {
+            logs.request_id = id;
+        }
+    }
 
-            IpcMessage::ZedAgentStatus(status) => {
-                debug!("Got ZedAgentStatus");
-                self.model.borrow_mut().update_zed_agent_status(status);
+    fn send_hello(&mut self) {
+        let sent = Rc::new(Cell::new(None));
+        let sent_id = sent.clone();
+        // EVE replies with IpcMessage::Version. EVE that predates versioning
+        // does not know Hello
+        let id = self.ipc_call_or_else(
+            "Hello",
+            |ipc| ipc.hello(PROTOCOL_VERSION),
+            |_| {},
+            move |app, e| {
+                let mut model = app.model.borrow_mut();
+                if model.protocol.hello_id().is_some() && model.protocol.hello_id() == sent_id.get()
+                {
+                    info!("EVE rejected Hello, using legacy protocol: {}", e);
+                    model.protocol = ProtocolState::Legacy;
+                }
+            },
+        );
+        sent.set(id);
+        match id {
+            Some(id) => self.model.borrow_mut().protocol = ProtocolState::Negotiating(id),
+            None => warn!("Cannot send Hello, the IPC collector is not running"),
+        }
+    }
+
+    fn handle_support_bundle_status(&mut self, status: SupportBundleStatus) {
+        let old_state = self
+            .model
+            .borrow()
+            .support_bundle
+            .as_ref()
+            .map(|s| s.state.clone());
+        // tell the user only about transitions, progress goes to the status bar
+        if old_state.as_ref() != Some(&status.state) {
+            match status.state {
+                SupportBundleState::Ready => self.ui.confirm_dialog(
+                    "Support bundle",
+                    &format!(
+                        "Support bundle {} is ready. Upload it to the controller?",
+                        status.path
+                    ),
+                    UiActions::AppAction(MonActions::UploadSupportBundle(status.path.clone())),
+                ),
+                SupportBundleState::Uploaded => self
+                    .ui
+                    .message_box("Support bundle", "Support bundle uploaded"),
+                SupportBundleState::Failed => {
+                    let error = status.error.clone().unwrap_or("unknown error".to_string());
+                    MonitorError::Ipc(anyhow!("Support bundle failed: {}", error)).log();
+                    self.ui
+                        .message_box("ERROR", &format!("Support bundle: {}", error));
+                }
+                SupportBundleState::Generating
+                | SupportBundleState::Uploading
+                | SupportBundleState::Unrecognized => {}
             }
+        }
+        self.model.borrow_mut().support_bundle = Some(status);
+    }
 
+    fn request_support_bundle(&mut self) {
+        let status = self.model.borrow().support_bundle.clone();
+        match status {
+            Some(status) if status.state == SupportBundleState::Ready => {
+                // the user dismissed the upload question before, ask again
+                self.ui.confirm_dialog(
+                    "Support bundle",
+                    &format!("Upload support bundle {} to the controller?", status.path),
+                    UiActions::AppAction(MonActions::UploadSupportBundle(status.path)),
+                );
+            }
+            Some(status)
+                if matches!(
+                    status.state,
+                    SupportBundleState::Generating | SupportBundleState::Uploading
+                ) =>
+            {
+                self.ui
+                    .message_box("Support bundle", "Support bundle is in progress");
+            }
             _ => {
-                warn!("Unhandled IPC message: {:?}", msg);
+                info!("Requesting support bundle");
+                self.ipc_call(
+                    "CreateSupportBundle",
+                    |ipc| ipc.create_support_bundle(),
+                    |_| {},
+                );
             }
         }
     }
 
+    fn request_attestation_retry(&mut self) {
+        let model = self.model.borrow();
+        if let Some(reason) = model.attestation.retry_blocker() {
+            drop(model);
+            self.ui.message_box("Attestation", reason);
+            return;
+        }
+        // restarting attestation that is still progressing only delays it
+        let message = if model.attestation.is_stuck(chrono::Local::now()) {
+            "Attestation looks stuck. Request EVE to restart it?"
+        } else {
+            "Attestation is still in progress. Restart it anyway?"
+        };
+        drop(model);
+        self.ui.confirm_dialog(
+            "Attestation",
+            message,
+            UiActions::AppAction(MonActions::RetryAttestation),
+        );
+    }
+
+    fn confirm_app_command(&mut self, command: AppCommand, uuid: Uuid) {
+        let Some(name) = self.model.borrow().apps.get(&uuid).map(|a| a.name.clone()) else {
+            return;
+        };
+        let message = match command {
+            AppCommand::Restart => format!("Restart {}?", name),
+            AppCommand::Purge => format!(
+                "Purge {}? Its volumes are recreated and all data in them is lost",
+                name
+            ),
+            AppCommand::PowerOff => format!("Power off {}?", name),
+        };
+        self.ui.confirm_dialog(
+            "Application",
+            &message,
+            UiActions::AppAction(MonActions::AppCommand(command, uuid)),
+        );
+    }
+
+    fn send_app_command(&mut self, command: AppCommand, uuid: Uuid) {
+        let name = self
+            .model
+            .borrow()
+            .apps
+            .get(&uuid)
+            .map_or(uuid.to_string(), |a| a.name.clone());
+        info!("Requesting {} of {}", command.name(), name);
+        let failed_name = name.clone();
+        let record = AuditRecord::new(command.name(), &name, None, None);
+        self.audited_ipc_call(
+            record,
+            |ipc| ipc.command(command.request(uuid)),
+            move |app| {
+                app.ui.message_box(
+                    "Application",
+                    &format!("EVE accepted the {} of {}", command.name(), name),
+                )
+            },
+            move |app, e| {
+                app.ui.message_box(
+                    "ERROR",
+                    &format!("Cannot {} {}: {}", command.name(), failed_name, e),
+                )
+            },
+        );
+    }
+
+    // the catch-all values would be sent back instead of the real ones
+    fn dpc_has_unknown_values(&mut self) -> bool {
+        if !self.model.borrow().has_unknown_enum_values("DPCList") {
+            return false;
+        }
+        MonitorError::Ipc(anyhow!("DPC has values unknown to the monitor")).log();
+        self.ui.message_box(
+            "ERROR",
+            "The network configuration from EVE has values this monitor does not understand. \
+             Change it from the controller. See the debug page for details",
+        );
+        true
+    }
+
+    // push an entry of the DPC history as the new manual DPC, EVE tests it
+    // like any other change
+    fn rollback_dpc(&mut self, key: &str, time_priority: &DateTime<Utc>) {
+        // the history window
+        self.ui.pop_layer();
+        if self.dpc_has_unknown_values() {
+            return;
+        }
+        let dpc = self.model.borrow().get_dpc_with_blobs(key, time_priority);
+        let Some(dpc) = dpc else {
+            self.ui.message_box(
+                "ERROR",
+                &format!(
+                    "Configuration '{}' is not available anymore. Nothing was changed",
+                    key
+                ),
+            );
+            return;
+        };
+        info!(
+            "Applying DPC '{}' from {} as manual DPC",
+            key, time_priority
+        );
+        let new_dpc = dpc.to_new_dpc_with_key("manual");
+        let record = AuditRecord::new(
+            "SetDPC",
+            "all ports",
+            self.model.borrow().dpc_key.clone(),
+            Some(format!("{} from {}", key, time_priority)),
+        );
+        self.audited_ipc_call(
+            record,
+            |ipc| ipc.set_dpc(new_dpc),
+            |_| {},
+            |app, e| app.report_request_error("SetDPC", e),
+        );
+    }
+
     pub fn send_dpc(&mut self, old: InterfaceState, new: InterfaceState) {
-        let current_dpc = self.model.borrow().get_current_dpc().cloned();
+        if self.dpc_has_unknown_values() {
+            return;
+        }
+        let current_dpc = self.model.borrow().get_current_dpc_with_blobs();
         if let Some(current_dpc) = current_dpc {
             info!("send_dpc: Sending DPC for iface {}", &new.iface_name);
+            let record = AuditRecord::new(
+                "SetDPC",
+                &new.iface_name,
+                Some(old.summary()),
+                Some(new.summary()),
+            );
             let mut new_dpc = current_dpc.to_new_dpc_with_key("manual");
+            // the port may be gone while the dialog was open
+            let Some(port) = new_dpc.get_port_by_name_mut(&new.iface_name) else {
+                self.ui.message_box(
+                    "ERROR",
+                    &format!(
+                        "Interface {} is no longer in the network configuration. Nothing was changed",
+                        new.iface_name
+                    ),
+                );
+                return;
+            };
+            // checked by the dialog already
+            let proxy_config = if new.same_proxy(&old) {
+                None
+            } else {
+                match new.create_proxy_config(port.proxy_config.clone()) {
+                    Ok(config) => Some(config),
+                    Err(e) => {
+                        self.ui.message_box("ERROR", &e);
+                        return;
+                    }
+                }
+            };
             // there are 3 cases:
             // 1. iface is switched DHCP -> Static
             // 2. iface is switched Static -> DHCP
@@ -197,50 +1140,29 @@ impl Application {
             match (old.is_dhcp(), new.is_dhcp()) {
                 (false, true) => {
                     // case 2
-                    new_dpc
-                        .get_port_by_name_mut(&new.iface_name)
-                        .unwrap()
-                        .to_dhcp();
+                    port.to_dhcp();
                 }
                 (_, false) => {
-                    let ip: IpAddr = new.ipv4.parse().unwrap();
-                    let mask: IpAddr = new.mask.parse().unwrap();
-
-                    // parse DNS server string and convert to Option<Vec<IpAddr>>
-                    let dns_servers = new
-                        .dns
-                        .split(',')
-                        .map(|s| s.parse::<IpAddr>().ok())
-                        .flatten()
-                        .collect::<Vec<IpAddr>>();
-
-                    // same for NTP. NTP can now be either IP or FQDN
-                    let ntp_servers = new
-                        .ntp
-                        .split(',')
-                        .map(|s| s.to_string())
-                        .collect::<Vec<String>>();
-
+                    // checked by the dialog already
+                    let config = match new.static_config() {
+                        Ok(config) => config,
+                        Err(e) => {
+                            self.ui.message_box("ERROR", &e);
+                            return;
+                        }
+                    };
                     // case 1,3
-                    new_dpc
-                        .get_port_by_name_mut(&new.iface_name)
-                        .unwrap()
-                        .to_static(
-                            IpNet::with_netmask(ip, mask).unwrap(),
-                            new.gw.parse().unwrap(),
-                            new.domain,
-                            if ntp_servers.is_empty() {
-                                None
-                            } else {
-                                Some(ntp_servers)
-                            },
-                            if dns_servers.is_empty() {
-                                None
-                            } else {
-                                Some(dns_servers)
-                            },
-                        );
+                    port.to_static(
+                        config.addr_subnets,
+                        config.gateway.map(|gw| gw.to_string()).unwrap_or_default(),
+                        new.domain,
+                        Some(config.ntp).filter(|ntp| !ntp.is_empty()),
+                        Some(config.dns).filter(|dns| !dns.is_empty()),
+                        config.network_type,
+                    );
                 }
+                // only the proxy changed
+                (true, true) if proxy_config.is_some() => {}
                 (true, true) => {
                     // this may actually happen if we add support for DHCP with some static fields e.g. custom DNS
                     // log an error for now
@@ -249,163 +1171,16 @@ impl Application {
                     );
                 } // do nothing
             }
-            self.send_ipc_message(IpcMessage::new_request(Request::SetDPC(new_dpc)), |_| {});
-        }
-    }
-
-    fn create_kmsg_task(
-        &mut self,
-    ) -> (
-        JoinHandle<Result<()>>,
-        CancellationToken,
-        UnboundedReceiver<rmesg::entry::Entry>,
-    ) {
-        let cancel_token = CancellationToken::new();
-        let cancel_token_child = cancel_token.clone();
-        let (dmesg_tx, dmesg_rx) = mpsc::unbounded_channel::<rmesg::entry::Entry>();
-        let is_desktop = Application::is_desktop();
-
-        let kmsg_task: JoinHandle<Result<()>> = tokio::spawn(async move {
-            if is_desktop {
-                let mut index = 0;
-                while !cancel_token_child.is_cancelled() {
-                    let dummy_entry = rmesg::entry::Entry {
-                        level: Some(rmesg::entry::LogLevel::Info),
-                        message: format!("[INFO] {} Desktop mode: no kmsg", index),
-                        facility: None,
-                        sequence_num: None,
-                        timestamp_from_system_start: None,
-                    };
-
-                    index += 1;
-
-                    tokio::select! {
-                        _ = cancel_token_child.cancelled() => {
-                            info!("Kmsg task was cancelled");
-                            return Ok(());
-                        }
-                        _ = tokio::time::timeout(tokio::time::Duration::from_millis(200), cancel_token_child.cancelled() ) => {
-                            dmesg_tx.send(dummy_entry.clone()).unwrap();
-                        }
-                    }
-                }
-            } else {
-                //FIXME: this stream is buggy!!! it leaves a thread behind and tokio cannot gracefully shutdown
-                let mut st = rmesg::logs_stream(rmesg::Backend::Default, true, false).await?;
-
-                while !cancel_token_child.is_cancelled() {
-                    tokio::select! {
-                        _ = cancel_token_child.cancelled() => {
-                            info!("Kmsg task was cancelled");
-                            return Ok(());
-                        }
-                        log = st.next() => {
-                            trace!("Got log entry");
-                            match log {
-                                Some(Ok(log)) => {
-                                    dmesg_tx.send(log).unwrap();
-                                }
-                                Some(Err(e)) => {
-                                    warn!("Error reading kmsg: {:?}", e);
-                                }
-                                None => {
-                                    warn!("Kmsg stream ended");
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            info!("Kmsg stream ended");
-            Ok(())
-        });
-
-        (kmsg_task, cancel_token, dmesg_rx)
-    }
-
-    fn create_timer_task(
-        &self,
-        period: u64,
-    ) -> (JoinHandle<()>, CancellationToken, UnboundedReceiver<Event>) {
-        let (timer_tx, timer_rx) = mpsc::unbounded_channel::<Event>();
-        let cancellation_token = CancellationToken::new();
-        let cancellation_token_child = cancellation_token.clone();
-        let timer_task = tokio::spawn(async move {
-            while !cancellation_token_child.is_cancelled() {
-                tokio::select! {
-                    _ = tokio::time::timeout(tokio::time::Duration::from_millis(period), cancellation_token_child.cancelled() ) => {
-                        timer_tx.send(Event::Tick).unwrap();
-                    }
-                }
-            }
-        });
-
-        (timer_task, cancellation_token, timer_rx)
-    }
-
-    fn create_ipc_task(
-        &mut self,
-    ) -> (
-        JoinHandle<()>,
-        CancellationToken,
-        UnboundedReceiver<IpcMessage>,
-    ) {
-        let (ipc_tx, ipc_rx) = mpsc::unbounded_channel::<IpcMessage>();
-        let (ipc_cmd_tx, mut ipc_cmd_rx) = mpsc::unbounded_channel::<IpcMessage>();
-        let ipc_cancel_token = CancellationToken::new();
-        let ipc_cancel_token_clone = ipc_cancel_token.clone();
-        self.ipc_tx = Some(ipc_cmd_tx);
-
-        let ipc_task = tokio::spawn(async move {
-            ipc_tx.send(IpcMessage::Connecting).unwrap();
-
-            let socket_path = Application::get_socket_path();
-
-            info!("Connecting to IPC socket {} ", &socket_path);
-            let stream = IpcClient::connect(&socket_path).await.unwrap();
-            let (mut sink, mut stream) = stream.split();
-
-            ipc_tx.send(IpcMessage::Ready).unwrap();
-
-            while !ipc_cancel_token_clone.is_cancelled() {
-                let ipc_event = stream.next().fuse();
-
-                tokio::select! {
-                    _ = ipc_cancel_token_clone.cancelled() => {
-                        info!("IPC task was cancelled");
-                        return;
-                    }
-                    msg = ipc_cmd_rx.recv() => {
-                        match msg {
-                            Some(msg) => {
-                                sink.send(msg.into()).await.unwrap();
-                            }
-                            None => {
-                                warn!("IPC message stream ended");
-                                break;
-                            }
-                        }
-                    },
-                    msg = ipc_event => {
-                        match msg {
-                            Some(Ok(msg)) => {
-                                ipc_tx.send(IpcMessage::from(msg)).unwrap();
-                            }
-                            Some(Err(e)) => {
-                                warn!("Error reading IPC message: {:?}", e);
-                            }
-                            None => {
-                                warn!("IPC message stream ended");
-                                break;
-                            }
-                        }
-                    }
-                }
+            if let Some(proxy_config) = proxy_config {
+                port.proxy_config = proxy_config;
             }
-        });
-
-        (ipc_task, ipc_cancel_token, ipc_rx)
+            self.audited_ipc_call(
+                record,
+                |ipc| ipc.set_dpc(new_dpc),
+                |_| {},
+                |app, e| app.report_request_error("SetDPC", e),
+            );
+        }
     }
 
     fn create_terminal_task(&mut self) -> (JoinHandle<()>, CancellationToken) {
@@ -413,6 +1188,7 @@ impl Application {
         let terminal_tx_clone = self.terminal_tx.clone();
         let terminal_cancel_token = CancellationToken::new();
         let terminal_cancel_token_child = terminal_cancel_token.clone();
+        let console_handed_off = self.console_handed_off.clone();
         let terminal_task = tokio::spawn(async move {
             loop {
                 let terminal_event = terminal_event_stream.next().fuse();
@@ -430,7 +1206,25 @@ impl Application {
                             Some(Ok(crossterm::event::Event::Resize(w, h))) => {
                                 terminal_tx_clone.send(Event::TerminalResize(w,h)).unwrap();
                             }
+                            Some(Ok(crossterm::event::Event::Paste(text))) => {
+                                terminal_tx_clone.send(Event::Paste(text)).unwrap();
+                            }
+                            // every pointer move would cause a redraw
+                            Some(Ok(crossterm::event::Event::Mouse(mouse)))
+                                if matches!(
+                                    mouse.kind,
+                                    crossterm::event::MouseEventKind::Down(_)
+                                        | crossterm::event::MouseEventKind::ScrollUp
+                                        | crossterm::event::MouseEventKind::ScrollDown
+                                ) =>
+                            {
+                                terminal_tx_clone.send(Event::Mouse(mouse)).unwrap();
+                            }
                             Some(Ok(_)) => {}
+                            // errors are expected while a guest owns the console
+                            Some(Err(e)) if console_handed_off.load(Ordering::Relaxed) => {
+                                debug!("Error reading terminal event during console handoff: {:?}", e);
+                            }
                             Some(Err(e)) => {
                                 warn!("Error reading terminal event: {:?}", e);
                             }
@@ -447,62 +1241,119 @@ impl Application {
     }
 
     pub async fn run(&mut self) -> Result<()> {
-        let (ipc_task, ipc_cancellation_token, mut ipc_rx) = self.create_ipc_task();
+        let replaying = self.replay.is_some();
+        match self.replay.take() {
+            Some(Replay::Snapshot(snapshot)) => self.replay_snapshot(snapshot),
+            Some(Replay::Recording(messages)) => {
+                info!("Playing back {} recorded IPC messages", messages.len());
+                self.model.borrow_mut().ipc_state = IpcConnectionState::Replay;
+                spawn_playback(messages, self.playback_tx.clone());
+            }
+            None => self.ipc.start(),
+        }
 
         // TODO: handle suspend/resume for the case when we give away /dev/tty
         // because we passed through the GPU to a guest VM
         let (terminal_task, terminal_cancel_token) = self.create_terminal_task();
 
         // spawn a timer to send tick events
-        let (timer_task, timer_cancellation_token, mut timer_rx) = self.create_timer_task(500);
+        self.timer.start();
 
         // start a task to fetch kernel messages using rmesg
-        let (kmsg_task, kmsg_cancellation_token, mut dmesg_rx) = self.create_kmsg_task();
+        let settings = self.model.borrow().settings.clone();
+        #[cfg(feature = "dmesg")]
+        if settings.kmsg {
+            self.kmsg.start();
+        }
+
+        // link and address changes are picked up faster than EVE reports them.
+        // The links of this host have nothing to do with a replayed snapshot
+        if settings.netlink && !replaying {
+            self.netlink.start();
+        }
+        if settings.netstats {
+            self.netstats.start();
+        }
+        if settings.metrics {
+            self.metrics.start();
+        }
+        if settings.sensors {
+            self.sensors.start();
+        }
+        // the pools of this host are not the ones of a replayed snapshot
+        if !replaying {
+            self.zpool.start();
+        }
+        self.update_collectors_status();
 
         // send initial redraw event
         self.invalidate();
 
-        #[allow(unused_assignments)]
-        let mut do_redraw = true;
+        // user input is drawn immediately, data-driven updates are coalesced
+        // and drawn at most once per FRAME_BUDGET
+        let mut redraw_pending = false;
+        let mut last_draw = Instant::now() - FRAME_BUDGET;
         let app_cancel_token = CancellationToken::new();
 
         // listen on the action channel and terminal channel
         while !app_cancel_token.is_cancelled() {
-            // TODO: set to true by default to make life easier for now
-            // Set to false in an action handler if it occurs often and doesn't require a redraw
-            do_redraw = true;
+            let mut draw_now = false;
 
             tokio::select! {
+                // poll user input before data updates
+                biased;
+
                 _ = app_cancel_token.cancelled() => {
                     info!("Application cancelled");
                     break;
                 }
-                tick = timer_rx.recv() => {
-                    match tick {
-                        Some(event) => {
-                            let action = self.ui.handle_event(event);
-                            if let Some(action) = action {
-                                trace!("Event loop got action on tick: {:?}", action);
-                            }
-                        }
-                        None => {
-                            warn!("Timer stream ended");
-                            break;
-                        }
-                    }
-                }
                 event = self.terminal_rx.recv() => {
                     match event {
+                        // the lock screen takes the keys, nothing reaches the pages
+                        Some(Event::Key(key)) if self.lock.is_locked() => {
+                            self.idle.touch();
+                            self.handle_lock_key(key);
+                            draw_now = true;
+                        }
+                        Some(Event::Paste(_)) | Some(Event::Mouse(_)) if self.lock.is_locked() => {}
+                        Some(Event::Key(key)) if self.model.borrow().attached_console.is_some() => {
+                            self.touch();
+                            self.handle_console_key(key);
+                        }
                         Some(Event::Key(key)) => {
+                            self.touch();
                             let action = self.ui.handle_event(Event::Key(key));
                             if let Some(action) = action {
                                 info!("Event loop got action: {:?}", action);
 
                                 self.handle_action(action);
                             }
+                            draw_now = true;
                          }
+                        Some(Event::Paste(text)) if self.model.borrow().attached_console.is_some() => {
+                            self.touch();
+                            self.send_console_input(text.into_bytes());
+                        }
+                        Some(Event::Paste(text)) => {
+                            self.touch();
+                            if let Some(action) = self.ui.handle_event(Event::Paste(text)) {
+                                self.handle_action(action);
+                            }
+                            draw_now = true;
+                        }
+                        // the guest owns the screen
+                        Some(Event::Mouse(_)) if self.model.borrow().attached_console.is_some() => {}
+                        Some(Event::Mouse(mouse)) => {
+                            self.touch();
+                            if let Some(action) = self.ui.handle_event(Event::Mouse(mouse)) {
+                                self.handle_action(action);
+                            }
+                            draw_now = true;
+                        }
                         Some(Event::TerminalResize(w, h)) => {
                             info!("Terminal resized: {}x{}", w, h);
+                            self.ui.handle_event(Event::TerminalResize(w, h));
+                            draw_now = true;
                         }
                         None => {
                             warn!("Terminal event stream ended");
@@ -512,19 +1363,6 @@ impl Application {
                     }
 
                 }
-                ipc_event = ipc_rx.recv() => {
-                    match ipc_event {
-                        Some(msg) => {
-                            // handle IPC message
-                            info!("IPC message: {:?}", msg);
-                            self.handle_ipc_message(msg);
-                        }
-                        None => {
-                            warn!("IPC message stream ended");
-                            break;
-                        }
-                    }
-                }
                 action = self.action_rx.recv() => {
                     match action {
                         Some(action) => {
@@ -533,8 +1371,13 @@ impl Application {
                                 UiActions::Quit => {
                                     app_cancel_token.cancel();
                                 }
+                                // dialogs may close themselves on timer
+                                UiActions::DismissDialog => {
+                                    self.ui.pop_layer();
+                                }
                                 _ => {}
                             }
+                            draw_now = true;
                         }
                         None => {
                             warn!("Action stream ended");
@@ -542,49 +1385,511 @@ impl Application {
                         }
                     }
                 }
-                dmesg = dmesg_rx.recv() => {
-                    match dmesg {
-                        Some(entry) => {
-                            // fetch all entries from the stream
-                            self.model.borrow_mut().dmesg.push(entry);
-                            while let Ok(entry) = dmesg_rx.try_recv() {
-                                self.model.borrow_mut().dmesg.push(entry);
+                diagnostics = self.diagnostics_rx.recv() => {
+                    // we keep a sender so the stream never ends
+                    if let Some(event) = diagnostics {
+                        if let Some(report) = self.model.borrow_mut().diagnostics.as_mut() {
+                            report.update(event.step, event.state);
+                        }
+                        redraw_pending = true;
+                    }
+                }
+                bundle = self.bundle_rx.recv() => {
+                    // we keep a sender so the stream never ends
+                    if let Some(event) = bundle {
+                        self.handle_bundle_event(event);
+                        draw_now = true;
+                    }
+                }
+                file_io = self.file_io_rx.recv() => {
+                    // we keep a sender so the stream never ends
+                    if let Some(event) = file_io {
+                        self.handle_file_io_event(event);
+                        draw_now = true;
+                    }
+                }
+                playback = self.playback_rx.recv() => {
+                    // we keep a sender so the stream never ends
+                    if let Some(msg) = playback {
+                        self.handle_ipc_message(msg);
+                        redraw_pending = true;
+                    }
+                }
+                // collectors that stopped never resolve recv(). Their health is shown on the Debug page
+                ipc_event = self.ipc.recv() => {
+                    match ipc_event {
+                        Some(msg) => {
+                            // handle IPC message
+                            trace!("IPC message: {:?}", msg);
+                            if let Some(recorder) = &self.recorder {
+                                recorder.record(&msg);
                             }
+                            self.handle_ipc_message(msg);
                         }
                         None => {
-                            warn!("Dmesg stream ended");
-                            break;
+                            // the health is set when the task is done
+                            self.ipc.task_mut().join().await;
+                            self.handle_ipc_task_ended();
                         }
                     }
+                    redraw_pending = true;
                 }
-
+                dmesg = recv_dmesg!(self) => {
+                    self.handle_dmesg(dmesg);
+                    redraw_pending = true;
+                }
+                netlink_event = self.netlink.recv() => {
+                    match netlink_event {
+                        Some(event) => self.model.borrow_mut().apply_netlink_event(event),
+                        None => {
+                            warn!("Netlink stream ended");
+                            self.update_collectors_status();
+                        }
+                    }
+                    redraw_pending = true;
+                }
+                reply = self.reply_rx.recv() => {
+                    // we keep a sender so the stream never ends
+                    if let Some((id, reply)) = reply {
+                        self.handle_reply(id, reply);
+                        redraw_pending = true;
+                    }
+                }
+                sample = self.netstats.recv() => {
+                    match sample {
+                        Some(sample) => self.model.borrow_mut().traffic.update(sample),
+                        None => {
+                            warn!("Netstats stream ended");
+                            self.update_collectors_status();
+                        }
+                    }
+                    redraw_pending = true;
+                }
+                sample = self.metrics.recv() => {
+                    match sample {
+                        Some(sample) => self.model.borrow_mut().resources.update(sample),
+                        None => {
+                            warn!("Metrics stream ended");
+                            self.update_collectors_status();
+                        }
+                    }
+                    redraw_pending = true;
+                }
+                pools = self.zpool.recv() => {
+                    match pools {
+                        Some(pools) => {
+                            self.model.borrow_mut().update_zfs_pools(pools);
+                            // EVE reports the pools itself, zpool is not needed anymore
+                            if self.model.borrow().zfs.source == Some(ZfsSource::Ipc) {
+                                self.zpool.stop();
+                            }
+                        }
+                        None => {
+                            warn!("Zpool stream ended");
+                            self.update_collectors_status();
+                        }
+                    }
+                    redraw_pending = true;
+                }
+                readings = self.sensors.recv() => {
+                    match readings {
+                        Some(readings) => self.model.borrow_mut().update_sensors(readings),
+                        None => {
+                            warn!("Sensors stream ended");
+                            self.update_collectors_status();
+                        }
+                    }
+                    redraw_pending = true;
+                }
+                tick = self.timer.recv() => {
+                    match tick {
+                        Some(event) => {
+                            self.idle.check();
+                            self.check_lock();
+                            self.update_collectors_status();
+                            self.model.borrow_mut().refresh_config_partition();
+                            self.model.borrow_mut().record_reachability(Instant::now().into_std());
+                            self.model.borrow_mut().evict_stale(Instant::now().into_std());
+                            self.model.borrow_mut().ipc_message_stats = self.ipc.message_stats();
+                            self.restart_failed_ipc();
+                            let action = self.ui.handle_event(event);
+                            if let Some(action) = action {
+                                trace!("Event loop got action on tick: {:?}", action);
+                            }
+                        }
+                        None => {
+                            warn!("Timer stream ended");
+                            self.update_collectors_status();
+                        }
+                    }
+                    redraw_pending = true;
+                }
+                _ = tokio::time::sleep_until(last_draw + FRAME_BUDGET), if redraw_pending => {}
             }
-            if do_redraw {
+
+            if draw_now || (redraw_pending && last_draw.elapsed() >= FRAME_BUDGET) {
                 trace!("Redraw requested");
+                redraw_pending = false;
                 self.draw_ui(self.model.clone())?;
+                last_draw = Instant::now();
             }
         }
         info!("Cancelling tasks");
-        timer_cancellation_token.cancel();
-        kmsg_cancellation_token.cancel();
+        self.timer.stop();
+        #[cfg(feature = "dmesg")]
+        self.kmsg.stop();
+        self.netlink.stop();
+        self.netstats.stop();
+        self.metrics.stop();
+        self.sensors.stop();
+        self.zpool.stop();
         terminal_cancel_token.cancel();
-        ipc_cancellation_token.cancel();
+        self.ipc.stop();
         info!("Waiting for tasks to finish");
-        let _ = kmsg_task.await;
-        info!("Kmsg task ended");
+        #[cfg(feature = "dmesg")]
+        {
+            self.kmsg.task_mut().join().await;
+            info!("Kmsg task ended");
+        }
+        self.netlink.task_mut().join().await;
+        info!("Netlink task ended");
         terminal_task.await?;
         info!("Terminal task ended");
-        //TODO: rewrite the task so we can cancel it
-        ipc_task.abort();
-        _ = ipc_task.await;
+        self.ipc.task_mut().join().await;
         info!("IPC task ended");
-        timer_task.await?;
+        self.timer.task_mut().join().await;
         info!("Timer task ended");
         info!("run() ended");
 
         Ok(())
     }
 
+    #[cfg(feature = "dmesg")]
+    fn handle_dmesg(&mut self, dmesg: Option<DmesgEntry>) {
+        match dmesg {
+            Some(entry) => {
+                // fetch all entries from the stream
+                self.push_dmesg(entry);
+                while let Some(entry) = self.kmsg.task_mut().try_recv() {
+                    self.push_dmesg(entry);
+                }
+            }
+            None => {
+                warn!("Dmesg stream ended");
+                self.update_collectors_status();
+            }
+        }
+    }
+
+    #[cfg(not(feature = "dmesg"))]
+    fn handle_dmesg(&mut self, dmesg: Option<DmesgEntry>) {
+        if let Some(entry) = dmesg {
+            match entry {}
+        }
+    }
+
+    #[cfg(feature = "dmesg")]
+    fn push_dmesg(&mut self, entry: DmesgEntry) {
+        let alert = self
+            .dmesg_alerts
+            .check(&entry.message)
+            .map(|(name, severity)| (format!("{}: {}", name, entry.message.trim()), severity));
+        let mut model = self.model.borrow_mut();
+        let seq = model.push_dmesg(entry);
+        if let Some((text, severity)) = alert {
+            model.raise_alert(severity, "dmesg", text, Some(AlertLink::Dmesg(seq)));
+        }
+    }
+
+    // certificates are loaded from the blob store only when the user asks for them
+    fn show_proxy_certs(&mut self, iface: &str) {
+        let model = self.model.borrow();
+        let certs = model.proxy_cert_refs(iface);
+        if certs.is_empty() {
+            return;
+        }
+        let text = certs
+            .iter()
+            .map(|blob| {
+                let cert = model
+                    .blobs
+                    .load(blob)
+                    .and_then(|pem| CertInfo::from_pem(&String::from_utf8_lossy(&pem)));
+                match cert {
+                    Some(cert) => format!(
+                        "{}, expires {}\nSHA-256 {}",
+                        cert.subject.as_deref().unwrap_or("<no CN>"),
+                        cert.not_after
+                            .map_or("N/A".to_string(), |t| t.format("%Y-%m-%d").to_string()),
+                        cert.fingerprint
+                    ),
+                    None => format!("Cannot load certificate {} bytes", blob.size),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        drop(model);
+        self.ui
+            .message_box(&format!("{} proxy certificates", iface), &text);
+    }
+
+    // the file is small, read it right away like grub.cfg
+    fn load_proxy_cert(&mut self, path: &Path) {
+        let name = path
+            .file_name()
+            .map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        let pems = match std::fs::metadata(path) {
+            Ok(meta) if meta.len() > MAX_CERT_FILE_SIZE => Err(format!(
+                "The file is larger than {} KiB",
+                MAX_CERT_FILE_SIZE / 1024
+            )),
+            Ok(_) => std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|data| certificates_to_pem(&data)),
+            Err(e) => Err(e.to_string()),
+        };
+        match pems {
+            Ok(pems) => {
+                info!(
+                    "Loaded {} proxy certificate(s) from {}",
+                    pems.len(),
+                    path.display()
+                );
+                self.ui
+                    .send_to_top_layer("file browser", UiActions::ProxyCertLoaded { name, pems });
+            }
+            Err(e) => {
+                warn!("Cannot load proxy certificate {}: {}", path.display(), e);
+                self.ui
+                    .message_box("ERROR", &format!("Cannot load {}: {}", name, e));
+            }
+        }
+    }
+
+    fn update_collectors_status(&mut self) {
+        let mut collectors = vec![self.ipc.status()];
+        #[cfg(feature = "dmesg")]
+        collectors.push(self.kmsg.status());
+        collectors.extend([
+            self.netlink.status(),
+            self.netstats.status(),
+            self.metrics.status(),
+            self.sensors.status(),
+            self.zpool.status(),
+            self.timer.status(),
+        ]);
+        self.model.borrow_mut().collectors = collectors;
+    }
+
+    // the IPC task ended while the UI is running. Nothing updates the model
+    // anymore, so tell the user, keep what went wrong and try to recover
+    fn handle_ipc_task_ended(&mut self) {
+        let status = self.ipc.status();
+        let error = match status.health {
+            CollectorHealth::Failed(e) => e,
+            health => format!("IPC task ended unexpectedly ({})", health),
+        };
+        let crash = IpcCrash {
+            error,
+            at: chrono::Local::now(),
+            state: format!("{:?}", self.model.borrow().ipc_state),
+            pending_requests: self.pending_requests.len(),
+            messages: status.events,
+            restarts: status.restarts,
+        };
+        MonitorError::Ipc(anyhow!("{}", crash)).log();
+
+        self.drop_pending_requests();
+        self.detach_console(Some("connection to EVE lost"));
+        let delay = task_restart_delay(status.restarts);
+        {
+            let mut model = self.model.borrow_mut();
+            model.ipc_state = IpcConnectionState::Failed {
+                restart_at: delay.map(|d| Instant::now().into_std() + d),
+            };
+            model.raise_alert(
+                AlertSeverity::Critical,
+                "ipc",
+                format!("Connection to EVE failed: {}", crash.error),
+                None,
+            );
+        }
+        match delay {
+            Some(delay) => info!("Restarting the IPC task in {:?}", delay),
+            None => self.ui.message_box(
+                "ERROR",
+                &format!(
+                    "The connection to EVE failed {} times, the data shown is outdated. Last error: {}",
+                    status.restarts + 1,
+                    crash.error
+                ),
+            ),
+        }
+        self.ipc_crash = Some(crash);
+        self.update_collectors_status();
+    }
+
+    // apply what changed right away and save all settings
+    fn change_settings(&mut self, settings: RuntimeSettings) {
+        let old = self.model.borrow().settings.clone();
+        if settings.log_level != old.log_level {
+            info!("Log level changed to {}", settings.log_level);
+            log::set_max_level(settings.log_level);
+        }
+        if settings.tick_ms != old.tick_ms {
+            self.timer
+                .set_period(Duration::from_millis(settings.tick_ms));
+            self.timer.restart();
+            // rates are sampled with the refresh interval
+            self.netstats
+                .set_period(Duration::from_millis(settings.tick_ms));
+            if settings.netstats {
+                self.netstats.restart();
+            }
+        }
+        if settings.idle_timeout != old.idle_timeout {
+            self.idle.set_timeout(settings.idle_duration());
+        }
+        if settings.theme != old.theme {
+            self.model.borrow_mut().theme = Theme::new(settings.theme, &self.theme_colors);
+        }
+        if settings.key_chords != old.key_chords {
+            self.ui.set_key_chords(settings.key_chords);
+        }
+        #[cfg(feature = "dmesg")]
+        if settings.kmsg != old.kmsg {
+            if settings.kmsg {
+                self.kmsg.start();
+            } else {
+                self.kmsg.stop();
+            }
+        }
+        if settings.netlink != old.netlink {
+            if settings.netlink {
+                self.netlink.start();
+            } else {
+                self.netlink.stop();
+            }
+        }
+        if settings.netstats != old.netstats {
+            if settings.netstats {
+                self.netstats.start();
+            } else {
+                self.netstats.stop();
+            }
+        }
+        if settings.metrics != old.metrics {
+            if settings.metrics {
+                self.metrics.start();
+            } else {
+                self.metrics.stop();
+            }
+        }
+        if settings.sensors != old.sensors {
+            if settings.sensors {
+                self.sensors.start();
+            } else {
+                self.sensors.stop();
+            }
+        }
+        self.update_collectors_status();
+
+        match MonitorConfig::with_settings(&settings) {
+            Ok((path, data)) => self.write_file(path, data),
+            Err(e) => {
+                e.log();
+                self.ui.message_box("ERROR", &e.user_message());
+            }
+        }
+        self.model.borrow_mut().settings = settings;
+    }
+
+    fn replay_snapshot(&mut self, snapshot: ModelSnapshot) {
+        info!(
+            "Replaying a model snapshot taken {} by monitor {}",
+            snapshot.taken, snapshot.monitor_version
+        );
+        for msg in snapshot.ipc_messages() {
+            self.handle_ipc_message(msg);
+        }
+        self.model.borrow_mut().ipc_state = IpcConnectionState::Replay;
+    }
+
+    fn save_model_snapshot(&mut self) {
+        let snapshot = self.model.borrow().state_messages.snapshot();
+        match snapshot.to_json() {
+            Ok(json) => self.write_file(snapshot_file(), json.into_bytes()),
+            Err(e) => self
+                .ui
+                .message_box("ERROR", &format!("Cannot save the model snapshot: {}", e)),
+        }
+    }
+
+    fn restart_failed_ipc(&mut self) {
+        let restart_at = match self.model.borrow().ipc_state {
+            IpcConnectionState::Failed {
+                restart_at: Some(restart_at),
+            } => restart_at,
+            _ => return,
+        };
+        if restart_at <= Instant::now().into_std() {
+            self.restart_collector("ipc");
+        }
+    }
+
+    // requests sent over the old connection will never be answered
+    fn drop_pending_requests(&mut self) {
+        if !self.pending_requests.is_empty() {
+            warn!(
+                "Dropping {} requests sent over the lost IPC connection",
+                self.pending_requests.len()
+            );
+        }
+        self.pending_requests.clear();
+        self.model.borrow_mut().pending_requests = 0;
+    }
+
+    fn restart_collector(&mut self, name: &str) {
+        info!("Restarting collector {}", name);
+        match name {
+            "ipc" => {
+                self.drop_pending_requests();
+                self.ipc.restart();
+            }
+            #[cfg(feature = "dmesg")]
+            "kmsg" => self.kmsg.restart(),
+            "netlink" => self.netlink.restart(),
+            "netstats" => self.netstats.restart(),
+            "metrics" => self.metrics.restart(),
+            "sensors" => self.sensors.restart(),
+            "zpool" => self.zpool.restart(),
+            "timer" => self.timer.restart(),
+            _ => warn!("Unknown collector {}", name),
+        }
+        self.update_collectors_status();
+    }
+
+    // a key press or the mouse, postpones idle mode and the lock
+    fn touch(&mut self) {
+        self.idle.touch();
+        self.lock.touch();
+    }
+
+    fn check_lock(&mut self) {
+        let now = Instant::now().into_std();
+        // keys go to the guest, they do not count as activity
+        if self.model.borrow().attached_console.is_none() {
+            self.lock.check(now);
+        }
+        self.model.borrow_mut().lock_screen = self.lock.screen(now);
+    }
+
+    fn handle_lock_key(&mut self, key: crossterm::event::KeyEvent) {
+        let now = Instant::now().into_std();
+        self.lock.handle_key(key, now);
+        self.model.borrow_mut().lock_screen = self.lock.screen(now);
+    }
+
     fn invalidate(&mut self) {
         self.action_tx
             .send(Action::new("app", UiActions::Redraw))
@@ -592,12 +1897,53 @@ impl Application {
     }
 
     fn draw_ui(&mut self, model: Rc<Model>) -> Result<()> {
-        self.ui.draw(model);
+        let start = Instant::now();
+        let handoff_state = model.borrow().console_handoff.as_ref().map(|h| h.state);
+        // do not fight with the guest for the console
+        if handoff_state == Some(ConsoleHandoffState::Active)
+            || model.borrow().attached_console.is_some()
+        {
+            trace!("Console is owned by a guest, skipping frame");
+            return Ok(());
+        }
+        if let Err(e) = self.ui.draw(model) {
+            if handoff_state == Some(ConsoleHandoffState::Pending) {
+                debug!("Draw failed during console handoff: {}", e);
+            } else {
+                e.log();
+            }
+        }
+        let elapsed = start.elapsed();
+        if elapsed > FRAME_BUDGET {
+            debug!("Slow frame: rendered in {:?}", elapsed);
+        } else {
+            trace!("Frame rendered in {:?}", elapsed);
+        }
         Ok(())
     }
 
     fn handle_action(&mut self, action: Action) {
+        let verdict = self
+            .action_bus
+            .dispatch(&action, &mut self.model.borrow_mut());
+        match verdict {
+            Verdict::Pass => self.perform_action(action),
+            Verdict::Block(reason) => self.ui.message_box("Not allowed", &reason),
+            Verdict::Confirm { title, message } => self.ui.confirm_dialog(
+                &title,
+                &message,
+                UiActions::Confirmed(Box::new(action.action)),
+            ),
+        }
+    }
+
+    fn perform_action(&mut self, action: Action) {
         match action.action {
+            UiActions::Confirmed(confirmed) => {
+                // close the confirmation dialog
+                self.ui.pop_layer();
+                self.perform_action(Action::new(action.source, *confirmed));
+            }
             UiActions::EditIfaceConfig(iface) => {
                 // get interface info by name
                 let iface_data = self
@@ -628,6 +1974,124 @@ impl Application {
                     self.ui.show_server_url_dialog(&url);
                 }
             }
+            #[cfg(feature = "dmesg")]
+            UiActions::SaveDmesg => {
+                self.save_dmesg();
+            }
+            UiActions::RestartCollector(name) => {
+                self.restart_collector(&name);
+            }
+            UiActions::SupportBundle => {
+                self.request_support_bundle();
+            }
+            UiActions::ShowProxyCerts(iface) => {
+                self.show_proxy_certs(&iface);
+            }
+            UiActions::BrowseProxyCert => self
+                .ui
+                .show_file_browser(&["pem", "crt", "cer"], UiActions::ProxyCertFile),
+            UiActions::ProxyCertFile(path) => {
+                // back to the IP dialog
+                self.ui.pop_layer();
+                self.load_proxy_cert(&path);
+            }
+            UiActions::ShowDpcHistory => self.ui.show_dpc_history(),
+            #[cfg(feature = "efi")]
+            UiActions::ShowEfiVariables => self.ui.show_efi_variables(),
+            #[cfg(feature = "efi")]
+            UiActions::EditBootOrder => self.ui.show_boot_order_editor(),
+            UiActions::Diagnose(iface) => self.start_diagnostics(&iface),
+            UiActions::CreateBundle => self.start_bundle(),
+            UiActions::CloseBundleDialog => {
+                // the bundler keeps running, the result is shown in a message box
+                self.model.borrow_mut().bundle = None;
+                self.ui.pop_layer();
+            }
+            UiActions::CloseDiagnostics => {
+                self.stop_diagnostics();
+                self.model.borrow_mut().diagnostics = None;
+                self.ui.pop_layer();
+            }
+            UiActions::RollbackDpc(key, time_priority) => self.rollback_dpc(&key, &time_priority),
+            UiActions::RetryAttestation => {
+                self.request_attestation_retry();
+            }
+            UiActions::ViewAppDetails(uuid) => self.ui.show_app_details(uuid),
+            UiActions::AttachConsole(uuid) => self.attach_console(uuid),
+            UiActions::AppCommand(command, uuid) => self.confirm_app_command(command, uuid),
+            UiActions::SummarySettings => self.ui.show_summary_settings(),
+            UiActions::SetSummaryPanels(panels) => {
+                self.ui.pop_layer();
+                match MonitorConfig::with_summary_panels(&panels) {
+                    Ok((path, data)) => self.write_file(path, data),
+                    Err(e) => {
+                        e.log();
+                        self.ui.message_box("ERROR", &e.user_message());
+                    }
+                }
+                self.ui.set_summary_panels(panels);
+            }
+            UiActions::ChangeSettings(settings) => self.change_settings(settings),
+            UiActions::TakeSnapshot(name) => {
+                self.ui.pop_layer();
+                let snapshot =
+                    Snapshot::capture(&name, &self.model.borrow(), Instant::now().into_std());
+                info!("Snapshot '{}' taken", name);
+                self.model.borrow_mut().snapshot = Some(snapshot);
+                self.ui.message_box(
+                    "Snapshot",
+                    &format!(
+                        "Snapshot '{}' taken. Press Alt+d to compare the current state with it",
+                        name
+                    ),
+                );
+            }
+            UiActions::ViewAppLogs(uuid) => self.open_app_logs(uuid),
+            UiActions::CloseAppLogs => {
+                self.ui.pop_layer();
+                let logs = self.model.borrow_mut().app_logs.take();
+                if let Some(logs) = logs {
+                    self.ipc_call("StopAppLogs", |ipc| ipc.stop_app_logs(logs.uuid), |_| {});
+                }
+            }
+            UiActions::AcknowledgeAlerts(id) => self.model.borrow_mut().acknowledge_alerts(id),
+            UiActions::DismissAlerts(id) => self.model.borrow_mut().dismiss_alerts(id),
+            UiActions::SaveModelSnapshot => self.save_model_snapshot(),
+            UiActions::ShowMonitorLog => {
+                let capacity = self.model.borrow().limits.monitor_log;
+                self.ui.show_monitor_log(capacity);
+            }
+            UiActions::ViewUnknownValues => {
+                let (content, highlight) = unknown_values_text(&self.model.borrow());
+                self.ui
+                    .show_file_viewer("Values not understood", &content, highlight);
+            }
+            UiActions::ViewGrubCfg => {
+                let grub_cfg = self.model.borrow().config_partition.read_grub_cfg();
+                match grub_cfg {
+                    Ok((content, changed)) => self.ui.show_file_viewer(GRUB_CFG, &content, changed),
+                    Err(e) => self
+                        .ui
+                        .message_box("ERROR", &format!("Cannot read {}: {}", GRUB_CFG, e)),
+                }
+            }
+            UiActions::ExportView(format) => {
+                let export = self.ui.export_current_view(&self.model.borrow());
+                match export {
+                    Some(export) => {
+                        info!("Exporting {} view as {:?}", export.name, format);
+                        self.write_file(export.file_path(format), export.serialize(format));
+                    }
+                    None => self
+                        .ui
+                        .message_box("Export", "This page cannot be exported"),
+                }
+            }
+            UiActions::ExportLines(export) => {
+                info!("Exporting {} selected lines", export.rows.len());
+                let format = ExportFormat::Text;
+                self.write_file(export.file_path(format), export.serialize(format));
+            }
             UiActions::AppAction(app_action) => match app_action {
                 MonActions::NetworkInterfaceUpdated(old, new) => {
                     debug!("Setting DPC for {}", &old.iface_name);
@@ -642,11 +2106,68 @@ impl Application {
                 }
                 MonActions::ServerUpdated(url) => {
                     debug!("Setting server URL to: {}", &url);
-                    self.send_ipc_message(
-                        IpcMessage::new_request(Request::SetServer(url.clone())),
+                    let new_url = url.clone();
+                    let record = AuditRecord::new(
+                        "SetServer",
+                        "controller",
+                        self.model.borrow().node_status.server.clone(),
+                        Some(url.clone()),
+                    );
+                    self.audited_ipc_call(
+                        record,
+                        |ipc| ipc.set_server(new_url),
                         move |app| {
                             app.model.borrow_mut().node_status.server = Some(url.clone());
                         },
+                        |app, e| app.report_request_error("SetServer", e),
+                    );
+                    self.ui.pop_layer();
+                }
+                MonActions::UploadSupportBundle(path) => {
+                    info!("Uploading support bundle {}", &path);
+                    self.ipc_call(
+                        "UploadSupportBundle",
+                        |ipc| ipc.upload_support_bundle(path),
+                        |_| {},
+                    );
+                    self.ui.pop_layer();
+                }
+                MonActions::RetryAttestation => {
+                    info!("Requesting attestation retry");
+                    self.ipc_call(
+                        "RetryAttestation",
+                        |ipc| ipc.retry_attestation(),
+                        |app| app.model.borrow_mut().attestation.retry_requested(),
+                    );
+                    self.ui.pop_layer();
+                }
+                MonActions::AppCommand(command, uuid) => {
+                    self.ui.pop_layer();
+                    self.send_app_command(command, uuid);
+                }
+                #[cfg(feature = "efi")]
+                MonActions::SetBootOrder { update, previous } => {
+                    info!("Setting boot order, inactive entries {:?}", update.inactive);
+                    let mut new = boot_order_text(&parse_boot_order(&update.boot_order));
+                    if !update.inactive.is_empty() {
+                        new.push_str(&format!(", inactive {}", boot_order_text(&update.inactive)));
+                    }
+                    let record = AuditRecord::new(
+                        "SetBootOrder",
+                        "EFI",
+                        Some(boot_order_text(&previous)),
+                        Some(new),
+                    );
+                    self.audited_ipc_call(
+                        record,
+                        |ipc| ipc.set_boot_order(update),
+                        |app| {
+                            app.ui.message_box(
+                                "Boot order",
+                                "EVE wrote the new boot order, it is used on the next boot",
+                            )
+                        },
+                        |app, e| app.report_request_error("SetBootOrder", e),
                     );
                     self.ui.pop_layer();
                 }
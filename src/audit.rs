@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{debug, error};
+use tokio::{fs::OpenOptions, io::AsyncWriteExt, sync::mpsc::UnboundedReceiver, task::JoinHandle};
+
+use crate::model::audit::AuditRecord;
+
+// the file is renamed to audit.log.1 when it reaches this size
+const MAX_SIZE: u64 = 256 * 1024;
+// rotated files kept besides the current one
+const ROTATE_COUNT: usize = 2;
+
+/// The audit file next to the log sessions, /persist/monitor/audit.log on EVE.
+/// None if logging is not initialized
+pub fn audit_file() -> Option<PathBuf> {
+    let session = PathBuf::from(std::env::var("EVE_MONITOR_LOG_DIR").ok()?);
+    Some(session.parent()?.parent()?.join("audit.log"))
+}
+
+// audit.log.1 is the newest rotated file
+fn rotated(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+async fn rotate(path: &Path) -> Result<()> {
+    for index in (1..ROTATE_COUNT).rev() {
+        let from = rotated(path, index);
+        if tokio::fs::try_exists(&from).await.unwrap_or(false) {
+            tokio::fs::rename(&from, rotated(path, index + 1))
+                .await
+                .with_context(|| format!("Cannot rotate {}", from.display()))?;
+        }
+    }
+    tokio::fs::rename(path, rotated(path, 1))
+        .await
+        .with_context(|| format!("Cannot rotate {}", path.display()))
+}
+
+async fn append_record(path: &Path, record: &AuditRecord) -> Result<()> {
+    let size = tokio::fs::metadata(path).await.map_or(0, |m| m.len());
+    if size >= MAX_SIZE {
+        rotate(path).await?;
+    }
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Cannot create directory {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Cannot open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .await
+        .with_context(|| format!("Cannot write to {}", path.display()))?;
+    file.flush()
+        .await
+        .with_context(|| format!("Cannot flush {}", path.display()))
+}
+
+/// Append the records sent over `records_rx` to the audit file at `path`.
+/// The task ends when all senders are gone
+pub fn spawn_audit_writer(
+    path: PathBuf,
+    mut records_rx: UnboundedReceiver<AuditRecord>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(record) = records_rx.recv().await {
+            debug!("Audit: {} {}", record.action, record.target);
+            if let Err(e) = append_record(&path, &record).await {
+                error!("Cannot write the audit record: {:#}", e);
+            }
+        }
+    })
+}
+
+/// Records of the audit file and the rotated ones, oldest first. Lines that
+/// cannot be parsed are skipped
+pub fn read_audit_records(path: &Path) -> Vec<AuditRecord> {
+    let mut files: Vec<PathBuf> = (1..=ROTATE_COUNT)
+        .rev()
+        .map(|index| rotated(path, index))
+        .collect();
+    files.push(path.to_path_buf());
+    files
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(file).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<AuditRecord>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_audit_rotation() {
+        let dir = std::env::temp_dir().join(format!("audit-test-{}", std::process::id()));
+        let path = dir.join("audit.log");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let record = AuditRecord::new(
+            "SetServer",
+            "controller",
+            Some("https://old.example.com".to_string()),
+            Some("https://new.example.com".to_string()),
+        );
+        append_record(&path, &record).await.unwrap();
+        // a full file is rotated before the next record
+        let padding = vec![b' '; MAX_SIZE as usize];
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| std::io::Write::write_all(&mut f, &padding))
+            .unwrap();
+        let failed = AuditRecord {
+            error: Some("timeout".to_string()),
+            ..record.clone()
+        };
+        append_record(&path, &failed).await.unwrap();
+        assert!(rotated(&path, 1).exists());
+
+        assert_eq!(read_audit_records(&path), vec![record, failed]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,312 @@
+// Support bundle made by the monitor itself: its logs, the model state, the TPM
+// results, the kernel messages and the config in one tar.gz. Unlike the bundle
+// of EVE it does not need the IPC connection. Secrets are redacted before
+// anything is written
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use log::{debug, info, warn};
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
+
+use crate::{
+    application::Application,
+    error::MonitorError,
+    file_io::write_file,
+    model::{
+        model::{MonitorModel, VaultStatus},
+        sanitize::{redact_secrets, sanitize_text},
+    },
+};
+
+#[derive(Debug)]
+pub enum BundleEvent {
+    Progress {
+        step: &'static str,
+        done: usize,
+        total: usize,
+    },
+    Completed {
+        path: PathBuf,
+    },
+    Failed {
+        error: MonitorError,
+    },
+}
+
+fn tpm_report(model: &MonitorModel) -> String {
+    let mut lines = vec![format!("Vault: {}", model.vault_status)];
+    match &model.vault_status {
+        VaultStatus::Locked(error, pcrs) => {
+            lines.push(format!("Error: {}", error.error));
+            if let Some(pcrs) = pcrs {
+                let pcrs: Vec<String> = pcrs.iter().map(|pcr| pcr.to_string()).collect();
+                lines.push(format!(
+                    "PCRs that changed since sealing: {}",
+                    pcrs.join(", ")
+                ));
+            }
+        }
+        VaultStatus::EncryptionDisabled(reason, tpm_used) => {
+            lines.push(format!("Reason: {}", reason.error));
+            lines.push(format!("TPM used: {}", tpm_used));
+        }
+        VaultStatus::Unlocked(tpm_used) => lines.push(format!("TPM used: {}", tpm_used)),
+        VaultStatus::Unknown => {}
+    }
+    lines.push(format!(
+        "Device key: {}",
+        match model.identity.tpm_backed {
+            Some(true) => "TPM-backed",
+            Some(false) => "software",
+            None => "N/A",
+        }
+    ));
+    lines.push(String::new());
+    lines.push("Attestation:".to_string());
+    for entry in model.attestation.entries() {
+        lines.push(format!("{} {:?}", entry.time.to_rfc3339(), entry.event));
+    }
+    lines.join("\n") + "\n"
+}
+
+// the parsed state, the messages it was built from are in snapshot.json
+fn model_report(model: &MonitorModel) -> String {
+    let mut apps: Vec<_> = model.apps.values().collect();
+    apps.sort_by(|a, b| a.name.cmp(&b.name));
+    format!(
+        "# Node\n{:#?}\n\n# Network\n{:#?}\n\n# Apps\n{:#?}\n\n# Alerts\n{:#?}\n\n# Audit\n{:#?}\n",
+        model.node_status, model.network, apps, model.alerts, model.audit
+    )
+}
+
+/// What goes into the bundle. The model is read here on the UI thread, the
+/// files are read by the bundler task
+#[derive(Debug)]
+pub struct BundleSources {
+    // name in the archive and content
+    texts: Vec<(String, String)>,
+    // one subdirectory per session
+    log_dir: Option<PathBuf>,
+    config_file: PathBuf,
+}
+
+impl BundleSources {
+    pub fn capture(model: &MonitorModel, dmesg: Option<String>, config_file: PathBuf) -> Self {
+        let mut texts = vec![
+            ("model.txt".to_string(), model_report(model)),
+            ("tpm.txt".to_string(), tpm_report(model)),
+        ];
+        // can be loaded with --replay
+        match model.state_messages.snapshot().to_json() {
+            Ok(json) => texts.push(("snapshot.json".to_string(), json)),
+            Err(e) => warn!("Cannot add the model snapshot to the bundle: {}", e),
+        }
+        if let Some(dmesg) = dmesg {
+            texts.push(("dmesg.txt".to_string(), dmesg));
+        }
+        let log_dir = std::env::var("EVE_MONITOR_LOG_DIR")
+            .ok()
+            .and_then(|session| Some(PathBuf::from(session).parent()?.to_path_buf()));
+        Self {
+            texts,
+            log_dir,
+            config_file,
+        }
+    }
+}
+
+/// Where the bundles are written
+pub fn bundle_dir() -> PathBuf {
+    Application::monitor_data_dir("bundles")
+}
+
+// files of `dir` and of its subdirectories, e.g. the log sessions
+async fn read_files(dir: &Path, depth: usize) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut files = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth > 0 {
+                files.extend(Box::pin(read_files(&path, depth - 1)).await);
+            }
+        } else if let Ok(data) = tokio::fs::read(&path).await {
+            files.push((path, data));
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+// octal number padded with zeros and terminated with NUL
+fn octal_field(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let digits = format!("{:0width$o}", value, width = width);
+    // too large values keep their lowest digits
+    field[..width].copy_from_slice(&digits.as_bytes()[digits.len() - width..]);
+    field[width] = 0;
+}
+
+/// Regular files in ustar format, names are cut to 99 bytes
+fn tar(files: &[(String, Vec<u8>)], mtime: u64) -> Vec<u8> {
+    let mut archive = Vec::new();
+    for (name, data) in files {
+        let mut header = [0u8; 512];
+        let name = &name.as_bytes()[..name.len().min(99)];
+        header[..name.len()].copy_from_slice(name);
+        octal_field(&mut header[100..108], 0o644);
+        octal_field(&mut header[108..116], 0);
+        octal_field(&mut header[116..124], 0);
+        octal_field(&mut header[124..136], data.len() as u64);
+        octal_field(&mut header[136..148], mtime);
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        // the checksum is computed with its own field set to spaces
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+        octal_field(&mut header[148..155], checksum as u64);
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(data);
+        archive.resize(archive.len().next_multiple_of(512), 0);
+    }
+    // two empty blocks end the archive
+    archive.resize(archive.len() + 1024, 0);
+    archive
+}
+
+fn gzip(data: &[u8], mtime: u32) -> Vec<u8> {
+    let mut result = vec![0x1f, 0x8b, 8, 0];
+    result.extend_from_slice(&mtime.to_le_bytes());
+    // no extra flags, unknown OS
+    result.extend_from_slice(&[0, 0xff]);
+    result.extend(miniz_oxide::deflate::compress_to_vec(data, 6));
+    result.extend_from_slice(&crc32(data).to_le_bytes());
+    result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    result
+}
+
+async fn create_bundle(
+    sources: BundleSources,
+    events_tx: &UnboundedSender<BundleEvent>,
+) -> Result<PathBuf> {
+    const STEPS: usize = 4;
+    let progress = |step, done| {
+        let _ = events_tx.send(BundleEvent::Progress {
+            step,
+            done,
+            total: STEPS,
+        });
+    };
+    let now = Local::now();
+    let name = format!("monitor-bundle-{}", now.format("%Y-%m-%d-%H-%M-%S"));
+
+    progress("Reading logs and the config", 0);
+    let mut files: Vec<(String, Vec<u8>)> = sources
+        .texts
+        .into_iter()
+        .map(|(file, text)| (file, text.into_bytes()))
+        .collect();
+    if let Some(log_dir) = sources.log_dir.as_ref() {
+        for (path, data) in read_files(log_dir, 1).await {
+            let relative = path.strip_prefix(log_dir).unwrap_or(&path);
+            files.push((format!("log/{}", relative.display()), data));
+        }
+    }
+    if let Ok(config) = tokio::fs::read(&sources.config_file).await {
+        files.push(("config.json".to_string(), config));
+    }
+
+    progress("Redacting secrets", 1);
+    let files: Vec<(String, Vec<u8>)> = files
+        .into_iter()
+        .map(|(file, data)| {
+            let text = redact_secrets(&sanitize_text(&String::from_utf8_lossy(&data)));
+            (format!("{}/{}", name, file), text.into_bytes())
+        })
+        .collect();
+
+    progress("Compressing", 2);
+    let mtime = now.timestamp().max(0) as u64;
+    let data = tokio::task::spawn_blocking(move || gzip(&tar(&files, mtime), mtime as u32))
+        .await
+        .context("Compression task failed")?;
+
+    progress("Writing", 3);
+    let path = bundle_dir().join(format!("{}.tar.gz", name));
+    write_file(&path, &data, |_| {}).await?;
+    progress("Done", STEPS);
+    Ok(path)
+}
+
+/// Create the bundle on a background task. Progress and the final result
+/// are reported through `events_tx`
+pub fn spawn_bundler(
+    sources: BundleSources,
+    events_tx: UnboundedSender<BundleEvent>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        debug!(
+            "Creating a support bundle with {} reports",
+            sources.texts.len()
+        );
+        let event = match create_bundle(sources, &events_tx).await {
+            Ok(path) => {
+                info!("Support bundle written to {}", path.display());
+                BundleEvent::Completed { path }
+            }
+            Err(e) => BundleEvent::Failed {
+                error: MonitorError::Io(e),
+            },
+        };
+        let _ = events_tx.send(event);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tar_gz() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        let files = vec![("bundle/model.txt".to_string(), b"hello".to_vec())];
+        let archive = tar(&files, 0);
+        assert_eq!(archive.len(), 512 + 512 + 1024);
+        assert_eq!(&archive[..16], b"bundle/model.txt");
+        assert_eq!(&archive[124..136], b"00000000005\0");
+        assert_eq!(&archive[512..517], b"hello");
+        // unsigned sum of the header with the checksum field as spaces
+        let mut header = archive[..512].to_vec();
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+        assert_eq!(&archive[148..155], format!("{:06o}\0", checksum).as_bytes());
+
+        let gz = gzip(&archive, 0);
+        assert_eq!(&gz[..3], &[0x1f, 0x8b, 8]);
+        let body = &gz[10..gz.len() - 8];
+        assert_eq!(
+            miniz_oxide::inflate::decompress_to_vec(body).unwrap(),
+            archive
+        );
+        assert_eq!(
+            gz[gz.len() - 8..gz.len() - 4],
+            crc32(&archive).to_le_bytes()
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use clap::{Args, Parser, Subcommand};
+use futures::StreamExt;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::ipc::{chunked::DEFAULT_MAX_MESSAGE, ipc_client::IpcClient, message::IpcMessage};
+use crate::lock::hash_passcode;
+use crate::model::diff::Change;
+use crate::model::model::{diff_network, MonitorModel, VaultStatus};
+use crate::model::onboarding::OnboardingState;
+use crate::traits::IWindow;
+use crate::ui::{
+    app_page::ApplicationsPage, export::ExportFormat, networkpage::create_network_page,
+};
+
+/// Without a subcommand the interactive UI is started
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "EVE-OS local monitor",
+    args_conflicts_with_subcommands = true
+)]
+pub struct Cli {
+    /// Print state changes as JSON lines instead of starting the interactive UI
+    #[arg(long)]
+    pub watch: bool,
+    /// Read a passcode from stdin and print its hash for lock.passcode_hash in the config file
+    #[arg(long)]
+    pub hash_passcode: bool,
+    /// Render every page against built-in EVE messages without a tty, exit with an error on failures
+    #[arg(long)]
+    pub self_test: bool,
+    /// Show the state of a model snapshot saved on the Debug page instead of connecting to EVE
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+    /// Append every message from EVE to FILE, with the time it arrived
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "playback"])]
+    pub record: Option<PathBuf>,
+    /// Feed the messages recorded with --record to the UI on their original schedule
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub playback: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print onboarding, vault and application summary
+    Status(QueryArgs),
+    /// Print network interfaces
+    Network(QueryArgs),
+    /// Print applications
+    Apps(QueryArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct QueryArgs {
+    /// Print JSON instead of text
+    #[arg(long)]
+    json: bool,
+    /// Seconds to wait for EVE to send the state
+    #[arg(long, default_value_t = 5)]
+    timeout: u64,
+}
+
+impl Command {
+    fn args(&self) -> &QueryArgs {
+        match self {
+            Command::Status(args) | Command::Network(args) | Command::Apps(args) => args,
+        }
+    }
+
+    // EVE sends the current state right after connecting. Wait for the message
+    // that carries the part we need
+    fn is_complete(&self, msg: &IpcMessage) -> bool {
+        matches!(
+            (self, msg),
+            (Command::Status(_), IpcMessage::NodeStatus(_))
+                | (Command::Network(_), IpcMessage::NetworkStatus(_))
+                | (Command::Apps(_), IpcMessage::AppsList(_))
+        )
+    }
+}
+
+fn vault_summary(vault: &VaultStatus) -> &'static str {
+    match vault {
+        VaultStatus::Unknown => "unknown",
+        VaultStatus::EncryptionDisabled(_, _) => "encryption disabled",
+        VaultStatus::Unlocked(_) => "unlocked",
+        VaultStatus::Locked(_, _) => "locked",
+    }
+}
+
+fn status_json(model: &MonitorModel) -> serde_json::Value {
+    let node = &model.node_status;
+    let apps = &node.app_summary;
+    json!({
+        "onboarding": format!("{:?}", node.onboarding_status.state()),
+        "device_uuid": node.onboarding_status.device_uuid(),
+        "onboarding_error": node.onboarding_status.blocking_error(),
+        "server": node.server,
+        "vault": vault_summary(&model.vault_status),
+        "apps": {
+            "starting": apps.total_starting,
+            "running": apps.total_running,
+            "stopping": apps.total_stopping,
+            "error": apps.total_error,
+        },
+    })
+}
+
+fn status_text(model: &MonitorModel) -> String {
+    let node = &model.node_status;
+    let apps = &node.app_summary;
+    let or_na = |value: Option<String>| value.unwrap_or("N/A".to_string());
+    format!(
+        "Onboarding: {:?}\nDevice UUID: {}\nOnboarding error: {}\nServer: {}\nVault: {}\n\
+         Apps: {} starting, {} running, {} stopping, {} error\n",
+        node.onboarding_status.state(),
+        or_na(node.onboarding_status.device_uuid().map(|u| u.to_string())),
+        or_na(node.onboarding_status.blocking_error().map(str::to_string)),
+        or_na(node.server.clone()),
+        vault_summary(&model.vault_status),
+        apps.total_starting,
+        apps.total_running,
+        apps.total_stopping,
+        apps.total_error
+    )
+}
+
+fn render(command: &Command, model: &MonitorModel) -> String {
+    let format = if command.args().json {
+        ExportFormat::Json
+    } else {
+        ExportFormat::Text
+    };
+    // the tables are the same as the UI pages export
+    let export = match command {
+        Command::Status(_) if command.args().json => {
+            return serde_json::to_string_pretty(&status_json(model)).unwrap_or_default() + "\n";
+        }
+        Command::Status(_) => return status_text(model),
+        Command::Network(_) => create_network_page().export(model),
+        Command::Apps(_) => ApplicationsPage::new().export(model),
+    };
+    export
+        .map(|export| String::from_utf8_lossy(&export.serialize(format)).into_owned())
+        .unwrap_or_default()
+}
+
+/// Connect to EVE, collect the current state and print it to stdout
+pub async fn run_query(command: Command, socket_path: &str) -> Result<()> {
+    let timeout = Duration::from_secs(command.args().timeout);
+    let mut model = MonitorModel::default();
+
+    let collect = async {
+        let mut stream = IpcClient::connect(socket_path, DEFAULT_MAX_MESSAGE, Arc::default())
+            .await
+            .with_context(|| format!("Cannot connect to {}", socket_path))?;
+        while let Some(bytes) = stream.next().await {
+            let msg = IpcMessage::from(bytes.context("Cannot read IPC message")?);
+            let complete = command.is_complete(&msg);
+            model.apply_ipc_message(msg);
+            if complete {
+                return Ok(());
+            }
+        }
+        bail!("IPC connection closed")
+    };
+    tokio::time::timeout(timeout, collect)
+        .await
+        .context("Timed out waiting for EVE")??;
+
+    print!("{}", render(&command, &model));
+    Ok(())
+}
+
+/// Print the hash of the passcode read from stdin, with a random salt
+pub fn run_hash_passcode() -> Result<()> {
+    let mut passcode = String::new();
+    std::io::stdin()
+        .read_line(&mut passcode)
+        .context("Cannot read the passcode")?;
+    let passcode = passcode.trim_end_matches(['\r', '\n']);
+    if passcode.is_empty() {
+        bail!("The passcode is empty");
+    }
+    let salt = Uuid::new_v4().simple().to_string();
+    println!("{}", hash_passcode(passcode, &salt));
+    Ok(())
+}
+
+fn onboarding_json(state: &OnboardingState) -> Value {
+    match state {
+        OnboardingState::Unknown => json!({ "state": "unknown" }),
+        OnboardingState::Onboarding => json!({ "state": "onboarding" }),
+        OnboardingState::Onboarded(uuid) => json!({ "state": "onboarded", "device_uuid": uuid }),
+        OnboardingState::Error(err) => json!({ "state": "error", "error": err }),
+    }
+}
+
+fn network_json(change: &Change) -> Value {
+    match change {
+        Change::Added(iface) => json!({ "interface": iface, "change": "added" }),
+        Change::Removed(iface) => json!({ "interface": iface, "change": "removed" }),
+        Change::Modified {
+            item,
+            field,
+            old,
+            new,
+        } => json!({
+            "interface": item,
+            "change": "modified",
+            "field": field,
+            "old": old,
+            "new": new,
+        }),
+    }
+}
+
+// one JSON object per line so the output can be consumed line by line
+fn emit(event: &str, mut data: Value) {
+    data["event"] = json!(event);
+    data["time"] = json!(Local::now().to_rfc3339());
+    println!("{}", data);
+}
+
+/// Print onboarding, vault and network changes until EVE closes the connection
+pub async fn run_watch(socket_path: &str) -> Result<()> {
+    let mut model = MonitorModel::default();
+    let mut stream = IpcClient::connect(socket_path, DEFAULT_MAX_MESSAGE, Arc::default())
+        .await
+        .with_context(|| format!("Cannot connect to {}", socket_path))?;
+
+    while let Some(bytes) = stream.next().await {
+        let msg = IpcMessage::from(bytes.context("Cannot read IPC message")?);
+        let onboarding = model.node_status.onboarding_status.state().clone();
+        let vault = vault_summary(&model.vault_status);
+        let network = model.network.clone();
+
+        model.apply_ipc_message(msg);
+
+        let new_onboarding = model.node_status.onboarding_status.state();
+        if *new_onboarding != onboarding {
+            emit(
+                "onboarding",
+                json!({ "old": onboarding_json(&onboarding), "new": onboarding_json(new_onboarding) }),
+            );
+        }
+        let new_vault = vault_summary(&model.vault_status);
+        if new_vault != vault {
+            emit("vault", json!({ "old": vault, "new": new_vault }));
+        }
+        for change in diff_network(&network, &model.network) {
+            emit("network", network_json(&change));
+        }
+    }
+    bail!("IPC connection closed")
+}
@@ -0,0 +1,305 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use bytes::BytesMut;
+use chrono::{DateTime, Local};
+use futures::{FutureExt, SinkExt, StreamExt};
+use log::{info, warn};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio_util::sync::CancellationToken;
+
+use crate::ipc::{
+    dedup::{MessageDedup, MessageStats},
+    ipc_client::{IpcClient, Waiters},
+    message::IpcMessage,
+};
+
+use super::{Collector, CollectorTask};
+
+const BACKOFF_MIN: Duration = Duration::from_millis(500);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+// the task is restarted after it failed, at most MAX_TASK_RESTARTS times
+const TASK_RESTART_DELAY: Duration = Duration::from_secs(2);
+const MAX_TASK_RESTARTS: u32 = 5;
+
+/// Delay before the task is started again after it failed `restarts` times
+/// already. None if it failed too often, restarting will not help
+pub fn task_restart_delay(restarts: u32) -> Option<Duration> {
+    (restarts < MAX_TASK_RESTARTS).then(|| TASK_RESTART_DELAY * 2u32.pow(restarts))
+}
+
+/// What was going on when the IPC task ended while the UI kept running
+#[derive(Debug, Clone)]
+pub struct IpcCrash {
+    pub error: String,
+    pub at: DateTime<Local>,
+    // connection state before the crash
+    pub state: String,
+    pub pending_requests: usize,
+    // messages received from the task since the application start
+    pub messages: u64,
+    pub restarts: u32,
+}
+
+impl std::fmt::Display for IpcCrash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "IPC task ended at {}: {}. State: {}, pending requests: {}, messages: {}, restarts: {}",
+            self.at.format("%Y-%m-%d %H:%M:%S"),
+            self.error,
+            self.state,
+            self.pending_requests,
+            self.messages,
+            self.restarts
+        )
+    }
+}
+
+/// Delay before reconnect `attempt` (0-based). Doubles with every attempt up to
+/// BACKOFF_MAX. `jitter` in 0..1000 spreads it by up to -20%..+20% so monitors
+/// on many devices do not hammer EVE in sync after it restarts
+fn backoff_delay(attempt: u32, jitter: u32) -> Duration {
+    let delay = BACKOFF_MIN
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(BACKOFF_MAX);
+    let permille = 800 + jitter.min(1000) * 400 / 1000;
+    delay * permille / 1000
+}
+
+fn jitter() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() % 1000)
+        .unwrap_or(0)
+}
+
+// routes the messages from EVE. Duplicates are dropped and responses go to the
+// request waiting for them, the rest to the application
+#[derive(Clone, Default)]
+struct Inbox {
+    waiters: Arc<Waiters>,
+    dedup: Arc<Mutex<MessageDedup>>,
+}
+
+impl Inbox {
+    fn route(&self, msg: BytesMut) -> Option<IpcMessage> {
+        if !self.dedup.lock().unwrap().pass(&msg, Instant::now()) {
+            return None;
+        }
+        self.waiters.resolve(IpcMessage::from(msg))
+    }
+}
+
+// messages from EVE over the monitor socket. Requests are sent through `client()`,
+// their responses go to the client instead of the message stream
+pub struct IpcCollector {
+    task: CollectorTask<IpcMessage>,
+    socket_path: String,
+    // biggest message reassembled from chunks
+    max_message: usize,
+    // EVE accepts compressed messages
+    compress: Arc<AtomicBool>,
+    cmd_tx: Option<UnboundedSender<IpcMessage>>,
+    inbox: Inbox,
+}
+
+impl IpcCollector {
+    pub fn new<S: Into<String>>(socket_path: S, max_message: usize) -> Self {
+        Self {
+            task: CollectorTask::new("ipc"),
+            socket_path: socket_path.into(),
+            max_message,
+            compress: Arc::default(),
+            cmd_tx: None,
+            inbox: Inbox::default(),
+        }
+    }
+
+    /// Messages received and dropped as duplicates per message type
+    pub fn message_stats(&self) -> BTreeMap<String, MessageStats> {
+        self.inbox.dedup.lock().unwrap().stats().clone()
+    }
+
+    /// Compress big requests, set after the handshake
+    pub fn set_compression(&self, enabled: bool) {
+        self.compress.store(enabled, Ordering::Relaxed);
+    }
+
+    /// None before the collector is started. Requests of the client are
+    /// cancelled when the collector stops
+    pub fn client(&self) -> Option<IpcClient> {
+        let tx = self.cmd_tx.clone()?;
+        Some(IpcClient::new(tx, self.inbox.waiters.clone()).with_cancel(self.task.cancel_token()))
+    }
+}
+
+// talk to EVE until the connection is lost. Returns why it was lost or None if
+// the collector is stopping
+async fn run_session(
+    socket_path: &str,
+    max_message: usize,
+    compress: &Arc<AtomicBool>,
+    ipc_tx: &UnboundedSender<IpcMessage>,
+    ipc_cmd_rx: &mut UnboundedReceiver<IpcMessage>,
+    inbox: &Inbox,
+    cancel_token: &CancellationToken,
+) -> anyhow::Result<Option<String>> {
+    info!("Connecting to IPC socket {} ", socket_path);
+    let stream = IpcClient::connect(socket_path, max_message, compress.clone())
+        .await
+        .with_context(|| format!("Cannot connect to {}", socket_path))?;
+    let (mut sink, mut stream) = stream.split();
+
+    // agreed on again in the handshake, EVE may have been updated
+    compress.store(false, Ordering::Relaxed);
+    inbox.dedup.lock().unwrap().reset();
+    ipc_tx.send(IpcMessage::Ready)?;
+
+    while !cancel_token.is_cancelled() {
+        let ipc_event = stream.next().fuse();
+
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            msg = ipc_cmd_rx.recv() => {
+                match msg {
+                    Some(msg) => {
+                        sink.send(msg.into()).await?;
+                    }
+                    None => {
+                        warn!("IPC message stream ended");
+                        break;
+                    }
+                }
+            },
+            msg = ipc_event => {
+                match msg {
+                    Some(Ok(msg)) => {
+                        if let Some(msg) = inbox.route(msg) {
+                            ipc_tx.send(msg)?;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        warn!("Error reading IPC message: {:?}", e);
+                    }
+                    None => {
+                        return Ok(Some("IPC connection closed".to_string()));
+                    }
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+impl Collector for IpcCollector {
+    type Output = IpcMessage;
+
+    fn task(&self) -> &CollectorTask<IpcMessage> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<IpcMessage> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let (ipc_cmd_tx, mut ipc_cmd_rx) = mpsc::unbounded_channel::<IpcMessage>();
+        self.cmd_tx = Some(ipc_cmd_tx);
+        let socket_path = self.socket_path.clone();
+        let max_message = self.max_message;
+        let compress = self.compress.clone();
+        let inbox = self.inbox.clone();
+
+        self.task.spawn(move |ipc_tx, cancel_token| async move {
+            let mut attempt = 0;
+            loop {
+                ipc_tx.send(IpcMessage::Connecting)?;
+                let connected_at = std::time::Instant::now();
+                let session = run_session(
+                    &socket_path,
+                    max_message,
+                    &compress,
+                    &ipc_tx,
+                    &mut ipc_cmd_rx,
+                    &inbox,
+                    &cancel_token,
+                )
+                .await;
+                // nothing answers the requests sent over the lost connection
+                inbox.waiters.clear();
+                let reason = match session {
+                    Ok(Some(reason)) => reason,
+                    Ok(None) => {
+                        info!("IPC task was cancelled");
+                        return Ok(());
+                    }
+                    Err(e) => format!("{:#}", e),
+                };
+                // a connection that lasted a while starts the backoff over
+                if connected_at.elapsed() > BACKOFF_MAX {
+                    attempt = 0;
+                }
+                let delay = backoff_delay(attempt, jitter());
+                attempt = attempt.saturating_add(1);
+                warn!("{}. Reconnecting in {:?}", reason, delay);
+                ipc_tx.send(IpcMessage::Disconnected {
+                    reason,
+                    retry_in_ms: delay.as_millis() as u64,
+                })?;
+
+                let sleep = tokio::time::sleep(delay);
+                tokio::pin!(sleep);
+                loop {
+                    tokio::select! {
+                        _ = cancel_token.cancelled() => return Ok(()),
+                        _ = &mut sleep => break,
+                        // requests made while disconnected are not sent later
+                        // when they may no longer make sense
+                        msg = ipc_cmd_rx.recv() => {
+                            let Some(msg) = msg else {
+                                return Ok(());
+                            };
+                            warn!("IPC is disconnected, dropping {:?}", msg);
+                            if let IpcMessage::Request { id, .. } = msg {
+                                inbox.waiters.remove(id);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(0, 500), BACKOFF_MIN);
+        assert_eq!(backoff_delay(3, 500), BACKOFF_MIN * 8);
+        assert_eq!(backoff_delay(20, 500), BACKOFF_MAX);
+        assert_eq!(backoff_delay(u32::MAX, 500), BACKOFF_MAX);
+        // jitter
+        assert_eq!(backoff_delay(0, 0), Duration::from_millis(400));
+        assert_eq!(backoff_delay(0, 1000), Duration::from_millis(600));
+        assert_eq!(backoff_delay(20, 1000), Duration::from_secs(36));
+    }
+
+    #[test]
+    fn test_task_restart_delay() {
+        assert_eq!(task_restart_delay(0), Some(TASK_RESTART_DELAY));
+        assert_eq!(task_restart_delay(2), Some(TASK_RESTART_DELAY * 4));
+        assert_eq!(task_restart_delay(MAX_TASK_RESTARTS), None);
+    }
+}
@@ -0,0 +1,90 @@
+use futures::StreamExt;
+use log::{info, trace, warn};
+use rmesg::entry::Entry;
+
+use super::{Collector, CollectorTask};
+
+// kernel messages from /dev/kmsg. Produces dummy entries on desktop
+pub struct KmsgCollector {
+    task: CollectorTask<Entry>,
+    is_desktop: bool,
+}
+
+impl KmsgCollector {
+    pub fn new(is_desktop: bool) -> Self {
+        Self {
+            task: CollectorTask::new("kmsg"),
+            is_desktop,
+        }
+    }
+}
+
+impl Collector for KmsgCollector {
+    type Output = Entry;
+
+    fn task(&self) -> &CollectorTask<Entry> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<Entry> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let is_desktop = self.is_desktop;
+        self.task.spawn(move |dmesg_tx, cancel_token| async move {
+            if is_desktop {
+                let mut index = 0;
+                while !cancel_token.is_cancelled() {
+                    let dummy_entry = Entry {
+                        level: Some(rmesg::entry::LogLevel::Info),
+                        message: format!("[INFO] {} Desktop mode: no kmsg", index),
+                        facility: None,
+                        sequence_num: None,
+                        timestamp_from_system_start: None,
+                    };
+
+                    index += 1;
+
+                    tokio::select! {
+                        _ = cancel_token.cancelled() => {
+                            info!("Kmsg task was cancelled");
+                            return Ok(());
+                        }
+                        _ = tokio::time::timeout(tokio::time::Duration::from_millis(200), cancel_token.cancelled() ) => {
+                            dmesg_tx.send(dummy_entry.clone())?;
+                        }
+                    }
+                }
+            } else {
+                //FIXME: this stream is buggy!!! it leaves a thread behind and tokio cannot gracefully shutdown
+                let mut st = rmesg::logs_stream(rmesg::Backend::Default, true, false).await?;
+
+                while !cancel_token.is_cancelled() {
+                    tokio::select! {
+                        _ = cancel_token.cancelled() => {
+                            info!("Kmsg task was cancelled");
+                            return Ok(());
+                        }
+                        log = st.next() => {
+                            trace!("Got log entry");
+                            match log {
+                                Some(Ok(log)) => {
+                                    dmesg_tx.send(log)?;
+                                }
+                                Some(Err(e)) => {
+                                    warn!("Error reading kmsg: {:?}", e);
+                                }
+                                None => {
+                                    anyhow::bail!("Kmsg stream ended");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            info!("Kmsg stream ended");
+            Ok(())
+        });
+    }
+}
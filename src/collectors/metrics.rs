@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use log::trace;
+use tokio::sync::watch;
+use tokio::time::Duration;
+
+use super::{sample_while_active, Collector, CollectorTask};
+
+/// Jiffies a CPU spent, from a "cpu" line of /proc/stat
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuTimes {
+    pub busy: u64,
+    pub total: u64,
+}
+
+/// Values of /proc/meminfo in kB
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MemInfo {
+    pub total: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+}
+
+impl MemInfo {
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    pub fn used_percent(&self) -> u64 {
+        (self.used() * 100).checked_div(self.total).unwrap_or(0)
+    }
+}
+
+/// 1, 5 and 15 minute load averages
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsSample {
+    // all CPUs together first, then every core
+    pub cpus: Vec<CpuTimes>,
+    pub memory: MemInfo,
+    pub load: LoadAverage,
+}
+
+// "cpu  user nice system idle iowait irq softirq steal guest guest_nice"
+fn parse_stat(stat: &str) -> Vec<CpuTimes> {
+    stat.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .map(|line| {
+            let values: Vec<u64> = line
+                .split_whitespace()
+                .skip(1)
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            // guest time is already in user and nice
+            let total: u64 = values.iter().take(8).sum();
+            let idle: u64 = values.iter().skip(3).take(2).sum();
+            CpuTimes {
+                busy: total.saturating_sub(idle),
+                total,
+            }
+        })
+        .collect()
+}
+
+fn parse_meminfo(meminfo: &str) -> MemInfo {
+    let value = |key: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|value| value.split_whitespace().next())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0)
+    };
+    MemInfo {
+        total: value("MemTotal"),
+        available: value("MemAvailable"),
+        swap_total: value("SwapTotal"),
+        swap_free: value("SwapFree"),
+    }
+}
+
+fn parse_loadavg(loadavg: &str) -> LoadAverage {
+    let mut values = loadavg.split_whitespace().map(|v| v.parse().unwrap_or(0.0));
+    LoadAverage {
+        one: values.next().unwrap_or(0.0),
+        five: values.next().unwrap_or(0.0),
+        fifteen: values.next().unwrap_or(0.0),
+    }
+}
+
+fn read(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path))
+}
+
+// CPU, memory and load of the host, sampled with a fixed period so the history
+// covers the same time whatever the refresh interval is
+pub struct MetricsCollector {
+    task: CollectorTask<MetricsSample>,
+    period: Duration,
+    // nothing is sampled while the user is idle
+    idle_rx: watch::Receiver<bool>,
+}
+
+impl MetricsCollector {
+    pub fn new(period: Duration, idle_rx: watch::Receiver<bool>) -> Self {
+        Self {
+            task: CollectorTask::new("metrics"),
+            period,
+            idle_rx,
+        }
+    }
+}
+
+impl Collector for MetricsCollector {
+    type Output = MetricsSample;
+
+    fn task(&self) -> &CollectorTask<MetricsSample> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<MetricsSample> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let period = self.period;
+        let idle_rx = self.idle_rx.clone();
+        self.task.spawn(move |tx, cancel_token| async move {
+            sample_while_active(period, idle_rx, &cancel_token, |_| {
+                let sample = MetricsSample {
+                    cpus: parse_stat(&read("/proc/stat")?),
+                    memory: parse_meminfo(&read("/proc/meminfo")?),
+                    load: parse_loadavg(&read("/proc/loadavg")?),
+                };
+                trace!("Sampled {} CPUs", sample.cpus.len());
+                tx.send(sample)?;
+                Ok(())
+            })
+            .await
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "cpu  400 0 100 1400 100 0 0 0 0 0\n\
+                    cpu0 300 0 50 600 50 0 0 0 0 0\n\
+                    cpu1 100 0 50 800 50 0 0 0 0 0\n\
+                    intr 12345 0 0\n\
+                    ctxt 6789\n";
+        assert_eq!(
+            parse_stat(stat),
+            vec![
+                CpuTimes {
+                    busy: 500,
+                    total: 2000
+                },
+                CpuTimes {
+                    busy: 350,
+                    total: 1000
+                },
+                CpuTimes {
+                    busy: 150,
+                    total: 1000
+                },
+            ]
+        );
+
+        let meminfo = "MemTotal:        4005564 kB\n\
+                       MemFree:          179208 kB\n\
+                       MemAvailable:    1502392 kB\n\
+                       SwapTotal:             0 kB\n\
+                       SwapFree:              0 kB\n";
+        assert_eq!(
+            parse_meminfo(meminfo),
+            MemInfo {
+                total: 4005564,
+                available: 1502392,
+                swap_total: 0,
+                swap_free: 0,
+            }
+        );
+
+        let load = parse_loadavg("0.52 0.58 0.59 2/1224 32125\n");
+        assert_eq!(load.one, 0.52);
+        assert_eq!(load.fifteen, 0.59);
+    }
+}
@@ -0,0 +1,282 @@
+pub mod ipc;
+#[cfg(feature = "dmesg")]
+pub mod kmsg;
+pub mod metrics;
+pub mod netlink;
+pub mod netstats;
+pub mod sensors;
+pub mod timer;
+pub mod zpool;
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use log::{info, warn};
+use strum::Display;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone, PartialEq, Display)]
+pub enum CollectorHealth {
+    Stopped,
+    Running,
+    #[strum(to_string = "Failed: {0}")]
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CollectorStatus {
+    pub name: &'static str,
+    pub health: CollectorHealth,
+    // number of items received from the collector since the application start
+    pub events: u64,
+    pub restarts: u32,
+}
+
+// Background task producing data for the application. Keeps the channel, the
+// cancellation token and the health of the task so the Application can manage
+// all collectors the same way
+pub struct CollectorTask<T> {
+    name: &'static str,
+    handle: Option<JoinHandle<()>>,
+    cancel_token: CancellationToken,
+    rx: Option<UnboundedReceiver<T>>,
+    health: Arc<Mutex<CollectorHealth>>,
+    events: u64,
+    restarts: u32,
+}
+
+impl<T: Send + 'static> CollectorTask<T> {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            handle: None,
+            cancel_token: CancellationToken::new(),
+            rx: None,
+            health: Arc::new(Mutex::new(CollectorHealth::Stopped)),
+            events: 0,
+            restarts: 0,
+        }
+    }
+
+    /// Spawn `f` on a new task. The task reports its result as the collector health
+    pub fn spawn<F, Fut>(&mut self, f: F)
+    where
+        F: FnOnce(UnboundedSender<T>, CancellationToken) -> Fut,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        if self.handle.is_some() {
+            self.restarts += 1;
+        }
+        let (tx, rx) = mpsc::unbounded_channel::<T>();
+        self.cancel_token = CancellationToken::new();
+        self.rx = Some(rx);
+        // a task of an earlier spawn may still end and report its result,
+        // it must not overwrite the health of this one
+        self.health = Arc::new(Mutex::new(CollectorHealth::Running));
+
+        let name = self.name;
+        let health = self.health.clone();
+        let future = f(tx, self.cancel_token.clone());
+        self.handle = Some(tokio::spawn(async move {
+            let new_health = match future.await {
+                Ok(()) => {
+                    info!("Collector {} stopped", name);
+                    CollectorHealth::Stopped
+                }
+                Err(e) => {
+                    warn!("Collector {} failed: {:#}", name, e);
+                    CollectorHealth::Failed(format!("{:#}", e))
+                }
+            };
+            *health.lock().unwrap() = new_health;
+        }));
+    }
+
+    /// Cancelled when the task is stopped or spawned again
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.cancel_token.clone()
+    }
+
+    pub fn stop(&mut self) {
+        self.cancel_token.cancel();
+        // some streams do not react on cancellation. Make sure the task is gone
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+        self.rx = None;
+        self.set_health(CollectorHealth::Stopped);
+    }
+
+    pub async fn join(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.await;
+        }
+    }
+
+    /// Receive next item. Never resolves if the collector is not running so it is
+    /// safe to use in select! loops
+    pub async fn recv(&mut self) -> Option<T> {
+        let Some(rx) = self.rx.as_mut() else {
+            return std::future::pending().await;
+        };
+        let item = rx.recv().await;
+        if item.is_some() {
+            self.events += 1;
+        } else {
+            // the task has exited, its health tells why
+            self.rx = None;
+        }
+        item
+    }
+
+    #[cfg(feature = "dmesg")]
+    pub fn try_recv(&mut self) -> Option<T> {
+        let item = self.rx.as_mut()?.try_recv().ok();
+        if item.is_some() {
+            self.events += 1;
+        }
+        item
+    }
+
+    pub fn health(&self) -> CollectorHealth {
+        self.health.lock().unwrap().clone()
+    }
+
+    fn set_health(&self, health: CollectorHealth) {
+        *self.health.lock().unwrap() = health;
+    }
+
+    pub fn status(&self) -> CollectorStatus {
+        CollectorStatus {
+            name: self.name,
+            health: self.health(),
+            events: self.events,
+            restarts: self.restarts,
+        }
+    }
+}
+
+/// Wait while the user is idle, see IdleTracker. False if the task was
+/// cancelled meanwhile
+async fn wait_until_active(
+    idle_rx: &mut watch::Receiver<bool>,
+    cancel_token: &CancellationToken,
+) -> bool {
+    while *idle_rx.borrow_and_update() {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return false,
+            changed = idle_rx.changed() => {
+                // the tracker is gone, nobody would wake us up
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Call `sample` every `period` while the user is active. Sampling stops as
+/// soon as the user goes idle and starts right away when they are back.
+/// Returns when the task is cancelled or `sample` fails
+pub async fn sample_while_active(
+    period: Duration,
+    mut idle_rx: watch::Receiver<bool>,
+    cancel_token: &CancellationToken,
+    mut sample: impl FnMut(Instant) -> Result<()>,
+) -> Result<()> {
+    let mut interval = tokio::time::interval(period);
+    loop {
+        if *idle_rx.borrow_and_update() {
+            if !wait_until_active(&mut idle_rx, cancel_token).await {
+                return Ok(());
+            }
+            interval.reset_immediately();
+        }
+        tokio::select! {
+            _ = cancel_token.cancelled() => return Ok(()),
+            Ok(_) = idle_rx.changed() => {}
+            at = interval.tick() => sample(at)?,
+        }
+    }
+}
+
+pub trait Collector {
+    type Output: Send + 'static;
+
+    fn task(&self) -> &CollectorTask<Self::Output>;
+    fn task_mut(&mut self) -> &mut CollectorTask<Self::Output>;
+
+    fn start(&mut self);
+
+    fn stop(&mut self) {
+        self.task_mut().stop();
+    }
+
+    fn restart(&mut self) {
+        self.stop();
+        self.start();
+    }
+
+    fn status(&self) -> CollectorStatus {
+        self.task().status()
+    }
+
+    fn recv(&mut self) -> impl Future<Output = Option<Self::Output>> {
+        self.task_mut().recv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_health_of_respawned_task() {
+        let mut task = CollectorTask::<()>::new("test");
+        let (fail_tx, fail_rx) = oneshot::channel::<()>();
+        // does not react on cancellation
+        task.spawn(|_, _| async move {
+            let _ = fail_rx.await;
+            anyhow::bail!("old task failed")
+        });
+        let old = task.handle.take().unwrap();
+        task.spawn(|_, cancel| async move {
+            cancel.cancelled().await;
+            Ok(())
+        });
+
+        fail_tx.send(()).unwrap();
+        old.await.unwrap();
+        assert_eq!(task.health(), CollectorHealth::Running);
+
+        task.stop();
+        assert_eq!(task.health(), CollectorHealth::Stopped);
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_active() {
+        let (idle_tx, mut idle_rx) = watch::channel(true);
+        let cancel = CancellationToken::new();
+        let waiter = tokio::spawn({
+            let cancel = cancel.clone();
+            async move { wait_until_active(&mut idle_rx, &cancel).await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+        idle_tx.send(false).unwrap();
+        assert!(waiter.await.unwrap());
+
+        let mut idle_rx = idle_tx.subscribe();
+        idle_tx.send(true).unwrap();
+        cancel.cancel();
+        assert!(!wait_until_active(&mut idle_rx, &cancel).await);
+    }
+}
@@ -0,0 +1,312 @@
+use std::ffi::CStr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+use anyhow::{anyhow, Result};
+use log::{debug, trace, warn};
+use tokio::io::unix::AsyncFd;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Collector, CollectorTask};
+
+// netlink wire format. See linux/netlink.h, linux/rtnetlink.h, linux/if_addr.h
+const NLMSG_HDR_LEN: usize = 16;
+const IFINFOMSG_LEN: usize = 16;
+const IFADDRMSG_LEN: usize = 8;
+const RTATTR_HDR_LEN: usize = 4;
+const IFLA_IFNAME: u16 = 3;
+const IFA_ADDRESS: u16 = 1;
+const IFA_LOCAL: u16 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetlinkEvent {
+    Link { name: String, up: bool },
+    AddressAdded { name: String, addr: IpAddr },
+    AddressRemoved { name: String, addr: IpAddr },
+}
+
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn read_u16(buf: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_ne_bytes(
+        buf.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_ne_bytes(
+        buf.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+// iterate over (type, payload) of route attributes
+fn attributes(mut buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        let len = read_u16(buf, 0)? as usize;
+        let kind = read_u16(buf, 2)?;
+        if len < RTATTR_HDR_LEN || len > buf.len() {
+            return None;
+        }
+        let payload = &buf[RTATTR_HDR_LEN..len];
+        buf = buf.get(align(len)..).unwrap_or_default();
+        Some((kind, payload))
+    })
+}
+
+fn index_to_name(index: u32) -> Option<String> {
+    let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+    // SAFETY: name is IF_NAMESIZE long as required by if_indextoname
+    let ret = unsafe { libc::if_indextoname(index, name.as_mut_ptr()) };
+    if ret.is_null() {
+        return None;
+    }
+    // SAFETY: if_indextoname returns a NUL terminated string on success
+    let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+    Some(name.to_string_lossy().to_string())
+}
+
+fn parse_link(msg_type: u16, payload: &[u8]) -> Option<NetlinkEvent> {
+    let flags = read_u32(payload, 8)?;
+    let name = attributes(payload.get(IFINFOMSG_LEN..)?)
+        .find(|(kind, _)| *kind == IFLA_IFNAME)
+        .and_then(|(_, name)| CStr::from_bytes_until_nul(name).ok())
+        .map(|name| name.to_string_lossy().to_string())?;
+    let running = libc::IFF_UP as u32 | libc::IFF_RUNNING as u32;
+    let up = msg_type == libc::RTM_NEWLINK && flags & running == running;
+    Some(NetlinkEvent::Link { name, up })
+}
+
+fn parse_address(msg_type: u16, payload: &[u8]) -> Option<NetlinkEvent> {
+    let family = *payload.first()? as i32;
+    let index = read_u32(payload, 4)?;
+
+    // for IPv4 IFA_LOCAL is the address of the interface, IFA_ADDRESS may be a peer
+    let mut address = None;
+    for (kind, data) in attributes(payload.get(IFADDRMSG_LEN..)?) {
+        let addr = match (family, data.len()) {
+            (libc::AF_INET, 4) => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(data).ok()?)),
+            (libc::AF_INET6, 16) => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(data).ok()?)),
+            _ => continue,
+        };
+        match kind {
+            IFA_LOCAL => address = Some(addr),
+            IFA_ADDRESS if address.is_none() => address = Some(addr),
+            _ => {}
+        }
+    }
+
+    let addr = address?;
+    let name = index_to_name(index)?;
+    if msg_type == libc::RTM_NEWADDR {
+        Some(NetlinkEvent::AddressAdded { name, addr })
+    } else {
+        Some(NetlinkEvent::AddressRemoved { name, addr })
+    }
+}
+
+fn parse_messages(mut buf: &[u8]) -> Vec<NetlinkEvent> {
+    let mut events = Vec::new();
+    while let (Some(len), Some(msg_type)) = (read_u32(buf, 0), read_u16(buf, 4)) {
+        let len = len as usize;
+        if len < NLMSG_HDR_LEN || len > buf.len() {
+            break;
+        }
+        let payload = &buf[NLMSG_HDR_LEN..len];
+        let event = match msg_type {
+            libc::RTM_NEWLINK | libc::RTM_DELLINK => parse_link(msg_type, payload),
+            libc::RTM_NEWADDR | libc::RTM_DELADDR => parse_address(msg_type, payload),
+            _ => None,
+        };
+        events.extend(event);
+        buf = buf.get(align(len)..).unwrap_or_default();
+    }
+    events
+}
+
+fn open_socket() -> Result<OwnedFd> {
+    // SAFETY: plain socket(2) call, the result is checked below
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_RAW | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC,
+            libc::NETLINK_ROUTE,
+        )
+    };
+    if fd < 0 {
+        return Err(anyhow!(
+            "Cannot open netlink socket: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    // SAFETY: fd is a valid descriptor we own
+    let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // SAFETY: sockaddr_nl is a plain C struct, all zeroes is a valid value
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    addr.nl_groups =
+        (libc::RTMGRP_LINK | libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR) as u32;
+    // SAFETY: addr is a valid sockaddr_nl and the size matches
+    let ret = unsafe {
+        libc::bind(
+            fd.as_raw_fd(),
+            &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+        )
+    };
+    if ret < 0 {
+        return Err(anyhow!(
+            "Cannot bind netlink socket: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(fd)
+}
+
+async fn read_events(tx: UnboundedSender<NetlinkEvent>) -> Result<()> {
+    let socket = AsyncFd::new(open_socket()?)?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let mut guard = socket.readable().await?;
+        let len = match guard.try_io(|fd| {
+            // SAFETY: buf is valid for buf.len() bytes
+            let ret = unsafe {
+                libc::recv(
+                    fd.as_raw_fd(),
+                    buf.as_mut_ptr() as *mut libc::c_void,
+                    buf.len(),
+                    0,
+                )
+            };
+            if ret < 0 {
+                Err(std::io::Error::last_os_error())
+            } else {
+                Ok(ret as usize)
+            }
+        }) {
+            Ok(Ok(len)) => len,
+            // the socket buffer overflowed on a burst of changes. The next
+            // NetworkStatus from EVE brings the model up to date again
+            Ok(Err(e)) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                warn!("Netlink events lost: {}", e);
+                continue;
+            }
+            Ok(Err(e)) => return Err(e.into()),
+            Err(_would_block) => continue,
+        };
+        for event in parse_messages(&buf[..len]) {
+            trace!("Netlink event: {:?}", event);
+            tx.send(event)?;
+        }
+    }
+}
+
+// link and address changes from the kernel. EVE reports them only periodically
+pub struct NetlinkCollector {
+    task: CollectorTask<NetlinkEvent>,
+}
+
+impl NetlinkCollector {
+    pub fn new() -> Self {
+        Self {
+            task: CollectorTask::new("netlink"),
+        }
+    }
+}
+
+impl Collector for NetlinkCollector {
+    type Output = NetlinkEvent;
+
+    fn task(&self) -> &CollectorTask<NetlinkEvent> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<NetlinkEvent> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        self.task.spawn(|tx, cancel_token| async move {
+            debug!("Subscribing to netlink link and address events");
+            tokio::select! {
+                _ = cancel_token.cancelled() => Ok(()),
+                result = read_events(tx) => result,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // dumps of the loopback interface, see fixtures/README.md
+    const NEWLINK: &[u8] = include_bytes!("../../fixtures/netlink/lo-newlink.bin");
+    const NEWADDR: &[u8] = include_bytes!("../../fixtures/netlink/lo-newaddr.bin");
+
+    #[test]
+    fn test_parse_messages() {
+        let lo_up = NetlinkEvent::Link {
+            name: "lo".to_string(),
+            up: true,
+        };
+        let lo_addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let buf = [NEWLINK, NEWADDR].concat();
+        assert_eq!(
+            parse_messages(&buf),
+            vec![
+                lo_up.clone(),
+                NetlinkEvent::AddressAdded {
+                    name: "lo".to_string(),
+                    addr: lo_addr
+                }
+            ]
+        );
+
+        let mut deladdr = NEWADDR.to_vec();
+        deladdr[4..6].copy_from_slice(&libc::RTM_DELADDR.to_ne_bytes());
+        assert_eq!(
+            parse_messages(&deladdr),
+            vec![NetlinkEvent::AddressRemoved {
+                name: "lo".to_string(),
+                addr: lo_addr
+            }]
+        );
+
+        // a message cut short is dropped, the ones before it are kept
+        for len in 0..buf.len() {
+            let expected = if len < NEWLINK.len() {
+                vec![]
+            } else {
+                vec![lo_up.clone()]
+            };
+            assert_eq!(parse_messages(&buf[..len]), expected, "length {}", len);
+        }
+
+        // nlmsg_len beyond the end of the buffer
+        let mut overrun = NEWLINK.to_vec();
+        overrun[0..4].copy_from_slice(&(NEWLINK.len() as u32 + 4).to_ne_bytes());
+        assert_eq!(parse_messages(&overrun), vec![]);
+
+        // rta_len of the first attribute beyond the end of the message
+        let mut overrun = NEWADDR.to_vec();
+        let first = NLMSG_HDR_LEN + IFADDRMSG_LEN;
+        overrun[first..first + 2].copy_from_slice(&u16::MAX.to_ne_bytes());
+        assert_eq!(parse_messages(&overrun), vec![]);
+        let mut overrun = NEWLINK.to_vec();
+        let first = NLMSG_HDR_LEN + IFINFOMSG_LEN;
+        overrun[first..first + 2].copy_from_slice(&u16::MAX.to_ne_bytes());
+        assert_eq!(parse_messages(&overrun), vec![]);
+
+        assert_eq!(
+            parse_link(libc::RTM_NEWLINK, &NEWLINK[NLMSG_HDR_LEN..20]),
+            None
+        );
+        assert_eq!(
+            parse_address(libc::RTM_NEWADDR, &NEWADDR[NLMSG_HDR_LEN..20]),
+            None
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::{path::Path, time::Instant};
+
+use anyhow::{Context, Result};
+use log::trace;
+use tokio::sync::watch;
+use tokio::time::Duration;
+
+use super::{sample_while_active, Collector, CollectorTask};
+
+const SYS_CLASS_NET: &str = "/sys/class/net";
+
+/// Counters of /sys/class/net/<iface>/statistics. They only grow until the
+/// interface is recreated
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IfaceCounters {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetStatsSample {
+    pub at: Instant,
+    pub counters: Vec<IfaceCounters>,
+}
+
+fn read_counter(dir: &Path, name: &str) -> u64 {
+    // virtual interfaces may lack some counters
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn read_counters(root: &Path) -> Result<Vec<IfaceCounters>> {
+    let entries =
+        std::fs::read_dir(root).with_context(|| format!("Cannot read {}", root.display()))?;
+    let mut counters: Vec<IfaceCounters> = entries
+        .flatten()
+        .map(|entry| {
+            let dir = entry.path().join("statistics");
+            IfaceCounters {
+                name: entry.file_name().to_string_lossy().to_string(),
+                rx_bytes: read_counter(&dir, "rx_bytes"),
+                tx_bytes: read_counter(&dir, "tx_bytes"),
+                rx_packets: read_counter(&dir, "rx_packets"),
+                tx_packets: read_counter(&dir, "tx_packets"),
+                rx_errors: read_counter(&dir, "rx_errors"),
+                tx_errors: read_counter(&dir, "tx_errors"),
+                rx_dropped: read_counter(&dir, "rx_dropped"),
+                tx_dropped: read_counter(&dir, "tx_dropped"),
+            }
+        })
+        .collect();
+    counters.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(counters)
+}
+
+// traffic counters of all interfaces, sampled with the UI refresh interval
+pub struct NetStatsCollector {
+    task: CollectorTask<NetStatsSample>,
+    period: Duration,
+    // nothing is sampled while the user is idle
+    idle_rx: watch::Receiver<bool>,
+}
+
+impl NetStatsCollector {
+    pub fn new(period: Duration, idle_rx: watch::Receiver<bool>) -> Self {
+        Self {
+            task: CollectorTask::new("netstats"),
+            period,
+            idle_rx,
+        }
+    }
+
+    /// Takes effect on the next start
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+    }
+}
+
+impl Collector for NetStatsCollector {
+    type Output = NetStatsSample;
+
+    fn task(&self) -> &CollectorTask<NetStatsSample> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<NetStatsSample> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let period = self.period;
+        let idle_rx = self.idle_rx.clone();
+        self.task.spawn(move |tx, cancel_token| async move {
+            sample_while_active(period, idle_rx, &cancel_token, |at| {
+                let counters = read_counters(Path::new(SYS_CLASS_NET))?;
+                trace!("Read counters of {} interfaces", counters.len());
+                tx.send(NetStatsSample {
+                    at: at.into_std(),
+                    counters,
+                })?;
+                Ok(())
+            })
+            .await
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_counters() {
+        let root = std::env::temp_dir().join(format!("netstats-{}", std::process::id()));
+        let stats = root.join("eth0").join("statistics");
+        std::fs::create_dir_all(&stats).unwrap();
+        std::fs::write(stats.join("rx_bytes"), "1234\n").unwrap();
+        std::fs::write(stats.join("tx_packets"), "7\n").unwrap();
+        // no statistics at all
+        std::fs::create_dir_all(root.join("bond0")).unwrap();
+
+        let counters = read_counters(&root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(counters.len(), 2);
+        assert_eq!(counters[0].name, "bond0");
+        assert_eq!(counters[0].rx_bytes, 0);
+        assert_eq!(counters[1].rx_bytes, 1234);
+        assert_eq!(counters[1].tx_packets, 7);
+    }
+}
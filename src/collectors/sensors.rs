@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::trace;
+use tokio::time::Duration;
+
+use super::{Collector, CollectorTask};
+
+const SYS_CLASS_HWMON: &str = "/sys/class/hwmon";
+
+/// A temperature sensor of /sys/class/hwmon in °C
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    // driver name, e.g. "coretemp"
+    pub chip: String,
+    // e.g. "Package id 0", "temp1" if the driver has no labels
+    pub label: String,
+    pub celsius: f64,
+    // limits the hardware reports
+    pub max: Option<f64>,
+    pub crit: Option<f64>,
+}
+
+impl SensorReading {
+    /// "chip/label", how thresholds in the config refer to the sensor
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.chip, self.label)
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+// values are in millidegrees
+fn read_celsius(path: &Path) -> Option<f64> {
+    read_trimmed(path)?
+        .parse::<i64>()
+        .ok()
+        .map(|m| m as f64 / 1000.0)
+}
+
+fn read_sensors(root: &Path) -> Result<Vec<SensorReading>> {
+    let entries =
+        std::fs::read_dir(root).with_context(|| format!("Cannot read {}", root.display()))?;
+    let mut chips: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    chips.sort();
+    let mut readings = Vec::new();
+    for dir in chips {
+        let chip = read_trimmed(&dir.join("name")).unwrap_or_else(|| {
+            dir.file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string()
+        });
+        let Ok(files) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        // temp1_input, temp2_input...
+        let mut inputs: Vec<String> = files
+            .flatten()
+            .filter_map(|f| {
+                let name = f.file_name().to_string_lossy().to_string();
+                Some(name.strip_suffix("_input")?.to_string())
+                    .filter(|sensor| sensor.starts_with("temp"))
+            })
+            .collect();
+        inputs.sort_by_key(|sensor| sensor[4..].parse::<u32>().unwrap_or(0));
+        for sensor in inputs {
+            // a sensor that cannot be read right now, e.g. of a sleeping disk
+            let Some(celsius) = read_celsius(&dir.join(format!("{}_input", sensor))) else {
+                continue;
+            };
+            readings.push(SensorReading {
+                chip: chip.clone(),
+                label: read_trimmed(&dir.join(format!("{}_label", sensor)))
+                    .unwrap_or_else(|| sensor.clone()),
+                celsius,
+                max: read_celsius(&dir.join(format!("{}_max", sensor))),
+                crit: read_celsius(&dir.join(format!("{}_crit", sensor))),
+            });
+        }
+    }
+    Ok(readings)
+}
+
+// temperatures of all hwmon chips. They change slowly, a fixed period is enough.
+// Sampled while the user is idle too, the overheating alerts are for unattended boxes
+pub struct SensorsCollector {
+    task: CollectorTask<Vec<SensorReading>>,
+    period: Duration,
+}
+
+impl SensorsCollector {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            task: CollectorTask::new("sensors"),
+            period,
+        }
+    }
+}
+
+impl Collector for SensorsCollector {
+    type Output = Vec<SensorReading>;
+
+    fn task(&self) -> &CollectorTask<Vec<SensorReading>> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<Vec<SensorReading>> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let period = self.period;
+        self.task.spawn(move |tx, cancel_token| async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = cancel_token.cancelled() => return Ok(()),
+                    _ = interval.tick() => {
+                        let readings = read_sensors(Path::new(SYS_CLASS_HWMON))?;
+                        trace!("Read {} sensors", readings.len());
+                        tx.send(readings)?;
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_sensors() {
+        let root = std::env::temp_dir().join(format!("hwmon-{}", std::process::id()));
+        let coretemp = root.join("hwmon1");
+        std::fs::create_dir_all(&coretemp).unwrap();
+        std::fs::write(coretemp.join("name"), "coretemp\n").unwrap();
+        std::fs::write(coretemp.join("temp1_input"), "45000\n").unwrap();
+        std::fs::write(coretemp.join("temp1_label"), "Package id 0\n").unwrap();
+        std::fs::write(coretemp.join("temp1_crit"), "100000\n").unwrap();
+        std::fs::write(coretemp.join("temp10_input"), "41500\n").unwrap();
+        std::fs::write(coretemp.join("temp2_input"), "43000\n").unwrap();
+        // not a temperature
+        std::fs::write(coretemp.join("fan1_input"), "1200\n").unwrap();
+
+        let readings = read_sensors(&root).unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            readings.iter().map(|r| r.key()).collect::<Vec<_>>(),
+            vec!["coretemp/Package id 0", "coretemp/temp2", "coretemp/temp10"]
+        );
+        assert_eq!(readings[0].celsius, 45.0);
+        assert_eq!(readings[0].crit, Some(100.0));
+        assert_eq!(readings[0].max, None);
+        assert_eq!(readings[2].celsius, 41.5);
+    }
+}
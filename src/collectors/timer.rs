@@ -0,0 +1,65 @@
+use tokio::sync::watch;
+use tokio::time::Duration;
+
+use crate::events::Event;
+
+use super::{Collector, CollectorTask};
+
+// tick period while the user is idle
+const IDLE_TICK_PERIOD: Duration = Duration::from_secs(5);
+
+// periodic Tick events. Slows down while the user is idle
+pub struct TimerCollector {
+    task: CollectorTask<Event>,
+    period: Duration,
+    idle_rx: watch::Receiver<bool>,
+}
+
+impl TimerCollector {
+    pub fn new(period: Duration, idle_rx: watch::Receiver<bool>) -> Self {
+        Self {
+            task: CollectorTask::new("timer"),
+            period,
+            idle_rx,
+        }
+    }
+
+    /// Takes effect on the next start
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+    }
+}
+
+impl Collector for TimerCollector {
+    type Output = Event;
+
+    fn task(&self) -> &CollectorTask<Event> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<Event> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let active_period = self.period;
+        let mut idle_rx = self.idle_rx.clone();
+        self.task.spawn(move |timer_tx, cancel_token| async move {
+            while !cancel_token.is_cancelled() {
+                let period = if *idle_rx.borrow_and_update() {
+                    IDLE_TICK_PERIOD
+                } else {
+                    active_period
+                };
+                tokio::select! {
+                    _ = tokio::time::timeout(period, cancel_token.cancelled() ) => {
+                        timer_tx.send(Event::Tick)?;
+                    }
+                    // switch the period as soon as the idle state changes
+                    Ok(_) = idle_rx.changed() => {}
+                }
+            }
+            Ok(())
+        });
+    }
+}
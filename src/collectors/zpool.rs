@@ -0,0 +1,76 @@
+use std::process::Stdio;
+
+use log::{info, warn};
+use tokio::process::Command;
+use tokio::time::Duration;
+
+use crate::model::device::zfs::{parse_zpool_status, ZfsPool};
+
+use super::{Collector, CollectorTask};
+
+// zpool may hang on a suspended pool, exactly when its state matters
+const ZPOOL_TIMEOUT: Duration = Duration::from_secs(10);
+
+// pools from `zpool status` for EVE versions that do not report them over IPC
+pub struct ZpoolCollector {
+    task: CollectorTask<Vec<ZfsPool>>,
+    period: Duration,
+}
+
+impl ZpoolCollector {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            task: CollectorTask::new("zpool"),
+            period,
+        }
+    }
+}
+
+impl Collector for ZpoolCollector {
+    type Output = Vec<ZfsPool>;
+
+    fn task(&self) -> &CollectorTask<Vec<ZfsPool>> {
+        &self.task
+    }
+
+    fn task_mut(&mut self) -> &mut CollectorTask<Vec<ZfsPool>> {
+        &mut self.task
+    }
+
+    fn start(&mut self) {
+        let period = self.period;
+        self.task.spawn(move |tx, cancel_token| async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                tokio::select! {
+                    _ = cancel_token.cancelled() => return Ok(()),
+                    _ = interval.tick() => {}
+                }
+                let zpool = Command::new("zpool")
+                    .arg("status")
+                    .stdin(Stdio::null())
+                    .kill_on_drop(true)
+                    .output();
+                let output = tokio::select! {
+                    _ = cancel_token.cancelled() => return Ok(()),
+                    output = tokio::time::timeout(ZPOOL_TIMEOUT, zpool) => output,
+                };
+                match output {
+                    Ok(Ok(output)) if output.status.success() => {
+                        tx.send(parse_zpool_status(&String::from_utf8_lossy(&output.stdout)))?;
+                    }
+                    Ok(Ok(output)) => warn!("zpool status failed: {}", output.status),
+                    // not every EVE build has ZFS
+                    Ok(Err(e)) => {
+                        info!("Cannot run zpool: {}", e);
+                        return Ok(());
+                    }
+                    Err(_) => warn!(
+                        "zpool status did not finish in {}s",
+                        ZPOOL_TIMEOUT.as_secs()
+                    ),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,470 @@
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, Context};
+use chrono::format::{Item, StrftimeItems};
+use log::{info, warn, LevelFilter};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::MonitorError;
+use crate::ipc::chunked::DEFAULT_MAX_MESSAGE;
+use crate::model::alerts::AlertSeverity;
+use crate::ui::summary_page::SummaryPanel;
+use crate::ui::theme::ThemeName;
+
+const EVE_MONITOR_CONFIG_EVE: &str = "/persist/monitor/config.json";
+const EVE_MONITOR_CONFIG: &str = "./persist/monitor/config.json";
+
+// devices with less RAM than this are considered low-memory. 1 GB boards report
+// a bit less than 1 GiB in MemTotal so leave some headroom
+const LOW_MEMORY_THRESHOLD_KB: u64 = 1536 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct MonitorConfig {
+    // force low-memory mode on or off. Detected from /proc/meminfo when not set
+    pub low_memory: Option<bool>,
+    // strftime format of the status bar clock. Empty string hides the clock
+    pub clock_format: String,
+    // seconds without key presses before collectors are slowed down. 0 disables it
+    pub idle_timeout: u64,
+    // kernel messages matching these patterns raise alerts. Replaces the default set
+    #[cfg(feature = "dmesg")]
+    pub dmesg_alerts: Vec<DmesgAlertRule>,
+    // alternative key sequences for consoles that mangle modifier keys
+    pub keymap: KeymapConfig,
+    // visible panels of the summary page in the order they are shown
+    pub summary_panels: Vec<SummaryPanel>,
+    // kernel messages kept in memory. 0 picks the default for the memory mode
+    pub dmesg_lines: usize,
+    // one of "error", "warn", "info" or "debug"
+    pub log_level: String,
+    // period of the UI timer in milliseconds
+    pub tick_ms: u64,
+    // collectors that are not started, only "kmsg", "netlink", "netstats",
+    // "metrics" and "sensors" can be disabled
+    pub disabled_collectors: Vec<String>,
+    // "dark", "light" or "high-contrast"
+    pub theme: ThemeName,
+    // theme color -> color name or "#rrggbb", e.g. "error": "#ff8000"
+    pub theme_colors: BTreeMap<String, String>,
+    // when the app summary turns yellow and red
+    pub app_thresholds: AppThresholds,
+    // when temperature sensors turn yellow and red
+    pub sensor_thresholds: SensorThresholds,
+    // which alerts are kept and shown as toasts
+    pub alerts: AlertsConfig,
+    // only show the state, actions that change the device are refused
+    pub read_only: bool,
+    // longer IPC messages from EVE are dropped
+    pub ipc_max_message_kb: usize,
+    // lock the UI with a passcode after a period without key presses
+    pub lock: LockConfig,
+}
+
+// faster ticks only burn CPU
+const MIN_TICK_MS: u64 = 100;
+const DEFAULT_CLOCK_FORMAT: &str = "%H:%M:%S";
+
+/// Settings that the settings page changes without a restart
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeSettings {
+    pub log_level: LevelFilter,
+    pub tick_ms: u64,
+    // seconds, 0 disables idle detection
+    pub idle_timeout: u64,
+    pub key_chords: bool,
+    pub theme: ThemeName,
+    // collectors that can be switched off
+    pub kmsg: bool,
+    pub netlink: bool,
+    pub netstats: bool,
+    pub metrics: bool,
+    pub sensors: bool,
+}
+
+impl RuntimeSettings {
+    pub fn idle_duration(&self) -> Option<Duration> {
+        match self.idle_timeout {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        MonitorConfig::default().settings()
+    }
+}
+
+/// Percent of the expected apps that must be running for the app summary
+/// to be green or at least yellow
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AppThresholds {
+    pub ok_percent: u8,
+    pub warning_percent: u8,
+}
+
+impl Default for AppThresholds {
+    fn default() -> Self {
+        Self {
+            ok_percent: 100,
+            warning_percent: 50,
+        }
+    }
+}
+
+/// Temperatures in °C at which sensors turn yellow and red. The limits the
+/// hardware reports win over `warning` and `critical`, the entries of
+/// `critical_by_sensor` win over both
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SensorThresholds {
+    pub warning: f64,
+    pub critical: f64,
+    // "chip/label" -> °C, e.g. "coretemp/Package id 0": 90
+    pub critical_by_sensor: BTreeMap<String, f64>,
+}
+
+impl Default for SensorThresholds {
+    fn default() -> Self {
+        Self {
+            warning: 80.0,
+            critical: 95.0,
+            critical_by_sensor: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    // alerts at least this severe pop up as toasts
+    pub toast_severity: AlertSeverity,
+    // seconds a toast stays, 0 disables toasts
+    pub toast_seconds: u64,
+    // alerts of these sources, e.g. "dmesg", are only logged
+    pub muted_sources: Vec<String>,
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            toast_severity: AlertSeverity::Warning,
+            toast_seconds: 5,
+            muted_sources: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LockConfig {
+    // minutes without key presses before the UI locks, 0 disables the lock
+    pub timeout_minutes: u64,
+    // as printed by `monitor --hash-passcode`. Empty disables the lock
+    pub passcode_hash: String,
+}
+
+impl Default for LockConfig {
+    fn default() -> Self {
+        Self {
+            timeout_minutes: 10,
+            passcode_hash: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct KeymapConfig {
+    // chords can be switched off without losing the leader and the chords
+    pub enabled: bool,
+    // key that starts a chord. Empty string disables chords
+    pub leader: String,
+    // key pressed after the leader -> key it stands for e.g. "e" -> "ctrl+e".
+    // Replaces the default set
+    pub chords: BTreeMap<String, String>,
+}
+
+impl Default for KeymapConfig {
+    fn default() -> Self {
+        let mut chords: BTreeMap<String, String> = [
+            ("h", "ctrl+left"),
+            ("l", "ctrl+right"),
+            ("s", "ctrl+s"),
+            ("b", "ctrl+b"),
+            ("e", "ctrl+e"),
+            ("r", "ctrl+r"),
+            ("p", "ctrl+p"),
+            ("t", "ctrl+t"),
+            ("g", "ctrl+g"),
+            ("o", "ctrl+o"),
+            ("c", "alt+c"),
+            ("j", "alt+j"),
+        ]
+        .iter()
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .collect();
+        for tab in '1'..='9' {
+            chords.insert(tab.to_string(), format!("alt+{}", tab));
+        }
+        Self {
+            enabled: true,
+            leader: "`".to_string(),
+            chords,
+        }
+    }
+}
+
+#[cfg(feature = "dmesg")]
+#[derive(Debug, Deserialize)]
+pub struct DmesgAlertRule {
+    pub name: String,
+    // regular expression matched against the message text
+    pub pattern: String,
+    pub severity: AlertSeverity,
+}
+
+#[cfg(feature = "dmesg")]
+impl DmesgAlertRule {
+    fn new(name: &str, pattern: &str, severity: AlertSeverity) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            severity,
+        }
+    }
+}
+
+#[cfg(feature = "dmesg")]
+fn default_dmesg_alerts() -> Vec<DmesgAlertRule> {
+    vec![
+        DmesgAlertRule::new(
+            "I/O error",
+            r"(?i)(I/O error|blk_update_request|critical medium error)",
+            AlertSeverity::Critical,
+        ),
+        DmesgAlertRule::new(
+            "OOM kill",
+            r"(?i)(Out of memory|oom-kill|Killed process \d+)",
+            AlertSeverity::Critical,
+        ),
+        DmesgAlertRule::new(
+            "Thermal throttling",
+            r"(?i)(temperature above threshold|clock throttled|thermal .*critical)",
+            AlertSeverity::Warning,
+        ),
+        DmesgAlertRule::new(
+            "Watchdog",
+            r"(?i)(watchdog.*(reset|timeout|did not stop)|soft lockup|hard LOCKUP)",
+            AlertSeverity::Critical,
+        ),
+    ]
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            low_memory: None,
+            clock_format: DEFAULT_CLOCK_FORMAT.to_string(),
+            idle_timeout: 300,
+            #[cfg(feature = "dmesg")]
+            dmesg_alerts: default_dmesg_alerts(),
+            keymap: KeymapConfig::default(),
+            summary_panels: SummaryPanel::defaults(),
+            dmesg_lines: 0,
+            log_level: "debug".to_string(),
+            tick_ms: 500,
+            disabled_collectors: Vec::new(),
+            theme: ThemeName::default(),
+            theme_colors: BTreeMap::new(),
+            app_thresholds: AppThresholds::default(),
+            sensor_thresholds: SensorThresholds::default(),
+            alerts: AlertsConfig::default(),
+            read_only: false,
+            ipc_max_message_kb: DEFAULT_MAX_MESSAGE / 1024,
+            lock: LockConfig::default(),
+        }
+    }
+}
+
+impl MonitorConfig {
+    pub fn get_config_path() -> PathBuf {
+        // same trick as for the log directory: XDG_RUNTIME_DIR is only set on desktop linux
+        if std::env::var("XDG_RUNTIME_DIR").is_ok() {
+            PathBuf::from(EVE_MONITOR_CONFIG)
+        } else {
+            PathBuf::from(EVE_MONITOR_CONFIG_EVE)
+        }
+    }
+
+    fn load_from(path: &PathBuf) -> Result<Self, MonitorError> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read {}", path.display()))
+            .map_err(MonitorError::Config)?;
+        let mut config: Self = serde_json::from_str(&data)
+            .with_context(|| format!("Cannot parse {}", path.display()))
+            .map_err(MonitorError::Config)?;
+        // chrono panics when formatting with a broken spec
+        if !is_valid_clock_format(&config.clock_format) {
+            warn!(
+                "Invalid clock_format '{}', using {}",
+                config.clock_format, DEFAULT_CLOCK_FORMAT
+            );
+            config.clock_format = DEFAULT_CLOCK_FORMAT.to_string();
+        }
+        Ok(config)
+    }
+
+    /// Load the configuration. A missing or broken file is not fatal, defaults are used
+    pub fn load() -> Self {
+        let path = Self::get_config_path();
+        if !path.exists() {
+            info!("No config file at {}, using defaults", path.display());
+            return Self::default();
+        }
+        match Self::load_from(&path) {
+            Ok(config) => {
+                info!("Loaded config: {:?}", config);
+                config
+            }
+            Err(e) => {
+                e.log();
+                info!("Using default config");
+                Self::default()
+            }
+        }
+    }
+
+    /// Contents of the config file with the summary panels replaced. The other
+    /// settings are kept as the user wrote them
+    pub fn with_summary_panels(
+        panels: &[SummaryPanel],
+    ) -> Result<(PathBuf, Vec<u8>), MonitorError> {
+        let panels = serde_json::to_value(panels).map_err(|e| MonitorError::Config(e.into()))?;
+        Self::with_values(vec![("summary_panels", panels)])
+    }
+
+    /// Contents of the config file with the runtime settings replaced
+    pub fn with_settings(settings: &RuntimeSettings) -> Result<(PathBuf, Vec<u8>), MonitorError> {
+        let disabled: Vec<&str> = [
+            ("kmsg", settings.kmsg),
+            ("netlink", settings.netlink),
+            ("netstats", settings.netstats),
+            ("metrics", settings.metrics),
+            ("sensors", settings.sensors),
+        ]
+        .iter()
+        .filter(|(_, enabled)| !enabled)
+        .map(|(name, _)| *name)
+        .collect();
+        Self::with_values(vec![
+            (
+                "log_level",
+                Value::from(settings.log_level.as_str().to_lowercase()),
+            ),
+            ("tick_ms", Value::from(settings.tick_ms)),
+            ("idle_timeout", Value::from(settings.idle_timeout)),
+            ("keymap.enabled", Value::from(settings.key_chords)),
+            ("theme", Value::from(settings.theme.to_string())),
+            ("disabled_collectors", Value::from(disabled)),
+        ])
+    }
+
+    // `values` are (key, value), a key like "keymap.leader" is nested
+    fn with_values(values: Vec<(&str, Value)>) -> Result<(PathBuf, Vec<u8>), MonitorError> {
+        let path = Self::get_config_path();
+        let mut config = if path.exists() {
+            let data = std::fs::read_to_string(&path)
+                .with_context(|| format!("Cannot read {}", path.display()))
+                .map_err(MonitorError::Config)?;
+            // do not overwrite a file the user has to fix by hand
+            serde_json::from_str(&data)
+                .with_context(|| format!("Cannot parse {}", path.display()))
+                .map_err(MonitorError::Config)?
+        } else {
+            Value::Object(Default::default())
+        };
+        for (key, value) in values {
+            let mut object = config.as_object_mut();
+            let mut parts = key.split('.').peekable();
+            while let Some(part) = parts.next() {
+                let Some(parent) = object else {
+                    return Err(MonitorError::Config(anyhow!(
+                        "{}: {} is not a JSON object",
+                        path.display(),
+                        key
+                    )));
+                };
+                if parts.peek().is_none() {
+                    parent.insert(part.to_string(), value);
+                    break;
+                }
+                object = parent
+                    .entry(part)
+                    .or_insert_with(|| Value::Object(Default::default()))
+                    .as_object_mut();
+            }
+        }
+        let data =
+            serde_json::to_vec_pretty(&config).map_err(|e| MonitorError::Config(e.into()))?;
+        Ok((path, data))
+    }
+
+    pub fn settings(&self) -> RuntimeSettings {
+        let log_level = LevelFilter::from_str(&self.log_level).unwrap_or_else(|_| {
+            warn!("Invalid log level '{}', using debug", self.log_level);
+            LevelFilter::Debug
+        });
+        let enabled = |name: &str| !self.disabled_collectors.iter().any(|c| c == name);
+        RuntimeSettings {
+            log_level,
+            tick_ms: self.tick_ms.max(MIN_TICK_MS),
+            idle_timeout: self.idle_timeout,
+            key_chords: self.keymap.enabled,
+            theme: self.theme,
+            kmsg: enabled("kmsg"),
+            netlink: enabled("netlink"),
+            netstats: enabled("netstats"),
+            metrics: enabled("metrics"),
+            sensors: enabled("sensors"),
+        }
+    }
+
+    pub fn is_low_memory(&self) -> bool {
+        self.low_memory.unwrap_or_else(|| {
+            total_memory_kb().is_some_and(|total| total < LOW_MEMORY_THRESHOLD_KB)
+        })
+    }
+}
+
+fn is_valid_clock_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| item == Item::Error)
+}
+
+fn total_memory_kb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    // MemTotal:        1004580 kB
+    meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_format() {
+        assert!(is_valid_clock_format(DEFAULT_CLOCK_FORMAT));
+        assert!(is_valid_clock_format("%Y-%m-%d %H:%M"));
+        assert!(is_valid_clock_format(""));
+        assert!(!is_valid_clock_format("%Q"));
+        assert!(!is_valid_clock_format("%H:%"));
+    }
+}
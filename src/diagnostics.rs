@@ -0,0 +1,473 @@
+// Ad-hoc connectivity checks of one interface: ping the gateway, resolve the
+// controller with the DNS servers of the interface and open a TLS connection
+// to it. Every socket is bound to the interface so the checks do not take
+// another route
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use log::{debug, info};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpSocket, TcpStream, UdpSocket},
+    process::Command,
+    sync::mpsc::UnboundedSender,
+    task::JoinHandle,
+    time::timeout,
+};
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::model::diagnostics::StepState;
+
+const PING_TIMEOUT: Duration = Duration::from_secs(15);
+const DNS_TIMEOUT: Duration = Duration::from_secs(3);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const TLS_TIMEOUT: Duration = Duration::from_secs(5);
+
+const STEP_PING: usize = 0;
+const STEP_DNS: usize = 1;
+const STEP_TCP: usize = 2;
+const STEP_TLS: usize = 3;
+
+#[derive(Debug)]
+pub struct DiagnosticsEvent {
+    pub step: usize,
+    pub state: StepState,
+}
+
+/// What to check, taken from the interface status and the node status
+#[derive(Debug, Clone)]
+pub struct DiagnosticsTarget {
+    pub iface: String,
+    pub gateway: Option<IpAddr>,
+    pub dns: Vec<IpAddr>,
+    // controller URL as EVE reports it, the scheme is optional
+    pub server: Option<String>,
+}
+
+impl DiagnosticsTarget {
+    /// Names of the steps in the order they run
+    pub fn step_names(&self) -> Vec<String> {
+        let gateway = self
+            .gateway
+            .map_or("gateway".to_string(), |gw| format!("gateway {}", gw));
+        let (host, port) = self
+            .server
+            .as_deref()
+            .and_then(controller_address)
+            .unwrap_or(("controller".to_string(), 443));
+        vec![
+            format!("Ping {}", gateway),
+            format!("Resolve {}", host),
+            format!("TCP connect to {}:{}", host, port),
+            format!("TLS handshake with {}", host),
+        ]
+    }
+}
+
+// host and port of the controller URL
+fn controller_address(server: &str) -> Option<(String, u16)> {
+    let server = server.trim();
+    let url = if server.contains("://") {
+        Url::parse(server)
+    } else {
+        Url::parse(&format!("https://{}", server))
+    }
+    .ok()?;
+    let host = url.host_str()?.trim_matches(['[', ']']).to_string();
+    Some((host, url.port_or_known_default().unwrap_or(443)))
+}
+
+// not for cryptography, only the DNS id and the TLS random
+fn pseudo_random() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    nanos ^ ((std::process::id() as u64) << 32)
+}
+
+async fn ping(iface: &str, gateway: IpAddr) -> Result<String> {
+    let output = Command::new("ping")
+        .args(["-c", "3", "-W", "2", "-I", iface, &gateway.to_string()])
+        .kill_on_drop(true)
+        .output();
+    let output = timeout(PING_TIMEOUT, output)
+        .await
+        .map_err(|_| anyhow!("no answer in {} seconds", PING_TIMEOUT.as_secs()))?
+        .context("cannot run ping")?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // "3 packets transmitted, 3 packets received, 0% packet loss"
+    let summary = stdout
+        .lines()
+        .find(|l| l.contains("packet loss"))
+        .map(|l| l.trim().to_string());
+    if output.status.success() {
+        Ok(summary.unwrap_or_else(|| "gateway answered".to_string()))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr.lines().next().map(|l| l.trim().to_string());
+        bail!(
+            "{}",
+            error
+                .or(summary)
+                .unwrap_or_else(|| "gateway did not answer".to_string())
+        )
+    }
+}
+
+fn build_dns_query(id: u16, host: &str) -> Vec<u8> {
+    let mut query = Vec::with_capacity(host.len() + 18);
+    query.extend_from_slice(&id.to_be_bytes());
+    // recursion desired, one question
+    query.extend_from_slice(&[0x01, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]);
+    for label in host.trim_end_matches('.').split('.') {
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    // type A, class IN
+    query.extend_from_slice(&[0x00, 0x01, 0x00, 0x01]);
+    query
+}
+
+// offset after a possibly compressed name
+fn skip_dns_name(data: &[u8], mut pos: usize) -> Result<usize> {
+    loop {
+        let len = *data.get(pos).context("truncated DNS answer")? as usize;
+        if len == 0 {
+            return Ok(pos + 1);
+        }
+        if len & 0xc0 == 0xc0 {
+            return Ok(pos + 2);
+        }
+        pos += 1 + len;
+    }
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u16> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .context("truncated DNS answer")
+}
+
+fn parse_dns_response(id: u16, data: &[u8]) -> Result<Vec<Ipv4Addr>> {
+    if data.len() < 12 || read_u16(data, 0)? != id {
+        bail!("unexpected DNS answer");
+    }
+    match data[3] & 0x0f {
+        0 => {}
+        2 => bail!("server failure"),
+        3 => bail!("host not found"),
+        5 => bail!("query refused"),
+        rcode => bail!("DNS error code {}", rcode),
+    }
+    let questions = read_u16(data, 4)?;
+    let answers = read_u16(data, 6)?;
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_dns_name(data, pos)? + 4;
+    }
+    let mut addrs = Vec::new();
+    for _ in 0..answers {
+        pos = skip_dns_name(data, pos)?;
+        let rtype = read_u16(data, pos)?;
+        let len = read_u16(data, pos + 8)? as usize;
+        pos += 10;
+        let rdata = data.get(pos..pos + len).context("truncated DNS answer")?;
+        // CNAME records come before the addresses
+        if rtype == 1 && len == 4 {
+            addrs.push(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]));
+        }
+        pos += len;
+    }
+    Ok(addrs)
+}
+
+async fn dns_query(iface: &str, server: IpAddr, host: &str) -> Result<Vec<Ipv4Addr>> {
+    let local: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (std::net::Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket
+        .bind_device(Some(iface.as_bytes()))
+        .context("cannot bind to the interface")?;
+    socket.connect((server, 53)).await?;
+
+    let id = pseudo_random() as u16;
+    socket.send(&build_dns_query(id, host)).await?;
+    let mut buf = [0u8; 512];
+    let len = timeout(DNS_TIMEOUT, socket.recv(&mut buf))
+        .await
+        .map_err(|_| anyhow!("no answer"))??;
+    parse_dns_response(id, &buf[..len])
+}
+
+async fn resolve(iface: &str, dns: &[IpAddr], host: &str) -> Result<(IpAddr, String)> {
+    if dns.is_empty() {
+        bail!("{} has no DNS server", iface);
+    }
+    let mut errors = Vec::new();
+    for server in dns {
+        match dns_query(iface, *server, host).await {
+            Ok(addrs) if !addrs.is_empty() => {
+                let list = addrs
+                    .iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Ok((IpAddr::V4(addrs[0]), format!("{} via {}", list, server)));
+            }
+            Ok(_) => errors.push(format!("{}: no IPv4 address", server)),
+            Err(e) => errors.push(format!("{}: {}", server, e)),
+        }
+    }
+    bail!("{}", errors.join("; "))
+}
+
+async fn connect(iface: &str, addr: SocketAddr) -> Result<TcpStream> {
+    let socket = if addr.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    socket
+        .bind_device(Some(iface.as_bytes()))
+        .context("cannot bind to the interface")?;
+    timeout(CONNECT_TIMEOUT, socket.connect(addr))
+        .await
+        .map_err(|_| anyhow!("no answer in {} seconds", CONNECT_TIMEOUT.as_secs()))?
+        .map_err(Into::into)
+}
+
+fn push_u16(buf: &mut Vec<u8>, value: usize) {
+    buf.extend_from_slice(&(value as u16).to_be_bytes());
+}
+
+// a TLS 1.2 ClientHello, enough for the server to answer with its hello and
+// certificate. The handshake is not finished
+fn build_client_hello(host: &str) -> Vec<u8> {
+    let mut extensions = Vec::new();
+    if host.parse::<IpAddr>().is_err() {
+        // server_name
+        extensions.extend_from_slice(&[0x00, 0x00]);
+        push_u16(&mut extensions, host.len() + 5);
+        push_u16(&mut extensions, host.len() + 3);
+        extensions.push(0);
+        push_u16(&mut extensions, host.len());
+        extensions.extend_from_slice(host.as_bytes());
+    }
+    // supported_groups: x25519, secp256r1, secp384r1
+    extensions.extend_from_slice(&[0x00, 0x0a, 0x00, 0x08, 0x00, 0x06, 0x00, 0x1d, 0x00, 0x17]);
+    extensions.extend_from_slice(&[0x00, 0x18]);
+    // ec_point_formats: uncompressed
+    extensions.extend_from_slice(&[0x00, 0x0b, 0x00, 0x02, 0x01, 0x00]);
+    // signature_algorithms: ECDSA and RSA-PSS/PKCS1 with SHA-256/384
+    extensions.extend_from_slice(&[0x00, 0x0d, 0x00, 0x0c, 0x00, 0x0a, 0x04, 0x03, 0x05, 0x03]);
+    extensions.extend_from_slice(&[0x08, 0x04, 0x08, 0x05, 0x04, 0x01]);
+
+    let ciphers: [u16; 8] = [
+        0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0x009c, 0x002f,
+    ];
+    let mut hello = vec![0x03, 0x03];
+    let seed = pseudo_random();
+    hello.extend((0..32).map(|i| (seed >> ((i % 8) * 8)) as u8 ^ i as u8));
+    // no session id
+    hello.push(0);
+    push_u16(&mut hello, ciphers.len() * 2);
+    for cipher in ciphers {
+        hello.extend_from_slice(&cipher.to_be_bytes());
+    }
+    // null compression
+    hello.extend_from_slice(&[0x01, 0x00]);
+    push_u16(&mut hello, extensions.len());
+    hello.extend_from_slice(&extensions);
+
+    let mut handshake = vec![0x01, 0];
+    push_u16(&mut handshake, hello.len());
+    handshake.extend_from_slice(&hello);
+
+    let mut record = vec![0x16, 0x03, 0x01];
+    push_u16(&mut record, handshake.len());
+    record.extend_from_slice(&handshake);
+    record
+}
+
+async fn tls_handshake(stream: &mut TcpStream, host: &str) -> Result<String> {
+    stream.write_all(&build_client_hello(host)).await?;
+    let mut header = [0u8; 5];
+    timeout(TLS_TIMEOUT, stream.read_exact(&mut header))
+        .await
+        .map_err(|_| anyhow!("no answer in {} seconds", TLS_TIMEOUT.as_secs()))?
+        .context("connection closed by the server")?;
+    match header[0] {
+        0x16 => Ok(format!(
+            "server answered with TLS 1.{} (certificate not verified)",
+            header[2].saturating_sub(1)
+        )),
+        0x15 => {
+            let mut alert = [0u8; 2];
+            stream.read_exact(&mut alert).await?;
+            bail!("server sent TLS alert {}", alert[1])
+        }
+        _ => bail!("not a TLS server"),
+    }
+}
+
+fn finished<T>(result: Result<T>, detail: impl FnOnce(&T) -> String) -> StepState {
+    match &result {
+        Ok(value) => StepState::Passed(detail(value)),
+        Err(e) => StepState::Failed(format!("{:#}", e)),
+    }
+}
+
+async fn run(target: DiagnosticsTarget, events_tx: &UnboundedSender<DiagnosticsEvent>) {
+    let send = |step, state| {
+        let _ = events_tx.send(DiagnosticsEvent { step, state });
+    };
+    let iface = target.iface.as_str();
+
+    // the gateway may not answer ICMP, the other steps run anyway
+    if let Some(gateway) = target.gateway {
+        send(STEP_PING, StepState::Running);
+        send(
+            STEP_PING,
+            finished(ping(iface, gateway).await, |s| s.clone()),
+        );
+    } else {
+        send(
+            STEP_PING,
+            StepState::Skipped(format!("{} has no gateway", iface)),
+        );
+    }
+
+    let Some((host, port)) = target.server.as_deref().and_then(controller_address) else {
+        let reason = "the controller URL is not known".to_string();
+        for step in [STEP_DNS, STEP_TCP, STEP_TLS] {
+            send(step, StepState::Skipped(reason.clone()));
+        }
+        return;
+    };
+
+    let addr = if let Ok(addr) = host.parse::<IpAddr>() {
+        send(
+            STEP_DNS,
+            StepState::Skipped("the controller is an IP address".to_string()),
+        );
+        addr
+    } else {
+        send(STEP_DNS, StepState::Running);
+        match resolve(iface, &target.dns, &host).await {
+            Ok((addr, detail)) => {
+                send(STEP_DNS, StepState::Passed(detail));
+                addr
+            }
+            Err(e) => {
+                send(STEP_DNS, StepState::Failed(format!("{:#}", e)));
+                let reason = "the controller was not resolved".to_string();
+                send(STEP_TCP, StepState::Skipped(reason.clone()));
+                send(STEP_TLS, StepState::Skipped(reason));
+                return;
+            }
+        }
+    };
+
+    send(STEP_TCP, StepState::Running);
+    let addr = SocketAddr::new(addr, port);
+    let mut stream = match connect(iface, addr).await {
+        Ok(stream) => {
+            send(
+                STEP_TCP,
+                StepState::Passed(format!("connected to {}", addr)),
+            );
+            stream
+        }
+        Err(e) => {
+            send(STEP_TCP, StepState::Failed(format!("{:#}", e)));
+            send(STEP_TLS, StepState::Skipped("no connection".to_string()));
+            return;
+        }
+    };
+
+    send(STEP_TLS, StepState::Running);
+    send(
+        STEP_TLS,
+        finished(tls_handshake(&mut stream, &host).await, |s| s.clone()),
+    );
+}
+
+/// Run the checks on a background task. Every step reports its state through
+/// `events_tx`, cancelling `token` stops the checks and kills a running ping
+pub fn spawn_diagnostics(
+    target: DiagnosticsTarget,
+    events_tx: UnboundedSender<DiagnosticsEvent>,
+    token: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let iface = target.iface.clone();
+        info!("Running diagnostics of {}", iface);
+        tokio::select! {
+            _ = token.cancelled() => debug!("Diagnostics of {} cancelled", iface),
+            _ = run(target, &events_tx) => debug!("Diagnostics of {} done", iface),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_query_and_response() {
+        let query = build_dns_query(0x1234, "zedcloud.example.net");
+        assert_eq!(&query[..2], &[0x12, 0x34]);
+        assert_eq!(query[12], 8);
+        assert_eq!(&query[13..21], b"zedcloud");
+
+        // the question is echoed, a CNAME and an A record follow
+        let mut response = query.clone();
+        response[2] = 0x81;
+        response[3] = 0x80;
+        response[7] = 2;
+        response.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x05, 0x00, 0x01, 0, 0, 0, 60, 0x00, 0x02]);
+        response.extend_from_slice(&[0xc0, 0x0c]);
+        response.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0, 0, 0, 60, 0x00, 0x04]);
+        response.extend_from_slice(&[10, 1, 2, 3]);
+        assert_eq!(
+            parse_dns_response(0x1234, &response).unwrap(),
+            vec![Ipv4Addr::new(10, 1, 2, 3)]
+        );
+        assert!(parse_dns_response(0x4321, &response).is_err());
+
+        assert!(parse_dns_response(0x1234, &response[..response.len() - 2]).is_err());
+
+        response[3] = 0x83;
+        assert_eq!(
+            parse_dns_response(0x1234, &response)
+                .unwrap_err()
+                .to_string(),
+            "host not found"
+        );
+    }
+
+    #[test]
+    fn test_controller_address() {
+        assert_eq!(
+            controller_address("zedcloud.example.net"),
+            Some(("zedcloud.example.net".to_string(), 443))
+        );
+        assert_eq!(
+            controller_address("https://10.0.0.5:8443/api"),
+            Some(("10.0.0.5".to_string(), 8443))
+        );
+        assert_eq!(
+            controller_address("http://[fd00::1]"),
+            Some(("fd00::1".to_string(), 80))
+        );
+    }
+}
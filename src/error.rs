@@ -0,0 +1,66 @@
+use std::fmt;
+
+use log::error;
+
+// Crate-wide error. Every variant keeps the full error chain for the log
+// and maps to a short message that is safe to show in the UI
+#[derive(Debug)]
+pub enum MonitorError {
+    Config(anyhow::Error),
+    Ipc(anyhow::Error),
+    Parse(anyhow::Error),
+    Render(anyhow::Error),
+    Io(anyhow::Error),
+}
+
+impl MonitorError {
+    fn domain(&self) -> &'static str {
+        match self {
+            MonitorError::Config(_) => "Config",
+            MonitorError::Ipc(_) => "IPC",
+            MonitorError::Parse(_) => "Parse",
+            MonitorError::Render(_) => "Render",
+            MonitorError::Io(_) => "I/O",
+        }
+    }
+
+    fn inner(&self) -> &anyhow::Error {
+        match self {
+            MonitorError::Config(e)
+            | MonitorError::Parse(e)
+            | MonitorError::Ipc(e)
+            | MonitorError::Render(e)
+            | MonitorError::Io(e) => e,
+        }
+    }
+
+    /// Concise message for dialogs. Only the outermost context is shown,
+    /// the full chain goes to the log
+    pub fn user_message(&self) -> String {
+        let e = self.inner();
+        match self {
+            MonitorError::Config(_) => format!("Configuration error: {}", e),
+            MonitorError::Ipc(_) => format!("Cannot communicate with EVE: {}", e),
+            // serde errors mean nothing to the user
+            MonitorError::Parse(_) => "Received malformed data from EVE".to_string(),
+            MonitorError::Render(_) => format!("Display error: {}", e),
+            MonitorError::Io(_) => e.to_string(),
+        }
+    }
+
+    pub fn log(&self) {
+        error!("{}", self);
+    }
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} error: {:#}", self.domain(), self.inner())
+    }
+}
+
+impl std::error::Error for MonitorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.inner().as_ref())
+    }
+}
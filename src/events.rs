@@ -1,8 +1,12 @@
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Event {
     Key(KeyEvent),
     Tick,
     TerminalResize(u16, u16),
+    // bracketed paste, the whole pasted text at once
+    Paste(String),
+    // clicks and the wheel only, the pointer moves are dropped
+    Mouse(MouseEvent),
 }
@@ -0,0 +1,79 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::debug;
+use tokio::{fs::File, io::AsyncWriteExt, sync::mpsc::UnboundedSender, task::JoinHandle};
+
+use crate::error::MonitorError;
+
+// write in chunks so progress can be reported for large files
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug)]
+pub enum FileIoEvent {
+    Progress {
+        path: PathBuf,
+        written: usize,
+        total: usize,
+    },
+    Completed {
+        path: PathBuf,
+    },
+    Failed {
+        path: PathBuf,
+        error: MonitorError,
+    },
+}
+
+/// Write `data` to `path`, creating the directory. `progress` gets the bytes
+/// written so far after every chunk
+pub async fn write_file(path: &Path, data: &[u8], mut progress: impl FnMut(usize)) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Cannot create directory {}", parent.display()))?;
+    }
+    let mut file = File::create(path)
+        .await
+        .with_context(|| format!("Cannot create {}", path.display()))?;
+
+    let mut written = 0;
+    for chunk in data.chunks(CHUNK_SIZE) {
+        file.write_all(chunk)
+            .await
+            .with_context(|| format!("Cannot write to {}", path.display()))?;
+        written += chunk.len();
+        progress(written);
+    }
+    file.flush()
+        .await
+        .with_context(|| format!("Cannot flush {}", path.display()))?;
+    Ok(())
+}
+
+/// Write `data` to `path` on a background task so the UI is not blocked.
+/// Progress and the final result are reported through `events_tx`
+pub fn spawn_write_file(
+    path: PathBuf,
+    data: Vec<u8>,
+    events_tx: UnboundedSender<FileIoEvent>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        debug!("Writing {} bytes to {}", data.len(), path.display());
+        let progress = |written| {
+            let _ = events_tx.send(FileIoEvent::Progress {
+                path: path.clone(),
+                written,
+                total: data.len(),
+            });
+        };
+        let event = match write_file(&path, &data, progress).await {
+            Ok(()) => FileIoEvent::Completed { path },
+            Err(e) => FileIoEvent::Failed {
+                path,
+                error: MonitorError::Io(e),
+            },
+        };
+        let _ = events_tx.send(event);
+    })
+}
@@ -0,0 +1,117 @@
+// Serial console of a guest VM attached to the monitor's terminal. EVE proxies
+// the console over IPC: keys are sent as Request::ConsoleInput and the guest
+// output comes back as IpcMessage::ConsoleOutput and is written to the
+// terminal as is, so the guest sees a real terminal
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use uuid::Uuid;
+
+// same as telnet and virsh console
+pub const DETACH_KEY: &str = "Ctrl+]";
+
+#[derive(Debug)]
+pub struct AttachedConsole {
+    pub uuid: Uuid,
+    pub app_name: String,
+}
+
+pub fn is_detach_key(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char(']') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
+/// Bytes a VT100 terminal sends for the key, None for keys it has no code for
+pub fn key_to_bytes(key: &KeyEvent) -> Option<Vec<u8>> {
+    if key.kind == KeyEventKind::Release {
+        return None;
+    }
+    let bytes: &[u8] = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+A is 0x01 ... Ctrl+Z is 0x1a, Ctrl+[ to Ctrl+_ follow
+            let c = c.to_ascii_uppercase();
+            return ('@'..='_').contains(&c).then(|| vec![c as u8 - b'@']);
+        }
+        KeyCode::Char(c) => {
+            let mut buf = [0; 4];
+            let mut bytes = c.encode_utf8(&mut buf).as_bytes().to_vec();
+            if key.modifiers.contains(KeyModifiers::ALT) {
+                bytes.insert(0, 0x1b);
+            }
+            return Some(bytes);
+        }
+        KeyCode::Enter => b"\r",
+        KeyCode::Tab => b"\t",
+        KeyCode::BackTab => b"\x1b[Z",
+        KeyCode::Backspace => b"\x7f",
+        KeyCode::Esc => b"\x1b",
+        KeyCode::Up => b"\x1b[A",
+        KeyCode::Down => b"\x1b[B",
+        KeyCode::Right => b"\x1b[C",
+        KeyCode::Left => b"\x1b[D",
+        KeyCode::Home => b"\x1b[H",
+        KeyCode::End => b"\x1b[F",
+        KeyCode::Insert => b"\x1b[2~",
+        KeyCode::Delete => b"\x1b[3~",
+        KeyCode::PageUp => b"\x1b[5~",
+        KeyCode::PageDown => b"\x1b[6~",
+        KeyCode::F(n) => match n {
+            1 => b"\x1bOP",
+            2 => b"\x1bOQ",
+            3 => b"\x1bOR",
+            4 => b"\x1bOS",
+            5 => b"\x1b[15~",
+            6 => b"\x1b[17~",
+            7 => b"\x1b[18~",
+            8 => b"\x1b[19~",
+            9 => b"\x1b[20~",
+            10 => b"\x1b[21~",
+            11 => b"\x1b[23~",
+            12 => b"\x1b[24~",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_key_to_bytes() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            key_to_bytes(&key(KeyCode::Char('a'), none)),
+            Some(b"a".to_vec())
+        );
+        assert_eq!(
+            key_to_bytes(&key(KeyCode::Char('ü'), none)),
+            Some("ü".as_bytes().to_vec())
+        );
+        assert_eq!(
+            key_to_bytes(&key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(vec![0x03])
+        );
+        assert_eq!(
+            key_to_bytes(&key(KeyCode::Char('x'), KeyModifiers::ALT)),
+            Some(b"\x1bx".to_vec())
+        );
+        assert_eq!(
+            key_to_bytes(&key(KeyCode::Enter, none)),
+            Some(b"\r".to_vec())
+        );
+        assert_eq!(
+            key_to_bytes(&key(KeyCode::Up, none)),
+            Some(b"\x1b[A".to_vec())
+        );
+        assert_eq!(key_to_bytes(&key(KeyCode::F(13), none)), None);
+        assert!(is_detach_key(&key(
+            KeyCode::Char(']'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_detach_key(&key(KeyCode::Char(']'), none)));
+    }
+}
@@ -0,0 +1,53 @@
+use log::info;
+use tokio::sync::watch;
+use tokio::time::{Duration, Instant};
+
+// Tracks user activity. Collectors subscribe to the idle state and slow down
+// or pause while nobody is using the monitor
+pub struct IdleTracker {
+    // None disables idle detection
+    timeout: Option<Duration>,
+    last_activity: Instant,
+    idle_tx: watch::Sender<bool>,
+}
+
+impl IdleTracker {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        let (idle_tx, _) = watch::channel(false);
+        Self {
+            timeout,
+            last_activity: Instant::now(),
+            idle_tx,
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+        // the new timeout counts from now
+        self.touch();
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.idle_tx.subscribe()
+    }
+
+    /// Register user activity. Wakes up collectors immediately if we were idle
+    pub fn touch(&mut self) {
+        self.last_activity = Instant::now();
+        if *self.idle_tx.borrow() {
+            info!("User activity detected, resuming collectors");
+            self.idle_tx.send_replace(false);
+        }
+    }
+
+    /// Must be called periodically to detect the idle state
+    pub fn check(&mut self) {
+        let Some(timeout) = self.timeout else {
+            return;
+        };
+        if !*self.idle_tx.borrow() && self.last_activity.elapsed() >= timeout {
+            info!("No user activity for {:?}, pausing collectors", timeout);
+            self.idle_tx.send_replace(true);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+// Framing of the monitor socket. EVE uses github.com/getlantern/framed, a
+// 2-byte little-endian length before every frame, so a frame carries at most
+// 64 KiB. Longer messages, e.g. TPM logs or proxy certificates, are split in
+// frames that start with CONTINUED, the last one starts with LAST. A message
+// that fits in one frame is sent as is, so short messages look the same to
+// peers that do not know about chunks. Neither JSON nor a compressed message
+// starts with a marker, the chunks of a compressed message can contain any
+// byte, which is why the last one is marked too.
+// Once both sides agreed on it in the handshake, big messages are sent as
+// COMPRESSED followed by a zlib stream of the JSON
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use bytes::{Bytes, BytesMut};
+use log::warn;
+use miniz_oxide::{deflate::compress_to_vec_zlib, inflate::decompress_to_vec_zlib_with_limit};
+use tokio_util::codec::{Decoder, Encoder, LengthDelimitedCodec};
+
+pub const MAX_FRAME: usize = u16::MAX as usize;
+const CONTINUED: u8 = 0x1e;
+const LAST: u8 = 0x1c;
+// payload of a frame that has a marker
+const CHUNK: usize = MAX_FRAME - 1;
+const COMPRESSED: u8 = 0x1f;
+// compressing small messages costs more than it saves
+const COMPRESS_MIN: usize = 4096;
+// fast enough for the slow CPUs of edge devices, JSON compresses well anyway
+const COMPRESS_LEVEL: u8 = 3;
+
+/// Biggest message reassembled when the config does not say otherwise
+pub const DEFAULT_MAX_MESSAGE: usize = 16 * 1024 * 1024;
+
+pub struct ChunkedCodec {
+    frames: LengthDelimitedCodec,
+    max_message: usize,
+    // frames of the message being reassembled
+    partial: BytesMut,
+    // the message grew over max_message, its remaining frames are dropped
+    discarding: bool,
+    // set when EVE accepts compressed messages
+    compress: Arc<AtomicBool>,
+}
+
+impl ChunkedCodec {
+    pub fn new(max_message: usize, compress: Arc<AtomicBool>) -> Self {
+        Self {
+            frames: LengthDelimitedCodec::builder()
+                .little_endian()
+                .length_field_type::<u16>()
+                .new_codec(),
+            max_message,
+            partial: BytesMut::new(),
+            discarding: false,
+            compress,
+        }
+    }
+
+    fn compress(&self, item: Bytes) -> Bytes {
+        if item.len() < COMPRESS_MIN || !self.compress.load(Ordering::Relaxed) {
+            return item;
+        }
+        let mut compressed = vec![COMPRESSED];
+        compressed.extend(compress_to_vec_zlib(&item, COMPRESS_LEVEL));
+        if compressed.len() < item.len() {
+            Bytes::from(compressed)
+        } else {
+            item
+        }
+    }
+
+    // the JSON of a reassembled message, None if it cannot be inflated
+    fn decompress(&self, message: BytesMut) -> Option<BytesMut> {
+        if message.first() != Some(&COMPRESSED) {
+            return Some(message);
+        }
+        match decompress_to_vec_zlib_with_limit(&message[1..], self.max_message) {
+            Ok(json) => Some(BytesMut::from(&json[..])),
+            Err(e) => {
+                warn!("Dropping compressed IPC message: {:?}", e.status);
+                None
+            }
+        }
+    }
+}
+
+impl Decoder for ChunkedCodec {
+    type Item = BytesMut;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<BytesMut>, Self::Error> {
+        while let Some(frame) = self.frames.decode(src)? {
+            let marker = frame.first().copied();
+            let chunked = matches!(marker, Some(CONTINUED) | Some(LAST));
+            // a whole message while the chunks of another one are expected
+            if !chunked && (self.discarding || !self.partial.is_empty()) {
+                warn!("IPC message ended without its last chunk, dropping it");
+                self.discarding = false;
+                self.partial = BytesMut::new();
+            }
+            let data = if chunked { &frame[1..] } else { &frame[..] };
+            if !self.discarding && self.partial.len() + data.len() > self.max_message {
+                warn!(
+                    "IPC message larger than {} bytes, dropping it",
+                    self.max_message
+                );
+                self.discarding = true;
+                self.partial = BytesMut::new();
+            }
+            if !self.discarding {
+                self.partial.extend_from_slice(data);
+            }
+            if marker == Some(CONTINUED) {
+                continue;
+            }
+            if std::mem::take(&mut self.discarding) {
+                continue;
+            }
+            let message = std::mem::take(&mut self.partial);
+            if let Some(message) = self.decompress(message) {
+                return Ok(Some(message));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Encoder<Bytes> for ChunkedCodec {
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        if item.len() > self.max_message {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "IPC message of {} bytes is larger than {} bytes",
+                    item.len(),
+                    self.max_message
+                ),
+            ));
+        }
+        let mut item = self.compress(item);
+        if item.len() <= MAX_FRAME {
+            return self.frames.encode(item, dst);
+        }
+        while !item.is_empty() {
+            let chunk = item.split_to(CHUNK.min(item.len()));
+            let marker = if item.is_empty() { LAST } else { CONTINUED };
+            let mut frame = BytesMut::with_capacity(MAX_FRAME);
+            frame.extend_from_slice(&[marker]);
+            frame.extend_from_slice(&chunk);
+            self.frames.encode(frame.freeze(), dst)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{SinkExt, StreamExt};
+    use tokio::net::UnixStream;
+    use tokio_util::codec::Framed;
+
+    use super::*;
+
+    fn message(len: usize) -> Bytes {
+        // {"data":""} is 11 bytes
+        let body = "x".repeat(len - 11);
+        Bytes::from(format!("{{\"data\":\"{}\"}}", body))
+    }
+
+    // hex digits from a fixed generator, compresses to about a half
+    fn noisy_message(seed: u64, len: usize) -> Bytes {
+        let mut state = seed;
+        let body: String = (0..len - 11)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                char::from(b"0123456789abcdef"[(state >> 60) as usize])
+            })
+            .collect();
+        Bytes::from(format!("{{\"data\":\"{}\"}}", body))
+    }
+
+    #[tokio::test]
+    async fn test_chunked_messages() {
+        let (a, b) = UnixStream::pair().unwrap();
+        let mut sender = Framed::new(a, ChunkedCodec::new(DEFAULT_MAX_MESSAGE, Arc::default()));
+        let mut receiver = Framed::new(b, ChunkedCodec::new(4 * 1024 * 1024, Arc::default()));
+
+        let messages = [
+            message(100),
+            message(MAX_FRAME),
+            message(MAX_FRAME + 1),
+            message(3 * 1024 * 1024),
+            // over the limit of the receiver, dropped without losing the stream
+            message(5 * 1024 * 1024),
+            message(200),
+        ];
+        let sent = messages.clone();
+        let send = tokio::spawn(async move {
+            for message in sent {
+                sender.send(message).await.unwrap();
+            }
+            sender
+        });
+
+        for expected in messages.iter().filter(|m| m.len() <= 4 * 1024 * 1024) {
+            let received = receiver.next().await.unwrap().unwrap();
+            assert_eq!(received.len(), expected.len());
+            assert_eq!(&received[..], &expected[..]);
+        }
+        let mut sender = send.await.unwrap();
+
+        // a single frame has no marker, peers without chunks understand it
+        let mut frames = LengthDelimitedCodec::builder()
+            .little_endian()
+            .length_field_type::<u16>()
+            .new_codec();
+        let mut buf = BytesMut::new();
+        ChunkedCodec::new(DEFAULT_MAX_MESSAGE, Arc::default())
+            .encode(message(20), &mut buf)
+            .unwrap();
+        assert_eq!(frames.decode(&mut buf).unwrap().unwrap(), message(20));
+
+        assert!(sender.send(message(DEFAULT_MAX_MESSAGE + 1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_compressed_messages() {
+        let compress = Arc::new(AtomicBool::new(true));
+        let mut codec = ChunkedCodec::new(DEFAULT_MAX_MESSAGE, compress.clone());
+        let mut receiver = ChunkedCodec::new(4 * 1024 * 1024, Arc::default());
+
+        let big = message(3 * 1024 * 1024);
+        let mut buf = BytesMut::new();
+        codec.encode(big.clone(), &mut buf).unwrap();
+        assert!(buf.len() < MAX_FRAME);
+        assert_eq!(receiver.decode(&mut buf).unwrap().unwrap(), big);
+
+        // inflated over the limit of the receiver
+        codec.encode(message(5 * 1024 * 1024), &mut buf).unwrap();
+        codec.encode(message(100), &mut buf).unwrap();
+        assert_eq!(receiver.decode(&mut buf).unwrap().unwrap(), message(100));
+
+        // not agreed on, sent as is
+        compress.store(false, Ordering::Relaxed);
+        codec.encode(big.clone(), &mut buf).unwrap();
+        assert!(buf.len() > big.len());
+        assert_eq!(receiver.decode(&mut buf).unwrap().unwrap(), big);
+
+        // the zlib stream is split in two chunks and the second one starts with
+        // the CONTINUED byte. The seed was searched for that
+        compress.store(true, Ordering::Relaxed);
+        let noisy = noisy_message(764, 200_000);
+        let compressed = codec.compress(noisy.clone());
+        assert!(compressed.len() > MAX_FRAME && compressed.len() <= 2 * CHUNK);
+        assert_eq!(compressed[CHUNK], CONTINUED);
+        codec.encode(noisy.clone(), &mut buf).unwrap();
+        codec.encode(message(100), &mut buf).unwrap();
+        assert_eq!(receiver.decode(&mut buf).unwrap().unwrap(), noisy);
+        assert_eq!(receiver.decode(&mut buf).unwrap().unwrap(), message(100));
+
+        // a chunked message cut short is dropped, the next one is not lost
+        let mut frames = LengthDelimitedCodec::builder()
+            .little_endian()
+            .length_field_type::<u16>()
+            .new_codec();
+        frames
+            .encode(Bytes::from_static(&[CONTINUED, b'{']), &mut buf)
+            .unwrap();
+        codec.encode(message(100), &mut buf).unwrap();
+        assert_eq!(receiver.decode(&mut buf).unwrap().unwrap(), message(100));
+    }
+}
@@ -0,0 +1,111 @@
+// EVE resends status messages that did not change. Parsing them, replacing the
+// model and redrawing is wasted work on small devices, so identical payloads
+// are dropped before they are parsed. A duplicate still gets through once in a
+// while because the model tracks when EVE last reported an item, see
+// MonitorModel::evict_stale
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+// less than the time after which the model evicts apps EVE stopped reporting
+const PASS_DUPLICATE_AFTER: Duration = Duration::from_secs(30);
+
+// message types that are never dropped, every one of them matters
+const ALWAYS_PASS: &[&str] = &["Response", "Version", "AppLogs", "ConsoleOutput"];
+
+#[derive(Deserialize)]
+struct Envelope<'a> {
+    #[serde(rename = "type", borrow)]
+    message_type: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MessageStats {
+    pub received: u64,
+    pub duplicates: u64,
+}
+
+#[derive(Default)]
+pub struct MessageDedup {
+    // message type -> hash of the last payload and when it was passed
+    last: HashMap<String, (u64, Instant)>,
+    stats: BTreeMap<String, MessageStats>,
+}
+
+impl MessageDedup {
+    /// Whether the raw message should be parsed
+    pub fn pass(&mut self, bytes: &[u8], now: Instant) -> bool {
+        // let the parser deal with garbage
+        let Ok(envelope) = serde_json::from_slice::<Envelope>(bytes) else {
+            return true;
+        };
+        let message_type = envelope.message_type;
+        let stats = self.stats.entry(message_type.to_string()).or_default();
+        stats.received += 1;
+        if ALWAYS_PASS.contains(&message_type) {
+            return true;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        let hash = hasher.finish();
+        match self.last.get(message_type) {
+            Some((last, passed_at))
+                if *last == hash && now.duration_since(*passed_at) < PASS_DUPLICATE_AFTER =>
+            {
+                stats.duplicates += 1;
+                false
+            }
+            _ => {
+                self.last.insert(message_type.to_string(), (hash, now));
+                true
+            }
+        }
+    }
+
+    /// Forget the payloads, e.g. on a new connection everything must get through
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+
+    pub fn stats(&self) -> &BTreeMap<String, MessageStats> {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup() {
+        let status = br#"{"type":"NodeStatus","message":{"Onboarded":true}}"#;
+        let changed = br#"{"type":"NodeStatus","message":{"Onboarded":false}}"#;
+        let response = br#"{"type":"Response","Ok":"","id":1}"#;
+        let start = Instant::now();
+        let mut dedup = MessageDedup::default();
+
+        assert!(dedup.pass(status, start));
+        assert!(!dedup.pass(status, start + Duration::from_secs(1)));
+        assert!(dedup.pass(changed, start + Duration::from_secs(2)));
+        assert!(dedup.pass(status, start + Duration::from_secs(3)));
+        // identical but the last pass was long ago
+        assert!(dedup.pass(status, start + Duration::from_secs(40)));
+        assert!(dedup.pass(response, start));
+        assert!(dedup.pass(response, start));
+        assert!(dedup.pass(b"not json", start));
+
+        assert_eq!(
+            dedup.stats()["NodeStatus"],
+            MessageStats {
+                received: 5,
+                duplicates: 1
+            }
+        );
+        assert_eq!(dedup.stats()["Response"].duplicates, 0);
+    }
+}
@@ -17,6 +17,9 @@ use std::net::IpAddr;
 use strum::Display;
 use uuid::Uuid;
 
+// Enums end with an `Unrecognized` catch-all for values added to EVE after the
+// monitor was built, see ipc::lenient
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct ResultData {
@@ -251,7 +254,7 @@ pub struct NetworkPortStatus {
     pub dhcp_ntp_servers: Option<Vec<IpAddr>>,
     pub addr_info_list: Option<Vec<AddrInfo>>,
     pub up: bool,
-    #[serde(deserialize_with = "deserialize_mac", skip_serializing)]
+    #[serde(deserialize_with = "deserialize_mac", skip_serializing, default)]
     pub mac_addr: Option<MacAddr>,
     pub default_routers: Option<Vec<IpAddr>>,
     #[serde(rename = "MTU")]
@@ -272,7 +275,7 @@ pub struct NetworkPortStatus {
 /// 2. If network_proxy_enable is false, then one of the proxies from the proxies list is used
 /// 3. Only one entry per proxy type  is possible in the proxies list
 /// 4. If [ProxyConfig::pacfile] is used then proxy configuration is taken from the .pac file
-#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct ProxyConfig {
     pub proxies: Option<Vec<ProxyEntry>>,
@@ -284,7 +287,110 @@ pub struct ProxyConfig {
     #[serde(rename = "WpadURL")]
     pub wpad_url: String,
     #[serde(rename = "pubsub-large-ProxyCertPEM")]
-    pub proxy_cert_pem: Option<Vec<Vec<u8>>>,
+    pub proxy_cert_pem: Option<Vec<LargeBlob<Vec<u8>>>>,
+}
+
+/// Content of a `pubsub-large-*` field. The model moves the content into the
+/// BlobStore and keeps only a reference to it. Stored blobs must be loaded back
+/// before the structure is sent to EVE
+#[derive(Debug, PartialEq, Clone)]
+pub enum LargeBlob<T> {
+    Inline(T),
+    Stored(BlobRef),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct BlobRef {
+    // SHA-256 of the content, lowercase hex
+    pub id: String,
+    pub size: usize,
+}
+
+pub trait BlobData {
+    fn into_bytes(self) -> Vec<u8>;
+    fn from_bytes(bytes: Vec<u8>) -> Self;
+}
+
+impl BlobData for Vec<u8> {
+    fn into_bytes(self) -> Vec<u8> {
+        self
+    }
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        bytes
+    }
+}
+
+impl BlobData for String {
+    fn into_bytes(self) -> Vec<u8> {
+        self.into()
+    }
+    // the bytes came from a String in the first place
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// Type-erased access to [LargeBlob] fields of different types
+pub trait BlobSlot {
+    fn blob_ref(&self) -> Option<&BlobRef>;
+    /// Replace inline content with the reference returned by `store`
+    fn store_with(&mut self, store: &mut dyn FnMut(Vec<u8>) -> BlobRef);
+    /// Replace the reference with the content returned by `load`. Returns false
+    /// if the content is not available
+    fn load_with(&mut self, load: &mut dyn FnMut(&BlobRef) -> Option<Vec<u8>>) -> bool;
+}
+
+impl<T: BlobData> BlobSlot for LargeBlob<T> {
+    fn blob_ref(&self) -> Option<&BlobRef> {
+        match self {
+            LargeBlob::Inline(_) => None,
+            LargeBlob::Stored(blob) => Some(blob),
+        }
+    }
+
+    fn store_with(&mut self, store: &mut dyn FnMut(Vec<u8>) -> BlobRef) {
+        if let LargeBlob::Inline(data) = self {
+            let data = std::mem::replace(data, T::from_bytes(Vec::new()));
+            *self = LargeBlob::Stored(store(data.into_bytes()));
+        }
+    }
+
+    fn load_with(&mut self, load: &mut dyn FnMut(&BlobRef) -> Option<Vec<u8>>) -> bool {
+        let LargeBlob::Stored(blob) = self else {
+            return true;
+        };
+        match load(blob) {
+            Some(data) => {
+                *self = LargeBlob::Inline(T::from_bytes(data));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for LargeBlob<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            LargeBlob::Inline(data) => data.serialize(serializer),
+            LargeBlob::Stored(blob) => Err(serde::ser::Error::custom(format!(
+                "blob {} is not loaded",
+                blob.id
+            ))),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for LargeBlob<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(LargeBlob::Inline)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -299,16 +405,16 @@ pub struct L2LinkConfig {
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default)]
 #[serde(rename_all = "PascalCase")]
 pub struct TestResults {
-    last_failed: DateTime<Utc>,
-    last_succeeded: DateTime<Utc>,
-    last_error: String,
+    pub last_failed: DateTime<Utc>,
+    pub last_succeeded: DateTime<Utc>,
+    pub last_error: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct WirelessStatus {
-    w_type: WirelessType,
-    cellular: WwanNetworkStatus,
+    pub w_type: WirelessType,
+    pub cellular: WwanNetworkStatus,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -362,7 +468,7 @@ pub struct CipherBlockStatus {
     pub cipher_context_id: String,
     pub initial_value: Option<String>, //Vec<u8>,
     #[serde(rename = "pubsub-large-CipherData")]
-    pub cipher_data: Option<String>, //Vec<u8>,
+    pub cipher_data: Option<LargeBlob<String>>, //Vec<u8>,
     pub clear_text_hash: Option<String>, //Vec<u8>,
     pub is_cipher: bool,
     pub cipher_context: Option<CipherContext>,
@@ -382,6 +488,8 @@ pub enum WifiKeySchemeType {
     KeySchemeWpaPsk = 1,
     KeySchemeWpaEap = 2,
     KeySchemeOther = 3,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -405,6 +513,7 @@ pub struct WwanNetworkStatus {
     pub probe_error: String,
     pub current_provider: WwanProvider,
     pub visible_providers: Option<Vec<WwanProvider>>,
+    #[serde(rename = "CurrentRATs")]
     pub current_rats: Option<Vec<WwanRAT>>,
     pub connected_at: u64,
     #[serde(rename = "IPSettings")]
@@ -416,11 +525,12 @@ fn ip_empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<IpAddr>, D:
 where
     D: Deserializer<'de>,
 {
-    let s: String = Deserialize::deserialize(deserializer)?;
-    if s.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(s.parse().map_err(serde::de::Error::custom)?))
+    // null is what we serialize None to
+    let s: Option<String> = Deserialize::deserialize(deserializer)?;
+    match s {
+        None => Ok(None),
+        Some(s) if s.is_empty() => Ok(None),
+        Some(s) => Ok(Some(s.parse().map_err(serde::de::Error::custom)?)),
     }
 }
 
@@ -438,38 +548,94 @@ pub struct WwanIPSettings {
     pub mtu: u16,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "PascalCase")]
+// the fields are empty for ports without a modem
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct WwanPhysAddrs {
-    // Define fields here
+    // name of the network interface of the modem
+    pub interface: String,
+    // USB address, e.g. 1:3.3
+    #[serde(rename = "USB")]
+    pub usb: String,
+    // PCI address, e.g. 0000:04:00.0
+    #[serde(rename = "PCI")]
+    pub pci: String,
+    // the device file of the modem control interface, e.g. /dev/cdc-wdm0
+    pub dev: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct WwanCellModule {
+    pub name: String,
+    #[serde(rename = "IMEI")]
+    pub imei: String,
+    pub model: String,
+    pub manufacturer: String,
+    // firmware version
+    pub revision: String,
+    // "qmi" or "mbim"
+    pub control_protocol: String,
+    // e.g. "online", "online-and-connected", "radio-off", "offline"
+    pub op_mode: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "PascalCase")]
-pub struct WwanCellModule {
-    // Define fields here
+#[repr(u8)]
+#[derive(Debug, Default, Serialize_repr, Deserialize_repr, PartialEq, Clone)]
+pub enum SimType {
+    #[default]
+    Unspecified = 0,
+    Physical = 1,
+    Embedded = 2,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct WwanSimCard {
-    // Define fields here
+    pub name: String,
+    // slots are numbered from 1
+    pub slot_number: u8,
+    // the modem uses the SIM in this slot
+    pub slot_activated: bool,
+    #[serde(rename = "ICCID")]
+    pub iccid: String,
+    #[serde(rename = "IMSI")]
+    pub imsi: String,
+    #[serde(rename = "Type")]
+    pub sim_type: SimType,
+    // e.g. "present", "absent", "inactive", "error"
+    pub state: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase", default)]
 pub struct WwanProvider {
-    // Define fields here
+    // Public Land Mobile Network code, MCC and MNC
+    #[serde(rename = "PLMN")]
+    pub plmn: String,
+    pub description: String,
+    pub current_serving: bool,
+    pub roaming: bool,
+    pub forbidden: bool,
 }
 
+// EVE sends the radio access technologies as lowercase strings
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
-#[serde(rename_all = "PascalCase")]
 pub enum WwanRAT {
+    #[serde(rename = "")]
     WwanRATUnspecified,
+    #[serde(rename = "gsm")]
     WwanRATGSM,
+    #[serde(rename = "umts")]
     WwanRATUMTS,
+    #[serde(rename = "lte")]
     WwanRATLTE,
+    #[serde(rename = "5gnr")]
     WwanRAT5GNR,
+    #[serde(other)]
+    Unrecognized,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -494,6 +660,8 @@ pub enum ConnectivityProbeMethod {
     ConnectivityProbeMethodNone = 0,
     ConnectivityProbeMethodICMP = 1,
     ConnectivityProbeMethodTCP = 2,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -569,9 +737,11 @@ pub struct CellularAccessPoint {
     // The set of cellular network operators that modem should preferably try to register
     // and connect into.
     // Network operator should be referenced by PLMN (Public Land Mobile Network) code.
+    #[serde(rename = "PreferredPLMNs")]
     pub preferred_plmns: Vec<String>,
     // The list of preferred Radio Access Technologies (RATs) to use for connecting
     // to the network.
+    #[serde(rename = "PreferredRATs")]
     pub preferred_rats: Vec<WwanRAT>,
     // If true, then modem will avoid connecting to networks with roaming.
     pub forbid_roaming: bool,
@@ -586,6 +756,8 @@ pub enum L2LinkType {
     L2LinkTypeVLAN = 1,
     // L2LinkTypeBond : Bond interface
     L2LinkTypeBond = 2,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -615,6 +787,8 @@ pub enum BondMode {
     BondModeBalanceTLB = 6,
     // BondModeBalanceALB : Adaptive load balancing
     BondModeBalanceALB = 7,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
@@ -653,6 +827,8 @@ pub enum LacpRate {
     LacpRateUnspecified = 0,
     LacpRateSlow = 1,
     LacpRateFast = 2,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 /// DhcpType enum
@@ -673,6 +849,8 @@ pub enum DhcpType {
     Deprecated = 3,
     /// DHCP client i.e. real DHCP client
     Client = 4,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 // DPCState enum
@@ -688,6 +866,8 @@ pub enum DPCState {
     IntfWait = 6,
     RemoteWait = 7,
     AsyncWait = 8,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 // NetworkType enum
@@ -700,6 +880,8 @@ pub enum NetworkType {
     Ipv4Only = 5,
     Ipv6Only = 7,
     DualStack = 8,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 // NetworkProxyType enum
@@ -712,6 +894,8 @@ pub enum NetworkProxyType {
     FTP = 3,
     NOPROXY = 4,
     LAST = 255,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 // WirelessType enum
@@ -721,6 +905,8 @@ pub enum WirelessType {
     None = 0,
     Cellular = 1,
     Wifi = 2,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 // WirelessConfig struct
@@ -781,6 +967,38 @@ impl DevicePortConfig {
         }
     }
 
+    /// The last test of the configuration failed, or it was never tested OK
+    pub fn last_test_failed(&self) -> bool {
+        let results = &self.test_results;
+        results.last_failed > results.last_succeeded || results.last_succeeded.timestamp() <= 0
+    }
+
+    /// All `pubsub-large-*` fields of the configuration
+    pub fn blobs_mut(&mut self) -> Vec<&mut dyn BlobSlot> {
+        let mut blobs: Vec<&mut dyn BlobSlot> = Vec::new();
+        for port in self.ports.iter_mut() {
+            for cert in port.proxy_config.proxy_cert_pem.iter_mut().flatten() {
+                blobs.push(cert);
+            }
+            let wireless = &mut port.wireless_cfg;
+            for wifi in wireless.wifi.iter_mut().flatten() {
+                if let Some(data) = wifi.cipher_block_status.cipher_data.as_mut() {
+                    blobs.push(data);
+                }
+            }
+            let access_points = wireless
+                .cellular_v2
+                .iter_mut()
+                .flat_map(|cell| cell.access_points.iter_mut().flatten());
+            for ap in access_points {
+                if let Some(data) = ap.encrypted_credentials.cipher_data.as_mut() {
+                    blobs.push(data);
+                }
+            }
+        }
+        blobs
+    }
+
     // pub fn update_or_insert_port(&mut self, port: NetworkPortConfig) {
     //     if let Some(p) = self.get_port_by_name_mut(&port.if_name) {
     //         *p = port;
@@ -805,6 +1023,14 @@ impl DevicePortConfigList {
             .and_then(|list| list.iter().find(|dpc| dpc.key == key))
     }
 
+    // keys repeat in the history, e.g. every local change is "manual"
+    pub fn get_dpc(&self, key: &str, time_priority: &DateTime<Utc>) -> Option<&DevicePortConfig> {
+        self.port_config_list.as_ref().and_then(|list| {
+            list.iter()
+                .find(|dpc| dpc.key == key && &dpc.time_priority == time_priority)
+        })
+    }
+
     pub fn get_current_dpc_ref(&self) -> Option<&DevicePortConfig> {
         self.port_config_list
             .as_ref()
@@ -821,6 +1047,14 @@ impl DevicePortConfigList {
         self.get_current_dpc_ref().map(|dpc| dpc.key.as_str())
     }
 
+    pub fn blobs_mut(&mut self) -> Vec<&mut dyn BlobSlot> {
+        self.port_config_list
+            .iter_mut()
+            .flatten()
+            .flat_map(|dpc| dpc.blobs_mut())
+            .collect()
+    }
+
     pub fn get_current_dpc_cloned(&self) -> Option<DevicePortConfig> {
         self.get_current_dpc_ref().map(|dpc| dpc.clone())
     }
@@ -872,6 +1106,7 @@ impl NetworkPortConfig {
         self.dhcp_config.dhcp = DhcpType::Client;
         // clean static ip fields
         self.dhcp_config.addr_subnet = None;
+        self.dhcp_config.addr_subnets.clear();
         self.dhcp_config.gateway = String::new();
         self.dhcp_config.domain_name = String::new();
         self.dhcp_config.ntp_servers = None;
@@ -882,14 +1117,16 @@ impl NetworkPortConfig {
 
     pub fn into_static(
         mut self,
-        addr_subnet: IpNet,
+        addr_subnets: Vec<IpNet>,
         gateway: String,
         domain_name: String,
         ntp_server: Option<Vec<String>>,
         dns_servers: Option<Vec<IpAddr>>,
     ) -> Self {
         self.dhcp_config.dhcp = DhcpType::Static;
-        self.dhcp_config.addr_subnet = Some(addr_subnet);
+        // EVE without the list takes the first address
+        self.dhcp_config.addr_subnet = addr_subnets.first().copied();
+        self.dhcp_config.addr_subnets = addr_subnets;
         self.dhcp_config.gateway = gateway;
         self.dhcp_config.domain_name = domain_name;
         self.dhcp_config.ntp_servers = ntp_server;
@@ -901,6 +1138,7 @@ impl NetworkPortConfig {
         self.dhcp_config.dhcp = DhcpType::Client;
         // clean static ip fields
         self.dhcp_config.addr_subnet = None;
+        self.dhcp_config.addr_subnets.clear();
         self.dhcp_config.gateway = String::new();
         self.dhcp_config.domain_name = String::new();
         self.dhcp_config.ntp_servers = None;
@@ -909,14 +1147,18 @@ impl NetworkPortConfig {
 
     pub fn to_static(
         &mut self,
-        addr_subnet: IpNet,
+        addr_subnets: Vec<IpNet>,
         gateway: String,
         domain_name: String,
         ntp_server: Option<Vec<String>>,
         dns_servers: Option<Vec<IpAddr>>,
+        network_type: NetworkType,
     ) {
         self.dhcp_config.dhcp = DhcpType::Static;
-        self.dhcp_config.addr_subnet = Some(addr_subnet);
+        self.dhcp_config.dhcp_type = network_type;
+        // EVE without the list takes the first address
+        self.dhcp_config.addr_subnet = addr_subnets.first().copied();
+        self.dhcp_config.addr_subnets = addr_subnets;
         self.dhcp_config.gateway = gateway;
         self.dhcp_config.domain_name = domain_name;
         self.dhcp_config.ntp_servers = ntp_server;
@@ -932,6 +1174,9 @@ pub struct DhcpConfig {
     pub dhcp: DhcpType,
     #[serde_as(as = "NoneAsEmptyString")]
     pub addr_subnet: Option<IpNet>,
+    // all addresses of a static port, AddrSubnet is the first one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addr_subnets: Vec<IpNet>,
     pub gateway: String,
     pub domain_name: String,
     #[serde(rename = "NTPServers")]
@@ -999,6 +1244,8 @@ pub enum ErrorSeverity {
     Notice = 1,
     Warning = 2,
     Error = 3,
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -1024,6 +1271,8 @@ pub enum ErrorEntityType {
     ContentTree = 9,
     ContentBlob = 10,
     Volume = 11,
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -1069,6 +1318,8 @@ pub enum PhyIoType {
     PhyIoTypeVCAN = 16,
     PhyIoTypeLCAN = 17,
     PhyIoTypeOther = 255,
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -1093,6 +1344,8 @@ pub enum PhyIoMemberUsage {
     PhyIoUsageDedicated = 3,
     PhyIoUsageDisabled = 4,
     PhyIoUsageMgmtOnly = 5,
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -1131,19 +1384,19 @@ pub struct AppInstanceStatus {
     pub volume_ref_status_list: Vec<VolumeRefStatus>,
     #[serde(skip)]
     pub app_net_adapters: Vec<AppNetAdapterStatus>,
-    pub boot_time: String, // Replace with a suitable time type
+    pub boot_time: DateTime<Utc>,
     #[serde(skip)]
     pub io_adapter_list: Vec<IoAdapter>,
     pub restart_inprogress: Inprogress,
-    pub restart_started_at: String, // Replace with a suitable time type
+    pub restart_started_at: DateTime<Utc>,
     pub purge_inprogress: Inprogress,
-    pub purge_started_at: String, // Replace with a suitable time type
+    pub purge_started_at: DateTime<Utc>,
     pub state: SwState,
     pub missing_network: bool,
     pub missing_memory: bool,
     #[serde(flatten)]
     pub error_and_time_with_source: ErrorAndTimeWithSource,
-    pub start_time: String, // Replace with a suitable time type
+    pub start_time: DateTime<Utc>,
     #[serde(skip)]
     pub snap_status: SnapshottingStatus,
     pub mem_overhead: u64,
@@ -1178,6 +1431,8 @@ pub enum SwState {
     Scheduling = 123,
     Failed = 124,
     MaxState = 125,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 impl SwState {
@@ -1235,6 +1490,8 @@ pub enum VmMode {
     FML = 3,
     NoHyper = 4,
     Legacy = 5,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -1277,6 +1534,8 @@ pub enum Format {
     Container = 8,
     ISO = 9,
     PVC = 10,
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[repr(i32)]
@@ -1288,6 +1547,8 @@ pub enum Target {
     Initrd = 3,
     RamDisk = 4,
     AppCustom = 5,
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -1306,6 +1567,8 @@ pub enum Inprogress {
     BringDown = 2,
     RecreateVolumes = 3,
     BringUp = 4,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 // Placeholder types for unknown ones
@@ -1347,6 +1610,8 @@ pub enum DataSecAtRestStatus {
     DataSecAtRestDisabled = 1, // Enabled, but not being used
     DataSecAtRestEnabled = 2,  // Enabled, and used
     DataSecAtRestError = 4,    // Enabled, but encountered an error
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[repr(i32)]
@@ -1355,6 +1620,8 @@ pub enum PCRStatus {
     PcrUnknown = 0,  // Status is unknown
     PcrEnabled = 1,  // Enabled PCR
     PcrDisabled = 2, // Disabled PCR
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 type AppCount = u8;
@@ -1391,6 +1658,8 @@ pub enum LedBlinkCount {
     LedBlinkInvalidControllerCert,
     LedBlinkInvalidAuthContainer,
     LedBlinkInvalidBootstrapConfig,
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -1406,11 +1675,11 @@ fn zero_uuid_as_none<'de, D>(deserializer: D) -> Result<Option<Uuid>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let s = String::deserialize(deserializer)?;
-    if s == "00000000-0000-0000-0000-000000000000" {
-        Ok(None)
-    } else {
-        Ok(Some(Uuid::parse_str(&s).map_err(serde::de::Error::custom)?))
+    // null is what we serialize None to
+    match Option::<String>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(s) if s == "00000000-0000-0000-0000-000000000000" => Ok(None),
+        Some(s) => Ok(Some(Uuid::parse_str(&s).map_err(serde::de::Error::custom)?)),
     }
 }
 
@@ -1461,9 +1730,11 @@ pub enum BootReason {
     BootReasonVaultFailure = 13, // Vault was not ready within the expected time
     BootReasonPoweroffCmd = 14, // Start after Local Profile Server poweroff
     BootReasonParseFail = 255, // BootReasonFromString didn't find match
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
-#[derive(Debug, Serialize_repr, Deserialize_repr)]
+#[derive(Debug, Serialize_repr, Deserialize_repr, Clone, Copy, PartialEq)]
 #[repr(i32)]
 pub enum AttestState {
     StateNone = 0,           // State when (Re)Starting attestation
@@ -1475,6 +1746,8 @@ pub enum AttestState {
     StateRestartWait,        // Waiting for restart timer to expire, to start all over again
     StateComplete,           // Everything w.r.t attestation is complete
     StateAny,                // Not a real state per se. helps defining wildcard transitions(below)
+    #[serde(other)]
+    Unrecognized = -1,
 }
 
 #[derive(Debug, Serialize_repr, Deserialize_repr)]
@@ -1489,6 +1762,8 @@ pub enum DeviceState {
     PreparingPowerOff = 6, // DEVICE_STATE_PREPARING_POWEROFF
     PoweringOff = 7,       // DEVICE_STATE_POWERING_OFF
     PreparedPowerOff = 8,  // DEVICE_STATE_PREPARED_POWEROFF
+    #[serde(other)]
+    Unrecognized = 254,
 }
 
 #[derive(Debug, Serialize_repr, Deserialize_repr)]
@@ -1498,4 +1773,150 @@ pub enum ConfigGetStatus {
     Fail = 2,          // ConfigGetFail
     TemporaryFail = 3, // ConfigGetTemporaryFail
     ReadSaved = 4,     // ConfigGetReadSaved
+    #[serde(other)]
+    Unrecognized = 254,
+}
+
+#[derive(Debug, Serialize_repr, Deserialize_repr, PartialEq, Clone)]
+#[repr(u8)]
+pub enum SupportBundleState {
+    Generating = 0,
+    Ready = 1,
+    Uploading = 2,
+    Uploaded = 3,
+    Failed = 4,
+    #[serde(other)]
+    Unrecognized = 254,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct SupportBundleStatus {
+    pub state: SupportBundleState,
+    // path of the bundle on the device
+    pub path: String,
+    // upload progress in percent
+    pub progress: u8,
+    pub error: Option<String>,
+}
+
+// state of a ZFS pool or vdev as zpool reports it
+#[derive(Debug, Default, Serialize_repr, Deserialize_repr, PartialEq, Clone, Copy, Display)]
+#[repr(u8)]
+pub enum StorageStatus {
+    #[default]
+    #[strum(to_string = "UNKNOWN")]
+    Unspecified = 0,
+    #[strum(to_string = "ONLINE")]
+    Online = 1,
+    #[strum(to_string = "DEGRADED")]
+    Degraded = 2,
+    #[strum(to_string = "FAULTED")]
+    Faulted = 3,
+    #[strum(to_string = "OFFLINE")]
+    Offline = 4,
+    #[strum(to_string = "UNAVAIL")]
+    Unavail = 5,
+    #[strum(to_string = "REMOVED")]
+    Removed = 6,
+    #[strum(to_string = "SUSPENDED")]
+    Suspended = 7,
+    #[serde(other)]
+    #[strum(to_string = "UNKNOWN")]
+    Unrecognized = 254,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct StorageDiskState {
+    // e.g. /dev/sdb
+    pub logical_name: String,
+    #[serde(default)]
+    pub serial_number: String,
+    pub status: StorageStatus,
+    // e.g. "too many errors"
+    #[serde(default)]
+    pub aux_state_str: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ZfsPoolStatus {
+    pub pool_name: String,
+    pub status: StorageStatus,
+    // the "status:" text of zpool status, empty if the pool is healthy
+    #[serde(default)]
+    pub status_msg: String,
+    // e.g. "raidz1", empty for a single disk
+    #[serde(default)]
+    pub raid: String,
+    #[serde(default)]
+    pub disks: Vec<StorageDiskState>,
+}
+
+#[derive(Debug, Serialize_repr, Deserialize_repr, PartialEq, Clone, Copy)]
+#[repr(u8)]
+pub enum ConsoleHandoffState {
+    None = 0,
+    // domainmgr is about to pass the GPU/console to a guest
+    Pending = 1,
+    // the guest owns the console
+    Active = 2,
+    #[serde(other)]
+    Unrecognized = 254,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConsoleHandoffStatus {
+    pub state: ConsoleHandoffState,
+    pub app_name: String,
+    // seconds left before the console is taken over
+    pub seconds_left: u32,
+}
+
+// restrictions the controller puts on the local monitor
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct MonitorPolicy {
+    // the monitor only shows the state, e.g. on kiosk deployments
+    pub read_only: bool,
+    // shown to the user
+    #[serde(default)]
+    pub reason: String,
+}
+
+// raw bytes of a guest serial console, in either direction
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct ConsoleData {
+    #[serde(rename = "AppUUID")]
+    pub app_uuid: Uuid,
+    #[serde_as(as = "Base64")]
+    pub data: Vec<u8>,
+}
+
+// new BootOrder for EVE to write to efivarfs
+#[cfg(feature = "efi")]
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct EfiBootOrderUpdate {
+    // data of the variable without the attribute bytes
+    #[serde_as(as = "Base64")]
+    pub boot_order: Vec<u8>,
+    // Boot#### entries to mark inactive, the others in the order are marked active
+    pub inactive: Vec<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct AppLogChunk {
+    #[serde(rename = "AppUUID")]
+    pub app_uuid: Uuid,
+    // new lines since the previous chunk
+    pub lines: Option<Vec<String>>,
+    // set if EVE cannot provide the logs of this app
+    pub error: String,
 }
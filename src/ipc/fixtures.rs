@@ -0,0 +1,108 @@
+// Messages captured from real devices live in
+// fixtures/ipc/<capture>/<message type>/<sequence>.json
+// <capture> is named after the EVE version the messages were captured from.
+// <sequence> is the order in which the messages were received within the capture
+// so the whole capture can be replayed. Every file is an IpcMessage as it is
+// sent over the socket: {"type":"NetworkStatus","message":{...}}
+use std::path::{Path, PathBuf};
+
+use format_serde_error::SerdeError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::message::IpcMessage;
+
+const FIXTURES_DIR: &str = "./fixtures/ipc";
+
+#[derive(Debug)]
+pub struct Fixture {
+    pub path: PathBuf,
+    pub capture: String,
+    pub message_type: String,
+    pub sequence: u32,
+    // raw json of the whole file
+    pub json: Value,
+}
+
+impl Fixture {
+    fn load(path: PathBuf) -> Option<Self> {
+        let sequence = path.file_stem()?.to_str()?.parse().ok()?;
+        let message_type = path.parent()?.file_name()?.to_str()?.to_string();
+        let capture = path.parent()?.parent()?.file_name()?.to_str()?.to_string();
+        let data = std::fs::read_to_string(&path).unwrap();
+        let json = serde_json::from_str(&data).unwrap();
+        Some(Self {
+            path,
+            capture,
+            message_type,
+            sequence,
+            json,
+        })
+    }
+
+    // panics with a pointer to the offending field so the test output is useful
+    fn parse<T: DeserializeOwned>(&self, json: &Value) -> T {
+        let data = json.to_string();
+        serde_json::from_str(&data)
+            .map_err(|err| SerdeError::new(data, err))
+            .unwrap_or_else(|err| panic!("{:?}: {}", self.path, err))
+    }
+
+    /// The payload deserialized into the given EVE type
+    pub fn message<T: DeserializeOwned>(&self) -> T {
+        self.parse(&self.json["message"])
+    }
+
+    /// The whole file as it would be received from the socket
+    pub fn ipc_message(&self) -> IpcMessage {
+        self.parse(&self.json)
+    }
+}
+
+fn files(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Load fixtures sorted by capture and sequence. `None` matches anything
+pub fn load(capture: Option<&str>, message_type: Option<&str>) -> Vec<Fixture> {
+    let mut fixtures: Vec<Fixture> = files(Path::new(FIXTURES_DIR))
+        .iter()
+        .flat_map(|dir| files(dir))
+        .flat_map(|dir| files(&dir))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(Fixture::load)
+        .filter(|f| capture.is_none_or(|c| f.capture == c))
+        .filter(|f| message_type.is_none_or(|t| f.message_type == t))
+        .collect();
+    fixtures.sort_by(|a, b| (&a.capture, a.sequence).cmp(&(&b.capture, b.sequence)));
+    fixtures
+}
+
+/// fixtures!() - all fixtures
+/// fixtures!(capture: "unknown") - one capture in the order it was received
+/// fixtures!("NetworkStatus") - raw fixtures of one message type
+/// fixtures!("NetworkStatus" => DeviceNetworkStatus) - (path, parsed payload) pairs
+macro_rules! fixtures {
+    () => {
+        $crate::ipc::fixtures::load(None, None)
+    };
+    (capture: $capture:expr) => {
+        $crate::ipc::fixtures::load(Some($capture), None)
+    };
+    ($message_type:expr) => {
+        $crate::ipc::fixtures::load(None, Some($message_type))
+    };
+    ($message_type:expr => $ty:ty) => {
+        $crate::ipc::fixtures::load(None, Some($message_type))
+            .into_iter()
+            .map(|f| {
+                let message: $ty = f.message();
+                (f.path, message)
+            })
+            .collect::<Vec<_>>()
+    };
+}
+
+pub(crate) use fixtures;
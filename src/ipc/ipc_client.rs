@@ -1,13 +1,225 @@
 use anyhow::{anyhow, Result};
 use async_inotify::Watcher;
 use inotify::EventMask;
-use log::{debug, info};
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
 use tokio::{net::UnixStream, task::JoinHandle};
-use tokio_util::codec::{Framed, LengthDelimitedCodec};
+use tokio_util::codec::Framed;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::chunked::ChunkedCodec;
+#[cfg(feature = "efi")]
+use super::eve_types::EfiBootOrderUpdate;
+use super::eve_types::{ConsoleData, DevicePortConfig};
+use super::message::{IpcMessage, Request, RequestId};
+use super::pending::{RequestError, MAX_RETRIES, REQUEST_TIMEOUT};
+
+type Answer = std::result::Result<String, String>;
+/// Result of a request that has no data in the answer
+pub type Reply = std::result::Result<(), RequestError>;
+
+/// Requests waiting for their response, shared by the clients and the IPC task
+/// that routes the responses
+#[derive(Default)]
+pub struct Waiters(Mutex<HashMap<RequestId, oneshot::Sender<Answer>>>);
+
+impl Waiters {
+    fn add(&self, id: RequestId) -> oneshot::Receiver<Answer> {
+        let (tx, rx) = oneshot::channel();
+        self.0.lock().unwrap().insert(id, tx);
+        rx
+    }
+
+    /// The request fails with RequestError::Disconnected
+    pub fn remove(&self, id: RequestId) {
+        self.0.lock().unwrap().remove(&id);
+    }
+
+    /// Completes the request the message answers. Other messages are returned
+    pub fn resolve(&self, msg: IpcMessage) -> Option<IpcMessage> {
+        let IpcMessage::Response { result, id } = msg else {
+            return Some(msg);
+        };
+        match self.0.lock().unwrap().remove(&id) {
+            Some(waiter) => {
+                // the caller may have given up already
+                let _ = waiter.send(result);
+                None
+            }
+            None => Some(IpcMessage::Response { result, id }),
+        }
+    }
+
+    /// The connection is lost, all requests fail
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+// a request on the wire. Dropping it, e.g. when the caller gives up, forgets the waiter
+struct Sent {
+    id: RequestId,
+    answer: oneshot::Receiver<Answer>,
+    waiters: Arc<Waiters>,
+}
+
+impl Drop for Sent {
+    fn drop(&mut self) {
+        self.waiters.remove(self.id);
+    }
+}
+
+/// Typed requests to EVE over the connection of the IPC collector
+#[derive(Clone)]
+pub struct IpcClient {
+    tx: UnboundedSender<IpcMessage>,
+    waiters: Arc<Waiters>,
+    timeout: Duration,
+    cancel: CancellationToken,
+}
 
-pub struct IpcClient {}
 impl IpcClient {
+    pub fn new(tx: UnboundedSender<IpcMessage>, waiters: Arc<Waiters>) -> Self {
+        Self {
+            tx,
+            waiters,
+            timeout: REQUEST_TIMEOUT,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    /// How long to wait for every attempt of a request
+    #[cfg(test)]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Requests fail with RequestError::Cancelled once `cancel` is cancelled
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    fn send(&self, request: &Request) -> std::result::Result<Sent, RequestError> {
+        let msg = IpcMessage::new_request(request.clone());
+        let IpcMessage::Request { id, .. } = msg else {
+            unreachable!()
+        };
+        let sent = Sent {
+            id,
+            answer: self.waiters.add(id),
+            waiters: self.waiters.clone(),
+        };
+        self.tx.send(msg).map_err(|_| RequestError::Disconnected)?;
+        Ok(sent)
+    }
+
+    async fn wait(&self, sent: &mut Sent) -> std::result::Result<String, RequestError> {
+        tokio::select! {
+            _ = self.cancel.cancelled() => Err(RequestError::Cancelled),
+            answer = tokio::time::timeout(self.timeout, &mut sent.answer) => match answer {
+                Err(_) => Err(RequestError::TimedOut),
+                // the waiter was dropped by the IPC task
+                Ok(Err(_)) => Err(RequestError::Disconnected),
+                Ok(Ok(answer)) => answer.map_err(RequestError::Rejected),
+            },
+        }
+    }
+
+    /// Send the request and wait for the answer of EVE. The request is sent
+    /// right away, so requests keep their order even if the futures are
+    /// polled in another one. Idempotent requests are sent again when EVE
+    /// does not answer in time
+    pub fn request(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = std::result::Result<String, RequestError>> + Send + 'static {
+        let client = self.clone();
+        let first = client.send(&request);
+        async move {
+            let mut sent = first?;
+            let mut retries = 0;
+            loop {
+                match client.wait(&mut sent).await {
+                    Err(RequestError::TimedOut)
+                        if request.is_idempotent() && retries < MAX_RETRIES =>
+                    {
+                        retries += 1;
+                        warn!("Request {} timed out, retry #{}", request, retries);
+                        sent = client.send(&request)?;
+                    }
+                    answer => return answer,
+                }
+            }
+        }
+    }
+
+    /// Like request() for requests EVE answers only with OK or an error
+    pub fn command(&self, request: Request) -> impl Future<Output = Reply> + Send + 'static {
+        let answer = self.request(request);
+        async move { answer.await.map(|_| ()) }
+    }
+
+    pub fn hello(&self, version: u32) -> impl Future<Output = Reply> {
+        self.command(Request::Hello(version))
+    }
+
+    pub fn resend_state(&self) -> impl Future<Output = Reply> {
+        self.command(Request::ResendState)
+    }
+
+    pub fn set_dpc(&self, dpc: DevicePortConfig) -> impl Future<Output = Reply> {
+        self.command(Request::SetDPC(dpc))
+    }
+
+    pub fn set_server(&self, url: String) -> impl Future<Output = Reply> {
+        self.command(Request::SetServer(url))
+    }
+
+    #[cfg(feature = "efi")]
+    pub fn set_boot_order(&self, update: EfiBootOrderUpdate) -> impl Future<Output = Reply> {
+        self.command(Request::SetBootOrder(update))
+    }
+
+    pub fn create_support_bundle(&self) -> impl Future<Output = Reply> {
+        self.command(Request::CreateSupportBundle)
+    }
+
+    pub fn upload_support_bundle(&self, path: String) -> impl Future<Output = Reply> {
+        self.command(Request::UploadSupportBundle(path))
+    }
+
+    pub fn retry_attestation(&self) -> impl Future<Output = Reply> {
+        self.command(Request::RetryAttestation)
+    }
+
+    pub fn start_app_logs(&self, app: Uuid) -> impl Future<Output = Reply> {
+        self.command(Request::StartAppLogs(app))
+    }
+
+    pub fn stop_app_logs(&self, app: Uuid) -> impl Future<Output = Reply> {
+        self.command(Request::StopAppLogs(app))
+    }
+
+    pub fn attach_console(&self, app: Uuid) -> impl Future<Output = Reply> {
+        self.command(Request::AttachConsole(app))
+    }
+
+    pub fn detach_console(&self, app: Uuid) -> impl Future<Output = Reply> {
+        self.command(Request::DetachConsole(app))
+    }
+
+    pub fn console_input(&self, app_uuid: Uuid, data: Vec<u8>) -> impl Future<Output = Reply> {
+        self.command(Request::ConsoleInput(ConsoleData { app_uuid, data }))
+    }
+
     async fn try_connect(path: &str, attempts: u32) -> Result<UnixStream> {
         for i in 0..attempts {
             match UnixStream::connect(path).await {
@@ -25,7 +237,13 @@ impl IpcClient {
         }
         Err(anyhow!("Failed to connect to socket"))
     }
-    pub async fn connect(path: &str) -> Result<Framed<UnixStream, LengthDelimitedCodec>> {
+    /// Connect to EVE. Messages longer than `max_message` bytes are dropped,
+    /// big messages are compressed while `compress` is set
+    pub async fn connect(
+        path: &str,
+        max_message: usize,
+        compress: Arc<AtomicBool>,
+    ) -> Result<Framed<UnixStream, ChunkedCodec>> {
         //spawn a task to wait for the socket file to be created
         let socket_path = PathBuf::from(path);
 
@@ -42,13 +260,10 @@ impl IpcClient {
 
         let unix_stream = Self::try_connect(path, 30).await?;
 
-        let stream = LengthDelimitedCodec::builder()
-            .little_endian()
-            // go module github.com/getlantern/framed expects 2-byte in little-endian format
-            // little-endian format as length field
-            .length_field_type::<u16>()
-            .new_framed(unix_stream);
-        Ok(stream)
+        Ok(Framed::new(
+            unix_stream,
+            ChunkedCodec::new(max_message, compress),
+        ))
     }
     async fn wait_for_socket_file(path: &Path) -> Result<(), anyhow::Error> {
         let dir = Path::new(path).parent().unwrap();
@@ -71,3 +286,77 @@ impl IpcClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn request_id(msg: IpcMessage) -> RequestId {
+        match msg {
+            IpcMessage::Request { id, .. } => id,
+            msg => panic!("not a request: {:?}", msg),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ipc_client() {
+        let (tx, mut eve) = mpsc::unbounded_channel();
+        let waiters = Arc::new(Waiters::default());
+        let cancel = CancellationToken::new();
+        let client = IpcClient::new(tx, waiters.clone())
+            .with_timeout(Duration::from_millis(50))
+            .with_cancel(cancel.clone());
+
+        // idempotent requests are sent again while the reply is awaited, the
+        // answer to the last one counts
+        let reply = tokio::spawn(client.set_server("https://zedcloud.local".to_string()));
+        for _ in 0..MAX_RETRIES {
+            request_id(eve.recv().await.unwrap());
+        }
+        let id = request_id(eve.recv().await.unwrap());
+        let response = IpcMessage::Response {
+            result: Ok("OK".to_string()),
+            id,
+        };
+        assert!(waiters.resolve(response).is_none());
+        assert_eq!(reply.await.unwrap(), Ok(()));
+
+        // a second support bundle is not what the user wants
+        let reply = client.create_support_bundle();
+        assert_eq!(reply.await, Err(RequestError::TimedOut));
+        request_id(eve.recv().await.unwrap());
+        assert!(eve.try_recv().is_err());
+
+        let reply = client.retry_attestation();
+        let id = request_id(eve.recv().await.unwrap());
+        waiters.resolve(IpcMessage::Response {
+            result: Err("no TPM".to_string()),
+            id,
+        });
+        assert_eq!(
+            reply.await,
+            Err(RequestError::Rejected("no TPM".to_string()))
+        );
+
+        // answers nobody waits for go to the application
+        let late = IpcMessage::Response {
+            result: Ok("OK".to_string()),
+            id,
+        };
+        assert!(waiters.resolve(late).is_some());
+
+        let reply = client.resend_state();
+        waiters.clear();
+        assert_eq!(reply.await, Err(RequestError::Disconnected));
+
+        let reply = client.hello(1);
+        cancel.cancel();
+        assert_eq!(reply.await, Err(RequestError::Cancelled));
+        assert!(waiters.0.lock().unwrap().is_empty());
+
+        drop(eve);
+        assert_eq!(client.resend_state().await, Err(RequestError::Disconnected));
+    }
+}
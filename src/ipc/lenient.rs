@@ -0,0 +1,116 @@
+// EVE types are Go structs that gain fields and enum values over time. Unknown
+// fields are ignored by serde and unknown enum values are parsed into the
+// `Unrecognized` catch-all variants so a message still gets through. What was
+// lost on the way is found by serializing the parsed message back and comparing
+// it to what EVE sent.
+use serde_json::Value;
+
+// catch-all variants of string enums serialize to it
+const UNRECOGNIZED_VARIANT: &str = "Unrecognized";
+
+// fields the types read but do not serialize back
+const SKIPPED_FIELDS: &[&str] = &["MacAddr", "SnapStatus"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnknownKind {
+    // the field is not in our types, the value is dropped
+    Field,
+    // an enum value parsed into the catch-all variant
+    EnumValue,
+}
+
+/// A value EVE sent that the monitor does not understand
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownValue {
+    // e.g. Ports[0].WirelessCfg.WType
+    pub path: String,
+    pub kind: UnknownKind,
+    pub value: Value,
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn collect(path: &str, received: &Value, parsed: &Value, unknown: &mut Vec<UnknownValue>) {
+    let kind = match (received, parsed) {
+        (Value::Object(received), Value::Object(parsed)) => {
+            for (key, value) in received {
+                let path = join(path, key);
+                match parsed.get(key) {
+                    Some(parsed) => collect(&path, value, parsed, unknown),
+                    None if SKIPPED_FIELDS.contains(&key.as_str()) => {}
+                    None => unknown.push(UnknownValue {
+                        path,
+                        kind: UnknownKind::Field,
+                        value: value.clone(),
+                    }),
+                }
+            }
+            return;
+        }
+        (Value::Array(received), Value::Array(parsed)) => {
+            for (i, (value, parsed)) in received.iter().zip(parsed).enumerate() {
+                collect(&format!("{}[{}]", path, i), value, parsed, unknown);
+            }
+            return;
+        }
+        // other differences are normalization e.g. of dates or empty strings
+        (Value::Number(_), Value::Number(_)) if received != parsed => UnknownKind::EnumValue,
+        (Value::String(_), Value::String(parsed)) if parsed == UNRECOGNIZED_VARIANT => {
+            UnknownKind::EnumValue
+        }
+        _ => return,
+    };
+    unknown.push(UnknownValue {
+        path: path.to_string(),
+        kind,
+        value: received.clone(),
+    });
+}
+
+/// Values of `received` that did not make it into `parsed`, the same message
+/// serialized back
+pub fn unknown_values(received: &Value, parsed: &Value) -> Vec<UnknownValue> {
+    let mut unknown = Vec::new();
+    collect("", received, parsed, &mut unknown);
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_unknown_values() {
+        let received = json!({
+            "State": 7,
+            "Time": "2024-12-06T15:09:29.02573841Z",
+            "MacAddr": "00:11:22:33:44:55",
+            "Ports": [{"Name": "eth0", "Rat": "6g", "NtpServer": "pool.ntp.org"}],
+        });
+        let parsed = json!({
+            "State": 254,
+            "Time": "2024-12-06T15:09:29.025738410Z",
+            "Ports": [{"Name": "eth0", "Rat": "Unrecognized"}],
+        });
+        let unknown: Vec<_> = unknown_values(&received, &parsed)
+            .into_iter()
+            .map(|u| (u.path, u.kind))
+            .collect();
+        assert_eq!(
+            unknown,
+            vec![
+                ("Ports[0].NtpServer".to_string(), UnknownKind::Field),
+                ("Ports[0].Rat".to_string(), UnknownKind::EnumValue),
+                ("State".to_string(), UnknownKind::EnumValue),
+            ]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 
@@ -5,23 +6,43 @@ use std::sync::atomic::Ordering;
 // use bytes::Buf;
 use bytes::Bytes;
 use bytes::BytesMut;
+use log::debug;
 use log::error;
+use log::warn;
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
+use strum::Display;
+use uuid::Uuid;
+
+use crate::error::MonitorError;
+use crate::model::sanitize::sanitize_json;
 
 use super::eve_types::AppInstanceStatus;
 use super::eve_types::AppInstanceSummary;
+use super::eve_types::AppLogChunk;
 use super::eve_types::AppsList;
+use super::eve_types::ConsoleData;
+use super::eve_types::ConsoleHandoffStatus;
 use super::eve_types::DeviceNetworkStatus;
 use super::eve_types::DevicePortConfig;
 use super::eve_types::DevicePortConfigList;
 use super::eve_types::DownloaderStatus;
+#[cfg(feature = "efi")]
+use super::eve_types::EfiBootOrderUpdate;
 use super::eve_types::EveNodeStatus;
 use super::eve_types::EveOnboardingStatus;
 use super::eve_types::EveVaultStatus;
 use super::eve_types::LedBlinkCounter;
+use super::eve_types::MonitorPolicy;
 use super::eve_types::PhysicalIOAdapterList;
+use super::eve_types::SupportBundleStatus;
 use super::eve_types::ZedAgentStatus;
+use super::eve_types::ZfsPoolStatus;
+use super::lenient::unknown_values;
+use super::lenient::UnknownValue;
+use super::protocol::supported_schema;
+use super::protocol::ProtocolVersion;
 
 /// WindowId is a unique identifier for a window that is incremented sequentially.
 pub type RequestId = u64;
@@ -36,11 +57,46 @@ impl RequestIdGenerator {
 // statically initialize the window id counter
 static REQ_ID: RequestIdGenerator = RequestIdGenerator(AtomicU64::new(1));
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Display)]
 #[serde(tag = "RequestType", content = "RequestData")]
 pub enum Request {
     SetDPC(DevicePortConfig),
     SetServer(String),
+    CreateSupportBundle,
+    // upload the bundle at the given path to the controller
+    UploadSupportBundle(String),
+    // restart attestation from the beginning
+    RetryAttestation,
+    // protocol version of the monitor, EVE replies with IpcMessage::Version
+    Hello(u32),
+    // send all status messages again, e.g. after a reconnect
+    ResendState,
+    // stream console and service logs of the app as IpcMessage::AppLogs
+    StartAppLogs(Uuid),
+    StopAppLogs(Uuid),
+    // proxy the serial console of the app as IpcMessage::ConsoleOutput
+    AttachConsole(Uuid),
+    DetachConsole(Uuid),
+    ConsoleInput(ConsoleData),
+    // app lifecycle, the state change is reported in AppStatus
+    RestartApp(Uuid),
+    PurgeApp(Uuid),
+    PowerOffApp(Uuid),
+    // takes effect on the next boot
+    #[cfg(feature = "efi")]
+    SetBootOrder(EfiBootOrderUpdate),
+}
+
+impl Request {
+    /// Whether sending the request twice has the same effect as sending it once
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            Request::SetDPC(_) | Request::SetServer(_) | Request::ResendState => true,
+            #[cfg(feature = "efi")]
+            Request::SetBootOrder(_) => true,
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,6 +104,11 @@ pub enum Request {
 pub enum IpcMessage {
     Connecting,
     Ready,
+    // the connection is lost, the next attempt is made after the delay
+    Disconnected {
+        reason: String,
+        retry_in_ms: u64,
+    },
     NetworkStatus(DeviceNetworkStatus),
     DPCList(DevicePortConfigList),
     DownloaderStatus(DownloaderStatus),
@@ -60,6 +121,27 @@ pub enum IpcMessage {
     NodeStatus(EveNodeStatus),
     AppsList(AppsList),
     ZedAgentStatus(ZedAgentStatus),
+    SupportBundleStatus(SupportBundleStatus),
+    ConsoleHandoff(ConsoleHandoffStatus),
+    ZfsPoolStatus(ZfsPoolStatus),
+    AppLogs(AppLogChunk),
+    ConsoleOutput(ConsoleData),
+    MonitorPolicy(MonitorPolicy),
+    Version(ProtocolVersion),
+    // a message of unknown type or schema that cannot be parsed
+    Unsupported {
+        message_type: String,
+        schema: Option<u32>,
+        error: String,
+    },
+    // a message with values the monitor does not understand, they are
+    // dropped from `message`
+    #[serde(skip)]
+    Lenient {
+        message_type: String,
+        message: Box<IpcMessage>,
+        unknown: Vec<UnknownValue>,
+    },
     Response {
         #[serde(flatten)]
         result: core::result::Result<String, String>,
@@ -108,30 +190,90 @@ impl IpcMessage {
     fn from_reader(bytes: Bytes) -> Self {
         // TODO: it is faster to call serde_json::from_reader directly
         // but I want to log the message if it fails to parse
-        if let Ok(s) = String::from_utf8(bytes.to_vec()) {
-            match serde_json::from_str(s.as_str()) {
-                Ok(message) => {
-                    dump_to_file(s.as_str(), false);
-                    message
+        let s = String::from_utf8_lossy(&bytes);
+        if let Cow::Owned(_) = s {
+            warn!("IPC message is not valid UTF-8, invalid sequences are replaced");
+        }
+        // strings are sanitized before they get into the model
+        let mut envelope = None;
+        let mut received = Value::Null;
+        let message = serde_json::from_str::<Value>(&s).and_then(|mut value| {
+            sanitize_json(&mut value);
+            envelope = Self::envelope(&value);
+            received = value.get("message").cloned().unwrap_or_default();
+            serde_json::from_value::<Self>(value)
+        });
+        match message {
+            Ok(message) => {
+                dump_to_file(&s, false);
+                match envelope {
+                    Some((message_type, _)) => message.with_unknown_values(message_type, &received),
+                    None => message,
+                }
+            }
+            Err(e) => match envelope {
+                // EVE sent a message we do not understand, keep going without it
+                Some((message_type, schema)) => {
+                    warn!(
+                        "Unsupported IPC message {} schema {:?} (supported {:?}): {}",
+                        message_type,
+                        schema,
+                        supported_schema(&message_type),
+                        e
+                    );
+                    dump_to_file(&s, true);
+                    Self::Unsupported {
+                        message_type,
+                        schema,
+                        error: e.to_string(),
+                    }
                 }
-                Err(e) => {
-                    error!("Failed to parse message: {}", e);
+                None => {
+                    let err =
+                        MonitorError::Parse(anyhow::Error::new(e).context("Invalid IPC message"));
+                    err.log();
                     error!("MESSAGE: {}", s);
-                    dump_to_file(s.as_str(), true);
+                    dump_to_file(&s, true);
                     Self::Response {
                         id: 0,
-                        result: Err("Failed to parse message".to_string()),
+                        result: Err(err.user_message()),
                     }
                 }
-            }
-        } else {
-            Self::Response {
-                id: 0,
-                result: Err("Failed to parse message to utf8".to_string()),
-            }
+            },
         }
     }
 
+    fn with_unknown_values(self, message_type: String, received: &Value) -> Self {
+        let parsed = match serde_json::to_value(&self) {
+            Ok(mut parsed) => parsed["message"].take(),
+            Err(_) => return self,
+        };
+        let unknown = unknown_values(received, &parsed);
+        if unknown.is_empty() {
+            return self;
+        }
+        debug!(
+            "{}: {} values are not understood",
+            message_type,
+            unknown.len()
+        );
+        Self::Lenient {
+            message_type,
+            message: Box::new(self),
+            unknown,
+        }
+    }
+
+    // type and schema version of a message from EVE
+    fn envelope(value: &Value) -> Option<(String, Option<u32>)> {
+        let message_type = value.get("type")?.as_str()?;
+        let schema = value
+            .get("schema")
+            .and_then(Value::as_u64)
+            .and_then(|schema| u32::try_from(schema).ok());
+        Some((message_type.to_string(), schema))
+    }
+
     pub fn new_request(request: Request) -> Self {
         let id = REQ_ID.next();
         Self::Request { request, id }
@@ -1,5 +1,13 @@
+pub mod chunked;
+pub mod dedup;
 pub mod eve_types;
+#[cfg(test)]
+pub mod fixtures;
 pub mod ipc_client;
+pub mod lenient;
 pub mod message;
+pub mod pending;
+pub mod protocol;
+pub mod replay;
 #[cfg(test)]
 mod tests;
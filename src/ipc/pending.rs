@@ -0,0 +1,81 @@
+// Requests of the UI waiting for a response from EVE. Deadlines and retries
+// are handled by IpcClient, here are only the handlers to run on the answer
+use std::{collections::HashMap, time::Duration};
+
+use super::message::RequestId;
+
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+// how many times a timed out idempotent request is sent again
+pub const MAX_RETRIES: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RequestError {
+    // EVE answered with an error
+    Rejected(String),
+    TimedOut,
+    // the connection was lost before EVE answered
+    Disconnected,
+    Cancelled,
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::Rejected(e) => write!(f, "{}", e),
+            RequestError::TimedOut => write!(f, "EVE did not answer in time"),
+            RequestError::Disconnected => write!(f, "not connected to EVE"),
+            RequestError::Cancelled => write!(f, "the request was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+pub struct PendingRequest<H> {
+    pub name: String,
+    pub handler: H,
+}
+
+impl<H> PendingRequest<H> {
+    pub fn new<S: Into<String>>(name: S, handler: H) -> Self {
+        Self {
+            name: name.into(),
+            handler,
+        }
+    }
+}
+
+pub struct PendingRequests<H> {
+    requests: HashMap<RequestId, PendingRequest<H>>,
+}
+
+impl<H> Default for PendingRequests<H> {
+    fn default() -> Self {
+        Self {
+            requests: HashMap::new(),
+        }
+    }
+}
+
+impl<H> PendingRequests<H> {
+    pub fn insert(&mut self, id: RequestId, request: PendingRequest<H>) {
+        self.requests.insert(id, request);
+    }
+
+    pub fn remove(&mut self, id: RequestId) -> Option<PendingRequest<H>> {
+        self.requests.remove(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.requests.clear();
+    }
+}
@@ -0,0 +1,134 @@
+// The monitor talks to EVE over a private socket and the messages are Go
+// structs serialized as is, so they change whenever EVE changes them. The
+// monitor sends Request::Hello with its protocol version right after
+// connecting and EVE replies with IpcMessage::Version listing the schema
+// version of every message type it sends. Every message also carries its own
+// schema version in the "schema" field of the envelope. EVE releases that
+// predate versioning reject Hello and send messages without "schema".
+// Since version 2 the monitor accepts zlib compressed messages and EVE lists
+// the compression it accepts in Version.
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::message::RequestId;
+
+/// Version of the protocol this build speaks
+pub const PROTOCOL_VERSION: u32 = 2;
+
+// schema versions of the messages this build was written against
+const SCHEMA_VERSIONS: &[(&str, u32)] = &[
+    ("NetworkStatus", 1),
+    ("DPCList", 1),
+    ("DownloaderStatus", 1),
+    ("IOAdapters", 1),
+    ("AppStatus", 1),
+    ("AppSummary", 1),
+    ("VaultStatus", 1),
+    ("OnboardingStatus", 1),
+    ("LedBlinkCounter", 1),
+    ("NodeStatus", 1),
+    ("AppsList", 1),
+    ("ZedAgentStatus", 1),
+    ("SupportBundleStatus", 1),
+    ("ConsoleHandoff", 1),
+    ("ZfsPoolStatus", 1),
+    ("AppLogs", 1),
+    ("ConsoleOutput", 1),
+    ("MonitorPolicy", 1),
+];
+
+/// Schema version of the message type this build understands, None if the
+/// type is not known at all
+pub fn supported_schema(message_type: &str) -> Option<u32> {
+    SCHEMA_VERSIONS
+        .iter()
+        .find(|(name, _)| *name == message_type)
+        .map(|(_, version)| *version)
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProtocolVersion {
+    pub version: u32,
+    // message type -> schema version
+    #[serde(default)]
+    pub schemas: BTreeMap<String, u32>,
+    // compression of messages EVE accepts, e.g. "zlib"
+    #[serde(default)]
+    pub compression: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaMismatch {
+    pub message_type: String,
+    // None if the message type is not known to this build
+    pub supported: Option<u32>,
+    pub sent: u32,
+}
+
+impl ProtocolVersion {
+    pub fn accepts_zlib(&self) -> bool {
+        self.compression.iter().any(|c| c == "zlib")
+    }
+
+    /// Message types EVE sends in a schema this build may not understand
+    pub fn mismatches(&self) -> Vec<SchemaMismatch> {
+        self.schemas
+            .iter()
+            .filter_map(|(message_type, sent)| {
+                let supported = supported_schema(message_type);
+                if supported.is_some_and(|supported| supported >= *sent) {
+                    return None;
+                }
+                Some(SchemaMismatch {
+                    message_type: message_type.clone(),
+                    supported,
+                    sent: *sent,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ProtocolState {
+    #[default]
+    Unknown,
+    // Hello is sent, waiting for the reply
+    Negotiating(RequestId),
+    // EVE does not support versioning
+    Legacy,
+    Negotiated(ProtocolVersion),
+}
+
+impl fmt::Display for ProtocolState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolState::Unknown => write!(f, "not negotiated"),
+            ProtocolState::Negotiating(_) => write!(f, "negotiating"),
+            ProtocolState::Legacy => write!(f, "legacy, EVE does not report versions"),
+            ProtocolState::Negotiated(version) => write!(
+                f,
+                "version {} (monitor {}), {} schema mismatches{}",
+                version.version,
+                PROTOCOL_VERSION,
+                version.mismatches().len(),
+                if version.accepts_zlib() {
+                    ", zlib compression"
+                } else {
+                    ""
+                }
+            ),
+        }
+    }
+}
+
+impl ProtocolState {
+    pub fn hello_id(&self) -> Option<RequestId> {
+        match self {
+            ProtocolState::Negotiating(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
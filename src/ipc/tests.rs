@@ -1,18 +1,13 @@
-use super::*;
-use anyhow::Result;
-use eve_types::AppInstanceStatus;
-use eve_types::AppInstanceSummary;
-use eve_types::DeviceNetworkStatus;
-use eve_types::DevicePortConfigList;
-use eve_types::DownloaderStatus;
-use eve_types::EveNodeStatus;
-use eve_types::EveOnboardingStatus;
-use eve_types::EveVaultStatus;
-use eve_types::LedBlinkCounter;
-use eve_types::PhysicalIOAdapterList;
-use eve_types::ZedAgentStatus;
-use format_serde_error::SerdeError;
-use std::path::PathBuf;
+use bytes::Bytes;
+
+use super::eve_types::{DeviceNetworkStatus, SimType, WwanRAT};
+use super::fixtures::fixtures;
+use super::lenient::UnknownKind;
+use super::message::IpcMessage;
+use super::protocol::ProtocolState;
+use crate::model::alerts::AlertSeverity;
+use crate::model::device::network::NetworkType;
+use crate::model::model::MonitorModel;
 
 // Common considerations for the tests:
 // 1. Date and Time
@@ -26,131 +21,204 @@ use std::path::PathBuf;
 // ----------------------------------------------------------------------
 // so some test data is fixed by hand to add the trailing zeros
 
-// Global HashMap to store the test data. Key is TestMessageType and value is the raw json data
+#[test]
+fn test_from_device_files() {
+    let fixtures = fixtures!();
+    assert!(!fixtures.is_empty());
+    for fixture in fixtures {
+        println!("Testing JSON file: {:?}", fixture.path);
+        // panics on unknown message types as well
+        let _ = fixture.ipc_message();
+    }
+}
 
-enum TestMessageType {
-    ZedAgentStatus,
-    NetworkStatus,
-    DPCList,
-    NodeStatus,
-    OnboardingStatus,
-    VaultStatus,
-    IOAdapters,
-    LedBlinkCounter,
-    DownloaderStatus,
-    AppSummary,
-    AppStatus,
-    Response,
-    Unknown(String),
+#[test]
+fn test_serde_round_trip() {
+    // the first pass normalizes e.g. dates, after that the output must be stable
+    for fixture in fixtures!() {
+        let json = serde_json::to_value(fixture.ipc_message()).unwrap();
+        let message: IpcMessage = serde_json::from_value(json.clone())
+            .unwrap_or_else(|err| panic!("{:?}: {}", fixture.path, err));
+        assert_eq!(
+            serde_json::to_value(message).unwrap(),
+            json,
+            "{:?}",
+            fixture.path
+        );
+    }
 }
 
-// create TestMessageType from string
-impl From<&str> for TestMessageType {
-    fn from(s: &str) -> Self {
-        match s {
-            "ZedAgentStatus" => TestMessageType::ZedAgentStatus,
-            "NetworkStatus" => TestMessageType::NetworkStatus,
-            "DPCList" => TestMessageType::DPCList,
-            "NodeStatus" => TestMessageType::NodeStatus,
-            "OnboardingStatus" => TestMessageType::OnboardingStatus,
-            "VaultStatus" => TestMessageType::VaultStatus,
-            "IOAdapters" => TestMessageType::IOAdapters,
-            "LedBlinkCounter" => TestMessageType::LedBlinkCounter,
-            "DownloaderStatus" => TestMessageType::DownloaderStatus,
-            "AppSummary" => TestMessageType::AppSummary,
-            "AppStatus" => TestMessageType::AppStatus,
-            "Response" => TestMessageType::Response,
-            _ => TestMessageType::Unknown(s.to_string()),
-        }
+#[test]
+fn test_network_status_ports() {
+    let statuses = fixtures!("NetworkStatus" => DeviceNetworkStatus);
+    assert!(!statuses.is_empty());
+    for (path, status) in statuses {
+        assert!(!status.dpc_key.is_empty(), "{:?}", path);
+    }
+}
+
+#[test]
+fn test_replay_into_model() {
+    let fixtures = fixtures!(capture: "unknown");
+    assert!(!fixtures.is_empty());
+    let mut model = MonitorModel::default();
+    for fixture in fixtures {
+        model.apply_ipc_message(fixture.ipc_message());
     }
+    assert!(model.node_status.is_onboarded());
+    assert!(!model.network.is_empty());
+    assert!(model.dpc_list.is_some());
 }
 
-// the function loads one JSON file and returns message type
-// e.g {"type":"ZedAgentStatus","message":  where 'message' is the raw json data
-// return message type and the raw json data
-fn load_json_test_data<P: Into<PathBuf>>(path: P) -> Result<(TestMessageType, String, PathBuf)> {
-    let path = path.into();
-    let data = std::fs::read_to_string(&path).unwrap();
-    let json_data: serde_json::Value = serde_json::from_str(&data).unwrap();
-    let message_type = json_data["type"].as_str().unwrap();
-    let data = json_data["message"].to_string();
-    Ok((TestMessageType::from(message_type), data, path))
+// as it comes from the socket
+fn receive(json: &str) -> IpcMessage {
+    IpcMessage::from(Bytes::copy_from_slice(json.as_bytes()))
 }
 
-fn load_all_files<P: Into<PathBuf>>(path: P) -> Result<Vec<(TestMessageType, String, PathBuf)>> {
-    let mut result = Vec::new();
-    let path = path.into();
-    for entry in std::fs::read_dir(path)? {
-        let entry = entry?;
-        let path = entry.path();
-        // skip if the extension is not .json
-        if path.extension().unwrap() != "json" {
-            continue;
-        }
-        let (message_type, data, path) = load_json_test_data(path)?;
-        result.push((message_type, data, path));
+#[test]
+fn test_unversioned_payloads() {
+    // messages of EVE releases that predate versioning have no "schema"
+    for fixture in fixtures!(capture: "unknown") {
+        let message = receive(&fixture.json.to_string());
+        assert!(
+            !matches!(message, IpcMessage::Unsupported { .. }),
+            "{:?}",
+            fixture.path
+        );
     }
-    Ok(result)
 }
 
 #[test]
-fn test_from_device_files() -> Result<()> {
-    // load all files from the specified directory
-    let data = load_all_files("./ipc-tests").unwrap();
-    for (message_type, data, path) in data {
-        // print file name
-        println!("Testing JSON file: {:?}", path);
-        match message_type {
-            TestMessageType::ZedAgentStatus => {
-                let _ = serde_json::from_str::<ZedAgentStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::NetworkStatus => {
-                let _ = serde_json::from_str::<DeviceNetworkStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::DPCList => {
-                let _ = serde_json::from_str::<DevicePortConfigList>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::NodeStatus => {
-                let _ = serde_json::from_str::<EveNodeStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::OnboardingStatus => {
-                let _ = serde_json::from_str::<EveOnboardingStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::VaultStatus => {
-                let _ = serde_json::from_str::<EveVaultStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::IOAdapters => {
-                let _ = serde_json::from_str::<PhysicalIOAdapterList>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::LedBlinkCounter => {
-                let _ = serde_json::from_str::<LedBlinkCounter>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::DownloaderStatus => {
-                let _ = serde_json::from_str::<DownloaderStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::AppSummary => {
-                let _ = serde_json::from_str::<AppInstanceSummary>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::AppStatus => {
-                let _ = serde_json::from_str::<AppInstanceStatus>(&data)
-                    .map_err(|err| SerdeError::new(data.to_string(), err))?;
-            }
-            TestMessageType::Response => {}
-            TestMessageType::Unknown(s) => {
-                println!("Unknown message type: {}", s);
-                panic!("Deserialization is not implemented!!!");
-            }
-        }
+fn test_newer_schema_payloads() {
+    let mut model = MonitorModel::default();
+    for json in [
+        include_str!("../../fixtures/protocol/version.json"),
+        include_str!("../../fixtures/protocol/network-status-v2.json"),
+        include_str!("../../fixtures/protocol/cellular-status.json"),
+    ] {
+        assert!(model.apply_ipc_message(receive(json)).is_none());
     }
-    Ok(())
+
+    let ProtocolState::Negotiated(version) = &model.protocol else {
+        panic!("protocol is not negotiated: {:?}", model.protocol);
+    };
+    let mismatches: Vec<_> = version
+        .mismatches()
+        .into_iter()
+        .map(|m| (m.message_type, m.supported, m.sent))
+        .collect();
+    assert_eq!(
+        mismatches,
+        vec![
+            ("CellularStatus".to_string(), None, 1),
+            ("NetworkStatus".to_string(), Some(1), 2),
+        ]
+    );
+
+    // the messages are skipped instead of breaking the connection
+    assert!(model.network.is_empty());
+    assert_eq!(model.unsupported_messages["NetworkStatus"].schema, Some(2));
+    assert_eq!(model.unsupported_messages["CellularStatus"].schema, Some(1));
+    // one for the schema mismatch and one for the skipped message of each type
+    let alerts: Vec<_> = model
+        .alerts
+        .iter()
+        .map(|a| (a.severity, a.source.as_str(), a.text.as_str()))
+        .collect();
+    assert_eq!(
+        alerts,
+        vec![
+            (
+                AlertSeverity::Warning,
+                "ipc",
+                "EVE sends CellularStatus schema 1, the monitor supports no schema"
+            ),
+            (
+                AlertSeverity::Warning,
+                "ipc",
+                "EVE sends NetworkStatus schema 2, the monitor supports schema 1"
+            ),
+            (
+                AlertSeverity::Warning,
+                "ipc",
+                "NetworkStatus from EVE is not supported by this monitor and is not shown"
+            ),
+            (
+                AlertSeverity::Warning,
+                "ipc",
+                "CellularStatus from EVE is not supported by this monitor and is not shown"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_values_captured() {
+    let fixture = &fixtures!("NetworkStatus")[0];
+    let mut json = fixture.json.clone();
+    // a DPC state and a field from a newer EVE
+    json["message"]["State"] = 99.into();
+    json["message"]["Uplinks"] = 2.into();
+
+    let mut model = MonitorModel::default();
+    model.apply_ipc_message(receive(&json.to_string()));
+    assert!(!model.network.is_empty());
+    let unknown: Vec<_> = model.unknown_values["NetworkStatus"]
+        .iter()
+        .map(|u| (u.path.as_str(), u.kind))
+        .collect();
+    assert!(unknown.contains(&("State", UnknownKind::EnumValue)));
+    assert!(unknown.contains(&("Uplinks", UnknownKind::Field)));
+    assert!(model.has_unknown_enum_values("NetworkStatus"));
+}
+
+#[test]
+fn test_cellular_port_status() {
+    let fixture = &fixtures!("NetworkStatus")[0];
+    let mut json = fixture.json.clone();
+    let port = &mut json["message"]["Ports"][0];
+    // a modem without configured access points
+    port["WirelessCfg"]["WType"] = 1.into();
+    port["WirelessStatus"]["Cellular"] = serde_json::json!({
+        "LogicalLabel": "modem",
+        "PhysAddrs": {"Interface": "wwan0", "USB": "1:3.3", "PCI": "", "Dev": "/dev/cdc-wdm0"},
+        "Module": {
+            "Name": "EG25", "IMEI": "867698040000000", "Model": "EG25-G",
+            "Manufacturer": "QUALCOMM INCORPORATED", "Revision": "EG25GGBR07A08M2G",
+            "ControlProtocol": "qmi", "OpMode": "online-and-connected"
+        },
+        "SimCards": [{
+            "Name": "sim1", "SlotNumber": 1, "SlotActivated": true, "ICCID": "89012703578345957137",
+            "IMSI": "310170845466094", "Type": 1, "State": "present"
+        }],
+        "ConfigError": "",
+        "ProbeError": "no ping response",
+        "CurrentProvider": {
+            "PLMN": "310-170", "Description": "AT&T", "CurrentServing": true,
+            "Roaming": false, "Forbidden": false
+        },
+        "VisibleProviders": null,
+        "CurrentRATs": ["lte", "5gnr"],
+        "ConnectedAt": 1700000000,
+        "IPSettings": {"Address": "10.1.2.3/30", "Gateway": "10.1.2.1", "DNSServers": null, "MTU": 1500},
+        "LocationTracking": false
+    });
+
+    let mut model = MonitorModel::default();
+    model.apply_ipc_message(receive(&json.to_string()));
+    let NetworkType::Cellular(cellular) = &model.network[0].media else {
+        panic!("not a cellular port: {:?}", model.network[0].media);
+    };
+    assert!(cellular.sims.is_empty());
+    let modem = &cellular.modem;
+    assert_eq!(modem.module.imei, "867698040000000");
+    assert_eq!(modem.phys_addrs.dev, "/dev/cdc-wdm0");
+    let sims = modem.sim_cards.as_ref().unwrap();
+    assert_eq!(sims[0].sim_type, SimType::Physical);
+    assert!(sims[0].slot_activated);
+    assert_eq!(modem.current_provider.plmn, "310-170");
+    assert_eq!(
+        modem.current_rats,
+        Some(vec![WwanRAT::WwanRATLTE, WwanRAT::WwanRAT5GNR])
+    );
 }
@@ -0,0 +1,330 @@
+// Locks the UI after a period without key presses. Consoles in the field may be
+// physically accessible, only someone with the passcode can use the monitor
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use log::{info, warn};
+
+use crate::{config::LockConfig, model::device::identity::sha256};
+
+// makes guessing from a leaked config file slow
+const ITERATIONS: u32 = 100_000;
+// verifying runs on the UI thread, a huge count from the config would freeze it
+const MAX_ITERATIONS: u32 = 10 * ITERATIONS;
+// wrong passcodes in a row before unlocking is refused for a while
+const MAX_FAILURES: u32 = 3;
+const BACKOFF: Duration = Duration::from_secs(30);
+const SHA256_BLOCK: usize = 64;
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// RFC 2104
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; SHA256_BLOCK];
+    if key.len() > SHA256_BLOCK {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(message);
+    let mut outer: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+// PBKDF2 of RFC 8018 with HMAC-SHA256, fills the whole output
+fn pbkdf2_sha256(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    for (index, chunk) in output.chunks_mut(32).enumerate() {
+        let mut message = salt.to_vec();
+        message.extend_from_slice(&(index as u32 + 1).to_be_bytes());
+        let mut u = hmac_sha256(password, &message);
+        let mut t = u;
+        for _ in 1..iterations {
+            u = hmac_sha256(password, &u);
+            t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+        }
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+}
+
+fn digest(passcode: &str, salt: &str, iterations: u32) -> [u8; 32] {
+    let mut digest = [0u8; 32];
+    pbkdf2_sha256(
+        passcode.as_bytes(),
+        salt.as_bytes(),
+        iterations,
+        &mut digest,
+    );
+    digest
+}
+
+/// The passcode hash as the config file keeps it:
+/// "pbkdf2-sha256$<iterations>$<salt>$<hex of the 32 byte key>". The key is
+/// PBKDF2-HMAC-SHA256 of the UTF-8 passcode with the UTF-8 salt
+pub fn hash_passcode(passcode: &str, salt: &str) -> String {
+    hash_with_iterations(passcode, salt, ITERATIONS)
+}
+
+fn hash_with_iterations(passcode: &str, salt: &str, iterations: u32) -> String {
+    format!(
+        "pbkdf2-sha256${}${}${}",
+        iterations,
+        salt,
+        to_hex(&digest(passcode, salt, iterations))
+    )
+}
+
+// iterations, salt and digest of a stored hash
+fn parse_hash(stored: &str) -> Option<(u32, &str, &str)> {
+    let mut parts = stored.split('$');
+    if parts.next()? != "pbkdf2-sha256" {
+        return None;
+    }
+    let iterations = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|i| (1..=MAX_ITERATIONS).contains(i))?;
+    let salt = parts.next()?;
+    let digest = parts.next().filter(|d| d.len() == 64)?;
+    parts.next().is_none().then_some((iterations, salt, digest))
+}
+
+pub fn verify_passcode(stored: &str, passcode: &str) -> bool {
+    let Some((iterations, salt, expected)) = parse_hash(stored) else {
+        return false;
+    };
+    let actual = to_hex(&digest(passcode, salt, iterations));
+    // do not tell how many characters matched
+    actual
+        .bytes()
+        .zip(expected.bytes())
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// What the lock screen shows, the passcode itself stays in SessionLock
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockScreen {
+    // characters typed so far
+    pub typed: usize,
+    pub message: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct SessionLock {
+    // None disables the lock
+    timeout: Option<Duration>,
+    passcode_hash: String,
+    last_activity: Instant,
+    locked: bool,
+    input: String,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl SessionLock {
+    pub fn new(config: &LockConfig) -> Self {
+        let timeout = match (config.timeout_minutes, config.passcode_hash.as_str()) {
+            (0, _) | (_, "") => None,
+            // a broken hash would lock the user out for good
+            (_, hash) if parse_hash(hash).is_none() => {
+                warn!("Invalid lock.passcode_hash in the config, the UI is not locked");
+                None
+            }
+            (minutes, _) => Some(Duration::from_secs(minutes * 60)),
+        };
+        Self {
+            timeout,
+            passcode_hash: config.passcode_hash.clone(),
+            last_activity: Instant::now(),
+            locked: false,
+            input: String::new(),
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Register user activity, it postpones the lock
+    pub fn touch(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    /// Must be called periodically. Returns true if the UI got locked
+    pub fn check(&mut self, now: Instant) -> bool {
+        let Some(timeout) = self.timeout else {
+            return false;
+        };
+        if self.locked || now.saturating_duration_since(self.last_activity) < timeout {
+            return false;
+        }
+        info!("No user activity for {:?}, locking the UI", timeout);
+        self.locked = true;
+        self.input.clear();
+        true
+    }
+
+    /// Type the passcode, Enter tries to unlock
+    pub fn handle_key(&mut self, key: KeyEvent, now: Instant) {
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(c)
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Esc => self.input.clear(),
+            KeyCode::Enter => self.unlock(now),
+            _ => {}
+        }
+    }
+
+    fn unlock(&mut self, now: Instant) {
+        let input = std::mem::take(&mut self.input);
+        if self.retry_at.is_some_and(|at| now < at) {
+            return;
+        }
+        if verify_passcode(&self.passcode_hash, &input) {
+            info!("UI unlocked");
+            self.locked = false;
+            self.failures = 0;
+            self.retry_at = None;
+            self.last_activity = now;
+            return;
+        }
+        self.failures += 1;
+        warn!(
+            "Wrong passcode on the lock screen, {} in a row",
+            self.failures
+        );
+        if self.failures.is_multiple_of(MAX_FAILURES) {
+            self.retry_at = Some(now + BACKOFF);
+        }
+    }
+
+    /// None while unlocked
+    pub fn screen(&self, now: Instant) -> Option<LockScreen> {
+        if !self.locked {
+            return None;
+        }
+        let message = match self.retry_at {
+            Some(at) if now < at => Some(format!(
+                "Too many wrong passcodes, try again in {}s",
+                at.saturating_duration_since(now).as_secs() + 1
+            )),
+            _ if self.failures > 0 => Some("Wrong passcode".to_string()),
+            _ => None,
+        };
+        Some(LockScreen {
+            typed: self.input.chars().count(),
+            message,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_passcode(lock: &mut SessionLock, passcode: &str, now: Instant) {
+        for c in passcode.chars() {
+            lock.handle_key(KeyEvent::from(KeyCode::Char(c)), now);
+        }
+        lock.handle_key(KeyEvent::from(KeyCode::Enter), now);
+    }
+
+    #[test]
+    fn test_passcode_hash() {
+        let hex = |output: &[u8]| to_hex(output);
+        // RFC 4231 test cases 1 and 6
+        assert_eq!(
+            hex(&hmac_sha256(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+        // RFC 6070 inputs with SHA-256 and the first vector of RFC 7914
+        let mut output = [0u8; 32];
+        pbkdf2_sha256(b"password", b"salt", 1, &mut output);
+        assert_eq!(
+            hex(&output),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        pbkdf2_sha256(b"password", b"salt", 4096, &mut output);
+        assert_eq!(
+            hex(&output),
+            "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"
+        );
+        let mut output = [0u8; 64];
+        pbkdf2_sha256(b"passwd", b"salt", 1, &mut output);
+        assert_eq!(
+            hex(&output),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+             49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        );
+
+        // a fixed salt gives a known hash
+        let stored = "pbkdf2-sha256$1000$fixedsalt$\
+                      8b680e99e4c02ffbaffd76e758060fd2d20e9ef1fbcebb4857cd2ddf18df32c1";
+        assert_eq!(hash_with_iterations("1234", "fixedsalt", 1000), stored);
+        assert!(verify_passcode(stored, "1234"));
+        assert!(!verify_passcode(stored, "1235"));
+        // other schemes and iteration counts that would freeze the UI
+        let digest = "282024630219480b0d7a0c8a19bd045c0ec7fbdf6cde8200eaa8769eab9c31ec";
+        assert!(!verify_passcode(
+            &format!("sha256$2$salt${}", digest),
+            "1234"
+        ));
+        assert!(parse_hash(&format!("pbkdf2-sha256$4000000000$salt${}", digest)).is_none());
+        assert!(parse_hash(&format!("pbkdf2-sha256$0$salt${}", digest)).is_none());
+    }
+
+    #[test]
+    fn test_session_lock() {
+        let hash = hash_with_iterations("1234", "salt", 1000);
+        assert!(verify_passcode(&hash, "1234"));
+        assert!(!verify_passcode(&hash, "12345"));
+        assert!(!verify_passcode("1234", "1234"));
+
+        let mut lock = SessionLock::new(&LockConfig {
+            timeout_minutes: 1,
+            passcode_hash: hash,
+        });
+        let start = Instant::now();
+        assert!(!lock.check(start));
+        assert!(lock.check(start + Duration::from_secs(61)));
+        assert!(lock.is_locked());
+
+        let now = start + Duration::from_secs(62);
+        for _ in 0..MAX_FAILURES {
+            type_passcode(&mut lock, "0000", now);
+        }
+        // the right passcode does not help while waiting
+        type_passcode(&mut lock, "1234", now);
+        assert!(lock.is_locked());
+        assert!(lock
+            .screen(now)
+            .and_then(|s| s.message)
+            .is_some_and(|m| m.starts_with("Too many")));
+
+        type_passcode(&mut lock, "1234", now + BACKOFF);
+        assert!(!lock.is_locked());
+        assert_eq!(lock.screen(now + BACKOFF), None);
+
+        // no passcode, no lock
+        let mut lock = SessionLock::new(&LockConfig::default());
+        assert!(!lock.check(start + Duration::from_secs(3600)));
+    }
+}
@@ -1,8 +1,23 @@
+mod action_bus;
 mod actions;
 mod application;
+mod audit;
+mod bundler;
+mod cli;
+mod collectors;
+mod config;
+mod diagnostics;
+mod error;
 mod events;
+mod file_io;
+mod guest_console;
+mod idle;
 mod ipc;
+mod lock;
 mod model;
+mod qrcode;
+mod selftest;
+mod smoke_test;
 mod terminal;
 mod traits;
 mod ui;
@@ -10,9 +25,13 @@ mod ui;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
-use application::Application;
+use application::{Application, Replay};
+use clap::Parser;
+use cli::Cli;
+use ipc::replay::load_recording;
 use libc::{EXIT_FAILURE, EXIT_SUCCESS};
 use log::{info, warn, LevelFilter};
+use model::model_snapshot::ModelSnapshot;
 use terminal::TerminalWrapper;
 
 const EVE_MONITOR_LOG_DIR_EVE: &str = "/persist/monitor/log";
@@ -168,11 +187,46 @@ fn log_system_info() {
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    // headless modes print to stdout and must not rotate the UI log sessions
+    let headless = if cli.watch {
+        Some(cli::run_watch(&Application::get_socket_path()).await)
+    } else if cli.hash_passcode {
+        Some(cli::run_hash_passcode())
+    } else if cli.self_test {
+        Some(smoke_test::run_smoke_test().await)
+    } else if let Some(command) = cli.command {
+        Some(cli::run_query(command, &Application::get_socket_path()).await)
+    } else {
+        None
+    };
+    if let Some(result) = headless {
+        if let Err(e) = result {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+        std::process::exit(EXIT_SUCCESS);
+    }
+
     let _log2 = init_logging();
     initialize_panic_handler()?;
     log_system_info();
 
-    let mut app = Application::new()?;
+    let replay = if let Some(path) = cli.replay.as_deref() {
+        ModelSnapshot::load(path).map(|snapshot| Some(Replay::Snapshot(snapshot)))
+    } else if let Some(path) = cli.playback.as_deref() {
+        load_recording(path).map(|messages| Some(Replay::Recording(messages)))
+    } else {
+        Ok(None)
+    };
+    let replay = match replay {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+    let mut app = Application::new(replay, cli.record)?;
     let result = app.run().await;
     if let Err(e) = &result {
         log::error!("Application error: {}", e);
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Local};
+#[cfg(feature = "dmesg")]
+use log::warn;
+#[cfg(feature = "dmesg")]
+use regex::Regex;
+use serde::Deserialize;
+use strum::Display;
+
+#[cfg(feature = "dmesg")]
+use crate::config::DmesgAlertRule;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+// where the user can find more details about the alert
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertLink {
+    // sequence number of the kernel message, see MonitorModel::push_dmesg
+    #[cfg(feature = "dmesg")]
+    Dmesg(u64),
+}
+
+#[cfg(feature = "dmesg")]
+impl AlertLink {
+    pub fn dmesg_seq(&self) -> Option<u64> {
+        match self {
+            AlertLink::Dmesg(seq) => Some(*seq),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    // increases with every alert raised, kept when older alerts are dropped
+    pub id: u64,
+    pub time: DateTime<Local>,
+    pub severity: AlertSeverity,
+    // subsystem that raised the alert e.g. "dmesg"
+    pub source: String,
+    pub text: String,
+    #[cfg(feature = "dmesg")]
+    pub link: Option<AlertLink>,
+    // the user has seen it, it is no longer counted as new
+    pub acknowledged: bool,
+}
+
+/// Raises alerts for kernel messages matching the configured patterns
+#[cfg(feature = "dmesg")]
+pub struct DmesgAlertMatcher {
+    rules: Vec<(String, AlertSeverity, Regex)>,
+}
+
+#[cfg(feature = "dmesg")]
+impl DmesgAlertMatcher {
+    pub fn new(rules: &[DmesgAlertRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(re) => Some((rule.name.clone(), rule.severity, re)),
+                Err(e) => {
+                    warn!("Ignoring dmesg alert rule {}: {}", rule.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Returns the name and severity of the first rule matching the message
+    pub fn check(&self, message: &str) -> Option<(&str, AlertSeverity)> {
+        self.rules
+            .iter()
+            .find(|(_, _, re)| re.is_match(message))
+            .map(|(name, severity, _)| (name.as_str(), *severity))
+    }
+}
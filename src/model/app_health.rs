@@ -0,0 +1,109 @@
+// The app summary of EVE turned into one health state. EVE only counts the
+// apps by state, the apps it reports in AppsList are the ones expected to run
+use crate::{config::AppThresholds, ipc::eve_types::AppInstanceSummary};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppHealth {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppCounts {
+    pub running: usize,
+    pub starting: usize,
+    pub stopping: usize,
+    pub error: usize,
+    // None until EVE reports the list of apps
+    pub expected: Option<usize>,
+}
+
+/// "1 app", "3 apps"
+pub fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+impl AppCounts {
+    pub fn new(summary: &AppInstanceSummary, known_apps: usize) -> Self {
+        let running = summary.total_running as usize;
+        let starting = summary.total_starting as usize;
+        let stopping = summary.total_stopping as usize;
+        let error = summary.total_error as usize;
+        // the app list may lag behind the summary
+        let counted = running + starting + stopping + error;
+        Self {
+            running,
+            starting,
+            stopping,
+            error,
+            expected: (known_apps > 0).then(|| known_apps.max(counted)),
+        }
+    }
+
+    pub fn health(&self, thresholds: &AppThresholds) -> AppHealth {
+        let percent = match self.expected {
+            Some(expected) if expected > 0 => self.running * 100 / expected,
+            // nothing to run
+            _ => 100,
+        };
+        if percent >= thresholds.ok_percent as usize && self.error == 0 {
+            AppHealth::Ok
+        } else if percent >= thresholds.warning_percent as usize {
+            AppHealth::Warning
+        } else {
+            AppHealth::Error
+        }
+    }
+
+    /// e.g. "3 of 5 apps running"
+    pub fn running_text(&self) -> String {
+        match self.expected {
+            Some(expected) => format!(
+                "{} of {} running",
+                self.running,
+                plural(expected, "app", "apps")
+            ),
+            None if self.running == 0 => "No apps".to_string(),
+            None => format!("{} running", plural(self.running, "app", "apps")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(running: u8, error: u8, known_apps: usize) -> AppCounts {
+        let summary = AppInstanceSummary {
+            total_running: running,
+            total_error: error,
+            ..Default::default()
+        };
+        AppCounts::new(&summary, known_apps)
+    }
+
+    #[test]
+    fn test_app_health() {
+        let thresholds = AppThresholds::default();
+        assert_eq!(counts(0, 0, 0).health(&thresholds), AppHealth::Ok);
+        assert_eq!(counts(0, 0, 0).running_text(), "No apps");
+        assert_eq!(counts(1, 0, 0).running_text(), "1 app running");
+
+        let apps = counts(3, 0, 5);
+        assert_eq!(apps.running_text(), "3 of 5 apps running");
+        assert_eq!(apps.health(&thresholds), AppHealth::Warning);
+        assert_eq!(counts(2, 0, 5).health(&thresholds), AppHealth::Error);
+        assert_eq!(counts(5, 0, 5).health(&thresholds), AppHealth::Ok);
+        // all running but one failed to restart
+        assert_eq!(counts(5, 1, 5).health(&thresholds), AppHealth::Warning);
+        // the list lags behind the summary
+        assert_eq!(counts(2, 0, 1).running_text(), "2 of 2 apps running");
+
+        let relaxed = AppThresholds {
+            ok_percent: 60,
+            warning_percent: 20,
+        };
+        assert_eq!(apps.health(&relaxed), AppHealth::Ok);
+    }
+}
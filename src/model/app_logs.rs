@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+use uuid::Uuid;
+
+use crate::ipc::{eve_types::AppLogChunk, message::RequestId};
+
+use super::sanitize::{sanitize_line, sanitize_line_in_place};
+
+// apps can be chatty, keep only the tail
+const MAX_LINES: usize = 5000;
+
+/// Logs of the app shown in the log viewer, streamed by EVE
+#[derive(Debug)]
+pub struct AppLogs {
+    pub uuid: Uuid,
+    pub app_name: String,
+    lines: VecDeque<String>,
+    // lines dropped from the head to stay within MAX_LINES
+    pub dropped: u64,
+    pub error: Option<String>,
+    // StartAppLogs request, EVE rejects it if it cannot stream logs
+    pub request_id: Option<RequestId>,
+}
+
+impl AppLogs {
+    pub fn new(uuid: Uuid, app_name: String) -> Self {
+        Self {
+            uuid,
+            app_name,
+            lines: VecDeque::new(),
+            dropped: 0,
+            error: None,
+            request_id: None,
+        }
+    }
+
+    pub fn lines(&self) -> &VecDeque<String> {
+        &self.lines
+    }
+
+    pub fn push(&mut self, chunk: AppLogChunk) {
+        if !chunk.error.is_empty() {
+            self.error = Some(sanitize_line(&chunk.error));
+        }
+        for mut line in chunk.lines.unwrap_or_default() {
+            if self.lines.len() == MAX_LINES {
+                self.lines.pop_front();
+                self.dropped += 1;
+            }
+            // a line must not break the layout of the viewer
+            sanitize_line_in_place(&mut line);
+            self.lines.push_back(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_keeps_tail() {
+        let mut logs = AppLogs::new(Uuid::nil(), "app".to_string());
+        logs.push(AppLogChunk {
+            app_uuid: Uuid::nil(),
+            lines: Some((0..MAX_LINES + 2).map(|i| i.to_string()).collect()),
+            error: String::new(),
+        });
+        assert_eq!(logs.lines().len(), MAX_LINES);
+        assert_eq!(logs.dropped, 2);
+        assert_eq!(logs.lines().front().map(String::as_str), Some("2"));
+        assert_eq!(logs.error, None);
+    }
+}
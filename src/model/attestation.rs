@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Local, TimeDelta};
+
+use crate::ipc::eve_types::AttestState;
+
+const HISTORY_LEN: usize = 20;
+// attestation waiting in the same state longer than this is considered stuck
+const STUCK_AFTER: TimeDelta = TimeDelta::minutes(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttestationEvent {
+    State { state: AttestState, error: String },
+    RetryRequested,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttestationEntry {
+    pub time: DateTime<Local>,
+    pub event: AttestationEvent,
+}
+
+/// Attestation state reported in ZedAgentStatus and the last transitions
+#[derive(Debug, Default)]
+pub struct AttestationHistory {
+    entries: VecDeque<AttestationEntry>,
+    state: Option<(AttestState, String)>,
+    since: Option<DateTime<Local>>,
+    retry_pending: bool,
+}
+
+impl AttestationHistory {
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &AttestationEntry> {
+        self.entries.iter()
+    }
+
+    pub fn state(&self) -> Option<AttestState> {
+        self.state.as_ref().map(|(state, _)| *state)
+    }
+
+    fn push(&mut self, event: AttestationEvent) {
+        if self.entries.len() == HISTORY_LEN {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(AttestationEntry {
+            time: Local::now(),
+            event,
+        });
+    }
+
+    pub fn update(&mut self, state: AttestState, error: String) {
+        if self.state.as_ref() == Some(&(state, error.clone())) {
+            return;
+        }
+        // EVE picked up the retry or moved on by itself
+        self.retry_pending = false;
+        self.push(AttestationEvent::State {
+            state,
+            error: error.clone(),
+        });
+        self.state = Some((state, error));
+        self.since = Some(Local::now());
+    }
+
+    pub fn is_stuck(&self, now: DateTime<Local>) -> bool {
+        match &self.state {
+            None | Some((AttestState::StateComplete, _)) => false,
+            Some((AttestState::StateRestartWait, _)) => true,
+            Some((_, error)) if !error.is_empty() => true,
+            Some(_) => self.since.is_some_and(|since| now - since > STUCK_AFTER),
+        }
+    }
+
+    /// Why a retry cannot be requested right now
+    pub fn retry_blocker(&self) -> Option<&'static str> {
+        match self.state() {
+            None => Some("Attestation state is not known yet"),
+            Some(AttestState::StateComplete) => Some("Attestation is complete"),
+            _ if self.retry_pending => Some("Retry is already requested, waiting for EVE"),
+            _ => None,
+        }
+    }
+
+    pub fn retry_requested(&mut self) {
+        self.retry_pending = true;
+        self.push(AttestationEvent::RetryRequested);
+    }
+}
@@ -0,0 +1,51 @@
+// Changes made to the device from the monitor. The records are kept in the
+// audit file under /persist so they survive reboots, see crate::audit
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    // assigned by the model, not stored
+    #[serde(skip)]
+    pub id: u64,
+    // when the user sent the change
+    pub time: DateTime<Utc>,
+    // the IPC request, e.g. SetDPC
+    pub action: String,
+    // what the change applies to, e.g. the interface or the application
+    pub target: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    // None if EVE accepted the change
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    pub fn new<A: Into<String>, T: Into<String>>(
+        action: A,
+        target: T,
+        old: Option<String>,
+        new: Option<String>,
+    ) -> Self {
+        Self {
+            id: 0,
+            time: Utc::now(),
+            action: action.into(),
+            target: target.into(),
+            old,
+            new,
+            error: None,
+        }
+    }
+
+    pub fn accepted(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn result(&self) -> String {
+        match &self.error {
+            None => "accepted".to_string(),
+            Some(e) => format!("failed: {}", e),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+
+use log::{debug, warn};
+
+use crate::ipc::eve_types::{BlobRef, BlobSlot};
+use crate::model::device::identity::fingerprint;
+
+/// Keeps `pubsub-large-*` payloads out of the model. Blobs are deduplicated by
+/// content, only `memory_limit` bytes are kept in RAM and the rest is moved to `dir`
+#[derive(Debug)]
+pub struct BlobStore {
+    memory: HashMap<String, Vec<u8>>,
+    // ids in the order they were added, the oldest are moved to disk first
+    order: VecDeque<String>,
+    memory_used: usize,
+    memory_limit: usize,
+    on_disk: HashSet<String>,
+    dir: PathBuf,
+}
+
+impl BlobStore {
+    pub fn new(dir: PathBuf, memory_limit: usize) -> Self {
+        Self {
+            memory: HashMap::new(),
+            order: VecDeque::new(),
+            memory_used: 0,
+            memory_limit,
+            on_disk: HashSet::new(),
+            dir,
+        }
+    }
+
+    /// Blobs left on disk by the previous run are not referenced by anything
+    pub fn remove_leftovers(&self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+
+    pub fn store(&mut self, data: Vec<u8>) -> BlobRef {
+        let blob = BlobRef {
+            id: fingerprint(&data),
+            size: data.len(),
+        };
+        if !self.memory.contains_key(&blob.id) && !self.on_disk.contains(&blob.id) {
+            self.memory_used += data.len();
+            self.memory.insert(blob.id.clone(), data);
+            self.order.push_back(blob.id.clone());
+            self.spill();
+        }
+        blob
+    }
+
+    pub fn load(&self, blob: &BlobRef) -> Option<Vec<u8>> {
+        if let Some(data) = self.memory.get(&blob.id) {
+            return Some(data.clone());
+        }
+        if !self.on_disk.contains(&blob.id) {
+            return None;
+        }
+        std::fs::read(self.dir.join(&blob.id))
+            .inspect_err(|e| warn!("Cannot read blob {}: {}", blob.id, e))
+            .ok()
+    }
+
+    /// Move inline content of the slots into the store
+    pub fn store_all(&mut self, slots: Vec<&mut dyn BlobSlot>) {
+        for slot in slots {
+            slot.store_with(&mut |data| self.store(data));
+        }
+    }
+
+    /// Put the content back into the slots. Returns false if any blob is missing
+    pub fn load_all(&self, slots: Vec<&mut dyn BlobSlot>) -> bool {
+        let mut loaded = true;
+        for slot in slots {
+            loaded &= slot.load_with(&mut |blob| self.load(blob));
+        }
+        loaded
+    }
+
+    /// Drop all blobs that are not referenced by `slots`
+    pub fn retain(&mut self, slots: Vec<&mut dyn BlobSlot>) {
+        let live: HashSet<String> = slots
+            .iter()
+            .filter_map(|slot| slot.blob_ref())
+            .map(|blob| blob.id.clone())
+            .collect();
+        for id in self.on_disk.iter().filter(|id| !live.contains(*id)) {
+            let _ = std::fs::remove_file(self.dir.join(id));
+        }
+        self.on_disk.retain(|id| live.contains(id));
+        self.memory.retain(|id, _| live.contains(id));
+        self.order.retain(|id| live.contains(id));
+        self.memory_used = self.memory.values().map(Vec::len).sum();
+    }
+
+    fn spill(&mut self) {
+        while self.memory_used > self.memory_limit {
+            let Some(id) = self.order.pop_front() else {
+                break;
+            };
+            let Some(data) = self.memory.remove(&id) else {
+                continue;
+            };
+            self.memory_used -= data.len();
+            let written = std::fs::create_dir_all(&self.dir)
+                .and_then(|_| std::fs::write(self.dir.join(&id), &data));
+            match written {
+                Ok(_) => {
+                    debug!("Blob {} ({} bytes) moved to disk", id, data.len());
+                    self.on_disk.insert(id);
+                }
+                Err(e) => {
+                    // better over the limit than losing the content
+                    warn!("Cannot move blob {} to disk: {}", id, e);
+                    self.memory_used += data.len();
+                    self.memory.insert(id.clone(), data);
+                    self.order.push_front(id);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::eve_types::LargeBlob;
+
+    #[test]
+    fn test_blob_store() {
+        let dir = std::env::temp_dir().join(format!("blobs-{}", std::process::id()));
+        let mut store = BlobStore::new(dir.clone(), 10);
+        let mut first = LargeBlob::Inline(vec![1u8; 8]);
+        let mut second = LargeBlob::Inline(vec![2u8; 8]);
+        let mut copy = LargeBlob::Inline(vec![2u8; 8]);
+        store.store_all(vec![&mut first, &mut second, &mut copy]);
+        assert_eq!(second, copy);
+
+        // the oldest blob went over the limit and was moved to disk
+        let LargeBlob::Stored(first_ref) = first.clone() else {
+            panic!("{:?} is not stored", first);
+        };
+        assert!(dir.join(&first_ref.id).exists());
+        assert_eq!(store.memory_used, 8);
+        assert_eq!(store.load(&first_ref), Some(vec![1u8; 8]));
+
+        // only the file of the first blob is left behind
+        store.retain(vec![&mut second]);
+        assert!(!dir.join(&first_ref.id).exists());
+        assert_eq!(store.load(&first_ref), None);
+        assert!(!store.load_all(vec![&mut first]));
+        assert!(store.load_all(vec![&mut second]));
+        assert_eq!(second, LargeBlob::Inline(vec![2u8; 8]));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,19 @@
+// Progress of the support bundle the monitor creates itself, see crate::bundler
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleProgress {
+    Running {
+        step: &'static str,
+        done: usize,
+        total: usize,
+    },
+    Completed(PathBuf),
+    Failed(String),
+}
+
+impl BundleProgress {
+    pub fn is_running(&self) -> bool {
+        matches!(self, BundleProgress::Running { .. })
+    }
+}
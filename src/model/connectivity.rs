@@ -0,0 +1,216 @@
+// A single 0-100 number telling how well the device can reach the controller.
+// Every problem found costs points and is listed as a reason so the number
+// is never a mystery
+use chrono::{DateTime, Utc};
+
+use crate::ipc::eve_types::{ConfigGetStatus, DPCState, TestResults};
+
+use super::device::network::{IpV6LinikLocal, NetworkInterfaceStatus};
+
+// points taken per problem. Of the port problems only the first that
+// applies counts, the rest add up
+const NO_MGMT_PORT: u8 = 40;
+const NO_PORT_UP: u8 = 40;
+const NO_ADDRESS: u8 = 30;
+const NO_GATEWAY: u8 = 10;
+const NO_DNS: u8 = 10;
+const DPC_FAILED: u8 = 25;
+const DPC_WAITING: u8 = 10;
+const NO_DPC: u8 = 10;
+const TEST_FAILED: u8 = 15;
+const CONTROLLER_FAILED: u8 = 20;
+const CONTROLLER_SAVED_CONFIG: u8 = 15;
+const CONTROLLER_TEMPORARY_FAIL: u8 = 10;
+const CONTROLLER_UNKNOWN: u8 = 10;
+
+/// Management ports by what they have
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PortSummary {
+    pub mgmt: usize,
+    pub up: usize,
+    pub with_address: usize,
+    pub with_gateway: usize,
+    pub with_dns: usize,
+}
+
+impl PortSummary {
+    pub fn new(ports: &[NetworkInterfaceStatus]) -> Self {
+        let mut summary = PortSummary::default();
+        for port in ports.iter().filter(|p| p.is_mgmt) {
+            summary.mgmt += 1;
+            if !port.up {
+                continue;
+            }
+            summary.up += 1;
+            // a link-local address does not reach the controller
+            let ipv4 = port.ipv4.as_ref().is_some_and(|a| !a.is_empty());
+            let ipv6 = port
+                .ipv6
+                .as_ref()
+                .is_some_and(|a| a.iter().any(|a| !a.is_link_local()));
+            if ipv4 || ipv6 {
+                summary.with_address += 1;
+            }
+            if port.gw.is_some() {
+                summary.with_gateway += 1;
+            }
+            if port.dns.as_ref().is_some_and(|d| !d.is_empty()) {
+                summary.with_dns += 1;
+            }
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectivityScore {
+    pub score: u8,
+    // why points were taken, worst first
+    pub reasons: Vec<String>,
+}
+
+// EVE sends the zero time for tests that did not run
+fn is_set(time: &DateTime<Utc>) -> bool {
+    time.timestamp() > 0
+}
+
+pub fn connectivity_score(
+    ports: &PortSummary,
+    dpc: Option<(&DPCState, &TestResults)>,
+    controller: Option<&ConfigGetStatus>,
+) -> ConnectivityScore {
+    let mut penalties: Vec<(u8, String)> = Vec::new();
+
+    let port_penalty = if ports.mgmt == 0 {
+        Some((NO_MGMT_PORT, "No management port".to_string()))
+    } else if ports.up == 0 {
+        Some((NO_PORT_UP, "No management port is up".to_string()))
+    } else if ports.with_address == 0 {
+        Some((
+            NO_ADDRESS,
+            "No management port has an IP address".to_string(),
+        ))
+    } else {
+        None
+    };
+    penalties.extend(port_penalty);
+    if ports.with_address > 0 {
+        if ports.with_gateway == 0 {
+            penalties.push((NO_GATEWAY, "No default gateway".to_string()));
+        }
+        if ports.with_dns == 0 {
+            penalties.push((NO_DNS, "No DNS server".to_string()));
+        }
+    }
+
+    match dpc {
+        None => penalties.push((NO_DPC, "No port configuration reported".to_string())),
+        Some((state, tests)) => {
+            match state {
+                DPCState::Fail | DPCState::FailWithIPAndDNS => penalties.push((
+                    DPC_FAILED,
+                    format!("Port configuration failed: {:?}", state),
+                )),
+                DPCState::IPDNSWait
+                | DPCState::PCIWait
+                | DPCState::IntfWait
+                | DPCState::RemoteWait
+                | DPCState::AsyncWait => penalties.push((
+                    DPC_WAITING,
+                    format!("Port configuration is waiting: {:?}", state),
+                )),
+                _ => {}
+            }
+            if is_set(&tests.last_failed) && tests.last_failed > tests.last_succeeded {
+                let error = tests.last_error.lines().next().unwrap_or_default();
+                penalties.push((
+                    TEST_FAILED,
+                    format!("Last connectivity test failed: {}", error),
+                ));
+            }
+        }
+    }
+
+    let controller_penalty = match controller {
+        None => Some((CONTROLLER_UNKNOWN, "Controller status unknown")),
+        Some(ConfigGetStatus::Success) => None,
+        Some(ConfigGetStatus::Fail) => Some((CONTROLLER_FAILED, "Cannot reach the controller")),
+        Some(ConfigGetStatus::TemporaryFail) => Some((
+            CONTROLLER_TEMPORARY_FAIL,
+            "Controller temporarily unreachable",
+        )),
+        Some(ConfigGetStatus::ReadSaved) => Some((
+            CONTROLLER_SAVED_CONFIG,
+            "Running the saved configuration, the controller is not reachable",
+        )),
+        Some(ConfigGetStatus::Unrecognized) => {
+            Some((CONTROLLER_UNKNOWN, "Controller status unknown"))
+        }
+    };
+    penalties.extend(controller_penalty.map(|(p, r)| (p, r.to_string())));
+
+    penalties.sort_by_key(|(penalty, _)| std::cmp::Reverse(*penalty));
+    let lost: u32 = penalties.iter().map(|(p, _)| *p as u32).sum();
+    ConnectivityScore {
+        score: 100u32.saturating_sub(lost) as u8,
+        reasons: penalties.into_iter().map(|(_, reason)| reason).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy_ports() -> PortSummary {
+        PortSummary {
+            mgmt: 2,
+            up: 1,
+            with_address: 1,
+            with_gateway: 1,
+            with_dns: 1,
+        }
+    }
+
+    #[test]
+    fn test_connectivity_score() {
+        let tests = TestResults::default();
+        let score = connectivity_score(
+            &healthy_ports(),
+            Some((&DPCState::Success, &tests)),
+            Some(&ConfigGetStatus::Success),
+        );
+        assert_eq!(score.score, 100);
+        assert!(score.reasons.is_empty());
+
+        let ports = PortSummary {
+            with_dns: 0,
+            ..healthy_ports()
+        };
+        let score = connectivity_score(
+            &ports,
+            Some((&DPCState::IPDNSWait, &tests)),
+            Some(&ConfigGetStatus::Fail),
+        );
+        assert_eq!(score.score, 60);
+        assert_eq!(score.reasons[0], "Cannot reach the controller");
+
+        let score = connectivity_score(&PortSummary::default(), None, None);
+        assert_eq!(score.score, 40);
+        let ports = PortSummary {
+            mgmt: 1,
+            ..Default::default()
+        };
+        // nothing works, the score does not go below zero
+        let failed = TestResults {
+            last_failed: Utc::now(),
+            ..Default::default()
+        };
+        let score = connectivity_score(
+            &ports,
+            Some((&DPCState::Fail, &failed)),
+            Some(&ConfigGetStatus::Fail),
+        );
+        assert_eq!(score.score, 0);
+        assert_eq!(score.reasons.len(), 4);
+    }
+}
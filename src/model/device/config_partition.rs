@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+use std::ffi::CString;
+use std::time::{Duration, Instant};
+
+const CONFIG_MOUNT_POINT: &str = "/config";
+pub const GRUB_CFG: &str = "/config/grub.cfg";
+// mounts rarely change, no need to read /proc/mounts on every tick
+const CHECK_PERIOD: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigPartitionState {
+    Unknown,
+    NotMounted,
+    Mounted {
+        device: String,
+        read_only: bool,
+        // the monitor itself can write to it
+        writable: bool,
+    },
+}
+
+impl ConfigPartitionState {
+    /// Whether advice like "revert changes in /config/grub.cfg" can be followed
+    pub fn is_usable(&self) -> bool {
+        matches!(
+            self,
+            ConfigPartitionState::Mounted {
+                read_only: false,
+                writable: true,
+                ..
+            }
+        )
+    }
+}
+
+/// Mount state and writability of the /config partition
+#[derive(Debug)]
+pub struct ConfigPartition {
+    pub state: ConfigPartitionState,
+    checked_at: Option<Instant>,
+    // grub.cfg as it was when the monitor started, to show what was changed since
+    grub_cfg_baseline: Option<String>,
+}
+
+impl Default for ConfigPartition {
+    fn default() -> Self {
+        Self {
+            state: ConfigPartitionState::Unknown,
+            checked_at: None,
+            grub_cfg_baseline: None,
+        }
+    }
+}
+
+// returns device and read-only flag of the mount point
+fn find_mount(mounts: &str, mount_point: &str) -> Option<(String, bool)> {
+    // the last mount wins if something is mounted on top
+    mounts.lines().rev().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        let device = fields.next()?;
+        if fields.next()? != mount_point {
+            return None;
+        }
+        let options = fields.nth(1)?;
+        let read_only = options.split(',').any(|opt| opt == "ro");
+        Some((device.to_string(), read_only))
+    })
+}
+
+fn is_writable(path: &str) -> bool {
+    let Ok(path) = CString::new(path) else {
+        return false;
+    };
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+impl ConfigPartition {
+    /// Check the partition if it is time to. Returns true if the state has changed
+    pub fn refresh(&mut self) -> bool {
+        if self
+            .checked_at
+            .is_some_and(|checked_at| checked_at.elapsed() < CHECK_PERIOD)
+        {
+            return false;
+        }
+        self.checked_at = Some(Instant::now());
+        let mounts = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+        let state = match find_mount(&mounts, CONFIG_MOUNT_POINT) {
+            None => ConfigPartitionState::NotMounted,
+            Some((device, read_only)) => ConfigPartitionState::Mounted {
+                device,
+                read_only,
+                writable: !read_only && is_writable(CONFIG_MOUNT_POINT),
+            },
+        };
+        if self.grub_cfg_baseline.is_none() {
+            self.grub_cfg_baseline = std::fs::read_to_string(GRUB_CFG).ok();
+        }
+        if state == self.state {
+            return false;
+        }
+        self.state = state;
+        true
+    }
+
+    /// Current grub.cfg and indexes of the lines that are not in the file the
+    /// monitor saw at start
+    pub fn read_grub_cfg(&self) -> std::io::Result<(String, HashSet<usize>)> {
+        let content = std::fs::read_to_string(GRUB_CFG)?;
+        let changed = match &self.grub_cfg_baseline {
+            Some(baseline) => {
+                let known: HashSet<&str> = baseline.lines().collect();
+                content
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| !known.contains(line))
+                    .map(|(i, _)| i)
+                    .collect()
+            }
+            None => HashSet::new(),
+        };
+        Ok((content, changed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_mount() {
+        let mounts = "proc /proc proc rw,relatime 0 0\n\
+                      /dev/sda4 /config vfat ro,relatime,fmask=0022 0 0\n\
+                      /dev/sda9 /persist ext4 rw,noatime 0 0\n";
+        assert_eq!(
+            find_mount(mounts, "/config"),
+            Some(("/dev/sda4".to_string(), true))
+        );
+        assert_eq!(
+            find_mount(mounts, "/persist"),
+            Some(("/dev/sda9".to_string(), false))
+        );
+        assert_eq!(find_mount(mounts, "/conf"), None);
+    }
+}
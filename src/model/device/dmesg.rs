@@ -1,27 +1,163 @@
-use crate::model::model::Model;
-use crate::ui::action::Action;
+use crate::model::alerts::AlertLink;
+use crate::model::model::{HistoryLimits, Model, MonitorModel};
+use crate::model::ring_buffer::RingBuffer;
+use crate::ui::action::{Action, UiActions};
 use crate::ui::activity::Activity;
-use crate::ui::traits::IntoRatatuiStyle;
+use crate::ui::command_palette::Command;
+use crate::ui::search::{highlight, matches};
+use crate::ui::theme::Theme;
 use std::cmp;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 use crate::events::Event;
 use crate::traits::{IEventHandler, IPresenter, IWindow};
-use crossterm::event::{KeyCode, KeyEvent};
+use chrono::{DateTime, Local, TimeDelta};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use log::trace;
+use ratatui::layout::{Constraint, Layout};
 use ratatui::prelude::Rect;
-use ratatui::style::Style;
+use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState};
 use ratatui::Frame;
 use rmesg::entry::{Entry, LogLevel};
 
+/// Ring buffer of kernel messages
+pub type DmesgBuffer = RingBuffer<Entry>;
+
+impl Default for DmesgBuffer {
+    fn default() -> Self {
+        Self::new(HistoryLimits::default().dmesg)
+    }
+}
+
+/// The buffer as `dmesg` prints it
+pub fn dmesg_text(buffer: &DmesgBuffer) -> String {
+    buffer
+        .iter()
+        .map(|entry| match entry.timestamp_from_system_start {
+            Some(ts) => format!("[{:.6}] {}\n", ts.as_secs_f32(), entry.message),
+            None => format!("{}\n", entry.message),
+        })
+        .collect()
+}
+
 #[derive(Debug, Default)]
 pub struct DmesgViewer {
     _mode: DmsgMode,
+    // position in the shown entries
     buffer_index: usize,
     lines_per_page: u16,
+    // number of shown entries
     buffer_len: usize,
+    timestamp_mode: TimestampMode,
+    // wall-clock time of the kernel timestamp 0. None if it cannot be determined
+    boot_time: Option<DateTime<Local>>,
+    // scroll on the next render, we need the model for that
+    jump: Option<Jump>,
+    // only entries at least this severe are shown, all if None
+    min_level: Option<LogLevel>,
+    search: String,
+    // the search string is being typed
+    searching: bool,
+    // of the last frame
+    theme: Theme,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Jump {
+    PreviousAlert,
+    NextMatch,
+    PreviousMatch,
+    LastMatch,
+}
+
+// severity of entries without a level
+const DEFAULT_LEVEL: LogLevel = LogLevel::Info;
+
+// 'l' cycles through these
+const LEVEL_FILTERS: [Option<LogLevel>; 4] = [
+    None,
+    Some(LogLevel::Warning),
+    Some(LogLevel::Error),
+    Some(LogLevel::Critical),
+];
+
+// entries that pass the level filter. Without a filter nothing is collected
+// so a frame only touches the entries on the page
+enum Shown {
+    All(usize),
+    // indexes in the buffer
+    Filtered(Vec<usize>),
+}
+
+impl Shown {
+    fn new(dmesg: &DmesgBuffer, min_level: Option<LogLevel>) -> Self {
+        match min_level {
+            None => Shown::All(dmesg.len()),
+            Some(_) => Shown::Filtered(
+                dmesg
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| is_shown(entry, min_level))
+                    .map(|(index, _)| index)
+                    .collect(),
+            ),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Shown::All(len) => *len,
+            Shown::Filtered(indexes) => indexes.len(),
+        }
+    }
+
+    // buffer index of the shown entry at `position`
+    fn index(&self, position: usize) -> usize {
+        match self {
+            Shown::All(_) => position,
+            Shown::Filtered(indexes) => indexes[position],
+        }
+    }
+
+    // position of the buffer entry, or where it would be if it is filtered out
+    fn position(&self, index: usize) -> usize {
+        match self {
+            Shown::All(_) => index,
+            Shown::Filtered(indexes) => indexes.binary_search(&index).unwrap_or_else(|p| p),
+        }
+    }
+}
+
+fn is_shown(entry: &Entry, min_level: Option<LogLevel>) -> bool {
+    // lower is more severe
+    min_level.is_none_or(|min| entry.level.unwrap_or(DEFAULT_LEVEL) as u8 <= min as u8)
+}
+
+#[derive(Default, Debug, PartialEq)]
+enum TimestampMode {
+    #[default]
+    BootRelative,
+    WallClock,
+}
+
+// width of "[YYYY-MM-DD HH:MM:SS.mmm] ", the widest timestamp
+const TIMESTAMP_WIDTH: usize = 26;
+
+// kernel timestamps use the monotonic clock
+fn get_boot_time() -> Option<DateTime<Local>> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid timespec
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) } != 0 {
+        return None;
+    }
+    let uptime = TimeDelta::new(ts.tv_sec, ts.tv_nsec as u32)?;
+    Some(Local::now() - uptime)
 }
 
 #[derive(Default, Debug)]
@@ -33,7 +169,197 @@ enum DmsgMode {
 
 impl DmesgViewer {
     pub fn new() -> Self {
-        DmesgViewer::default()
+        DmesgViewer {
+            boot_time: get_boot_time(),
+            ..Default::default()
+        }
+    }
+
+    fn toggle_timestamp_mode(&mut self) {
+        self.timestamp_mode = match self.timestamp_mode {
+            TimestampMode::BootRelative => TimestampMode::WallClock,
+            TimestampMode::WallClock => TimestampMode::BootRelative,
+        };
+    }
+
+    fn format_timestamp(&self, entry: &Entry) -> String {
+        let Some(ts) = entry.timestamp_from_system_start else {
+            return String::new();
+        };
+        match (&self.timestamp_mode, self.boot_time) {
+            (TimestampMode::WallClock, Some(boot_time)) => {
+                let time = TimeDelta::from_std(ts)
+                    .ok()
+                    .and_then(|ts| boot_time.checked_add_signed(ts));
+                match time {
+                    Some(time) => format!("[{}] ", time.format("%Y-%m-%d %H:%M:%S%.3f")),
+                    None => format!("[{:.6}] ", ts.as_secs_f64()),
+                }
+            }
+            _ => format!("[{:>12.6}] ", ts.as_secs_f64()),
+        }
+    }
+
+    // continuation lines of multiline messages are aligned under the first line
+    fn entry_to_lines(&self, entry: &Entry, alerted: bool) -> Vec<Line<'static>> {
+        let timestamp = self.format_timestamp(entry);
+        let width = match self.timestamp_mode {
+            TimestampMode::BootRelative => timestamp.len(),
+            TimestampMode::WallClock => TIMESTAMP_WIDTH,
+        };
+        let theme = &self.theme;
+        let timestamp_style = if alerted {
+            Style::default().fg(theme.text).bg(theme.error)
+        } else {
+            Style::default().fg(theme.muted)
+        };
+        let style = level_style(entry.level, theme);
+        entry
+            .message
+            .trim_end_matches('\n')
+            .split('\n')
+            .enumerate()
+            .map(|(i, text)| {
+                let prefix = if i == 0 {
+                    format!("{:<width$}", timestamp)
+                } else {
+                    " ".repeat(width)
+                };
+                let mut spans = vec![Span::styled(prefix, timestamp_style)];
+                spans.extend(highlight(text, &self.search, &self.theme));
+                Line::from(spans).style(style)
+            })
+            .collect()
+    }
+
+    fn do_jump(&mut self, jump: Jump, model: &MonitorModel, shown: &Shown) {
+        // positions in `shown` of the entries to jump between
+        let targets: Vec<usize> = match jump {
+            Jump::PreviousAlert => {
+                let mut targets: Vec<usize> = model
+                    .alerts
+                    .iter()
+                    .filter_map(|alert| alert.link.as_ref().and_then(AlertLink::dmesg_seq))
+                    .filter_map(|seq| model.dmesg.index(seq))
+                    .map(|index| shown.position(index))
+                    .filter(|p| *p < shown.len())
+                    .collect();
+                targets.sort();
+                targets
+            }
+            _ => (0..shown.len())
+                .filter(|p| {
+                    model
+                        .dmesg
+                        .get(shown.index(*p))
+                        .is_some_and(|entry| matches(&entry.message, &self.search))
+                })
+                .collect(),
+        };
+        let following = matches!(self._mode, DmsgMode::Follow);
+        let target = match jump {
+            Jump::PreviousAlert | Jump::PreviousMatch if !following => targets
+                .iter()
+                .rev()
+                .find(|p| **p < self.buffer_index)
+                .or(targets.last()),
+            Jump::NextMatch if !following => targets
+                .iter()
+                .find(|p| **p > self.buffer_index)
+                .or(targets.first()),
+            _ => targets.last(),
+        };
+        if let Some(position) = target {
+            self.switch_to_scroll_mode();
+            self.buffer_index = cmp::min(*position, self.max_buffer_index());
+        }
+    }
+
+    fn cycle_level_filter(&mut self) {
+        let current = LEVEL_FILTERS
+            .iter()
+            .position(|level| *level == self.min_level)
+            .unwrap_or(0);
+        self.min_level = LEVEL_FILTERS[(current + 1) % LEVEL_FILTERS.len()];
+        // positions are in the shown entries, they all changed
+        self._mode = DmsgMode::Follow;
+    }
+
+    // the search string is typed at the bottom of the page
+    fn handle_keys_search(&mut self, key: KeyEvent) -> Option<Activity> {
+        match key.code {
+            KeyCode::Enter => {
+                self.searching = false;
+                if !self.search.is_empty() {
+                    self.jump = Some(Jump::LastMatch);
+                }
+            }
+            KeyCode::Esc => {
+                self.searching = false;
+                self.search.clear();
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search.push(c);
+            }
+            _ => return None,
+        }
+        Some(Activity::redraw())
+    }
+
+    // keys that work the same when following and scrolling
+    fn handle_keys_common(&mut self, key: KeyEvent) -> Option<Activity> {
+        match key.code {
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.search.clear();
+            }
+            KeyCode::Char('n') if !self.search.is_empty() => self.jump = Some(Jump::NextMatch),
+            KeyCode::Char('N') if !self.search.is_empty() => self.jump = Some(Jump::PreviousMatch),
+            KeyCode::Esc if !self.search.is_empty() => self.search.clear(),
+            KeyCode::Char('l') => self.cycle_level_filter(),
+            _ => return None,
+        }
+        Some(Activity::redraw())
+    }
+
+    fn status_line(&self, shown: usize, dmesg: &DmesgBuffer) -> Line<'static> {
+        let theme = &self.theme;
+        let total = dmesg.len();
+        let mode = match self._mode {
+            DmsgMode::Follow => " FOLLOW ",
+            DmsgMode::Scroll => " SCROLL ",
+        };
+        let level = match self.min_level {
+            None => format!("all levels, {} of {} kept", total, dmesg.capacity()),
+            Some(level) => format!("{} and worse, {} of {}", level, shown, total),
+        };
+        let mut spans = vec![
+            Span::styled(mode, Style::default().fg(theme.surface).bg(theme.text)),
+            Span::raw(format!(" {} ", level)),
+        ];
+        if self.searching {
+            spans.push(Span::raw(format!("/{}", self.search)).bold());
+            spans.push(Span::raw("_").slow_blink());
+            spans.push(Span::raw("  Enter to find, Esc to cancel").fg(theme.muted));
+        } else {
+            if !self.search.is_empty() {
+                spans.push(Span::raw(format!("/{}  ", self.search)).bold());
+            }
+            spans.push(
+                Span::raw("/ search, n/N next/prev, l level, space follow, t time, a alert")
+                    .fg(theme.muted),
+            );
+        }
+        Line::from(spans)
+    }
+
+    // last valid scroll position for the current page size. The page size changes
+    // on terminal resize so the position must be clamped before use
+    fn max_buffer_index(&self) -> usize {
+        self.buffer_len.saturating_sub(self.lines_per_page as usize)
     }
 
     fn switch_to_scroll_mode(&mut self) {
@@ -52,17 +378,22 @@ impl DmesgViewer {
                 self.switch_to_scroll_mode();
                 self.handle_keys_scroll(key)
             }
-            _ => None,
+            KeyCode::Char('t') => {
+                self.toggle_timestamp_mode();
+                Some(Activity::redraw())
+            }
+            KeyCode::Char('a') => {
+                self.jump = Some(Jump::PreviousAlert);
+                Some(Activity::redraw())
+            }
+            _ => self.handle_keys_common(key),
         }
     }
 
     pub fn handle_keys_scroll(&mut self, key: KeyEvent) -> Option<Activity> {
         match key.code {
             KeyCode::Down => {
-                self.buffer_index = cmp::min(
-                    self.buffer_index + 1 as usize,
-                    self.buffer_len - self.lines_per_page as usize,
-                );
+                self.buffer_index = cmp::min(self.buffer_index + 1, self.max_buffer_index());
             }
             KeyCode::Up => {
                 self.buffer_index = self.buffer_index.saturating_sub(1);
@@ -70,7 +401,7 @@ impl DmesgViewer {
             KeyCode::PageDown => {
                 self.buffer_index = cmp::min(
                     self.buffer_index + self.lines_per_page as usize,
-                    self.buffer_len - self.lines_per_page as usize,
+                    self.max_buffer_index(),
                 );
             }
             KeyCode::PageUp => {
@@ -79,7 +410,7 @@ impl DmesgViewer {
                     .saturating_sub(self.lines_per_page as usize);
             }
             KeyCode::End => {
-                self.buffer_index = self.buffer_len - self.lines_per_page as usize;
+                self.buffer_index = self.max_buffer_index();
             }
             KeyCode::Home => {
                 self.buffer_index = 0;
@@ -87,68 +418,84 @@ impl DmesgViewer {
             KeyCode::Char(' ') => {
                 self._mode = DmsgMode::Follow;
             }
-            _ => return None,
+            KeyCode::Char('t') => {
+                self.toggle_timestamp_mode();
+            }
+            KeyCode::Char('a') => {
+                self.jump = Some(Jump::PreviousAlert);
+            }
+            _ => return self.handle_keys_common(key),
         }
         Some(Activity::redraw())
     }
 }
 
-impl IntoRatatuiStyle for Option<LogLevel> {
-    fn style(&self) -> Style {
-        match self {
-            Some(LogLevel::Emergency) => Style::default().fg(ratatui::style::Color::Red),
-            Some(LogLevel::Alert) => Style::default().fg(ratatui::style::Color::Red),
-            Some(LogLevel::Critical) => Style::default().fg(ratatui::style::Color::Red),
-            Some(LogLevel::Error) => Style::default().fg(ratatui::style::Color::Red),
-            Some(LogLevel::Warning) => Style::default().fg(ratatui::style::Color::Yellow),
-            Some(LogLevel::Notice) => Style::default().fg(ratatui::style::Color::Yellow),
-            Some(LogLevel::Info) => Style::default(),
-            Some(LogLevel::Debug) => Style::default().fg(ratatui::style::Color::Blue),
-            None => Style::default(),
-        }
+fn level_style(level: Option<LogLevel>, theme: &Theme) -> Style {
+    match level {
+        Some(LogLevel::Emergency) => Style::default()
+            .fg(theme.text)
+            .bg(theme.error)
+            .add_modifier(Modifier::BOLD),
+        Some(LogLevel::Alert) => Style::default().fg(theme.text).bg(theme.error),
+        Some(LogLevel::Critical) => Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD),
+        Some(LogLevel::Error) => Style::default().fg(theme.error),
+        Some(LogLevel::Warning) => Style::default().fg(theme.warning),
+        Some(LogLevel::Notice) => Style::default().fg(theme.accent),
+        Some(LogLevel::Info) => Style::default(),
+        Some(LogLevel::Debug) => Style::default().fg(theme.muted),
+        None => Style::default(),
     }
 }
 
 impl IPresenter for DmesgViewer {
     fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let [area, status_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(*area);
+        let area = &area;
         let page_size = area.height as usize;
-        self.buffer_len = model.borrow().dmesg.len();
+        let model = model.borrow();
+        self.theme = model.theme;
+        let shown = Shown::new(&model.dmesg, self.min_level);
+        self.buffer_len = shown.len();
         self.lines_per_page = area.height;
         trace!(
-            "Rendering dmesg: {:?}, page={} log_size={}",
+            "Rendering dmesg: {:?}, page={} log_size={} shown={}",
             area,
             page_size,
-            model.borrow().dmesg.len()
+            model.dmesg.len(),
+            shown.len()
         );
 
-        let dmesg = &model.borrow().dmesg;
-        // get last page_size entries from or the whole buffer if it's smaller
-        let content: Vec<&Entry> = match self._mode {
-            DmsgMode::Follow => {
-                self.buffer_index = self.buffer_len.saturating_sub(page_size);
-                dmesg.iter().rev().take(page_size).rev().collect()
-            }
-            DmsgMode::Scroll => dmesg
-                .iter()
-                .skip(self.buffer_index)
-                .take(page_size)
-                .collect(),
-        };
-
-        let lines: Vec<Line> = content
+        if let Some(jump) = self.jump.take() {
+            self.do_jump(jump, &model, &shown);
+        }
+        // dmesg entries with alerts are highlighted
+        let alerted: HashSet<u64> = model
+            .alerts
             .iter()
-            .map(|entry| {
-                Line::from(entry.timestamp_from_system_start.map_or_else(
-                    || Span::styled(format!("{:4}{}\n", "", entry.message), entry.level.style()),
-                    |ts| {
-                        Span::styled(
-                            format!("[{:.6}] {}\n", ts.as_secs_f32(), entry.message),
-                            entry.level.style(),
-                        )
-                    },
-                ))
+            .filter_map(|alert| alert.link.as_ref().and_then(AlertLink::dmesg_seq))
+            .collect();
+
+        let dmesg = &model.dmesg;
+        self.buffer_index = match self._mode {
+            DmsgMode::Follow => self.max_buffer_index(),
+            // page may have grown since the last key press
+            DmsgMode::Scroll => cmp::min(self.buffer_index, self.max_buffer_index()),
+        };
+        let page_end = cmp::min(self.buffer_index + page_size, shown.len());
+        let mut lines: Vec<Line> = (self.buffer_index..page_end)
+            .map(|position| shown.index(position))
+            .filter_map(|index| dmesg.get(index).map(|entry| (index, entry)))
+            .flat_map(|(index, entry)| {
+                self.entry_to_lines(entry, alerted.contains(&dmesg.seq(index)))
             })
             .collect();
+        // multiline entries may not fit. Keep the newest lines visible when following
+        if let DmsgMode::Follow = self._mode {
+            lines.drain(..lines.len().saturating_sub(page_size));
+        }
 
         // render vertical scrollbar on the right
         let mut scrollbar_state = ScrollbarState::new(self.buffer_len).position(self.buffer_index);
@@ -159,14 +506,29 @@ impl IPresenter for DmesgViewer {
         frame.render_widget(Paragraph::new(lines), *area);
 
         frame.render_stateful_widget(scrollbar, *area, &mut scrollbar_state);
+        frame.render_widget(self.status_line(shown.len(), dmesg), status_area);
     }
 }
 
-impl IWindow for DmesgViewer {}
+impl IWindow for DmesgViewer {
+    fn commands(&self) -> Vec<Command> {
+        vec![Command::new(
+            "Save kernel messages",
+            "ctrl+s",
+            UiActions::SaveDmesg,
+        )]
+    }
+}
 impl IEventHandler for DmesgViewer {
     fn handle_event(&mut self, event: crate::events::Event) -> Option<Action> {
         let activity = match event {
-            Event::Tick | Event::TerminalResize(_, _) => None, // we want this to trigger a rerender, but that will happen even if we do nothing here
+            Event::Tick | Event::TerminalResize(_, _) | Event::Paste(_) | Event::Mouse(_) => None, // we want this to trigger a rerender, but that will happen even if we do nothing here
+            Event::Key(key)
+                if key.code == KeyCode::Char('s') && key.modifiers == KeyModifiers::CONTROL =>
+            {
+                Some(Activity::ui_action(UiActions::SaveDmesg))
+            }
+            Event::Key(key) if self.searching => self.handle_keys_search(key),
             Event::Key(key) => match self._mode {
                 DmsgMode::Follow => self.handle_keys_following(key),
                 DmsgMode::Scroll => self.handle_keys_scroll(key),
@@ -178,3 +540,41 @@ impl IEventHandler for DmesgViewer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(message: &str) -> Entry {
+        Entry {
+            facility: None,
+            level: None,
+            sequence_num: None,
+            timestamp_from_system_start: None,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_dmesg_buffer() {
+        let mut buffer = DmesgBuffer::new(3);
+        for i in 0..5 {
+            assert_eq!(buffer.push(entry(&i.to_string())), i);
+        }
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.index(1), None);
+        assert_eq!(buffer.index(2), Some(0));
+        assert_eq!(buffer.seq(2), 4);
+        assert_eq!(buffer.index(5), None);
+        assert_eq!(buffer.iter().next().map(|e| e.message.as_str()), Some("2"));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let spans = highlight("USB disconnect, usb 1-1", "usb", &Theme::default());
+        let texts: Vec<&str> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(texts, vec!["USB", " disconnect, ", "usb", " 1-1"]);
+        assert!(matches("ÄÖ usb", "USB"));
+        assert!(!matches("anything", ""));
+    }
+}
@@ -0,0 +1,370 @@
+use std::path::Path;
+
+use super::usb::UsbBootPath;
+
+const EFIVARS: &str = "/sys/firmware/efi/efivars";
+// EFI_GLOBAL_VARIABLE, the vendor GUID of Boot#### and BootCurrent
+const EFI_GLOBAL: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+
+/// A Boot#### EFI variable
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfiBootEntry {
+    pub number: u16,
+    pub description: String,
+    pub active: bool,
+    // None if the entry does not boot from USB
+    pub usb: Option<UsbBootPath>,
+    // unique GUID of the GPT partition the entry loads from
+    pub partition: Option<String>,
+}
+
+pub fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub fn u64_at(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+// UCS-2 up to the terminating 0 or the end of the data, and the bytes used
+pub fn utf16_string(data: &[u8]) -> (String, usize) {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|u| *u != 0)
+        .collect();
+    let used = (units.len() * 2 + 2).min(data.len());
+    (String::from_utf16_lossy(&units), used)
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// GUIDs are stored with the first 3 groups little endian
+pub fn format_guid(data: &[u8]) -> Option<String> {
+    let data = data.get(..16)?;
+    Some(format!(
+        "{:08x}-{:04x}-{:04x}-{}-{}",
+        u32_at(data, 0)?,
+        u16_at(data, 4)?,
+        u16_at(data, 6)?,
+        hex(&data[8..10]),
+        hex(&data[10..16])
+    ))
+}
+
+// the nodes of an EFI device path we need, see "Device Path Protocol" in the UEFI spec
+fn parse_device_path(mut path: &[u8]) -> (Option<UsbBootPath>, Option<String>) {
+    let mut ports = Vec::new();
+    let mut usb = None;
+    let mut partition = None;
+    while path.len() >= 4 {
+        let (node_type, subtype) = (path[0], path[1]);
+        let len = u16_at(path, 2).unwrap_or_default() as usize;
+        if len < 4 || len > path.len() || node_type == 0x7f {
+            break;
+        }
+        let node = &path[4..len];
+        match (node_type, subtype) {
+            // USB: parent port, interface
+            (0x03, 0x05) => ports.extend(node.first()),
+            // USB Class: vendor, product, class, subclass, protocol
+            (0x03, 0x0f) => {
+                usb = u16_at(node, 0)
+                    .zip(u16_at(node, 2))
+                    .map(|(vendor_id, product_id)| UsbBootPath::Device {
+                        vendor_id,
+                        product_id,
+                        serial: None,
+                    });
+            }
+            // USB WWID: interface, vendor, product, serial
+            (0x03, 0x10) => {
+                let (serial, _) = utf16_string(node.get(6..).unwrap_or_default());
+                usb = u16_at(node, 2)
+                    .zip(u16_at(node, 4))
+                    .map(|(vendor_id, product_id)| UsbBootPath::Device {
+                        vendor_id,
+                        product_id,
+                        serial: Some(serial).filter(|s| !s.is_empty()),
+                    });
+            }
+            // Hard Drive: number, start, size, signature, MBR type, signature type
+            (0x04, 0x01) if node.get(37) == Some(&2) => {
+                partition = node.get(20..36).and_then(format_guid);
+            }
+            _ => {}
+        }
+        path = &path[len..];
+    }
+    if usb.is_none() && !ports.is_empty() {
+        usb = Some(UsbBootPath::Ports(ports));
+    }
+    (usb, partition)
+}
+
+// an EFI_LOAD_OPTION after the 4 bytes of variable attributes efivarfs puts first
+fn parse_boot_entry(number: u16, data: &[u8]) -> Option<EfiBootEntry> {
+    let option = data.get(4..)?;
+    let attributes = u32_at(option, 0)?;
+    let path_len = u16_at(option, 4)? as usize;
+    let (description, used) = utf16_string(option.get(6..)?);
+    let (usb, partition) = parse_device_path(option.get(6 + used..6 + used + path_len)?);
+    Some(EfiBootEntry {
+        number,
+        description,
+        // LOAD_OPTION_ACTIVE
+        active: attributes & 1 != 0,
+        usb,
+        partition,
+    })
+}
+
+/// A variable of efivarfs with the attributes split from the data
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfiVariable {
+    pub name: String,
+    pub vendor: String,
+    pub attributes: u32,
+    pub data: Vec<u8>,
+}
+
+impl EfiVariable {
+    // efivarfs files start with the 4 attribute bytes
+    fn parse(file_name: &str, raw: &[u8]) -> Option<Self> {
+        // the vendor GUID has 36 characters
+        let split = file_name
+            .len()
+            .checked_sub(37)
+            .filter(|split| file_name.is_char_boundary(*split))?;
+        let (name, vendor) = file_name.split_at(split);
+        Some(Self {
+            name: name.to_string(),
+            vendor: vendor.strip_prefix('-')?.to_string(),
+            attributes: u32_at(raw, 0)?,
+            data: raw[4..].to_vec(),
+        })
+    }
+
+    pub fn attribute_names(&self) -> Vec<&'static str> {
+        [
+            (0x01, "NV"),
+            (0x02, "BS"),
+            (0x04, "RT"),
+            (0x08, "HW_ERR"),
+            (0x10, "AUTH"),
+            (0x20, "TIME_AUTH"),
+            (0x40, "APPEND"),
+        ]
+        .into_iter()
+        .filter(|(bit, _)| self.attributes & bit != 0)
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
+/// File names of all EFI variables, sorted. Empty without EFI
+pub fn efi_variable_names() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(EFIVARS) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| Some(entry.ok()?.file_name().to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Read a variable by its efivarfs file name, e.g. BootCurrent-<GUID>
+pub fn read_efi_variable(file_name: &str) -> Option<EfiVariable> {
+    EfiVariable::parse(
+        file_name,
+        &std::fs::read(Path::new(EFIVARS).join(file_name)).ok()?,
+    )
+}
+
+fn read_efi_var(name: &str) -> Option<Vec<u8>> {
+    std::fs::read(Path::new(EFIVARS).join(format!("{}-{}", name, EFI_GLOBAL))).ok()
+}
+
+/// Boot entries of the firmware in number order, empty without EFI
+pub fn efi_boot_entries() -> Vec<EfiBootEntry> {
+    let Ok(entries) = std::fs::read_dir(EFIVARS) else {
+        return Vec::new();
+    };
+    let mut boot: Vec<EfiBootEntry> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().to_string_lossy().to_string();
+            let number = name
+                .strip_prefix("Boot")?
+                .strip_suffix(&format!("-{}", EFI_GLOBAL))?;
+            // BootOrder, BootCurrent...
+            let number = u16::from_str_radix(number, 16)
+                .ok()
+                .filter(|_| number.len() == 4)?;
+            parse_boot_entry(number, &read_efi_var(&format!("Boot{:04X}", number))?)
+        })
+        .collect();
+    boot.sort_by_key(|entry| entry.number);
+    boot
+}
+
+// BootOrder is a list of little endian entry numbers
+pub fn parse_boot_order(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect()
+}
+
+/// The data of the BootOrder variable for `order`, without the attributes
+pub fn boot_order_bytes(order: &[u16]) -> Vec<u8> {
+    order.iter().flat_map(|n| n.to_le_bytes()).collect()
+}
+
+/// "0001,0000,0003" like efibootmgr shows it
+pub fn boot_order_text(order: &[u16]) -> String {
+    order
+        .iter()
+        .map(|n| format!("{:04X}", n))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Boot entry numbers in the order the firmware tries them, empty without EFI
+pub fn efi_boot_order() -> Vec<u16> {
+    read_efi_var("BootOrder")
+        .map(|data| parse_boot_order(data.get(4..).unwrap_or_default()))
+        .unwrap_or_default()
+}
+
+/// The number of the boot entry the system was started from
+pub fn efi_boot_current() -> Option<u16> {
+    u16_at(&read_efi_var("BootCurrent")?, 4)
+}
+
+/// The boot entry the system was started from
+pub fn efi_current_boot_entry() -> Option<EfiBootEntry> {
+    let current = efi_boot_current()?;
+    parse_boot_entry(current, &read_efi_var(&format!("Boot{:04X}", current))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::device::usb::UsbDevice;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .chain([0])
+            .flat_map(|u| u.to_le_bytes())
+            .collect()
+    }
+
+    // efivarfs attributes, then the load option with `path`
+    fn boot_var(description: &str, path: &[u8]) -> Vec<u8> {
+        let mut data = vec![7, 0, 0, 0, 1, 0, 0, 0];
+        data.extend((path.len() as u16).to_le_bytes());
+        data.extend(utf16(description));
+        data.extend(path);
+        data
+    }
+
+    #[test]
+    fn test_efi_variable() {
+        let var = EfiVariable::parse(
+            &format!("BootCurrent-{}", EFI_GLOBAL),
+            &[0x06, 0, 0, 0, 0x02, 0x00],
+        )
+        .unwrap();
+        assert_eq!(var.name, "BootCurrent");
+        assert_eq!(var.vendor, EFI_GLOBAL);
+        assert_eq!(var.data, vec![0x02, 0x00]);
+        assert_eq!(var.attribute_names(), vec!["BS", "RT"]);
+        assert!(EfiVariable::parse("short", &[0; 8]).is_none());
+        assert!(EfiVariable::parse(&format!("Boot-{}", EFI_GLOBAL), &[0; 2]).is_none());
+    }
+
+    #[test]
+    fn test_boot_order() {
+        let data = [0x03, 0x00, 0x01, 0x00, 0x80, 0x00];
+        assert_eq!(parse_boot_order(&data), vec![3, 1, 0x80]);
+        assert_eq!(boot_order_bytes(&[3, 1, 0x80]), data.to_vec());
+    }
+
+    #[test]
+    fn test_parse_boot_entry() {
+        // PciRoot(0)/Pci(0x14,0)/USB(2,0)/USB(1,0), End
+        let ports = [
+            &[0x02, 0x01, 0x0c, 0x00, 0xd0, 0x41, 0x03, 0x0a, 0, 0, 0, 0][..],
+            &[0x01, 0x01, 0x06, 0x00, 0x00, 0x14],
+            &[0x03, 0x05, 0x06, 0x00, 0x02, 0x00],
+            &[0x03, 0x05, 0x06, 0x00, 0x01, 0x00],
+            &[0x7f, 0xff, 0x04, 0x00],
+        ]
+        .concat();
+        let entry = parse_boot_entry(3, &boot_var("UEFI: USB stick", &ports)).unwrap();
+        assert_eq!(entry.description, "UEFI: USB stick");
+        assert!(entry.active);
+        assert_eq!(entry.usb, Some(UsbBootPath::Ports(vec![2, 1])));
+
+        // UsbWwid(0x0781,0x5581,0,"4C53")
+        let mut wwid = vec![0x03, 0x10, 0x00, 0x00, 0x00, 0x00, 0x81, 0x07, 0x81, 0x55];
+        wwid.extend(utf16("4C53"));
+        wwid[2] = wwid.len() as u8;
+        wwid.extend([0x7f, 0xff, 0x04, 0x00]);
+        let entry = parse_boot_entry(4, &boot_var("SanDisk", &wwid)).unwrap();
+        let usb = entry.usb.unwrap();
+        let mut device = UsbDevice {
+            vendor_id: 0x0781,
+            product_id: 0x5581,
+            serial: "4C53".to_string(),
+            ..Default::default()
+        };
+        assert!(usb.matches(&device));
+        device.serial = "other".to_string();
+        assert!(!usb.matches(&device));
+
+        // HD(...) on a SATA disk is not USB
+        let disk = [
+            &[0x03, 0x12, 0x0a, 0x00, 0, 0, 0, 0, 0, 0][..],
+            &[0x7f, 0xff, 0x04, 0x00],
+        ]
+        .concat();
+        assert_eq!(
+            parse_boot_entry(0, &boot_var("eve", &disk)).unwrap().usb,
+            None
+        );
+        // truncated
+        assert_eq!(parse_boot_entry(0, &[7, 0, 0, 0, 1, 0]), None);
+
+        // HD(1,GPT,c12a7328-f81f-11d2-ba4b-00a0c93ec93b,0x800,0x32000)/\EFI\BOOT\BOOTX64.EFI
+        let mut hd = vec![0x04, 0x01, 0x2a, 0x00, 0x01, 0, 0, 0];
+        hd.extend(0x800u64.to_le_bytes());
+        hd.extend(0x32000u64.to_le_bytes());
+        hd.extend([
+            0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11, 0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e,
+            0xc9, 0x3b, 0x02, 0x02,
+        ]);
+        let mut file = vec![0x04, 0x04, 0x00, 0x00];
+        file.extend(utf16("\\EFI\\BOOT\\BOOTX64.EFI"));
+        file[2] = file.len() as u8;
+        let path = [&hd[..], &file, &[0x7f, 0xff, 0x04, 0x00]].concat();
+        let entry = parse_boot_entry(1, &boot_var("EVE", &path)).unwrap();
+        assert_eq!(entry.usb, None);
+        assert_eq!(
+            entry.partition.as_deref(),
+            Some("c12a7328-f81f-11d2-ba4b-00a0c93ec93b")
+        );
+    }
+}
@@ -1,3 +1,18 @@
+pub mod config_partition;
+#[cfg(feature = "dmesg")]
 pub mod dmesg;
+#[cfg(feature = "efi")]
+pub mod efi;
+pub mod identity;
 pub mod network;
+pub mod removable;
+pub mod resources;
+pub mod sensors;
+#[cfg(feature = "efi")]
+pub mod storage;
+#[cfg(any(debug_assertions, feature = "home-page"))]
 pub mod summary;
+pub mod traffic;
+#[cfg(feature = "efi")]
+pub mod usb;
+pub mod zfs;
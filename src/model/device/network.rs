@@ -1,6 +1,9 @@
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
-use crate::ipc::eve_types::{DhcpType, NetworkPortStatus, NetworkProxyType, WirelessType};
+use crate::ipc::eve_types::{
+    DhcpType, NetworkPortStatus, NetworkProxyType, WirelessType, WwanNetworkStatus,
+};
+use crate::model::sanitize::sanitize_line;
 use ipnet::IpNet;
 use macaddr::MacAddr;
 
@@ -16,7 +19,10 @@ pub struct SimStatus {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CellularStatus {
-    sims: Vec<SimStatus>,
+    // configured access points
+    pub sims: Vec<SimStatus>,
+    // state of the modem as reported by EVE, boxed as it is large
+    pub modem: Box<WwanNetworkStatus>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -144,7 +150,7 @@ impl From<&crate::ipc::eve_types::ProxyConfig> for ProxyConfig {
                     });
                 }
                 NetworkProxyType::NOPROXY => {}
-                NetworkProxyType::LAST => {}
+                NetworkProxyType::LAST | NetworkProxyType::Unrecognized => {}
             });
 
             let manual_proxies = ProxyConfig::Manual {
@@ -197,8 +203,80 @@ pub struct NetworkInterfaceStatus {
     pub subnet: Option<IpNet>,
     pub is_dhcp: bool,
     pub proxy_config: ProxyConfig,
+    // hosts reached without the proxy
+    pub proxy_exceptions: Vec<String>,
     pub domain: Option<String>,
     pub cost: u8,
+    // resolver configuration actually in use for the port. Not part of the EVE status
+    pub resolver: Option<ResolvConf>,
+    // changed by a kernel event EVE has not reported yet
+    pub locally_observed: bool,
+}
+
+// dhcpcd keeps a resolv.conf per interface and per protocol. EVE merges them
+const RESOLV_CONF_DIR: &str = "/run/dhcpcd/resolv.conf";
+const RESOLV_CONF_SUFFIXES: [&str; 3] = ["dhcp", "dhcp6", "ra"];
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvConf {
+    pub nameservers: Vec<IpAddr>,
+    pub search: Vec<String>,
+}
+
+impl ResolvConf {
+    pub fn parse(content: &str) -> Self {
+        let mut conf = ResolvConf::default();
+        for line in content.lines() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("nameserver") => {
+                    // IPv6 link-local servers may have a %<zone> suffix
+                    let addr = fields.next().and_then(|a| a.split('%').next());
+                    if let Some(addr) = addr.and_then(|a| a.parse().ok()) {
+                        conf.nameservers.push(addr);
+                    }
+                }
+                Some("search") | Some("domain") => {
+                    conf.search.extend(fields.map(sanitize_line));
+                }
+                _ => {}
+            }
+        }
+        conf
+    }
+
+    /// Load and merge all resolv.conf files of the interface. None if there are none
+    pub fn load_for_port(if_name: &str) -> Option<Self> {
+        let mut merged: Option<ResolvConf> = None;
+        for suffix in RESOLV_CONF_SUFFIXES {
+            let path = format!("{}/{}.{}", RESOLV_CONF_DIR, if_name, suffix);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let conf = ResolvConf::parse(&content);
+            let merged = merged.get_or_insert_with(ResolvConf::default);
+            merged.nameservers.extend(conf.nameservers);
+            merged.search.extend(conf.search);
+        }
+        merged
+    }
+}
+
+impl NetworkInterfaceStatus {
+    /// True if the nameservers in use differ from the DNS servers reported by EVE
+    pub fn dns_mismatch(&self) -> bool {
+        let Some(resolver) = &self.resolver else {
+            return false;
+        };
+        let configured = self.dns.as_deref().unwrap_or_default();
+        resolver
+            .nameservers
+            .iter()
+            .any(|ns| !configured.contains(ns))
+            || configured
+                .iter()
+                .any(|dns| !resolver.nameservers.contains(dns))
+    }
 }
 
 pub trait ToInnerIpAddr {
@@ -255,7 +333,8 @@ impl From<&NetworkPortStatus> for NetworkInterfaceStatus {
 
         // set media type
         let media = match port.wireless_cfg.w_type {
-            WirelessType::None => NetworkType::Ethernet,
+            // a new wireless type is shown as a plain port
+            WirelessType::None | WirelessType::Unrecognized => NetworkType::Ethernet,
             WirelessType::Wifi => NetworkType::WiFi(WiFiStatus {
                 //FIXME: why we have a Vec of WifiConfig?
                 ssid: port
@@ -282,7 +361,9 @@ impl From<&NetworkPortStatus> for NetworkInterfaceStatus {
                             )
                         })
                     })
-                    .unwrap(),
+                    // the port may have no access points configured yet
+                    .unwrap_or_default(),
+                modem: Box::new(port.wireless_status.cellular.clone()),
             }),
         };
 
@@ -351,6 +432,16 @@ impl From<&NetworkPortStatus> for NetworkInterfaceStatus {
                 Some(port.domain_name.clone())
             },
             proxy_config: (&port.proxy_config).into(),
+            proxy_exceptions: port
+                .proxy_config
+                .exceptions
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(str::to_string)
+                .collect(),
+            resolver: None,
+            locally_observed: false,
         }
     }
 }
@@ -0,0 +1,102 @@
+use std::path::{Path, PathBuf};
+
+/// A mounted partition of a USB stick or disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsbMount {
+    pub device: String,
+    pub mount_point: PathBuf,
+}
+
+// /proc/mounts escapes blanks in paths as octal, e.g. "\040" for a space
+pub fn unescape_mount_path(path: &str) -> String {
+    let mut out = Vec::with_capacity(path.len());
+    let bytes = path.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).and_then(|digits| {
+            let digits = std::str::from_utf8(digits).ok()?;
+            u8::from_str_radix(digits, 8).ok()
+        });
+        match (bytes[i], octal) {
+            (b'\\', Some(byte)) => {
+                out.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// mounts of block devices `is_usb` accepts, in the order of /proc/mounts
+fn find_usb_mounts(mounts: &str, is_usb: impl Fn(&str) -> bool) -> Vec<UsbMount> {
+    let mut found: Vec<UsbMount> = Vec::new();
+    for line in mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(mount_point)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Some(name) = device.strip_prefix("/dev/") else {
+            continue;
+        };
+        // a partition mounted twice is listed once
+        if !is_usb(name) || found.iter().any(|m| m.device == device) {
+            continue;
+        }
+        found.push(UsbMount {
+            device: device.to_string(),
+            mount_point: PathBuf::from(unescape_mount_path(mount_point)),
+        });
+    }
+    found
+}
+
+// the sysfs path of a block device goes through the USB controller it hangs on
+fn is_usb_block_device(name: &str) -> bool {
+    Path::new("/sys/class/block")
+        .join(name)
+        .canonicalize()
+        .is_ok_and(|path| {
+            // e.g. /sys/devices/pci0000:00/0000:00:14.0/usb2/2-1/...
+            path.components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with("usb"))
+        })
+}
+
+/// Mounted partitions of USB storage, e.g. to import files on a device
+/// without network
+pub fn usb_mounts() -> Vec<UsbMount> {
+    let mounts = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+    find_usb_mounts(&mounts, is_usb_block_device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_usb_mounts() {
+        let mounts = "proc /proc proc rw,relatime 0 0\n\
+                      /dev/sda4 /config vfat ro,relatime 0 0\n\
+                      /dev/sdb1 /media/USB\\040STICK vfat rw,relatime 0 0\n\
+                      /dev/sdb1 /mnt vfat rw,relatime 0 0\n\
+                      /dev/sdc2 /media/data ext4 rw,relatime 0 0\n";
+        let usb = find_usb_mounts(mounts, |name| name.starts_with("sdb") || name == "sdc2");
+        assert_eq!(
+            usb,
+            vec![
+                UsbMount {
+                    device: "/dev/sdb1".to_string(),
+                    mount_point: PathBuf::from("/media/USB STICK"),
+                },
+                UsbMount {
+                    device: "/dev/sdc2".to_string(),
+                    mount_point: PathBuf::from("/media/data"),
+                },
+            ]
+        );
+    }
+}
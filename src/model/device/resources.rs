@@ -0,0 +1,147 @@
+// CPU and memory usage computed from consecutive metrics samples
+use std::collections::VecDeque;
+
+use crate::{
+    collectors::metrics::{CpuTimes, LoadAverage, MemInfo, MetricsSample},
+    model::model::HistoryLimits,
+};
+
+#[derive(Debug)]
+pub struct ResourceHistory {
+    // number of samples kept
+    window: usize,
+    last_cpus: Vec<CpuTimes>,
+    // percent of all CPUs, oldest first
+    pub cpu: VecDeque<u64>,
+    // percent of every core at the last sample
+    pub cores: Vec<u64>,
+    // percent of memory in use, oldest first
+    pub memory: VecDeque<u64>,
+    pub meminfo: Option<MemInfo>,
+    pub load: Option<LoadAverage>,
+}
+
+impl Default for ResourceHistory {
+    fn default() -> Self {
+        Self::new(HistoryLimits::default().resources)
+    }
+}
+
+fn push_value(values: &mut VecDeque<u64>, value: u64, window: usize) {
+    values.push_back(value);
+    while values.len() > window {
+        values.pop_front();
+    }
+}
+
+// busy share of the jiffies between two samples
+fn cpu_percent(new: &CpuTimes, old: &CpuTimes) -> u64 {
+    let total = new.total.saturating_sub(old.total);
+    (new.busy.saturating_sub(old.busy) * 100)
+        .checked_div(total)
+        .unwrap_or(0)
+        .min(100)
+}
+
+impl ResourceHistory {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            last_cpus: Vec::new(),
+            cpu: VecDeque::new(),
+            cores: Vec::new(),
+            memory: VecDeque::new(),
+            meminfo: None,
+            load: None,
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Usage of all CPUs at the last sample
+    pub fn cpu_percent(&self) -> Option<u64> {
+        self.cpu.back().copied()
+    }
+
+    pub fn update(&mut self, sample: MetricsSample) {
+        // CPUs that went offline or came up make the old times useless
+        if self.last_cpus.len() == sample.cpus.len() {
+            let percents: Vec<u64> = sample
+                .cpus
+                .iter()
+                .zip(self.last_cpus.iter())
+                .map(|(new, old)| cpu_percent(new, old))
+                .collect();
+            if let Some((total, cores)) = percents.split_first() {
+                push_value(&mut self.cpu, *total, self.window);
+                self.cores = cores.to_vec();
+            }
+        }
+        self.last_cpus = sample.cpus;
+        push_value(&mut self.memory, sample.memory.used_percent(), self.window);
+        self.meminfo = Some(sample.memory);
+        self.load = Some(sample.load);
+    }
+}
+
+/// e.g. "15.9 GB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpus: &[(u64, u64)], available: u64) -> MetricsSample {
+        MetricsSample {
+            cpus: cpus
+                .iter()
+                .map(|(busy, total)| CpuTimes {
+                    busy: *busy,
+                    total: *total,
+                })
+                .collect(),
+            memory: MemInfo {
+                total: 1000,
+                available,
+                ..Default::default()
+            },
+            load: LoadAverage::default(),
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut history = ResourceHistory::new(2);
+        history.update(sample(&[(100, 1000), (50, 500), (50, 500)], 800));
+        // the first sample has nothing to compare with
+        assert_eq!(history.cpu_percent(), None);
+        assert_eq!(history.memory, [20]);
+
+        history.update(sample(&[(300, 1200), (150, 600), (150, 600)], 600));
+        assert_eq!(history.cpu_percent(), Some(100));
+        history.update(sample(&[(350, 1400), (200, 700), (150, 700)], 500));
+        assert_eq!(history.cpu, [100, 25]);
+        assert_eq!(history.cores, vec![50, 0]);
+        assert_eq!(history.memory, [40, 50]);
+
+        // a core went offline
+        history.update(sample(&[(400, 1500), (250, 800)], 500));
+        assert_eq!(history.cpu, [100, 25]);
+        assert_eq!(history.cores, vec![50, 0]);
+    }
+}
@@ -0,0 +1,77 @@
+// Temperature readings judged against the hardware limits and the config
+use crate::{collectors::sensors::SensorReading, config::SensorThresholds};
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum SensorLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// The temperature at which the sensor is critical
+pub fn critical_celsius(reading: &SensorReading, thresholds: &SensorThresholds) -> f64 {
+    thresholds
+        .critical_by_sensor
+        .get(&reading.key())
+        .copied()
+        .or(reading.crit)
+        .unwrap_or(thresholds.critical)
+}
+
+pub fn sensor_level(reading: &SensorReading, thresholds: &SensorThresholds) -> SensorLevel {
+    let critical = critical_celsius(reading, thresholds);
+    // a warning limit above the critical one would never show
+    let warning = reading.max.unwrap_or(thresholds.warning).min(critical);
+    if reading.celsius >= critical {
+        SensorLevel::Critical
+    } else if reading.celsius >= warning {
+        SensorLevel::Warning
+    } else {
+        SensorLevel::Normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(celsius: f64, max: Option<f64>, crit: Option<f64>) -> SensorReading {
+        SensorReading {
+            chip: "coretemp".to_string(),
+            label: "Core 0".to_string(),
+            celsius,
+            max,
+            crit,
+        }
+    }
+
+    #[test]
+    fn test_sensor_level() {
+        let mut thresholds = SensorThresholds::default();
+        assert_eq!(
+            sensor_level(&reading(50.0, None, None), &thresholds),
+            SensorLevel::Normal
+        );
+        assert_eq!(
+            sensor_level(&reading(85.0, None, None), &thresholds),
+            SensorLevel::Warning
+        );
+        // the hardware limits win over the defaults
+        assert_eq!(
+            sensor_level(&reading(85.0, Some(84.0), Some(85.0)), &thresholds),
+            SensorLevel::Critical
+        );
+        assert_eq!(
+            sensor_level(&reading(85.0, Some(90.0), Some(100.0)), &thresholds),
+            SensorLevel::Normal
+        );
+        // and the config wins over them
+        thresholds
+            .critical_by_sensor
+            .insert("coretemp/Core 0".to_string(), 70.0);
+        assert_eq!(
+            sensor_level(&reading(75.0, Some(90.0), Some(100.0)), &thresholds),
+            SensorLevel::Critical
+        );
+    }
+}
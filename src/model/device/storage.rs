@@ -0,0 +1,380 @@
+use std::{
+    ffi::CString,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use strum::Display;
+
+use super::{
+    efi::{format_guid, u32_at, u64_at, utf16_string},
+    removable::unescape_mount_path,
+};
+
+// a partition table larger than this is not something EVE created
+const MAX_GPT_SIZE: usize = 1024 * 1024;
+pub const PERSIST_MOUNT_POINT: &str = "/persist";
+
+/// What EVE uses a partition for
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum PartitionRole {
+    #[strum(to_string = "EFI")]
+    Efi,
+    #[strum(to_string = "IMGA")]
+    ImgA,
+    #[strum(to_string = "IMGB")]
+    ImgB,
+    #[strum(to_string = "CONFIG")]
+    Config,
+    #[strum(to_string = "PERSIST")]
+    Persist,
+}
+
+impl PartitionRole {
+    // by GPT partition type, labels tell the two images apart
+    fn from_type(type_guid: &str, label: &str) -> Option<Self> {
+        match type_guid {
+            "c12a7328-f81f-11d2-ba4b-00a0c93ec93b" => Some(PartitionRole::Efi),
+            "5dfbf5f4-2848-4bac-aa5e-0d9a20b745a6" if label == "IMGB" => Some(PartitionRole::ImgB),
+            "5dfbf5f4-2848-4bac-aa5e-0d9a20b745a6" => Some(PartitionRole::ImgA),
+            "13307e62-cd9c-4920-8f9b-91b45828b798" => Some(PartitionRole::Config),
+            "5f24425a-2dfa-11e8-a270-7b663faccc2c" => Some(PartitionRole::Persist),
+            _ => None,
+        }
+    }
+
+    // when the partition table cannot be read
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "EFI System" => Some(PartitionRole::Efi),
+            "IMGA" => Some(PartitionRole::ImgA),
+            "IMGB" => Some(PartitionRole::ImgB),
+            "CONFIG" => Some(PartitionRole::Config),
+            "P3" => Some(PartitionRole::Persist),
+            _ => None,
+        }
+    }
+}
+
+/// An entry of a GUID partition table
+#[derive(Debug, Clone, PartialEq)]
+pub struct GptEntry {
+    // as in the kernel's partition names, sda1 is number 1
+    pub number: u32,
+    pub type_guid: String,
+    pub guid: String,
+    pub label: String,
+}
+
+/// Used and total bytes of a mounted file system
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Usage {
+    pub used: u64,
+    pub total: u64,
+}
+
+impl Usage {
+    pub fn percent(&self) -> u64 {
+        (self.used * 100)
+            .checked_div(self.total)
+            .unwrap_or_default()
+    }
+}
+
+/// A disk or partition from /proc/partitions
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlockDevice {
+    pub name: String,
+    // the disk of a partition, None for disks
+    pub disk: Option<String>,
+    pub size: u64,
+    pub role: Option<PartitionRole>,
+    pub label: String,
+    // unique GPT partition GUID
+    pub guid: Option<String>,
+    pub mount_point: Option<String>,
+    pub usage: Option<Usage>,
+}
+
+// "major minor #blocks name" with 1 KiB blocks, after two header lines
+fn parse_partitions(partitions: &str) -> Vec<(String, u64)> {
+    partitions
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [_, _, blocks, name] = fields[..] else {
+                return None;
+            };
+            if ["loop", "ram", "zram"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            {
+                return None;
+            }
+            Some((name.to_string(), blocks.parse::<u64>().ok()? * 1024))
+        })
+        .collect()
+}
+
+/// Entries of the GPT in `data`, read from the start of a disk with
+/// `sector` byte blocks. Empty if there is none
+pub fn parse_gpt(data: &[u8], sector: usize) -> Vec<GptEntry> {
+    let Some(header) = data.get(sector..sector + 92) else {
+        return Vec::new();
+    };
+    if &header[..8] != b"EFI PART" {
+        return Vec::new();
+    }
+    let (Some(lba), Some(count), Some(size)) =
+        (u64_at(header, 72), u32_at(header, 80), u32_at(header, 84))
+    else {
+        return Vec::new();
+    };
+    let (start, size) = (lba as usize * sector, size as usize);
+    if size < 128 {
+        return Vec::new();
+    }
+    (0..count as usize)
+        .filter_map(|i| {
+            let entry = data.get(start + i * size..start + (i + 1) * size)?;
+            // unused entries have a zero type
+            if entry[..16].iter().all(|b| *b == 0) {
+                return None;
+            }
+            Some(GptEntry {
+                number: i as u32 + 1,
+                type_guid: format_guid(&entry[..16])?,
+                guid: format_guid(&entry[16..32])?,
+                label: utf16_string(&entry[56..128]).0,
+            })
+        })
+        .collect()
+}
+
+fn sysfs_block(name: &str) -> PathBuf {
+    Path::new("/sys/class/block").join(name)
+}
+
+// the partition table of a disk, the monitor runs as root on EVE
+fn read_gpt(disk: &str) -> Vec<GptEntry> {
+    let sector = std::fs::read_to_string(sysfs_block(disk).join("queue/logical_block_size"))
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .unwrap_or(512);
+    let Ok(mut file) = File::open(Path::new("/dev").join(disk)) else {
+        return Vec::new();
+    };
+    let mut data = vec![0; sector * 2];
+    if file.read_exact(&mut data).is_err() {
+        return Vec::new();
+    }
+    // the header tells how far the entries go
+    let header = &data[sector..];
+    let end = u64_at(header, 72).unwrap_or_default() as usize * sector
+        + u32_at(header, 80).unwrap_or_default() as usize
+            * u32_at(header, 84).unwrap_or_default() as usize;
+    if end > data.len() && end <= MAX_GPT_SIZE {
+        let mut rest = vec![0; end - data.len()];
+        if file.read_exact(&mut rest).is_err() {
+            return Vec::new();
+        }
+        data.extend(rest);
+    }
+    parse_gpt(&data, sector)
+}
+
+// PARTN and PARTNAME of a partition, None for disks
+fn read_partition_uevent(name: &str) -> Option<(u32, String)> {
+    let uevent = std::fs::read_to_string(sysfs_block(name).join("uevent")).ok()?;
+    let value = |key: &str| {
+        uevent
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+    };
+    Some((
+        value("PARTN")?.parse().ok()?,
+        value("PARTNAME").unwrap_or_default().to_string(),
+    ))
+}
+
+// the disk directory is the parent of the partition's in sysfs
+fn parent_disk(name: &str) -> Option<String> {
+    let path = sysfs_block(name).canonicalize().ok()?;
+    Some(path.parent()?.file_name()?.to_string_lossy().to_string())
+}
+
+// mount points by device name, the first mount of a device
+fn find_mount_points(mounts: &str) -> Vec<(String, String)> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    for line in mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(mount_point)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if found.iter().any(|(d, _)| d == device) {
+            continue;
+        }
+        found.push((device.to_string(), unescape_mount_path(mount_point)));
+    }
+    found
+}
+
+/// Used and total bytes of the file system mounted at `path`
+pub fn fs_usage(path: &str) -> Option<Usage> {
+    let path = CString::new(path).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let block = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * block;
+    Some(Usage {
+        used: total.saturating_sub(stat.f_bfree as u64 * block),
+        total,
+    })
+}
+
+/// Disks and their partitions in the order of /proc/partitions
+pub fn block_devices() -> Vec<BlockDevice> {
+    let partitions = std::fs::read_to_string("/proc/partitions").unwrap_or_default();
+    let mounts = find_mount_points(&std::fs::read_to_string("/proc/mounts").unwrap_or_default());
+    // partition tables by disk, read once
+    let mut tables: Vec<(String, Vec<GptEntry>)> = Vec::new();
+    parse_partitions(&partitions)
+        .into_iter()
+        .map(|(name, size)| {
+            let mut device = BlockDevice {
+                mount_point: mounts
+                    .iter()
+                    .find(|(d, _)| d.strip_prefix("/dev/") == Some(name.as_str()))
+                    .map(|(_, m)| m.clone()),
+                name,
+                size,
+                ..Default::default()
+            };
+            if let Some((number, label)) = read_partition_uevent(&device.name) {
+                device.disk = parent_disk(&device.name);
+                device.label = label;
+                let gpt = device.disk.as_ref().and_then(|disk| {
+                    if !tables.iter().any(|(d, _)| d == disk) {
+                        tables.push((disk.clone(), read_gpt(disk)));
+                    }
+                    let (_, entries) = tables.iter().find(|(d, _)| d == disk)?;
+                    entries.iter().find(|e| e.number == number).cloned()
+                });
+                device.role = match gpt {
+                    Some(entry) => {
+                        let role = PartitionRole::from_type(&entry.type_guid, &entry.label);
+                        device.label = entry.label;
+                        device.guid = Some(entry.guid);
+                        role
+                    }
+                    None => PartitionRole::from_label(&device.label),
+                };
+            }
+            // persist may be a ZFS pool, not mounted by device name
+            if device.role == Some(PartitionRole::Persist) && device.mount_point.is_none() {
+                device.mount_point = mounts
+                    .iter()
+                    .find(|(_, m)| m == PERSIST_MOUNT_POINT)
+                    .map(|(_, m)| m.clone());
+            }
+            device.usage = device.mount_point.as_deref().and_then(fs_usage);
+            device
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guid_bytes(guid: &str) -> Vec<u8> {
+        let guid = guid.replace('-', "");
+        let hex: Vec<u8> = (0..16)
+            .map(|i| u8::from_str_radix(&guid[i * 2..i * 2 + 2], 16).unwrap())
+            .collect();
+        // the first 3 groups are little endian
+        [
+            hex[..4].iter().rev().copied().collect::<Vec<_>>(),
+            hex[4..6].iter().rev().copied().collect(),
+            hex[6..8].iter().rev().copied().collect(),
+            hex[8..].to_vec(),
+        ]
+        .concat()
+    }
+
+    fn entry(type_guid: &str, guid: &str, label: &str) -> Vec<u8> {
+        let mut entry = [guid_bytes(type_guid), guid_bytes(guid)].concat();
+        entry.resize(56, 0);
+        entry.extend(label.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        entry.resize(128, 0);
+        entry
+    }
+
+    #[test]
+    fn test_parse_gpt() {
+        let sector = 512;
+        let mut data = vec![0; sector];
+        let mut header = b"EFI PART".to_vec();
+        header.resize(72, 0);
+        header.extend(2u64.to_le_bytes());
+        header.extend(4u32.to_le_bytes());
+        header.extend(128u32.to_le_bytes());
+        header.resize(sector, 0);
+        data.extend(header);
+        data.extend(entry(
+            "c12a7328-f81f-11d2-ba4b-00a0c93ec93b",
+            "ad6871ee-31f9-4cf3-9e09-6f7a25c30050",
+            "EFI System",
+        ));
+        data.extend(entry(
+            "5dfbf5f4-2848-4bac-aa5e-0d9a20b745a6",
+            "ad6871ee-31f9-4cf3-9e09-6f7a25c30051",
+            "IMGB",
+        ));
+        // unused
+        data.extend([0; 128]);
+        data.extend(entry(
+            "5f24425a-2dfa-11e8-a270-7b663faccc2c",
+            "ad6871ee-31f9-4cf3-9e09-6f7a25c30059",
+            "P3",
+        ));
+
+        let entries = parse_gpt(&data, sector);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].number, 1);
+        assert_eq!(entries[0].guid, "ad6871ee-31f9-4cf3-9e09-6f7a25c30050");
+        assert_eq!(entries[0].label, "EFI System");
+        assert_eq!(
+            PartitionRole::from_type(&entries[1].type_guid, &entries[1].label),
+            Some(PartitionRole::ImgB)
+        );
+        assert_eq!(entries[2].number, 4);
+        assert_eq!(
+            PartitionRole::from_type(&entries[2].type_guid, &entries[2].label),
+            Some(PartitionRole::Persist)
+        );
+
+        // no signature
+        data[sector] = 0;
+        assert!(parse_gpt(&data, sector).is_empty());
+    }
+
+    #[test]
+    fn test_parse_partitions() {
+        let partitions = "major minor  #blocks  name\n\n\
+                          \x20  7        0      65536 loop0\n\
+                          \x20  8        0   15625216 sda\n\
+                          \x20  8        1      36864 sda1\n";
+        assert_eq!(
+            parse_partitions(partitions),
+            vec![
+                ("sda".to_string(), 15625216 * 1024),
+                ("sda1".to_string(), 36864 * 1024)
+            ]
+        );
+    }
+}
@@ -33,9 +33,10 @@ impl DeviceSummary {
 }
 
 fn get_usb() -> Vec<String> {
-    let cmd_out = Command::new("lsusb")
-        .output()
-        .expect("failed to execute process");
+    // the tool is not installed everywhere
+    let Ok(cmd_out) = Command::new("lsusb").output() else {
+        return vec!["lsusb is not available".to_string()];
+    };
     if cmd_out.status.success() {
         if let Some(string) = String::from_utf8(cmd_out.stdout).ok() {
             return string.split('\n').map(|s| s.to_string()).collect();
@@ -46,9 +47,10 @@ fn get_usb() -> Vec<String> {
 }
 
 fn get_pci() -> Vec<String> {
-    let cmd_out = Command::new("lspci")
-        .output()
-        .expect("failed to execute process");
+    // the tool is not installed everywhere
+    let Ok(cmd_out) = Command::new("lspci").output() else {
+        return vec!["lspci is not available".to_string()];
+    };
     if cmd_out.status.success() {
         if let Some(string) = String::from_utf8(cmd_out.stdout).ok() {
             return string.split('\n').map(|s| s.to_string()).collect();
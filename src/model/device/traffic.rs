@@ -0,0 +1,154 @@
+// RX/TX rates of the interfaces computed from consecutive counter samples
+use std::{collections::BTreeMap, collections::VecDeque, time::Instant};
+
+use crate::{
+    collectors::netstats::{IfaceCounters, NetStatsSample},
+    model::model::HistoryLimits,
+};
+
+#[derive(Debug)]
+pub struct IfaceTraffic {
+    pub counters: IfaceCounters,
+    at: Instant,
+    // bytes per second, oldest first
+    pub rx_rates: VecDeque<u64>,
+    pub tx_rates: VecDeque<u64>,
+}
+
+impl IfaceTraffic {
+    pub fn rx_rate(&self) -> u64 {
+        self.rx_rates.back().copied().unwrap_or(0)
+    }
+
+    pub fn tx_rate(&self) -> u64 {
+        self.tx_rates.back().copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug)]
+pub struct TrafficHistory {
+    // number of rates kept per interface
+    window: usize,
+    pub ifaces: BTreeMap<String, IfaceTraffic>,
+}
+
+impl Default for TrafficHistory {
+    fn default() -> Self {
+        Self::new(HistoryLimits::default().traffic)
+    }
+}
+
+fn push_rate(rates: &mut VecDeque<u64>, rate: u64, window: usize) {
+    rates.push_back(rate);
+    while rates.len() > window {
+        rates.pop_front();
+    }
+}
+
+impl TrafficHistory {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            ifaces: BTreeMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    pub fn update(&mut self, sample: NetStatsSample) {
+        // removed interfaces are dropped with their history
+        self.ifaces
+            .retain(|name, _| sample.counters.iter().any(|c| c.name == *name));
+        for counters in sample.counters {
+            let Some(iface) = self.ifaces.get_mut(&counters.name) else {
+                self.ifaces.insert(
+                    counters.name.clone(),
+                    IfaceTraffic {
+                        counters,
+                        at: sample.at,
+                        rx_rates: VecDeque::new(),
+                        tx_rates: VecDeque::new(),
+                    },
+                );
+                continue;
+            };
+            let elapsed = sample.at.saturating_duration_since(iface.at).as_secs_f64();
+            if elapsed > 0.0 {
+                // counters go back when the interface is recreated, count that as idle
+                let rate = |new: u64, old: u64| (new.saturating_sub(old) as f64 / elapsed) as u64;
+                let rx = rate(counters.rx_bytes, iface.counters.rx_bytes);
+                let tx = rate(counters.tx_bytes, iface.counters.tx_bytes);
+                push_rate(&mut iface.rx_rates, rx, self.window);
+                push_rate(&mut iface.tx_rates, tx, self.window);
+            }
+            iface.counters = counters;
+            iface.at = sample.at;
+        }
+    }
+}
+
+/// e.g. "1.5 MB/s"
+pub fn format_rate(bytes_per_second: u64) -> String {
+    const UNITS: [&str; 4] = ["B/s", "kB/s", "MB/s", "GB/s"];
+    let mut value = bytes_per_second as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes_per_second, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn sample(at: Instant, counters: &[(&str, u64, u64)]) -> NetStatsSample {
+        NetStatsSample {
+            at,
+            counters: counters
+                .iter()
+                .map(|(name, rx, tx)| IfaceCounters {
+                    name: name.to_string(),
+                    rx_bytes: *rx,
+                    tx_bytes: *tx,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_rates() {
+        let start = Instant::now();
+        let mut history = TrafficHistory::new(2);
+        history.update(sample(start, &[("eth0", 1000, 0), ("wlan0", 0, 0)]));
+        assert!(history.ifaces["eth0"].rx_rates.is_empty());
+
+        let after = |secs| start + Duration::from_secs(secs);
+        history.update(sample(after(2), &[("eth0", 5000, 200)]));
+        assert!(!history.ifaces.contains_key("wlan0"));
+        assert_eq!(history.ifaces["eth0"].rx_rate(), 2000);
+        assert_eq!(history.ifaces["eth0"].tx_rate(), 100);
+
+        // recreated interface
+        history.update(sample(after(3), &[("eth0", 10, 10)]));
+        history.update(sample(after(4), &[("eth0", 110, 10)]));
+        assert_eq!(history.ifaces["eth0"].rx_rates, [0, 100]);
+    }
+
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(999), "999 B/s");
+        assert_eq!(format_rate(1500), "1.5 kB/s");
+        assert_eq!(format_rate(12_345_678), "12.3 MB/s");
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::Path;
+
+const USB_DEVICES: &str = "/sys/bus/usb/devices";
+
+/// A device on the USB bus as the kernel sees it
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UsbDevice {
+    // sysfs name, e.g. 1-2.3 for port 3 of the hub on port 2 of bus 1
+    pub name: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub class: u8,
+    pub manufacturer: String,
+    pub product: String,
+    pub serial: String,
+}
+
+/// USB base classes, class 0 means each interface has its own
+pub fn usb_class_name(class: u8) -> &'static str {
+    match class {
+        0x00 => "per interface",
+        0x01 => "audio",
+        0x02 => "communications",
+        0x03 => "HID",
+        0x06 => "image",
+        0x07 => "printer",
+        0x08 => "mass storage",
+        0x09 => "hub",
+        0x0a => "CDC data",
+        0x0b => "smart card",
+        0x0e => "video",
+        0xe0 => "wireless",
+        0xef => "miscellaneous",
+        0xff => "vendor specific",
+        _ => "other",
+    }
+}
+
+/// How a boot entry refers to a USB device, from the nodes of its device path
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsbBootPath {
+    // by ports only, the device behind them cannot be told
+    Ports(Vec<u8>),
+    Device {
+        vendor_id: u16,
+        product_id: u16,
+        // USB WWID nodes only
+        serial: Option<String>,
+    },
+}
+
+impl UsbBootPath {
+    pub fn matches(&self, device: &UsbDevice) -> bool {
+        match self {
+            UsbBootPath::Ports(_) => false,
+            UsbBootPath::Device {
+                vendor_id,
+                product_id,
+                serial,
+            } => {
+                // 0xffff matches any ID in USB Class nodes
+                (*vendor_id == 0xffff || *vendor_id == device.vendor_id)
+                    && (*product_id == 0xffff || *product_id == device.product_id)
+                    && serial.as_ref().is_none_or(|s| *s == device.serial)
+            }
+        }
+    }
+}
+
+fn read_attr(dir: &Path, name: &str) -> String {
+    std::fs::read_to_string(dir.join(name))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn read_hex_attr(dir: &Path, name: &str) -> Option<u16> {
+    u16::from_str_radix(&read_attr(dir, name), 16).ok()
+}
+
+/// Connected USB devices, root hubs and interfaces are left out
+pub fn usb_devices() -> Vec<UsbDevice> {
+    let Ok(entries) = std::fs::read_dir(USB_DEVICES) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<UsbDevice> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            // usb1 is a root hub, 1-2:1.0 an interface
+            if name.starts_with("usb") || name.contains(':') {
+                return None;
+            }
+            let dir = entry.path();
+            Some(UsbDevice {
+                vendor_id: read_hex_attr(&dir, "idVendor")?,
+                product_id: read_hex_attr(&dir, "idProduct")?,
+                class: read_hex_attr(&dir, "bDeviceClass").unwrap_or_default() as u8,
+                manufacturer: read_attr(&dir, "manufacturer"),
+                product: read_attr(&dir, "product"),
+                serial: read_attr(&dir, "serial"),
+                name,
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
@@ -0,0 +1,227 @@
+use crate::ipc::eve_types::{StorageStatus, ZfsPoolStatus};
+
+/// Where the pool state comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZfsSource {
+    Ipc,
+    Zpool,
+}
+
+/// A disk or a group of disks (mirror, raidz) of a pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZfsVdev {
+    pub name: String,
+    // 0 for the top level vdevs
+    pub depth: usize,
+    pub state: StorageStatus,
+    // e.g. "too many errors"
+    pub note: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZfsPool {
+    pub name: String,
+    pub state: StorageStatus,
+    // what zpool says is wrong, empty if nothing
+    pub status: String,
+    pub vdevs: Vec<ZfsVdev>,
+}
+
+/// Whether a pool or vdev in this state needs attention
+pub fn is_unhealthy(state: StorageStatus) -> bool {
+    !matches!(state, StorageStatus::Online | StorageStatus::Unspecified)
+}
+
+impl ZfsPool {
+    pub fn is_healthy(&self) -> bool {
+        !is_unhealthy(self.state) && !self.vdevs.iter().any(|v| is_unhealthy(v.state))
+    }
+
+    /// Vdevs that are not online
+    pub fn unhealthy_vdevs(&self) -> impl Iterator<Item = &ZfsVdev> {
+        self.vdevs.iter().filter(|v| is_unhealthy(v.state))
+    }
+}
+
+impl From<ZfsPoolStatus> for ZfsPool {
+    fn from(status: ZfsPoolStatus) -> Self {
+        let mut vdevs = Vec::new();
+        let depth = if status.raid.is_empty() {
+            0
+        } else {
+            vdevs.push(ZfsVdev {
+                name: status.raid,
+                depth: 0,
+                state: status.status,
+                note: String::new(),
+            });
+            1
+        };
+        vdevs.extend(status.disks.into_iter().map(|disk| ZfsVdev {
+            name: disk.logical_name,
+            depth,
+            state: disk.status,
+            note: disk.aux_state_str,
+        }));
+        Self {
+            name: status.pool_name,
+            state: status.status,
+            status: status.status_msg,
+            vdevs,
+        }
+    }
+}
+
+fn parse_state(state: &str) -> StorageStatus {
+    match state {
+        "ONLINE" => StorageStatus::Online,
+        "DEGRADED" => StorageStatus::Degraded,
+        "FAULTED" => StorageStatus::Faulted,
+        "OFFLINE" => StorageStatus::Offline,
+        "UNAVAIL" => StorageStatus::Unavail,
+        "REMOVED" => StorageStatus::Removed,
+        "SUSPENDED" => StorageStatus::Suspended,
+        _ => StorageStatus::Unrecognized,
+    }
+}
+
+/// Pools in the output of `zpool status`
+pub fn parse_zpool_status(output: &str) -> Vec<ZfsPool> {
+    let mut pools: Vec<ZfsPool> = Vec::new();
+    // the vdev table of the current pool is being read
+    let mut in_config = false;
+    // the status text continues on indented lines
+    let mut in_status = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("pool:") {
+            pools.push(ZfsPool {
+                name: name.trim().to_string(),
+                state: StorageStatus::Unspecified,
+                status: String::new(),
+                vdevs: Vec::new(),
+            });
+            in_config = false;
+            in_status = false;
+            continue;
+        }
+        let Some(pool) = pools.last_mut() else {
+            continue;
+        };
+        if let Some(state) = trimmed.strip_prefix("state:") {
+            pool.state = parse_state(state.trim());
+            in_status = false;
+        } else if let Some(status) = trimmed.strip_prefix("status:") {
+            pool.status = status.trim().to_string();
+            in_status = true;
+        } else if trimmed.starts_with("config:") || trimmed.starts_with("errors:") {
+            in_config = trimmed.starts_with("config:");
+            in_status = false;
+        } else if in_status && line.starts_with('\t') && !trimmed.contains(':') {
+            pool.status.push(' ');
+            pool.status.push_str(trimmed);
+        } else if in_config && !trimmed.is_empty() {
+            in_status = false;
+            let fields: Vec<&str> = trimmed.split_whitespace().collect();
+            // the header and the pool itself, whose state we have
+            if fields[0] == "NAME" || fields[0] == pool.name {
+                continue;
+            }
+            // one tab, then two blanks per level below the pool
+            let indent = line.trim_start_matches('\t').len() - line.trim_start().len();
+            pool.vdevs.push(ZfsVdev {
+                name: fields[0].to_string(),
+                depth: indent.saturating_sub(2) / 2,
+                state: fields
+                    .get(1)
+                    .map_or(StorageStatus::Unspecified, |s| parse_state(s)),
+                note: fields.get(5..).unwrap_or_default().join(" "),
+            });
+        } else {
+            in_status = false;
+        }
+    }
+    pools
+}
+
+/// ZFS pools from EVE or, until EVE sends them, from `zpool status`
+#[derive(Debug, Default)]
+pub struct ZfsPools {
+    pub pools: Vec<ZfsPool>,
+    pub source: Option<ZfsSource>,
+}
+
+impl ZfsPools {
+    /// Pools from `zpool status`, ignored once EVE reports them. Returns true
+    /// if anything has changed
+    pub fn update_from_zpool(&mut self, pools: Vec<ZfsPool>) -> bool {
+        if self.source == Some(ZfsSource::Ipc) || pools == self.pools {
+            return false;
+        }
+        self.pools = pools;
+        self.source = Some(ZfsSource::Zpool);
+        true
+    }
+
+    /// A pool as EVE reports it. EVE knows better than zpool run by us
+    pub fn update(&mut self, status: ZfsPoolStatus) {
+        if self.source != Some(ZfsSource::Ipc) {
+            self.pools.clear();
+            self.source = Some(ZfsSource::Ipc);
+        }
+        let pool = ZfsPool::from(status);
+        match self.pools.iter_mut().find(|p| p.name == pool.name) {
+            Some(known) => *known = pool,
+            None => self.pools.push(pool),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_zpool_status() {
+        let output = "  pool: persist\n \
+                      state: DEGRADED\n\
+                      status: One or more devices could not be used because the label is missing or\n\
+                      \tinvalid.  Sufficient replicas exist for the pool to continue\n\
+                      \tfunctioning in a degraded state.\n\
+                      action: Replace the device using 'zpool replace'.\n   \
+                      scan: none requested\n\
+                      config:\n\
+                      \n\
+                      \tNAME        STATE     READ WRITE CKSUM\n\
+                      \tpersist     DEGRADED     0     0     0\n\
+                      \t  mirror-0  DEGRADED     0     0     0\n\
+                      \t    sda9    ONLINE       0     0     0\n\
+                      \t    sdb     UNAVAIL      0     0     0  corrupted data\n\
+                      \n\
+                      errors: No known data errors\n";
+        let pools = parse_zpool_status(output);
+        assert_eq!(pools.len(), 1);
+        let pool = &pools[0];
+        assert_eq!(pool.name, "persist");
+        assert_eq!(pool.state, StorageStatus::Degraded);
+        assert!(pool.status.starts_with("One or more devices"));
+        assert!(pool.status.ends_with("in a degraded state."));
+        assert_eq!(
+            pool.vdevs
+                .iter()
+                .map(|v| (v.name.as_str(), v.depth, v.state))
+                .collect::<Vec<_>>(),
+            vec![
+                ("mirror-0", 0, StorageStatus::Degraded),
+                ("sda9", 1, StorageStatus::Online),
+                ("sdb", 1, StorageStatus::Unavail),
+            ]
+        );
+        assert_eq!(pool.vdevs[2].note, "corrupted data");
+        assert_eq!(
+            pool.unhealthy_vdevs().map(|v| &v.name).collect::<Vec<_>>(),
+            vec!["mirror-0", "sdb"]
+        );
+        assert!(!pool.is_healthy());
+    }
+}
@@ -0,0 +1,59 @@
+// Report of the connectivity diagnostics started from the Network page
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepState {
+    Pending,
+    Running,
+    Passed(String),
+    Failed(String),
+    // not run, e.g. there is no gateway to ping
+    Skipped(String),
+}
+
+impl StepState {
+    pub fn is_done(&self) -> bool {
+        !matches!(self, StepState::Pending | StepState::Running)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagnosticStep {
+    pub name: String,
+    pub state: StepState,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagnosticsReport {
+    pub steps: Vec<DiagnosticStep>,
+}
+
+impl DiagnosticsReport {
+    pub fn new(names: Vec<String>) -> Self {
+        Self {
+            steps: names
+                .into_iter()
+                .map(|name| DiagnosticStep {
+                    name,
+                    state: StepState::Pending,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn update(&mut self, step: usize, state: StepState) {
+        if let Some(s) = self.steps.get_mut(step) {
+            s.state = state;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.steps.iter().all(|s| s.state.is_done())
+    }
+
+    pub fn failures(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|s| matches!(s.state, StepState::Failed(_)))
+            .count()
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+// Concise summaries of model changes for logging and alerts, so we do not have
+// to dump whole structs to find out what has changed
+
+/// Extracts a printable value of an item field e.g. link state of an interface
+pub type Field<T> = (&'static str, fn(&T) -> String);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Modified {
+        item: String,
+        field: &'static str,
+        old: String,
+        new: String,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(item) => write!(f, "{}: added", item),
+            Change::Removed(item) => write!(f, "{}: removed", item),
+            Change::Modified {
+                item,
+                field,
+                old,
+                new,
+            } => write!(f, "{}: {} {}→{}", item, field, old, new),
+        }
+    }
+}
+
+/// Compare two versions of a model section. Items are matched by `key` which
+/// is also used as the item name in the summaries
+pub fn diff_items<'a, T: 'a>(
+    old: impl IntoIterator<Item = &'a T>,
+    new: impl IntoIterator<Item = &'a T>,
+    key: impl Fn(&T) -> String,
+    fields: &[Field<T>],
+) -> Vec<Change> {
+    let mut old: Vec<&T> = old.into_iter().collect();
+    let mut changes = Vec::new();
+
+    for new_item in new {
+        let name = key(new_item);
+        let Some(pos) = old.iter().position(|old_item| key(old_item) == name) else {
+            changes.push(Change::Added(name));
+            continue;
+        };
+        let old_item = old.swap_remove(pos);
+        for (field, get) in fields {
+            let (old_value, new_value) = (get(old_item), get(new_item));
+            if old_value != new_value {
+                changes.push(Change::Modified {
+                    item: name.clone(),
+                    field,
+                    old: old_value,
+                    new: new_value,
+                });
+            }
+        }
+    }
+    changes.extend(old.into_iter().map(|item| Change::Removed(key(item))));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Port {
+        name: &'static str,
+        up: bool,
+    }
+
+    const FIELDS: &[Field<Port>] = &[("link", |p| if p.up { "up" } else { "down" }.to_string())];
+
+    #[test]
+    fn test_diff_items() {
+        let old = [
+            Port {
+                name: "eth0",
+                up: false,
+            },
+            Port {
+                name: "eth1",
+                up: true,
+            },
+        ];
+        let new = [
+            Port {
+                name: "eth0",
+                up: true,
+            },
+            Port {
+                name: "wlan0",
+                up: true,
+            },
+        ];
+        let changes: Vec<String> = diff_items(&old, &new, |p| p.name.to_string(), FIELDS)
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            changes,
+            vec!["eth0: link down→up", "wlan0: added", "eth1: removed"]
+        );
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let ports = [Port {
+            name: "eth0",
+            up: true,
+        }];
+        assert!(diff_items(&ports, &ports, |p| p.name.to_string(), FIELDS).is_empty());
+    }
+}
@@ -1,2 +1,21 @@
+pub mod alerts;
+pub mod app_health;
+pub mod app_logs;
+pub mod attestation;
+pub mod audit;
+pub mod blobs;
+pub mod bundle;
+pub mod connectivity;
 pub mod device;
+pub mod diagnostics;
+pub mod diff;
 pub mod model;
+pub mod model_snapshot;
+pub mod monitor_log;
+pub mod onboarding;
+pub mod reachability;
+pub mod restarts;
+pub mod ring_buffer;
+pub mod sanitize;
+pub mod snapshot;
+pub mod verification;
@@ -1,23 +1,66 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    net::IpAddr,
+    time::{Duration, Instant},
+};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
+use log::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::application::Application;
 use crate::ipc::eve_types::{
-    AppInstanceStatus, AppInstanceSummary, AppsList, DataSecAtRestStatus, DeviceNetworkStatus,
-    DevicePortConfig, DevicePortConfigList, DownloaderStatus, ErrorAndTime, EveNodeStatus,
-    EveOnboardingStatus, EveVaultStatus, PCRStatus, SwState, ZedAgentStatus,
+    AppInstanceStatus, AppInstanceSummary, AppsList, ConsoleHandoffState, ConsoleHandoffStatus,
+    DataSecAtRestStatus, DeviceNetworkStatus, DevicePortConfig, DevicePortConfigList,
+    DownloaderStatus, ErrorAndTime, EveNodeStatus, EveOnboardingStatus, EveVaultStatus,
+    LedBlinkCounter, MonitorPolicy, PCRStatus, SupportBundleStatus, SwState, ZedAgentStatus,
 };
+use crate::ipc::eve_types::{BlobRef, BlobSlot, VmMode};
 
-use super::device::network::NetworkInterfaceStatus;
+use super::alerts::{Alert, AlertLink, AlertSeverity};
+use super::app_health::AppCounts;
+use super::app_logs::AppLogs;
+use super::attestation::AttestationHistory;
+use super::audit::AuditRecord;
+use super::blobs::BlobStore;
+use super::bundle::BundleProgress;
+use super::connectivity::{connectivity_score, ConnectivityScore, PortSummary};
+use super::device::config_partition::{ConfigPartition, ConfigPartitionState};
+#[cfg(feature = "dmesg")]
+use super::device::dmesg::DmesgBuffer;
+use super::device::identity::DeviceIdentity;
+use super::device::network::{IpV6LinikLocal, NetworkInterfaceStatus, ResolvConf};
+use super::device::resources::ResourceHistory;
+use super::device::sensors::{sensor_level, SensorLevel};
+use super::device::traffic::TrafficHistory;
+use super::device::zfs::{ZfsPool, ZfsPools};
+use super::diagnostics::DiagnosticsReport;
+use super::diff::{diff_items, Change, Field};
+use super::model_snapshot::StateMessages;
+use super::onboarding::{OnboardingEvent, OnboardingStatus};
+use super::reachability::{reachability, ReachabilityHistory};
+use super::restarts::RestartHistory;
+use super::sanitize::sanitize_line;
+use super::snapshot::Snapshot;
+use super::verification::{volume_name, ShaMismatch, Verifications};
+use crate::action_bus::ActionStats;
+use crate::collectors::{netlink::NetlinkEvent, sensors::SensorReading, CollectorStatus};
+use crate::config::{AlertsConfig, AppThresholds, RuntimeSettings, SensorThresholds};
+use crate::guest_console::AttachedConsole;
+use crate::ipc::dedup::MessageStats;
+use crate::ipc::lenient::{UnknownKind, UnknownValue};
+use crate::ipc::message::IpcMessage;
+use crate::ipc::protocol::{ProtocolState, ProtocolVersion};
+use crate::lock::LockScreen;
+use crate::ui::theme::Theme;
 
-#[derive(Debug, Clone, Default)]
-pub enum OnboardingStatus {
-    #[default]
-    Unknown,
-    Onboarding,
-    Onboarded(Uuid),
-    Error(String),
+// a message type EVE sends in a format this build cannot parse
+#[derive(Debug, Clone)]
+pub struct UnsupportedMessage {
+    pub schema: Option<u32>,
+    pub error: String,
+    pub count: u64,
 }
 
 #[derive(Debug, Default)]
@@ -29,22 +72,27 @@ pub struct NodeStatus {
 
 impl NodeStatus {
     pub fn is_onboarded(&self) -> bool {
-        matches!(self.onboarding_status, OnboardingStatus::Onboarded(_))
+        self.onboarding_status.is_onboarded()
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AppInstanceState {
     Normal(SwState),
     Error(SwState, String),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AppInstance {
     pub name: String,
     pub uuid: Uuid,
     pub version: String,
     pub state: AppInstanceState,
+    pub restarts: RestartHistory,
+    // as last reported, for the details view
+    pub status: AppInstanceStatus,
+    // last time EVE reported the app in AppStatus or AppsList
+    pub last_seen: Instant,
 }
 
 #[derive(Debug)]
@@ -70,25 +118,167 @@ pub enum VaultStatus {
     Locked(EveError, Option<Vec<i32>>),
 }
 
+impl std::fmt::Display for VaultStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultStatus::Unknown => write!(f, "unknown"),
+            VaultStatus::EncryptionDisabled(..) => write!(f, "encryption disabled"),
+            VaultStatus::Unlocked(_) => write!(f, "unlocked"),
+            VaultStatus::Locked(err, _) => write!(f, "locked ({})", err.error),
+        }
+    }
+}
+
+// limits for the buffers that grow over time
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryLimits {
+    pub dmesg: usize,
+    pub alerts: usize,
+    // bytes of pubsub-large-* payloads kept in RAM
+    pub blobs: usize,
+    // traffic rates kept per interface
+    pub traffic: usize,
+    // CPU and memory samples, one per second
+    pub resources: usize,
+    // records of the monitor's own log in its viewer
+    pub monitor_log: usize,
+    // minutes of controller reachability
+    pub reachability: usize,
+    // records of the audit file on the Audit page
+    pub audit: usize,
+}
+
+impl Default for HistoryLimits {
+    fn default() -> Self {
+        Self {
+            dmesg: 10000,
+            alerts: 500,
+            blobs: 4 * 1024 * 1024,
+            traffic: 120,
+            resources: 300,
+            monitor_log: 5000,
+            reachability: 24 * 60,
+            audit: 1000,
+        }
+    }
+}
+
+impl HistoryLimits {
+    // keep the footprint small on 1 GB devices
+    pub fn low_memory() -> Self {
+        Self {
+            dmesg: 1000,
+            alerts: 100,
+            blobs: 256 * 1024,
+            traffic: 60,
+            resources: 120,
+            monitor_log: 1000,
+            reachability: 6 * 60,
+            audit: 200,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum IpcConnectionState {
+    #[default]
+    Disconnected,
+    Connecting,
+    Connected,
+    // waiting before the next connection attempt
+    Reconnecting {
+        attempt: u32,
+        retry_at: Instant,
+    },
+    // the IPC task itself ended, it is restarted at `restart_at` if at all
+    Failed {
+        restart_at: Option<Instant>,
+    },
+    // the state comes from a model snapshot or a recording, see --replay and --playback
+    Replay,
+}
+
 pub type Model = RefCell<MonitorModel>;
 #[derive(Debug)]
 pub struct MonitorModel {
-    pub dmesg: Vec<rmesg::entry::Entry>,
+    pub limits: HistoryLimits,
+    #[cfg(feature = "dmesg")]
+    pub dmesg: DmesgBuffer,
     pub network: Vec<NetworkInterfaceStatus>,
+    pub traffic: TrafficHistory,
+    pub resources: ResourceHistory,
+    pub reachability: ReachabilityHistory,
     pub downloader: Option<DownloaderStatus>,
+    downloader_seen: Option<Instant>,
+    // verification state of downloaded images and volumes
+    pub verifications: Verifications,
     pub node_status: NodeStatus,
     pub apps: HashMap<Uuid, AppInstance>,
     pub vault_status: VaultStatus,
+    // large payloads of dpc_list are kept in blobs
     pub dpc_list: Option<DevicePortConfigList>,
+    pub blobs: BlobStore,
     pub dpc_key: Option<String>,
     pub z_status: Option<ZedAgentStatus>,
+    // EVE does not apply configuration changes while in maintenance mode
+    pub maintenance_mode: bool,
+    pub attestation: AttestationHistory,
+    pub ipc_state: IpcConnectionState,
+    pub protocol: ProtocolState,
+    // message type -> last failure, the data of these types is not shown
+    pub unsupported_messages: BTreeMap<String, UnsupportedMessage>,
+    // message type -> values of the last such message we did not understand
+    pub unknown_values: BTreeMap<String, Vec<UnknownValue>>,
+    // message type -> messages received and dropped as duplicates
+    pub ipc_message_stats: BTreeMap<String, MessageStats>,
+    // number of IPC requests waiting for a response
+    pub pending_requests: usize,
+    pub action_stats: ActionStats,
+    pub collectors: Vec<CollectorStatus>,
+    pub settings: RuntimeSettings,
+    pub theme: Theme,
+    pub app_thresholds: AppThresholds,
+    pub sensor_thresholds: SensorThresholds,
+    // temperatures of the last hwmon sample
+    pub sensors: Vec<SensorReading>,
+    pub identity: DeviceIdentity,
+    pub config_partition: ConfigPartition,
+    pub zfs: ZfsPools,
+    pub support_bundle: Option<SupportBundleStatus>,
+    pub alerts: Vec<Alert>,
+    pub alerts_config: AlertsConfig,
+    // id of the last alert raised
+    pub last_alert_id: u64,
+    // only show the state, set in the config file
+    pub read_only_config: bool,
+    // the last policy of the controller
+    pub policy: Option<MonitorPolicy>,
+    // Some while the UI is locked, see crate::lock
+    pub lock_screen: Option<LockScreen>,
+    // changes made from the monitor, oldest first
+    pub audit: Vec<AuditRecord>,
+    last_audit_id: u64,
+    pub console_handoff: Option<ConsoleHandoffStatus>,
+    // logs of the app open in the log viewer
+    pub app_logs: Option<AppLogs>,
+    // guest console that owns the terminal
+    pub attached_console: Option<AttachedConsole>,
+    pub snapshot: Option<Snapshot>,
+    // connectivity checks shown in the diagnostics dialog
+    pub diagnostics: Option<DiagnosticsReport>,
+    // Some while the bundle dialog is open
+    pub bundle: Option<BundleProgress>,
+    // the last state messages of EVE, saved as a model snapshot on request
+    pub state_messages: StateMessages,
 }
 
 impl From<EveVaultStatus> for VaultStatus {
     fn from(vault_status: EveVaultStatus) -> Self {
         let tpm_used = vault_status.pcr_status == PCRStatus::PcrEnabled;
         match vault_status.status {
-            DataSecAtRestStatus::DataSecAtRestUnknown => Self::Unknown,
+            DataSecAtRestStatus::DataSecAtRestUnknown | DataSecAtRestStatus::Unrecognized => {
+                Self::Unknown
+            }
             DataSecAtRestStatus::DataSecAtRestDisabled => {
                 let reason = EveError::from(vault_status.error_and_time);
                 Self::EncryptionDisabled(reason, tpm_used)
@@ -110,6 +300,7 @@ impl From<EveVaultStatus> for VaultStatus {
 
 impl From<AppInstanceStatus> for AppInstance {
     fn from(app: AppInstanceStatus) -> Self {
+        let status = app.clone();
         let state = if !app
             .error_and_time_with_source
             .error_description
@@ -124,15 +315,31 @@ impl From<AppInstanceStatus> for AppInstance {
             AppInstanceState::Normal(app.state)
         };
 
+        let mut restarts = RestartHistory::default();
+        restarts.update(app.boot_time);
+
         AppInstance {
             name: app.display_name,
             uuid: app.uuid_and_version.uuid,
             version: app.uuid_and_version.version,
             state,
+            restarts,
+            status,
+            last_seen: Instant::now(),
         }
     }
 }
 
+impl AppInstance {
+    /// Only fully virtualized guests have an emulated serial port EVE can proxy
+    pub fn has_serial_console(&self) -> bool {
+        matches!(
+            self.status.fixed_resources.virtualization_mode,
+            VmMode::HVM | VmMode::FML
+        )
+    }
+}
+
 impl From<AppsList> for HashMap<Uuid, AppInstance> {
     fn from(apps_list: AppsList) -> Self {
         apps_list
@@ -143,22 +350,97 @@ impl From<AppsList> for HashMap<Uuid, AppInstance> {
     }
 }
 
-impl From<EveNodeStatus> for NodeStatus {
-    fn from(node_status: EveNodeStatus) -> Self {
-        let onboarding_status = match (node_status.onboarded, node_status.node_uuid) {
-            (true, Some(uuid)) => OnboardingStatus::Onboarded(uuid),
-            (true, None) => OnboardingStatus::Error("Node UUID is missing".to_string()),
-            (false, _) => OnboardingStatus::Onboarding,
+impl MonitorModel {
+    pub fn new(limits: HistoryLimits) -> Self {
+        let blobs = BlobStore::new(Application::monitor_data_dir("blobs"), limits.blobs);
+        blobs.remove_leftovers();
+        MonitorModel {
+            limits,
+            #[cfg(feature = "dmesg")]
+            dmesg: DmesgBuffer::new(limits.dmesg),
+            traffic: TrafficHistory::new(limits.traffic),
+            resources: ResourceHistory::new(limits.resources),
+            reachability: ReachabilityHistory::new(limits.reachability),
+            identity: DeviceIdentity::load(),
+            blobs,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the sequence number of the entry. It stays valid when the buffer is trimmed
+    #[cfg(feature = "dmesg")]
+    pub fn push_dmesg(&mut self, mut entry: rmesg::entry::Entry) -> u64 {
+        super::sanitize::sanitize_text_in_place(&mut entry.message);
+        self.dmesg.push(entry)
+    }
+
+    /// Tell the user about something that needs attention. `source` names the
+    /// subsystem, alerts of sources muted in the config are only logged
+    pub fn raise_alert<S: Into<String>, T: Into<String>>(
+        &mut self,
+        severity: AlertSeverity,
+        source: S,
+        text: T,
+        link: Option<AlertLink>,
+    ) {
+        self.last_alert_id += 1;
+        let alert = Alert {
+            id: self.last_alert_id,
+            time: Local::now(),
+            severity,
+            source: source.into(),
+            // alert texts often quote kernel messages
+            text: sanitize_line(&text.into()),
+            #[cfg(feature = "dmesg")]
+            link,
+            acknowledged: false,
         };
-        NodeStatus {
-            server: node_status.server.clone(),
-            app_summary: node_status.app_instance_summary.unwrap_or_default(),
-            onboarding_status,
+        // only kernel messages can be linked to
+        #[cfg(not(feature = "dmesg"))]
+        let _ = link;
+        info!(
+            "Alert [{}] {}: {}",
+            alert.severity, alert.source, alert.text
+        );
+        if self.alerts_config.muted_sources.contains(&alert.source) {
+            return;
+        }
+        self.alerts.push(alert);
+        if self.alerts.len() > self.limits.alerts {
+            self.alerts.remove(0);
         }
     }
-}
 
-impl MonitorModel {
+    /// Add a record to the audit list, the oldest ones are dropped
+    pub fn push_audit(&mut self, mut record: AuditRecord) {
+        self.last_audit_id += 1;
+        record.id = self.last_audit_id;
+        self.audit.push(record);
+        if self.audit.len() > self.limits.audit {
+            self.audit.remove(0);
+        }
+    }
+
+    /// Alerts the user has not acknowledged yet
+    pub fn new_alerts(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter().filter(|alert| !alert.acknowledged)
+    }
+
+    /// Acknowledge one alert or all of them if `id` is None
+    pub fn acknowledge_alerts(&mut self, id: Option<u64>) {
+        for alert in self.alerts.iter_mut() {
+            if id.is_none_or(|id| id == alert.id) {
+                alert.acknowledged = true;
+            }
+        }
+    }
+
+    /// Remove one alert or all of them if `id` is None
+    pub fn dismiss_alerts(&mut self, id: Option<u64>) {
+        self.alerts
+            .retain(|alert| id.is_some_and(|id| id != alert.id));
+    }
+
     fn get_network_settings(
         &self,
         network_status: &DeviceNetworkStatus,
@@ -166,24 +448,204 @@ impl MonitorModel {
         let ports = network_status.ports.as_ref()?;
         Some(ports.iter().map(|p| p.into()).collect())
     }
+    /// Update the state from an IPC message. Messages that are not about the
+    /// device state are returned to the caller
+    pub fn apply_ipc_message(&mut self, msg: IpcMessage) -> Option<IpcMessage> {
+        self.state_messages.record(&msg);
+        match msg {
+            IpcMessage::DPCList(cfg) => {
+                debug!("Got DPC list");
+                self.set_dpc_list(cfg);
+            }
+            IpcMessage::NetworkStatus(cfg) => {
+                debug!("Got Network status");
+                self.update_network_status(cfg);
+            }
+            IpcMessage::AppStatus(app) => {
+                debug!("Got AppStatus");
+                self.update_app_status(app);
+            }
+
+            IpcMessage::DownloaderStatus(dnl) => {
+                debug!("Got DownloaderStatus");
+                self.update_downloader_status(dnl);
+            }
+
+            // this event is guaranteed to be sent before periodic events
+            IpcMessage::AppSummary(summary) => {
+                debug!("Got AppSummary");
+                self.update_app_summary(summary);
+            }
+
+            // this event is guaranteed to be sent before periodic events
+            IpcMessage::NodeStatus(node_status) => {
+                debug!("Got NodeStatus");
+                self.update_node_status(node_status);
+            }
+
+            IpcMessage::OnboardingStatus(o_status) => {
+                debug!("Got OnboardingStatus");
+                self.update_onboarding_status(o_status);
+            }
+
+            IpcMessage::VaultStatus(status) => {
+                debug!("Got VaultStatus");
+                self.update_vault_status(status);
+            }
+
+            IpcMessage::LedBlinkCounter(led) => {
+                debug!("Got LedBlinkCounter");
+                self.update_led_blink_counter(led);
+            }
+
+            // this event is guaranteed to be sent before periodic events
+            IpcMessage::AppsList(app_list) => {
+                debug!("Got AppsList");
+                self.update_app_list(app_list);
+            }
+
+            IpcMessage::ZedAgentStatus(status) => {
+                debug!("Got ZedAgentStatus");
+                self.update_zed_agent_status(status);
+            }
+
+            IpcMessage::MonitorPolicy(policy) => {
+                info!("Got MonitorPolicy: {:?}", policy);
+                self.update_policy(policy);
+            }
+
+            IpcMessage::ZfsPoolStatus(status) => {
+                debug!("Got ZfsPoolStatus");
+                let before = self.zfs.pools.clone();
+                self.zfs.update(status);
+                self.check_zfs_pools(&before);
+            }
+
+            IpcMessage::Lenient {
+                message_type,
+                message,
+                unknown,
+            } => {
+                self.unknown_values.insert(message_type, unknown);
+                return self.apply_ipc_message(*message);
+            }
+
+            IpcMessage::AppLogs(chunk) => {
+                // chunks of a stream we have stopped may still be on the way
+                match self.app_logs.as_mut() {
+                    Some(logs) if logs.uuid == chunk.app_uuid => logs.push(chunk),
+                    _ => debug!("Dropping logs of {}", chunk.app_uuid),
+                }
+            }
+
+            IpcMessage::Version(version) => {
+                debug!("Got Version");
+                self.set_protocol_version(version);
+            }
+
+            IpcMessage::Unsupported {
+                message_type,
+                schema,
+                error,
+            } => {
+                self.add_unsupported_message(message_type, schema, error);
+            }
+
+            msg => return Some(msg),
+        }
+        None
+    }
+
     pub fn update_app_status(&mut self, state: AppInstanceStatus) {
-        let app_guid = &state.uuid_and_version.uuid;
-        self.apps
-            .entry(*app_guid)
-            .and_modify(|e| *e = AppInstance::from(state.clone()))
-            .or_insert(AppInstance::from(state));
+        let mut app = AppInstance::from(state);
+        if let Some(old) = self.apps.get(&app.uuid) {
+            app.restarts.continue_from(&old.restarts);
+        }
+        let changes = diff_items(self.apps.get(&app.uuid), Some(&app), app_key, APP_FIELDS);
+        self.update_volume_verifications(&app);
+        self.apps.insert(app.uuid, app);
+        self.handle_app_changes(changes);
     }
 
     pub fn update_app_list(&mut self, apps_list: AppsList) {
-        self.apps = HashMap::from(apps_list);
+        let mut apps = HashMap::from(apps_list);
+        for (uuid, app) in apps.iter_mut() {
+            if let Some(old) = self.apps.get(uuid) {
+                app.restarts.continue_from(&old.restarts);
+            }
+        }
+        let changes = diff_items(self.apps.values(), apps.values(), app_key, APP_FIELDS);
+        for app in apps.values() {
+            self.update_volume_verifications(app);
+        }
+        self.apps = apps;
+        self.handle_app_changes(changes);
+    }
+
+    fn update_volume_verifications(&mut self, app: &AppInstance) {
+        let now = Instant::now();
+        for volume in app.status.volume_ref_status_list.iter() {
+            if let Some(mismatch) = self.verifications.update_volume(volume, now) {
+                self.alert_sha_mismatch(&volume_name(volume), mismatch);
+            }
+        }
+    }
+
+    fn alert_sha_mismatch(&mut self, image: &str, mismatch: ShaMismatch) {
+        self.raise_alert(
+            AlertSeverity::Critical,
+            "verifier",
+            format!(
+                "{} failed verification: expected SHA-256 {}, computed {}",
+                image, mismatch.expected, mismatch.computed
+            ),
+            None,
+        );
+    }
+
+    fn handle_app_changes(&mut self, changes: Vec<Change>) {
+        for change in changes {
+            info!("App change: {}", change);
+            if let Change::Modified { field, new, .. } = &change {
+                if new.starts_with(APP_ERROR_PREFIX) || (*field == CRASH_LOOP_FIELD && new == "yes")
+                {
+                    self.raise_alert(AlertSeverity::Warning, "apps", change.to_string(), None);
+                }
+            }
+        }
+    }
+
+    fn handle_network_changes(&mut self, changes: Vec<Change>) {
+        for change in changes {
+            info!("Network change: {}", change);
+            if let Change::Modified {
+                field: "link", new, ..
+            } = &change
+            {
+                if new == "down" {
+                    self.raise_alert(AlertSeverity::Warning, "network", change.to_string(), None);
+                }
+            }
+        }
     }
 
     pub fn update_downloader_status(&mut self, status: DownloaderStatus) {
+        if let Some(mismatch) = self.verifications.update_download(&status, Instant::now()) {
+            self.alert_sha_mismatch(&status.name, mismatch);
+        }
         self.downloader = Some(status);
+        self.downloader_seen = Some(Instant::now());
     }
 
     pub fn update_node_status(&mut self, status: EveNodeStatus) {
-        self.node_status = NodeStatus::from(status);
+        self.node_status.server = status.server;
+        self.node_status.app_summary = status.app_instance_summary.unwrap_or_default();
+        self.node_status
+            .onboarding_status
+            .apply(OnboardingEvent::NodeStatus {
+                onboarded: status.onboarded,
+                node_uuid: status.node_uuid,
+            });
     }
 
     pub fn update_app_summary(&mut self, app_summary: AppInstanceSummary) {
@@ -191,22 +653,281 @@ impl MonitorModel {
     }
 
     pub fn update_network_status(&mut self, net_status: DeviceNetworkStatus) {
-        self.network = self.get_network_settings(&net_status).unwrap_or_default();
+        let mut network = self.get_network_settings(&net_status).unwrap_or_default();
+        for iface in network.iter_mut() {
+            iface.resolver = ResolvConf::load_for_port(&iface.name);
+        }
+        // keep the order stable, EVE may list the ports in any order
+        network.sort_by(|a, b| a.name.cmp(&b.name));
+        let changes = diff_network(&self.network, &network);
+        self.network = network;
         self.dpc_key = Some(net_status.dpc_key);
+        self.handle_network_changes(changes);
+    }
+
+    // Apply a kernel link/address change to a port reported by EVE. The next
+    // DeviceNetworkStatus from EVE replaces the port data, so any difference is
+    // reconciled as soon as EVE catches up
+    pub fn apply_netlink_event(&mut self, event: NetlinkEvent) {
+        let name = match &event {
+            NetlinkEvent::Link { name, .. }
+            | NetlinkEvent::AddressAdded { name, .. }
+            | NetlinkEvent::AddressRemoved { name, .. } => name,
+        };
+        let Some(iface) = self.network.iter_mut().find(|iface| &iface.name == name) else {
+            return;
+        };
+        let old = iface.clone();
+        // a new lease usually comes with new resolv.conf
+        iface.resolver = ResolvConf::load_for_port(&iface.name);
+        match event {
+            NetlinkEvent::Link { up, .. } => iface.up = up,
+            NetlinkEvent::AddressAdded { addr, .. } => match addr {
+                IpAddr::V4(addr) => {
+                    let addrs = iface.ipv4.get_or_insert_with(Vec::new);
+                    if !addrs.contains(&addr) {
+                        addrs.push(addr);
+                    }
+                }
+                // EVE doesn't report link-local addresses either
+                IpAddr::V6(addr) if !addr.is_link_local() => {
+                    let addrs = iface.ipv6.get_or_insert_with(Vec::new);
+                    if !addrs.contains(&addr) {
+                        addrs.push(addr);
+                    }
+                }
+                IpAddr::V6(_) => {}
+            },
+            NetlinkEvent::AddressRemoved { addr, .. } => match addr {
+                IpAddr::V4(addr) => {
+                    if let Some(addrs) = iface.ipv4.as_mut() {
+                        addrs.retain(|a| *a != addr);
+                    }
+                }
+                IpAddr::V6(addr) => {
+                    if let Some(addrs) = iface.ipv6.as_mut() {
+                        addrs.retain(|a| *a != addr);
+                    }
+                }
+            },
+        }
+        let changes = diff_network(&[old], std::slice::from_ref(iface));
+        if !changes.is_empty() {
+            iface.locally_observed = true;
+        }
+        self.handle_network_changes(changes);
+    }
+
+    pub fn update_led_blink_counter(&mut self, led: LedBlinkCounter) {
+        self.node_status
+            .onboarding_status
+            .apply(OnboardingEvent::LedBlink(led.blink_counter));
     }
 
     pub fn update_vault_status(&mut self, vault_status: EveVaultStatus) {
+        let was_locked = matches!(self.vault_status, VaultStatus::Locked(_, _));
         self.vault_status = VaultStatus::from(vault_status);
+        if let VaultStatus::Locked(err, _) = &self.vault_status {
+            if !was_locked {
+                let text = format!("The vault is locked: {}", err.error);
+                self.raise_alert(AlertSeverity::Critical, "vault", text, None);
+            }
+        }
     }
 
     pub fn update_onboarding_status(&mut self, status: EveOnboardingStatus) {
-        self.node_status.onboarding_status = OnboardingStatus::Onboarded(status.device_uuid);
+        self.node_status
+            .onboarding_status
+            .apply(OnboardingEvent::Onboarded(status.device_uuid));
+        // the device certificate may have been replaced during onboarding
+        self.identity = DeviceIdentity::load();
+    }
+
+    /// Apps sorted by name, the map has no stable order
+    pub fn sorted_apps(&self) -> Vec<&AppInstance> {
+        let mut apps: Vec<&AppInstance> = self.apps.values().collect();
+        apps.sort_by(|a, b| (&a.name, a.uuid).cmp(&(&b.name, b.uuid)));
+        apps
     }
 
-    pub fn set_dpc_list(&mut self, dpc_list: DevicePortConfigList) {
+    /// Drop entries EVE stopped reporting. EVE tells us about new and changed
+    /// apps and downloads but not always about removed ones
+    pub fn evict_stale(&mut self, now: Instant) {
+        if self.snapshot.as_ref().is_some_and(|s| s.is_expired(now)) {
+            info!("Dropping expired snapshot");
+            self.snapshot = None;
+        }
+
+        let downloading = self
+            .downloader
+            .as_ref()
+            .is_some_and(|d| d.state == SwState::Downloading);
+        if !downloading
+            && self
+                .downloader_seen
+                .is_some_and(|seen| now.duration_since(seen) > DOWNLOAD_STALE_AFTER)
+        {
+            debug!("Dropping stale downloader status");
+            self.downloader = None;
+            self.downloader_seen = None;
+        }
+
+        // apps that are quiet are not necessarily gone, only evict when the
+        // summary says there are fewer apps than we have
+        let summary = &self.node_status.app_summary;
+        let total: usize = [
+            summary.total_starting,
+            summary.total_running,
+            summary.total_stopping,
+            summary.total_error,
+        ]
+        .iter()
+        .map(|count| *count as usize)
+        .sum();
+        if self.apps.len() <= total {
+            return;
+        }
+        let stale: Vec<Uuid> = self
+            .apps
+            .values()
+            .filter(|app| now.duration_since(app.last_seen) > APP_STALE_AFTER)
+            .map(|app| app.uuid)
+            .collect();
+        let changes = stale
+            .iter()
+            .filter_map(|uuid| self.apps.remove(uuid))
+            .map(|app| Change::Removed(app_key(&app)))
+            .collect();
+        self.handle_app_changes(changes);
+    }
+
+    pub fn refresh_config_partition(&mut self) {
+        if !self.config_partition.refresh() {
+            return;
+        }
+        let state = self.config_partition.state.clone();
+        info!("/config partition: {:?}", state);
+        if state == ConfigPartitionState::NotMounted {
+            self.raise_alert(
+                AlertSeverity::Warning,
+                "config",
+                "/config partition is not mounted".to_string(),
+                None,
+            );
+        }
+    }
+
+    pub fn update_sensors(&mut self, readings: Vec<SensorReading>) {
+        // alert once when a sensor gets critical, not on every sample
+        let critical: Vec<String> = readings
+            .iter()
+            .filter(|r| sensor_level(r, &self.sensor_thresholds) == SensorLevel::Critical)
+            .filter(|r| {
+                self.sensors
+                    .iter()
+                    .find(|old| old.key() == r.key())
+                    .is_none_or(|old| {
+                        sensor_level(old, &self.sensor_thresholds) != SensorLevel::Critical
+                    })
+            })
+            .map(|r| format!("{} is at {:.1}°C", r.key(), r.celsius))
+            .collect();
+        for text in critical {
+            self.raise_alert(AlertSeverity::Critical, "sensors", text, None);
+        }
+        self.sensors = readings;
+    }
+
+    /// The hottest sensor over its critical threshold
+    pub fn critical_sensor(&self) -> Option<&SensorReading> {
+        self.sensors
+            .iter()
+            .filter(|r| sensor_level(r, &self.sensor_thresholds) == SensorLevel::Critical)
+            .max_by(|a, b| a.celsius.total_cmp(&b.celsius))
+    }
+
+    pub fn update_zfs_pools(&mut self, pools: Vec<ZfsPool>) {
+        let before = self.zfs.pools.clone();
+        if self.zfs.update_from_zpool(pools) {
+            self.check_zfs_pools(&before);
+        }
+    }
+
+    // alert once when a pool goes bad, not on every update
+    fn check_zfs_pools(&mut self, before: &[ZfsPool]) {
+        let failed: Vec<String> = self
+            .zfs
+            .pools
+            .iter()
+            .filter(|pool| !pool.is_healthy())
+            .filter(|pool| {
+                before
+                    .iter()
+                    .find(|p| p.name == pool.name)
+                    .is_none_or(|p| p.is_healthy())
+            })
+            .map(|pool| {
+                let vdevs: Vec<String> = pool
+                    .unhealthy_vdevs()
+                    .map(|v| format!("{} {}", v.name, v.state))
+                    .collect();
+                if vdevs.is_empty() {
+                    format!("ZFS pool {} is {}", pool.name, pool.state)
+                } else {
+                    format!(
+                        "ZFS pool {} is {}: {}",
+                        pool.name,
+                        pool.state,
+                        vdevs.join(", ")
+                    )
+                }
+            })
+            .collect();
+        for text in failed {
+            self.raise_alert(AlertSeverity::Critical, "zfs", text, None);
+        }
+    }
+
+    pub fn set_dpc_list(&mut self, mut dpc_list: DevicePortConfigList) {
+        self.blobs.store_all(dpc_list.blobs_mut());
+        self.blobs.retain(dpc_list.blobs_mut());
         self.dpc_list = Some(dpc_list);
     }
 
+    /// Proxy CA certificates configured for the port in the current DPC
+    pub fn proxy_cert_refs(&self, iface: &str) -> Vec<BlobRef> {
+        self.get_current_dpc()
+            .and_then(|dpc| dpc.get_port_by_name(iface))
+            .and_then(|port| port.proxy_config.proxy_cert_pem.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|cert| cert.blob_ref().cloned())
+            .collect()
+    }
+
+    /// Current DPC with the large payloads loaded e.g. to send it back to EVE
+    pub fn get_current_dpc_with_blobs(&self) -> Option<DevicePortConfig> {
+        self.with_blobs(self.get_current_dpc()?)
+    }
+
+    /// An entry of the DPC history with the large payloads loaded
+    pub fn get_dpc_with_blobs(
+        &self,
+        key: &str,
+        time_priority: &DateTime<Utc>,
+    ) -> Option<DevicePortConfig> {
+        self.with_blobs(self.get_dpc_list()?.get_dpc(key, time_priority)?)
+    }
+
+    fn with_blobs(&self, dpc: &DevicePortConfig) -> Option<DevicePortConfig> {
+        let mut dpc = dpc.clone();
+        if !self.blobs.load_all(dpc.blobs_mut()) {
+            warn!("Some large fields of DPC {} are not available", dpc.key);
+            return None;
+        }
+        Some(dpc)
+    }
+
     pub fn get_dpc_list(&self) -> Option<&DevicePortConfigList> {
         self.dpc_list.as_ref()
     }
@@ -216,23 +937,245 @@ impl MonitorModel {
         self.get_dpc_list()?.get_dpc_by_key(&key)
     }
 
+    /// App counts of the last summary with the number of apps EVE runs
+    pub fn app_counts(&self) -> AppCounts {
+        AppCounts::new(&self.node_status.app_summary, self.apps.len())
+    }
+
+    /// How well the device can reach the controller and why it is not 100
+    pub fn connectivity(&self) -> ConnectivityScore {
+        connectivity_score(
+            &PortSummary::new(&self.network),
+            self.get_current_dpc()
+                .map(|dpc| (&dpc.state, &dpc.test_results)),
+            self.z_status.as_ref().map(|z| &z.config_get_status),
+        )
+    }
+
+    /// Add the current controller reachability to its history, once per tick
+    pub fn record_reachability(&mut self, now: Instant) {
+        let state = reachability(
+            &PortSummary::new(&self.network),
+            self.get_current_dpc().map(|dpc| &dpc.test_results),
+            self.z_status.as_ref().map(|z| &z.config_get_status),
+        );
+        self.reachability.record(state, now);
+    }
+
+    /// True while the console is about to be or has been given to a guest
+    pub fn is_console_handed_off(&self) -> bool {
+        self.console_handoff
+            .as_ref()
+            .is_some_and(|h| h.state != ConsoleHandoffState::None)
+    }
+
+    pub fn set_protocol_version(&mut self, version: ProtocolVersion) {
+        info!("EVE protocol version {}", version.version);
+        for mismatch in version.mismatches() {
+            let supported = match mismatch.supported {
+                Some(supported) => format!("schema {}", supported),
+                None => "no schema".to_string(),
+            };
+            let text = format!(
+                "EVE sends {} schema {}, the monitor supports {}",
+                mismatch.message_type, mismatch.sent, supported
+            );
+            self.raise_alert(AlertSeverity::Warning, "ipc", text, None);
+        }
+        self.protocol = ProtocolState::Negotiated(version);
+    }
+
+    /// Whether EVE sent enum values we cannot send back unchanged
+    pub fn has_unknown_enum_values(&self, message_type: &str) -> bool {
+        self.unknown_values
+            .get(message_type)
+            .is_some_and(|values| values.iter().any(|v| v.kind == UnknownKind::EnumValue))
+    }
+
+    fn add_unsupported_message(
+        &mut self,
+        message_type: String,
+        schema: Option<u32>,
+        error: String,
+    ) {
+        if let Some(unsupported) = self.unsupported_messages.get_mut(&message_type) {
+            unsupported.count += 1;
+            unsupported.schema = schema;
+            unsupported.error = error;
+            return;
+        }
+        let text = format!(
+            "{} from EVE is not supported by this monitor and is not shown",
+            message_type
+        );
+        self.raise_alert(AlertSeverity::Warning, "ipc", text, None);
+        self.unsupported_messages.insert(
+            message_type,
+            UnsupportedMessage {
+                schema,
+                error,
+                count: 1,
+            },
+        );
+    }
+
+    fn update_policy(&mut self, policy: MonitorPolicy) {
+        let was_read_only = self.policy.as_ref().is_some_and(|p| p.read_only);
+        if policy.read_only != was_read_only {
+            let text = if policy.read_only {
+                "The controller made the monitor read-only"
+            } else {
+                "The controller allows changes from the monitor again"
+            };
+            self.raise_alert(AlertSeverity::Info, "policy", text, None);
+        }
+        self.policy = Some(policy);
+    }
+
+    /// Why actions that change the device are refused, None if they are allowed
+    pub fn read_only_reason(&self) -> Option<String> {
+        match &self.policy {
+            Some(policy) if policy.read_only && !policy.reason.is_empty() => Some(format!(
+                "set by the controller: {}",
+                sanitize_line(&policy.reason)
+            )),
+            Some(policy) if policy.read_only => Some("set by the controller".to_string()),
+            _ if self.read_only_config => Some("set in the config file".to_string()),
+            _ if self.ipc_state == IpcConnectionState::Replay => {
+                Some("replaying recorded state, there is no EVE to apply changes".to_string())
+            }
+            _ => None,
+        }
+    }
+
     pub fn update_zed_agent_status(&mut self, status: ZedAgentStatus) {
+        if status.maintenance_mode != self.maintenance_mode {
+            info!("Maintenance mode: {}", status.maintenance_mode);
+            self.maintenance_mode = status.maintenance_mode;
+        }
+        self.attestation
+            .update(status.attest_state, status.attest_error.clone());
         self.z_status = Some(status);
     }
 }
 
+// how long to keep entries EVE stopped reporting
+const DOWNLOAD_STALE_AFTER: Duration = Duration::from_secs(60);
+const APP_STALE_AFTER: Duration = Duration::from_secs(120);
+
+// summaries of app states with errors start with it so they can be alerted on
+const APP_ERROR_PREFIX: &str = "error: ";
+
+fn app_key(app: &AppInstance) -> String {
+    app.name.clone()
+}
+
+const CRASH_LOOP_FIELD: &str = "crash loop";
+
+const APP_FIELDS: &[Field<AppInstance>] = &[
+    ("state", |app| match &app.state {
+        AppInstanceState::Normal(st) => st.to_string(),
+        AppInstanceState::Error(st, err) => format!("{}{} ({})", APP_ERROR_PREFIX, st, err),
+    }),
+    ("restarts", |app| app.restarts.count().to_string()),
+    (CRASH_LOOP_FIELD, |app| {
+        if app.restarts.is_crash_looping(Utc::now()) {
+            "yes"
+        } else {
+            "no"
+        }
+        .to_string()
+    }),
+];
+
+/// State and restart changes between two snapshots of the app list
+pub fn diff_apps(old: &[AppInstance], new: &[AppInstance]) -> Vec<Change> {
+    diff_items(old, new, app_key, APP_FIELDS)
+}
+
+fn iface_key(iface: &NetworkInterfaceStatus) -> String {
+    iface.name.clone()
+}
+
+fn join_addresses<T: ToString>(list: &Option<Vec<T>>) -> String {
+    list.iter()
+        .flatten()
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+const NETWORK_FIELDS: &[Field<NetworkInterfaceStatus>] = &[
+    ("link", |iface| {
+        if iface.up { "up" } else { "down" }.to_string()
+    }),
+    ("ipv4", |iface| join_addresses(&iface.ipv4)),
+    ("ipv6", |iface| join_addresses(&iface.ipv6)),
+    ("dns", |iface| join_addresses(&iface.dns)),
+];
+
+/// Link and address changes between two snapshots of the interface list
+pub fn diff_network(old: &[NetworkInterfaceStatus], new: &[NetworkInterfaceStatus]) -> Vec<Change> {
+    diff_items(old, new, iface_key, NETWORK_FIELDS)
+}
+
 impl Default for MonitorModel {
     fn default() -> Self {
         MonitorModel {
-            dmesg: Vec::with_capacity(1000),
+            limits: HistoryLimits::default(),
+            #[cfg(feature = "dmesg")]
+            dmesg: DmesgBuffer::default(),
             network: Vec::new(),
+            traffic: TrafficHistory::default(),
+            resources: ResourceHistory::default(),
+            reachability: ReachabilityHistory::new(HistoryLimits::default().reachability),
             downloader: None,
+            downloader_seen: None,
+            verifications: Verifications::default(),
             node_status: NodeStatus::default(),
             apps: HashMap::new(),
             vault_status: VaultStatus::Unknown,
             dpc_list: None,
+            blobs: BlobStore::new(
+                Application::monitor_data_dir("blobs"),
+                HistoryLimits::default().blobs,
+            ),
             dpc_key: None,
             z_status: None,
+            maintenance_mode: false,
+            attestation: AttestationHistory::default(),
+            ipc_state: IpcConnectionState::default(),
+            protocol: ProtocolState::default(),
+            unsupported_messages: BTreeMap::new(),
+            unknown_values: BTreeMap::new(),
+            ipc_message_stats: BTreeMap::new(),
+            pending_requests: 0,
+            action_stats: ActionStats::default(),
+            collectors: Vec::new(),
+            settings: RuntimeSettings::default(),
+            theme: Theme::default(),
+            app_thresholds: AppThresholds::default(),
+            sensor_thresholds: SensorThresholds::default(),
+            sensors: Vec::new(),
+            identity: DeviceIdentity::default(),
+            config_partition: ConfigPartition::default(),
+            zfs: ZfsPools::default(),
+            support_bundle: None,
+            alerts: Vec::new(),
+            alerts_config: AlertsConfig::default(),
+            last_alert_id: 0,
+            read_only_config: false,
+            policy: None,
+            lock_screen: None,
+            audit: Vec::new(),
+            last_audit_id: 0,
+            console_handoff: None,
+            app_logs: None,
+            attached_console: None,
+            snapshot: None,
+            diagnostics: None,
+            bundle: None,
+            state_messages: StateMessages::default(),
         }
     }
 }
@@ -0,0 +1,174 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+};
+
+use log::Level;
+
+use super::{ring_buffer::RingBuffer, sanitize::sanitize_line};
+
+// the file rotates at 1 MiB, the first read of a session takes it all
+const MAX_READ: u64 = 1024 * 1024;
+
+/// A record of the monitor's own log. Lines without the header of a record
+/// are continuation lines of the record before them
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub time: String,
+    pub level: Option<Level>,
+    // module and message
+    pub message: String,
+}
+
+// "[2024-01-31 12:00:00.123] [INFO] ui/ui: message" as written by log2
+fn parse_line(line: &str) -> Option<LogRecord> {
+    let rest = line.strip_prefix('[')?;
+    let (time, rest) = rest.split_once("] [")?;
+    let (level, message) = rest.split_once("] ")?;
+    Some(LogRecord {
+        time: time.to_string(),
+        level: Some(level.parse().ok()?),
+        message: sanitize_line(message),
+    })
+}
+
+/// The log file of the current session, None if logging is not initialized
+pub fn session_log_file() -> Option<PathBuf> {
+    let dir = std::env::var("EVE_MONITOR_LOG_DIR").ok()?;
+    Some(PathBuf::from(dir).join("monitor.log"))
+}
+
+/// Follows the monitor's log file like `tail -F`. When log2 rotates the file
+/// the new one is read from the start
+#[derive(Debug)]
+pub struct MonitorLogTail {
+    path: PathBuf,
+    inode: u64,
+    offset: u64,
+    // the end of the last read if it was not a complete line
+    partial: Vec<u8>,
+}
+
+impl MonitorLogTail {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            inode: 0,
+            offset: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Append the records written since the last call to `buffer`. Returns
+    /// true if there were any
+    pub fn read(&mut self, buffer: &mut RingBuffer<LogRecord>) -> std::io::Result<bool> {
+        let mut file = File::open(&self.path)?;
+        let metadata = file.metadata()?;
+        if metadata.ino() != self.inode || metadata.len() < self.offset {
+            self.inode = metadata.ino();
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if metadata.len() == self.offset {
+            return Ok(false);
+        }
+        // the head of a large backlog is dropped by the buffer anyway
+        let start = self.offset.max(metadata.len().saturating_sub(MAX_READ));
+        file.seek(SeekFrom::Start(start))?;
+        let mut data = std::mem::take(&mut self.partial);
+        let read = file.take(MAX_READ).read_to_end(&mut data)?;
+        self.offset = start + read as u64;
+
+        let complete = match data.iter().rposition(|b| *b == b'\n') {
+            Some(end) => {
+                self.partial = data.split_off(end + 1);
+                data
+            }
+            None => {
+                self.partial = data;
+                return Ok(false);
+            }
+        };
+        push_lines(&String::from_utf8_lossy(&complete), buffer);
+        Ok(true)
+    }
+}
+
+fn push_lines(text: &str, buffer: &mut RingBuffer<LogRecord>) {
+    for line in text.lines() {
+        match (parse_line(line), buffer.last_mut()) {
+            (Some(record), _) => {
+                buffer.push(record);
+            }
+            (None, Some(last)) => {
+                last.message.push('\n');
+                last.message.push_str(&sanitize_line(line));
+            }
+            // the head of a record cut off by a partial read
+            (None, None) => {
+                buffer.push(LogRecord {
+                    time: String::new(),
+                    level: None,
+                    message: sanitize_line(line),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_follows_rotation() {
+        let dir = std::env::temp_dir().join(format!("monitor-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("monitor.log");
+        std::fs::write(
+            &path,
+            "[2024-01-31 12:00:00.123] [INFO] main: started\n\
+             [2024-01-31 12:00:01.000] [ERROR] ipc/ipc: failed\n  caused by: EOF\n\
+             [2024-01-31 12:00:02.000] [WARN] ui/ui: no newline yet",
+        )
+        .unwrap();
+
+        let mut buffer = RingBuffer::new(10);
+        let mut tail = MonitorLogTail::new(path.clone());
+        assert!(tail.read(&mut buffer).unwrap());
+        assert!(!tail.read(&mut buffer).unwrap());
+        let records: Vec<LogRecord> = buffer.iter().cloned().collect();
+        assert_eq!(
+            records,
+            vec![
+                LogRecord {
+                    time: "2024-01-31 12:00:00.123".to_string(),
+                    level: Some(Level::Info),
+                    message: "main: started".to_string(),
+                },
+                LogRecord {
+                    time: "2024-01-31 12:00:01.000".to_string(),
+                    level: Some(Level::Error),
+                    message: "ipc/ipc: failed\n  caused by: EOF".to_string(),
+                },
+            ]
+        );
+
+        // rotated, the new file is read from its start
+        std::fs::rename(&path, dir.join("monitor.1.log")).unwrap();
+        std::fs::write(&path, "[2024-01-31 12:00:03.000] [DEBUG] main: rotated\n").unwrap();
+        assert!(tail.read(&mut buffer).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(
+            buffer.iter().last().map(|r| r.message.as_str()),
+            Some("main: rotated")
+        );
+    }
+}
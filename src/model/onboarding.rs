@@ -0,0 +1,212 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local};
+use log::{info, warn};
+use uuid::Uuid;
+
+use crate::ipc::eve_types::LedBlinkCount;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OnboardingState {
+    #[default]
+    Unknown,
+    Onboarding,
+    Onboarded(Uuid),
+    Error(String),
+}
+
+/// What EVE tells us about onboarding. Different messages carry different parts
+/// of the picture
+#[derive(Debug, Clone, PartialEq)]
+pub enum OnboardingEvent {
+    NodeStatus {
+        onboarded: bool,
+        node_uuid: Option<Uuid>,
+    },
+    Onboarded(Uuid),
+    LedBlink(LedBlinkCount),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OnboardingStatus {
+    state: OnboardingState,
+    // time of the last transition. None while the state is unknown
+    since: Option<DateTime<Local>>,
+}
+
+// LED blink codes that stop onboarding until the configuration is fixed
+fn led_error(count: &LedBlinkCount) -> Option<&'static str> {
+    match count {
+        LedBlinkCount::LedBlinkOnboardingFailure => Some("Onboarding failed"),
+        LedBlinkCount::LedBlinkRespWithoutTLS => Some("Controller responded without TLS"),
+        LedBlinkCount::LedBlinkRespWithoutOSCP => Some("Controller responded without OCSP"),
+        LedBlinkCount::LedBlinkInvalidControllerCert => Some("Invalid controller certificate"),
+        LedBlinkCount::LedBlinkInvalidAuthContainer => Some("Invalid auth container"),
+        LedBlinkCount::LedBlinkInvalidBootstrapConfig => Some("Invalid bootstrap config"),
+        _ => None,
+    }
+}
+
+impl OnboardingStatus {
+    pub fn state(&self) -> &OnboardingState {
+        &self.state
+    }
+
+    pub fn since(&self) -> Option<DateTime<Local>> {
+        self.since
+    }
+
+    pub fn is_onboarded(&self) -> bool {
+        matches!(self.state, OnboardingState::Onboarded(_))
+    }
+
+    pub fn device_uuid(&self) -> Option<Uuid> {
+        match self.state {
+            OnboardingState::Onboarded(uuid) => Some(uuid),
+            _ => None,
+        }
+    }
+
+    /// The error that keeps the device from onboarding, if any
+    pub fn blocking_error(&self) -> Option<&str> {
+        match &self.state {
+            OnboardingState::Error(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    fn validate(&self, to: &OnboardingState) -> Result<()> {
+        match (&self.state, to) {
+            (_, OnboardingState::Unknown) => bail!("onboarding state cannot become unknown"),
+            // a stale node status must not undo onboarding
+            (OnboardingState::Onboarded(_), OnboardingState::Onboarding) => {
+                bail!("device is already onboarded")
+            }
+            (OnboardingState::Onboarded(old), OnboardingState::Onboarded(new)) if old != new => {
+                bail!("device UUID cannot change from {} to {}", old, new)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Move to the new state. Returns Ok(false) if the state is the same
+    pub fn transition(&mut self, to: OnboardingState) -> Result<bool> {
+        if self.state == to {
+            return Ok(false);
+        }
+        self.validate(&to)?;
+        info!("Onboarding state: {:?} -> {:?}", self.state, to);
+        self.state = to;
+        self.since = Some(Local::now());
+        Ok(true)
+    }
+
+    pub fn apply(&mut self, event: OnboardingEvent) {
+        let to = match &event {
+            OnboardingEvent::NodeStatus {
+                onboarded: true,
+                node_uuid: Some(uuid),
+            }
+            | OnboardingEvent::Onboarded(uuid) => OnboardingState::Onboarded(*uuid),
+            OnboardingEvent::NodeStatus {
+                onboarded: true,
+                node_uuid: None,
+            } => OnboardingState::Error("Node UUID is missing".to_string()),
+            OnboardingEvent::NodeStatus {
+                onboarded: false, ..
+            } => OnboardingState::Onboarding,
+            // blink codes only matter until the device is onboarded
+            OnboardingEvent::LedBlink(_) if self.is_onboarded() => return,
+            OnboardingEvent::LedBlink(count) => match led_error(count) {
+                Some(err) => OnboardingState::Error(err.to_string()),
+                None if *count == LedBlinkCount::LedBlinkConnectingToController => {
+                    OnboardingState::Onboarding
+                }
+                None => return,
+            },
+        };
+        if let Err(e) = self.transition(to) {
+            warn!("Ignoring {:?}: {}", event, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ipc::fixtures::fixtures;
+    use crate::ipc::message::IpcMessage;
+
+    // messages captured from a device in the order they were received
+    fn captured_events() -> Vec<OnboardingEvent> {
+        fixtures!(capture: "unknown")
+            .iter()
+            .filter_map(|fixture| match fixture.ipc_message() {
+                IpcMessage::NodeStatus(status) => Some(OnboardingEvent::NodeStatus {
+                    onboarded: status.onboarded,
+                    node_uuid: status.node_uuid,
+                }),
+                IpcMessage::OnboardingStatus(status) => {
+                    Some(OnboardingEvent::Onboarded(status.device_uuid))
+                }
+                IpcMessage::LedBlinkCounter(led) => {
+                    Some(OnboardingEvent::LedBlink(led.blink_counter))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_captured_sequence() {
+        let events = captured_events();
+        assert!(!events.is_empty());
+        let mut status = OnboardingStatus::default();
+        let mut states = Vec::new();
+        for event in events {
+            status.apply(event);
+            if states.last() != Some(status.state()) {
+                states.push(status.state().clone());
+            }
+        }
+        let uuid = Uuid::parse_str("c9d95dbd-2230-4a10-91c5-d9fc42fbd454").unwrap();
+        assert_eq!(status.state(), &OnboardingState::Onboarded(uuid));
+        assert!(status.is_onboarded());
+        assert!(status.since().is_some());
+        assert_eq!(
+            states,
+            vec![
+                OnboardingState::Onboarding,
+                OnboardingState::Onboarded(uuid)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transitions() {
+        let uuid = Uuid::new_v4();
+        let mut status = OnboardingStatus::default();
+        status.apply(OnboardingEvent::LedBlink(
+            LedBlinkCount::LedBlinkInvalidControllerCert,
+        ));
+        assert_eq!(
+            status.blocking_error(),
+            Some("Invalid controller certificate")
+        );
+        status.apply(OnboardingEvent::Onboarded(uuid));
+        assert_eq!(status.device_uuid(), Some(uuid));
+        // stale node status and LED codes do not undo onboarding
+        status.apply(OnboardingEvent::NodeStatus {
+            onboarded: false,
+            node_uuid: None,
+        });
+        status.apply(OnboardingEvent::LedBlink(
+            LedBlinkCount::LedBlinkOnboardingFailure,
+        ));
+        assert!(status.is_onboarded());
+        assert!(status.transition(OnboardingState::Unknown).is_err());
+        assert!(status
+            .transition(OnboardingState::Onboarded(Uuid::new_v4()))
+            .is_err());
+        assert!(!status.transition(OnboardingState::Onboarded(uuid)).unwrap());
+    }
+}
@@ -0,0 +1,251 @@
+// Whether the controller was reachable over time. Every tick adds a sample,
+// samples are kept per minute so a day of history stays small
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use strum::Display;
+
+use crate::ipc::eve_types::{ConfigGetStatus, TestResults};
+
+use super::connectivity::PortSummary;
+
+pub const SLOT: Duration = Duration::from_secs(60);
+
+// ordered from good to bad, a slot shows the worst state it saw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+pub enum Reachability {
+    Unknown,
+    Up,
+    // the controller answers but a port test failed or EVE retries
+    Degraded,
+    Down,
+}
+
+/// Reachability from the controller status EVE reports, the last test of the
+/// port configuration and the local state of the management ports
+pub fn reachability(
+    ports: &PortSummary,
+    tests: Option<&TestResults>,
+    controller: Option<&ConfigGetStatus>,
+) -> Reachability {
+    // without an address nothing gets out, whatever EVE reported last
+    if ports.mgmt > 0 && ports.with_address == 0 {
+        return Reachability::Down;
+    }
+    let test_failed =
+        tests.is_some_and(|t| t.last_failed.timestamp() > 0 && t.last_failed > t.last_succeeded);
+    match controller {
+        Some(ConfigGetStatus::Success) if test_failed => Reachability::Degraded,
+        Some(ConfigGetStatus::Success) => Reachability::Up,
+        Some(ConfigGetStatus::TemporaryFail) => Reachability::Degraded,
+        Some(ConfigGetStatus::Fail) | Some(ConfigGetStatus::ReadSaved) => Reachability::Down,
+        Some(ConfigGetStatus::Unrecognized) | None if test_failed => Reachability::Down,
+        Some(ConfigGetStatus::Unrecognized) | None => Reachability::Unknown,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReachabilitySlot {
+    pub up: u32,
+    pub degraded: u32,
+    pub down: u32,
+}
+
+impl ReachabilitySlot {
+    pub fn state(&self) -> Reachability {
+        if self.down > 0 {
+            Reachability::Down
+        } else if self.degraded > 0 {
+            Reachability::Degraded
+        } else if self.up > 0 {
+            Reachability::Up
+        } else {
+            Reachability::Unknown
+        }
+    }
+
+    fn add(&mut self, state: Reachability) {
+        match state {
+            Reachability::Up => self.up += 1,
+            Reachability::Degraded => self.degraded += 1,
+            Reachability::Down => self.down += 1,
+            Reachability::Unknown => {}
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReachabilityHistory {
+    // oldest first, the last one is filling
+    slots: VecDeque<ReachabilitySlot>,
+    capacity: usize,
+    slot_start: Option<Instant>,
+    current: Reachability,
+    // when the current state began
+    since: Option<Instant>,
+    // transitions between known states, for telling flapping from hard-down
+    changes: VecDeque<Instant>,
+}
+
+impl ReachabilityHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: VecDeque::new(),
+            capacity: capacity.max(1),
+            slot_start: None,
+            current: Reachability::Unknown,
+            since: None,
+            changes: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, state: Reachability, now: Instant) {
+        let start = *self.slot_start.get_or_insert(now);
+        // ticks may stop while idle, the missed minutes stay unknown
+        let elapsed = (now.saturating_duration_since(start).as_secs() / SLOT.as_secs()) as usize;
+        if self.slots.is_empty() {
+            self.slots.push_back(ReachabilitySlot::default());
+        }
+        for _ in 0..elapsed.min(self.capacity) {
+            self.slots.push_back(ReachabilitySlot::default());
+        }
+        self.slot_start = Some(start + SLOT * elapsed as u32);
+        while self.slots.len() > self.capacity {
+            self.slots.pop_front();
+        }
+        if let Some(slot) = self.slots.back_mut() {
+            slot.add(state);
+        }
+
+        if state != self.current {
+            if self.current != Reachability::Unknown && state != Reachability::Unknown {
+                self.changes.push_back(now);
+            }
+            self.current = state;
+            self.since = Some(now);
+        }
+        let window = self.window();
+        while self
+            .changes
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) > window)
+        {
+            self.changes.pop_front();
+        }
+    }
+
+    pub fn slots(&self) -> impl DoubleEndedIterator<Item = &ReachabilitySlot> + ExactSizeIterator {
+        self.slots.iter()
+    }
+
+    /// How far back the history goes when it is full
+    pub fn window(&self) -> Duration {
+        SLOT * self.capacity as u32
+    }
+
+    pub fn current(&self) -> Reachability {
+        self.current
+    }
+
+    /// How long the current state has lasted
+    pub fn duration(&self, now: Instant) -> Option<Duration> {
+        self.since.map(|since| now.saturating_duration_since(since))
+    }
+
+    /// Changes between known states within the window
+    pub fn changes(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Percent of the known samples the controller was up or degraded
+    pub fn availability(&self) -> Option<f64> {
+        let (reachable, known) = self.slots.iter().fold((0, 0), |(r, k), slot| {
+            (
+                r + slot.up + slot.degraded,
+                k + slot.up + slot.degraded + slot.down,
+            )
+        });
+        (known > 0).then(|| reachable as f64 * 100.0 / known as f64)
+    }
+}
+
+/// "45s", "12m", "3h 05m"
+pub fn short_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn test_reachability() {
+        let ports = PortSummary {
+            mgmt: 1,
+            up: 1,
+            with_address: 1,
+            with_gateway: 1,
+            with_dns: 1,
+        };
+        let ok = TestResults::default();
+        let failed = TestResults {
+            last_failed: Utc::now(),
+            ..Default::default()
+        };
+        let success = Some(&ConfigGetStatus::Success);
+        assert_eq!(reachability(&ports, Some(&ok), success), Reachability::Up);
+        assert_eq!(
+            reachability(&ports, Some(&failed), success),
+            Reachability::Degraded
+        );
+        assert_eq!(reachability(&ports, None, None), Reachability::Unknown);
+        assert_eq!(
+            reachability(&ports, Some(&failed), None),
+            Reachability::Down
+        );
+        let no_address = PortSummary {
+            with_address: 0,
+            ..ports
+        };
+        assert_eq!(
+            reachability(&no_address, Some(&ok), success),
+            Reachability::Down
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let start = Instant::now();
+        let mut history = ReachabilityHistory::new(3);
+        history.record(Reachability::Up, start);
+        history.record(Reachability::Down, start + Duration::from_secs(10));
+        history.record(Reachability::Up, start + Duration::from_secs(70));
+        assert_eq!(history.slots().len(), 2);
+        assert_eq!(history.slots().next().unwrap().state(), Reachability::Down);
+        assert_eq!(history.changes(), 2);
+        assert_eq!(history.availability(), Some(200.0 / 3.0));
+
+        // a gap longer than the window leaves only unknown slots and the new one
+        history.record(Reachability::Up, start + Duration::from_secs(600));
+        assert_eq!(history.slots().len(), 3);
+        assert_eq!(history.availability(), Some(100.0));
+        assert_eq!(history.changes(), 0);
+        assert_eq!(
+            history.duration(start + Duration::from_secs(700)),
+            Some(Duration::from_secs(630))
+        );
+        assert_eq!(
+            short_duration(Duration::from_secs(3 * 3600 + 300)),
+            "3h 05m"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Datelike, TimeDelta, Utc};
+
+// an app that restarted this many times within the window is crash-looping
+const CRASH_LOOP_RESTARTS: usize = 3;
+const CRASH_LOOP_WINDOW: TimeDelta = TimeDelta::minutes(10);
+
+/// Restarts of an app instance. EVE does not count them for us so a restart is
+/// detected when the boot time of the domain moves forward
+#[derive(Debug, Clone, Default)]
+pub struct RestartHistory {
+    boot_time: Option<DateTime<Utc>>,
+    count: u32,
+    // boot times of the restarts that may still be within the window
+    recent: VecDeque<DateTime<Utc>>,
+}
+
+impl RestartHistory {
+    pub fn boot_time(&self) -> Option<DateTime<Utc>> {
+        self.boot_time
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Record the boot time reported by EVE. Returns true if the app restarted
+    pub fn update(&mut self, boot_time: DateTime<Utc>) -> bool {
+        // go sends 0001-01-01T00:00:00Z while the domain has not booted
+        if boot_time.year() <= 1 {
+            return false;
+        }
+        let restarted = self.boot_time.is_some_and(|old| boot_time > old);
+        if restarted {
+            self.count += 1;
+            self.recent.push_back(boot_time);
+            while self
+                .recent
+                .front()
+                .is_some_and(|t| boot_time - *t > CRASH_LOOP_WINDOW)
+            {
+                self.recent.pop_front();
+            }
+        }
+        self.boot_time = Some(boot_time);
+        restarted
+    }
+
+    /// Continue the history of the previous status of the same app
+    pub fn continue_from(&mut self, previous: &RestartHistory) {
+        let boot_time = self.boot_time;
+        *self = previous.clone();
+        if let Some(boot_time) = boot_time {
+            self.update(boot_time);
+        }
+    }
+
+    pub fn is_crash_looping(&self, now: DateTime<Utc>) -> bool {
+        self.recent
+            .iter()
+            .filter(|t| now - **t <= CRASH_LOOP_WINDOW)
+            .count()
+            >= CRASH_LOOP_RESTARTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_loop() {
+        let start = Utc::now();
+        let mut history = RestartHistory::default();
+        assert!(!history.update("0001-01-01T00:00:00Z".parse().unwrap()));
+        assert!(!history.update(start));
+        assert!(!history.update(start));
+        for minute in 1..=3 {
+            let mut next = RestartHistory::default();
+            next.update(start + TimeDelta::minutes(minute));
+            next.continue_from(&history);
+            history = next;
+        }
+        assert_eq!(history.count(), 3);
+        assert!(history.is_crash_looping(start + TimeDelta::minutes(4)));
+        // the loop is over once the restarts get out of the window
+        assert!(!history.is_crash_looping(start + TimeDelta::minutes(12)));
+        assert!(history.update(start + TimeDelta::minutes(30)));
+        assert_eq!(history.count(), 4);
+        assert!(!history.is_crash_looping(start + TimeDelta::minutes(30)));
+    }
+}
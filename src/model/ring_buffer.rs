@@ -0,0 +1,64 @@
+use std::collections::VecDeque;
+
+/// Ring buffer of log entries. The oldest entry is dropped once the capacity
+/// is reached, entries keep their sequence numbers
+#[derive(Debug)]
+pub struct RingBuffer<T> {
+    entries: VecDeque<T>,
+    capacity: usize,
+    // number of entries dropped from the head
+    trimmed: u64,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            trimmed: 0,
+        }
+    }
+
+    /// Returns the sequence number of the entry
+    pub fn push(&mut self, entry: T) -> u64 {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.trimmed += 1;
+        }
+        self.entries.push_back(entry);
+        self.trimmed + self.entries.len() as u64 - 1
+    }
+
+    #[cfg(feature = "dmesg")]
+    /// Index of the entry with the given sequence number
+    pub fn index(&self, seq: u64) -> Option<usize> {
+        let index = seq.checked_sub(self.trimmed)? as usize;
+        (index < self.entries.len()).then_some(index)
+    }
+
+    #[cfg(feature = "dmesg")]
+    pub fn seq(&self, index: usize) -> u64 {
+        self.trimmed + index as u64
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.entries.get(index)
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.entries.back_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[cfg(feature = "dmesg")]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.entries.iter()
+    }
+}
@@ -0,0 +1,108 @@
+// A named copy of the network, apps and vault state to compare the live
+// state against during troubleshooting, e.g. "take a snapshot, plug the
+// cable, compare"
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use strum::Display;
+
+use super::{
+    diff::Change,
+    model::{diff_apps, diff_network, AppInstance, MonitorModel},
+};
+use crate::model::device::network::NetworkInterfaceStatus;
+
+// a forgotten snapshot is not worth comparing against
+pub const SNAPSHOT_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Display)]
+pub enum SnapshotSection {
+    Network,
+    Apps,
+    Vault,
+}
+
+#[derive(Debug)]
+pub struct Snapshot {
+    pub name: String,
+    pub taken: DateTime<Local>,
+    taken_at: Instant,
+    network: Vec<NetworkInterfaceStatus>,
+    apps: Vec<AppInstance>,
+    vault: String,
+}
+
+fn sorted_apps(model: &MonitorModel) -> Vec<AppInstance> {
+    let mut apps: Vec<AppInstance> = model.apps.values().cloned().collect();
+    apps.sort_by(|a, b| a.name.cmp(&b.name));
+    apps
+}
+
+impl Snapshot {
+    pub fn capture(name: &str, model: &MonitorModel, now: Instant) -> Self {
+        Self {
+            name: name.to_string(),
+            taken: Local::now(),
+            taken_at: now,
+            network: model.network.clone(),
+            apps: sorted_apps(model),
+            vault: model.vault_status.to_string(),
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.taken_at) > SNAPSHOT_MAX_AGE
+    }
+
+    /// What changed in `model` since the snapshot was taken
+    pub fn compare(&self, model: &MonitorModel) -> Vec<(SnapshotSection, Change)> {
+        let mut changes: Vec<(SnapshotSection, Change)> = Vec::new();
+        changes.extend(
+            diff_network(&self.network, &model.network)
+                .into_iter()
+                .map(|c| (SnapshotSection::Network, c)),
+        );
+        changes.extend(
+            diff_apps(&self.apps, &sorted_apps(model))
+                .into_iter()
+                .map(|c| (SnapshotSection::Apps, c)),
+        );
+        let vault = model.vault_status.to_string();
+        if vault != self.vault {
+            changes.push((
+                SnapshotSection::Vault,
+                Change::Modified {
+                    item: "vault".to_string(),
+                    field: "status",
+                    old: self.vault.clone(),
+                    new: vault,
+                },
+            ));
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::model::model::VaultStatus;
+
+    #[test]
+    fn test_compare() {
+        let mut model = MonitorModel::default();
+        let now = Instant::now();
+        let snapshot = Snapshot::capture("before", &model, now);
+        assert!(snapshot.compare(&model).is_empty());
+
+        model.vault_status = VaultStatus::Unlocked(true);
+        let changes = snapshot.compare(&model);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, SnapshotSection::Vault);
+        assert_eq!(changes[0].1.to_string(), "vault: status unknown→unlocked");
+
+        assert!(!snapshot.is_expired(now + Duration::from_secs(60)));
+        assert!(snapshot.is_expired(now + SNAPSHOT_MAX_AGE * 2));
+    }
+}
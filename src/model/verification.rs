@@ -0,0 +1,177 @@
+// Images EVE downloads and verifies for volumes. A checksum mismatch is only
+// reported as an error string of the downloader or the volume, so the hashes
+// are parsed out of it to tell the user what was expected
+use std::{collections::BTreeMap, sync::LazyLock, time::Instant};
+
+use regex::Regex;
+
+use crate::ipc::eve_types::{DownloaderStatus, SwState, VolumeRefStatus};
+
+// images of removed apps are not reported as gone, keep the recent ones
+const MAX_IMAGES: usize = 32;
+
+// the verifier reports "computed <sha> configured <sha>", older releases
+// "expected <sha> got <sha>"
+static SHA_MISMATCH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)computed\s+(?P<computed>[0-9a-f]{64})\s+configured\s+(?P<expected>[0-9a-f]{64})|expected\s+(?P<expected2>[0-9a-f]{64})\W+got\s+(?P<computed2>[0-9a-f]{64})",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaMismatch {
+    pub expected: String,
+    pub computed: String,
+}
+
+pub fn volume_name(volume: &VolumeRefStatus) -> String {
+    if volume.display_name.is_empty() {
+        volume.volume_id.to_string()
+    } else {
+        volume.display_name.clone()
+    }
+}
+
+pub fn parse_sha_mismatch(error: &str) -> Option<ShaMismatch> {
+    let captures = SHA_MISMATCH.captures(error)?;
+    let hash = |a: &str, b: &str| {
+        captures
+            .name(a)
+            .or_else(|| captures.name(b))
+            .map(|m| m.as_str().to_lowercase())
+    };
+    Some(ShaMismatch {
+        expected: hash("expected", "expected2")?,
+        computed: hash("computed", "computed2")?,
+    })
+}
+
+#[derive(Debug)]
+pub struct ImageVerification {
+    // state and progress are shown on the Home page only
+    #[cfg(any(debug_assertions, feature = "home-page"))]
+    pub state: SwState,
+    // download progress in percent, volumes do not report it
+    #[cfg(any(debug_assertions, feature = "home-page"))]
+    pub progress: Option<u32>,
+    pub mismatch: Option<ShaMismatch>,
+    pub updated: Instant,
+}
+
+#[cfg(any(debug_assertions, feature = "home-page"))]
+impl ImageVerification {
+    pub fn is_verifying(&self) -> bool {
+        matches!(self.state, SwState::Downloading | SwState::Verifying)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Verifications {
+    // by image or volume name
+    images: BTreeMap<String, ImageVerification>,
+}
+
+impl Verifications {
+    /// Returns the mismatch if it is new for the image, it deserves an alert
+    pub fn update_download(
+        &mut self,
+        status: &DownloaderStatus,
+        now: Instant,
+    ) -> Option<ShaMismatch> {
+        self.update(
+            &status.name,
+            status.state,
+            Some(status.progress),
+            &status.error_and_time.error_description.error,
+            now,
+        )
+    }
+
+    pub fn update_volume(&mut self, volume: &VolumeRefStatus, now: Instant) -> Option<ShaMismatch> {
+        self.update(
+            &volume_name(volume),
+            volume.state,
+            None,
+            &volume.error_and_time_with_source.error_description.error,
+            now,
+        )
+    }
+
+    fn update(
+        &mut self,
+        name: &str,
+        state: SwState,
+        progress: Option<u32>,
+        error: &str,
+        now: Instant,
+    ) -> Option<ShaMismatch> {
+        #[cfg(not(any(debug_assertions, feature = "home-page")))]
+        let _ = (state, progress);
+        let mismatch = parse_sha_mismatch(error);
+        let old = self.images.insert(
+            name.to_string(),
+            ImageVerification {
+                #[cfg(any(debug_assertions, feature = "home-page"))]
+                state,
+                #[cfg(any(debug_assertions, feature = "home-page"))]
+                progress,
+                mismatch: mismatch.clone(),
+                updated: now,
+            },
+        );
+        if self.images.len() > MAX_IMAGES {
+            let oldest = self
+                .images
+                .iter()
+                .min_by_key(|(_, image)| image.updated)
+                .map(|(name, _)| name.clone());
+            if let Some(oldest) = oldest {
+                self.images.remove(&oldest);
+            }
+        }
+        match old {
+            Some(old) if old.mismatch == mismatch => None,
+            _ => mismatch,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ImageVerification> {
+        self.images.get(name)
+    }
+
+    #[cfg(any(debug_assertions, feature = "home-page"))]
+    /// Most recently updated first
+    pub fn recent(&self) -> Vec<(&String, &ImageVerification)> {
+        let mut images: Vec<_> = self.images.iter().collect();
+        images.sort_by_key(|(_, image)| std::cmp::Reverse(image.updated));
+        images
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sha_mismatch() {
+        let expected = "a".repeat(64);
+        let computed = "B".repeat(64);
+        let mismatch = Some(ShaMismatch {
+            expected: expected.clone(),
+            computed: computed.to_lowercase(),
+        });
+        assert_eq!(
+            parse_sha_mismatch(&format!(
+                "verifyObjectSha: computed   {} configured {}",
+                computed, expected
+            )),
+            mismatch
+        );
+        assert_eq!(
+            parse_sha_mismatch(&format!("expected {}, got {}", expected, computed)),
+            mismatch
+        );
+        assert_eq!(parse_sha_mismatch("no space left on device"), None);
+    }
+}
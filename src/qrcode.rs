@@ -0,0 +1,471 @@
+// A minimal QR code encoder: byte mode, error correction level L and versions
+// 1 to 10, up to 271 bytes. Enough for the identifiers shown on the summary page
+use anyhow::{bail, Result};
+
+const MAX_VERSION: usize = 10;
+// level L, by version
+const ECC_CODEWORDS_PER_BLOCK: [usize; MAX_VERSION] = [7, 10, 15, 20, 26, 18, 20, 24, 30, 18];
+const NUM_BLOCKS: [usize; MAX_VERSION] = [1, 1, 1, 1, 1, 2, 2, 2, 2, 4];
+// level L in the format information
+const ECC_FORMAT_BITS: u32 = 0b01;
+
+fn bit(value: u32, index: usize) -> bool {
+    (value >> index) & 1 != 0
+}
+
+// GF(256) with the polynomial x^8 + x^4 + x^3 + x^2 + 1
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ if z & 0x80 != 0 { 0x1d } else { 0 };
+        if (y >> i) & 1 != 0 {
+            z ^= x;
+        }
+    }
+    z
+}
+
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0u8; degree];
+    divisor[degree - 1] = 1;
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_mul(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    divisor
+}
+
+/// Reed-Solomon error correction codewords of `data`
+fn rs_remainder(data: &[u8], degree: usize) -> Vec<u8> {
+    let divisor = rs_divisor(degree);
+    let mut result = vec![0u8; degree];
+    for b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, d) in result.iter_mut().zip(divisor.iter()) {
+            *r ^= gf_mul(*d, factor);
+        }
+    }
+    result
+}
+
+// modules left for data and error correction
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let align = version / 7 + 2;
+        result -= (25 * align - 10) * align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8 - ECC_CODEWORDS_PER_BLOCK[version - 1] * NUM_BLOCKS[version - 1]
+}
+
+fn char_count_bits(version: usize) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+fn alignment_positions(version: usize, size: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let mut positions: Vec<usize> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+/// 15 bits, the error correction level and the mask with their BCH code
+fn format_bits(mask: u8) -> u32 {
+    let data = (ECC_FORMAT_BITS << 3) | mask as u32;
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | rem) ^ 0x5412
+}
+
+/// 18 bits, the version with its BCH code, versions 7 and up only
+fn version_bits(version: usize) -> u32 {
+    let mut rem = version as u32;
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1f25);
+    }
+    ((version as u32) << 12) | rem
+}
+
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, len: usize) {
+        self.0.extend((0..len).rev().map(|i| bit(value, i)));
+    }
+}
+
+// data and error correction codewords, interleaved by block
+fn codewords(data: &[u8], version: usize) -> Vec<u8> {
+    let capacity = data_codewords(version);
+    let mut bits = BitBuffer(Vec::new());
+    // byte mode
+    bits.push(0b0100, 4);
+    bits.push(data.len() as u32, char_count_bits(version));
+    for b in data {
+        bits.push(*b as u32, 8);
+    }
+    let terminator = (capacity * 8 - bits.0.len()).min(4);
+    bits.push(0, terminator);
+    bits.push(0, (8 - bits.0.len() % 8) % 8);
+    let mut bytes: Vec<u8> = bits
+        .0
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0, |b, bit| (b << 1) | *bit as u8))
+        .collect();
+    for pad in [0xec, 0x11].into_iter().cycle() {
+        if bytes.len() >= capacity {
+            break;
+        }
+        bytes.push(pad);
+    }
+
+    let num_blocks = NUM_BLOCKS[version - 1];
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[version - 1];
+    let raw_codewords = raw_data_modules(version) / 8;
+    // the long blocks have one more data codeword
+    let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+    let short_block_len = raw_codewords / num_blocks;
+    let mut blocks: Vec<Vec<u8>> = Vec::new();
+    let mut offset = 0;
+    for i in 0..num_blocks {
+        let len = short_block_len - ecc_len + usize::from(i >= num_short_blocks);
+        let mut block = bytes[offset..offset + len].to_vec();
+        offset += len;
+        let ecc = rs_remainder(&block, ecc_len);
+        // a placeholder keeps the columns of short and long blocks aligned
+        if i < num_short_blocks {
+            block.push(0);
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_block_len {
+        for (j, block) in blocks.iter().enumerate() {
+            if i != short_block_len - ecc_len || j >= num_short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QrCode {
+    size: usize,
+    // row by row, true is dark
+    modules: Vec<bool>,
+    // finder, timing, alignment and format patterns, not masked
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// The smallest code that holds `data`
+    pub fn encode(data: &[u8]) -> Result<Self> {
+        let code = Self::unmasked(data)?;
+        // the mask with the lowest penalty makes the code easier to scan
+        let best = (0..8)
+            .map(|mask| code.masked(mask))
+            .min_by_key(|candidate| candidate.penalty());
+        Ok(best.unwrap_or(code))
+    }
+
+    fn unmasked(data: &[u8]) -> Result<Self> {
+        let Some(version) = (1..=MAX_VERSION)
+            .find(|v| 4 + char_count_bits(*v) + data.len() * 8 <= data_codewords(*v) * 8)
+        else {
+            bail!("{} bytes do not fit in a QR code", data.len());
+        };
+        let size = version * 4 + 17;
+        let mut code = Self {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        code.draw_function_patterns(version);
+        code.draw_codewords(&codewords(data, version));
+        Ok(code)
+    }
+
+    fn masked(&self, mask: u8) -> Self {
+        let mut code = self.clone();
+        code.apply_mask(mask);
+        code.draw_format_bits(mask);
+        code
+    }
+
+    /// Modules per side
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// True for a dark module, the area around the code is light
+    pub fn module(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            self.draw_finder(x, y);
+        }
+        let positions = alignment_positions(version, size);
+        let last = positions.len().saturating_sub(1);
+        for (i, y) in positions.iter().enumerate() {
+            for (j, x) in positions.iter().enumerate() {
+                // the corners with finders
+                if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+                    continue;
+                }
+                for dy in 0..5usize {
+                    for dx in 0..5usize {
+                        let dist = dx.abs_diff(2).max(dy.abs_diff(2));
+                        self.set_function(x + dx - 2, y + dy - 2, dist != 1);
+                    }
+                }
+            }
+        }
+        // reserve the format areas, drawn for real once the mask is chosen
+        self.draw_format_bits(0);
+        if version >= 7 {
+            let bits = version_bits(version);
+            for i in 0..18 {
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, bit(bits, i));
+                self.set_function(b, a, bit(bits, i));
+            }
+        }
+    }
+
+    // the finder with its separator, centered at x, y
+    fn draw_finder(&mut self, x: usize, y: usize) {
+        for dy in 0..9 {
+            for dx in 0..9 {
+                let (Some(xx), Some(yy)) = ((x + dx).checked_sub(4), (y + dy).checked_sub(4))
+                else {
+                    continue;
+                };
+                if xx < self.size && yy < self.size {
+                    let dist = dx.abs_diff(4).max(dy.abs_diff(4));
+                    self.set_function(xx, yy, dist != 2 && dist != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        let bits = format_bits(mask);
+        let size = self.size;
+        // around the top left finder
+        for i in 0..6 {
+            self.set_function(8, i, bit(bits, i));
+        }
+        self.set_function(8, 7, bit(bits, 6));
+        self.set_function(8, 8, bit(bits, 7));
+        self.set_function(7, 8, bit(bits, 8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(bits, i));
+        }
+        // split between the other two finders
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(bits, i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(bits, i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    // zigzag through pairs of columns from the bottom right corner
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        while right >= 1 {
+            // skip the vertical timing pattern
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                let y = if upward { size - 1 - vert } else { vert };
+                for x in [right, right - 1] {
+                    if !self.function[y * size + x] && i < data.len() * 8 {
+                        self.modules[y * size + x] = bit(data[i / 8] as u32, 7 - i % 8);
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                self.modules[index] ^= invert && !self.function[index];
+            }
+        }
+    }
+
+    // the penalty rules of the standard, lower is better
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let rows = (0..size).map(|y| (0..size).map(|x| self.module(x, y)).collect::<Vec<_>>());
+        let columns = (0..size).map(|x| (0..size).map(|y| self.module(x, y)).collect::<Vec<_>>());
+        let mut result = 0;
+        for line in rows.chain(columns) {
+            // runs of five or more modules of the same color
+            for run in line.chunk_by(|a, b| a == b) {
+                if run.len() >= 5 {
+                    result += run.len() - 2;
+                }
+            }
+            // patterns that look like a finder, light modules around are outside the code too
+            let mut padded = vec![false; 4];
+            padded.extend(&line);
+            padded.extend([false; 4]);
+            let finder = [true, false, true, true, true, false, true];
+            for window in padded.windows(11) {
+                let light = |range: std::ops::Range<usize>| window[range].iter().all(|m| !m);
+                if (window[..7] == finder && light(7..11)) || (window[4..] == finder && light(0..4))
+                {
+                    result += 40;
+                }
+            }
+        }
+        // 2x2 blocks of the same color
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.module(x, y);
+                if color == self.module(x + 1, y)
+                    && color == self.module(x, y + 1)
+                    && color == self.module(x + 1, y + 1)
+                {
+                    result += 3;
+                }
+            }
+        }
+        // dark modules far from half of the total
+        let total = size * size;
+        let dark = self.modules.iter().filter(|m| **m).count();
+        let k = (dark * 20).abs_diff(total * 10).div_ceil(total);
+        result + k.saturating_sub(1) * 10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qrcode() {
+        // HELLO WORLD at 1-M from the standard
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            rs_remainder(&data, 10),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+        assert_eq!(format_bits(0), 0b111011111000100);
+        assert_eq!(version_bits(7), 0b000111110010010100);
+        assert_eq!(alignment_positions(7, 45), vec![6, 22, 38]);
+        assert_eq!(data_codewords(1), 19);
+        assert_eq!(data_codewords(10), 274);
+
+        // 2-L with mask 2 from the encoder of Kazuhiko Arase, the one npm ships
+        let expected = [
+            "#######..#.#.#..#.#######",
+            "#.....#.###.#.###.#.....#",
+            "#.###.#..###..#.#.#.###.#",
+            "#.###.#.##...#....#.###.#",
+            "#.###.#....#.#..#.#.###.#",
+            "#.....#.#.#..####.#.....#",
+            "#######.#.#.#.#.#.#######",
+            "..............#..........",
+            "#####.#####.####.#.#.#.#.",
+            "###.#....#.#.#..#..#...#.",
+            "##########....##...###.##",
+            "#..#.#.#.###....####....#",
+            "..##..#.##.####..##.#.###",
+            "#..##..##.###....#.#.#.#.",
+            "#....##......###.#####.##",
+            "#.##.#.##.........###...#",
+            "#.###.##.##.##.######.#..",
+            "........####.#.##...##...",
+            "#######.#.....#.#.#.#.###",
+            "#.....#....#..#.#...##...",
+            "#.###.#.##..#########.##.",
+            "#.###.#.#.##.....##.#####",
+            "#.###.#.###.#.#......##.#",
+            "#.....#.#.....#.#..###..#",
+            "#######.###.###...#######",
+        ];
+        let code = QrCode::unmasked(b"https://github.com/lf-edge/eve")
+            .unwrap()
+            .masked(2);
+        assert_eq!(code.size(), 25);
+        let rows: Vec<String> = (0..code.size())
+            .map(|y| {
+                (0..code.size())
+                    .map(|x| if code.module(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(rows, expected);
+        assert!(!code.module(25, 0));
+
+        assert_eq!(QrCode::encode(&[b'x'; 200]).unwrap().size(), 53);
+        assert_eq!(QrCode::encode(&[b'x'; 271]).unwrap().size(), 57);
+        assert!(QrCode::encode(&[b'x'; 300]).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+
+use chrono::{NaiveDate, Utc};
+use log::{info, warn};
+
+// the monitor didn't exist before that, so the clock is surely wrong
+const MIN_SANE_DATE: (i32, u32, u32) = (2024, 1, 1);
+const MIN_TERMINAL_SIZE: (u16, u16) = (80, 24);
+
+#[derive(Debug)]
+pub struct SelfTestResult {
+    pub name: &'static str,
+    pub passed: bool,
+    pub details: String,
+}
+
+impl SelfTestResult {
+    fn new<S: Into<String>>(name: &'static str, passed: bool, details: S) -> Self {
+        Self {
+            name,
+            passed,
+            details: details.into(),
+        }
+    }
+}
+
+fn check_ipc_socket(socket_path: &str) -> SelfTestResult {
+    // do not connect here. The server may not like extra clients
+    match std::fs::metadata(socket_path) {
+        Ok(m) if m.file_type().is_socket() => {
+            SelfTestResult::new("IPC socket", true, format!("{} found", socket_path))
+        }
+        Ok(_) => SelfTestResult::new(
+            "IPC socket",
+            false,
+            format!("{} is not a socket", socket_path),
+        ),
+        Err(e) => SelfTestResult::new("IPC socket", false, format!("{}: {}", socket_path, e)),
+    }
+}
+
+fn check_persist_writable() -> SelfTestResult {
+    let Ok(log_dir) = std::env::var("EVE_MONITOR_LOG_DIR") else {
+        return SelfTestResult::new("Persist", false, "EVE_MONITOR_LOG_DIR is not set");
+    };
+    let probe = Path::new(&log_dir).join(".selftest");
+    let result = std::fs::write(&probe, b"selftest").and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(_) => SelfTestResult::new("Persist", true, format!("{} is writable", log_dir)),
+        Err(e) => SelfTestResult::new("Persist", false, format!("{}: {}", log_dir, e)),
+    }
+}
+
+fn check_terminal(size: Option<(u16, u16)>) -> SelfTestResult {
+    let term = std::env::var("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return SelfTestResult::new("Terminal", false, "TERM=dumb");
+    }
+    let (min_w, min_h) = MIN_TERMINAL_SIZE;
+    match size {
+        Some((w, h)) if w >= min_w && h >= min_h => {
+            SelfTestResult::new("Terminal", true, format!("{}x{} TERM={}", w, h, term))
+        }
+        Some((w, h)) => SelfTestResult::new(
+            "Terminal",
+            false,
+            format!("{}x{} is smaller than {}x{}", w, h, min_w, min_h),
+        ),
+        None => SelfTestResult::new("Terminal", false, "cannot get terminal size"),
+    }
+}
+
+fn check_clock() -> SelfTestResult {
+    let now = Utc::now();
+    let (y, m, d) = MIN_SANE_DATE;
+    let min_date = NaiveDate::from_ymd_opt(y, m, d).unwrap_or_default();
+    let details = now.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    SelfTestResult::new("Clock", now.date_naive() >= min_date, details)
+}
+
+/// Run quick startup checks and log the results
+pub fn run_self_tests(socket_path: &str, terminal_size: Option<(u16, u16)>) -> Vec<SelfTestResult> {
+    let results = vec![
+        check_ipc_socket(socket_path),
+        check_persist_writable(),
+        check_terminal(terminal_size),
+        check_clock(),
+    ];
+
+    for r in results.iter() {
+        if r.passed {
+            info!("Self-test {}: PASS ({})", r.name, r.details);
+        } else {
+            warn!("Self-test {}: FAIL ({})", r.name, r.details);
+        }
+    }
+    results
+}
@@ -0,0 +1,200 @@
+// End-to-end check without a tty for CI: a fake EVE serves captured messages
+// over a socket, the IPC collector feeds them to the model and a scripted
+// sequence of keys goes through every tab of the UI rendered into a buffer.
+// Any panic or render error fails the run
+use std::{
+    cell::RefCell,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::Path,
+    rc::Rc,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use bytes::Bytes;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use futures::{SinkExt, StreamExt};
+use log::{debug, info};
+use strum::EnumCount;
+use tokio::{net::UnixListener, sync::mpsc, task::JoinHandle};
+use tokio_util::codec::Framed;
+
+use crate::{
+    collectors::{ipc::IpcCollector, Collector},
+    config::MonitorConfig,
+    events::Event,
+    ipc::{
+        chunked::{ChunkedCodec, DEFAULT_MAX_MESSAGE},
+        message::IpcMessage,
+    },
+    model::model::{IpcConnectionState, Model, MonitorModel},
+    terminal::TerminalWrapper,
+    ui::ui::{Ui, UiTabs},
+};
+
+const TERMINAL_SIZE: (u16, u16) = (120, 40);
+const STATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// what EVE sends right after a client connects, captured from real devices
+const FIXTURES: &[&[u8]] = &[
+    include_bytes!("../fixtures/ipc/unknown/DPCList/002.json"),
+    include_bytes!("../fixtures/ipc/unknown/NetworkStatus/005.json"),
+    include_bytes!("../fixtures/ipc/unknown-errors/AppStatus/009.json"),
+];
+
+// keys pressed on every tab. Enter may open a dialog, Esc closes it again
+const TAB_KEYS: &[(KeyCode, KeyModifiers)] = &[
+    (KeyCode::Down, KeyModifiers::NONE),
+    (KeyCode::Down, KeyModifiers::NONE),
+    (KeyCode::Up, KeyModifiers::NONE),
+    (KeyCode::Tab, KeyModifiers::NONE),
+    (KeyCode::Enter, KeyModifiers::NONE),
+    (KeyCode::Esc, KeyModifiers::NONE),
+    (KeyCode::Char('p'), KeyModifiers::CONTROL),
+    (KeyCode::Esc, KeyModifiers::NONE),
+];
+
+// serves the fixtures to the first client and reads whatever it sends
+fn spawn_fake_eve(path: &Path) -> Result<JoinHandle<Result<()>>> {
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("Cannot bind {}", path.display()))?;
+    Ok(tokio::spawn(async move {
+        let (stream, _) = listener.accept().await?;
+        let mut framed = Framed::new(
+            stream,
+            ChunkedCodec::new(DEFAULT_MAX_MESSAGE, Arc::default()),
+        );
+        for fixture in FIXTURES {
+            framed.send(Bytes::from_static(fixture)).await?;
+        }
+        while let Some(request) = framed.next().await {
+            debug!("Fake EVE got {} bytes", request?.len());
+        }
+        Ok(())
+    }))
+}
+
+// messages from the fake EVE until all fixtures are in the model
+async fn collect_state(socket_path: &Path, model: &Rc<Model>) -> Result<()> {
+    let mut ipc = IpcCollector::new(socket_path.to_string_lossy(), DEFAULT_MAX_MESSAGE);
+    ipc.start();
+    let mut received = 0;
+    let collect = async {
+        while received < FIXTURES.len() {
+            let msg = ipc.recv().await.context("IPC collector stopped")?;
+            match msg {
+                IpcMessage::Connecting | IpcMessage::Disconnected { .. } => {}
+                IpcMessage::Ready => {
+                    model.borrow_mut().ipc_state = IpcConnectionState::Connected;
+                }
+                msg => {
+                    received += 1;
+                    model.borrow_mut().apply_ipc_message(msg);
+                }
+            }
+        }
+        anyhow::Ok(())
+    };
+    let result = tokio::time::timeout(STATE_TIMEOUT, collect).await;
+    ipc.stop();
+    result.with_context(|| {
+        format!(
+            "Got {} of {} messages from the fake EVE",
+            received,
+            FIXTURES.len()
+        )
+    })?
+}
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+fn screen_text(ui: &Ui) -> String {
+    ui.terminal
+        .buffer()
+        .map(|buffer| buffer.content().iter().map(|cell| cell.symbol()).collect())
+        .unwrap_or_default()
+}
+
+// every tab from the first to the last, TAB_KEYS on each
+fn run_script(ui: &mut Ui, model: &Rc<Model>) -> Result<usize> {
+    let mut frames = 0;
+    let mut draw = |ui: &mut Ui| -> Result<()> {
+        ui.draw(model.clone())?;
+        frames += 1;
+        Ok(())
+    };
+    draw(ui)?;
+    for index in 0..UiTabs::COUNT {
+        let tab = ui.selected_tab;
+        if tab as usize != index {
+            bail!("Expected tab {} to be selected, got {}", index + 1, tab);
+        }
+        for (code, modifiers) in TAB_KEYS {
+            ui.handle_event(key(*code, *modifiers));
+            draw(ui)?;
+        }
+        if !screen_text(ui).contains(&tab.to_string()) {
+            bail!("The title of the {} tab is not on the screen", tab);
+        }
+        ui.handle_event(key(KeyCode::Right, KeyModifiers::CONTROL));
+        draw(ui)?;
+    }
+    ui.handle_event(Event::TerminalResize(80, 24));
+    draw(ui)?;
+    Ok(frames)
+}
+
+/// Run the UI against a fake EVE and the scripted keys
+pub async fn run_smoke_test() -> Result<()> {
+    let socket_path =
+        std::env::temp_dir().join(format!("monitor-smoke-test-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&socket_path);
+    let fake_eve = spawn_fake_eve(&socket_path)?;
+
+    let model: Rc<Model> = Rc::new(RefCell::new(MonitorModel::default()));
+    let result = collect_state(&socket_path, &model).await;
+    fake_eve.abort();
+    let _ = std::fs::remove_file(&socket_path);
+    result?;
+
+    // the actions are for the application, nothing executes them here
+    let (action_tx, _action_rx) = mpsc::unbounded_channel();
+    let (width, height) = TERMINAL_SIZE;
+    let terminal = TerminalWrapper::headless(width, height)?;
+    let mut ui = Ui::new(action_tx, terminal, &MonitorConfig::default())?;
+
+    let frames = catch_unwind(AssertUnwindSafe(|| {
+        // the Home page runs lsusb and lspci, CI hosts may not have them
+        ui.init(true);
+        run_script(&mut ui, &model)
+    }))
+    .map_err(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        anyhow!("The UI panicked: {}", message)
+    })??;
+    info!("Smoke test rendered {} frames", frames);
+    println!(
+        "Smoke test passed: {} messages, {} tabs, {} frames",
+        FIXTURES.len(),
+        UiTabs::COUNT,
+        frames
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_smoke_test() {
+        run_smoke_test().await.unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 use anyhow::Result;
 use crossterm::{
-    cursor, execute,
+    cursor,
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
+    execute,
     terminal::{
         disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, Clear, EnterAlternateScreen,
         LeaveAlternateScreen,
@@ -9,14 +11,36 @@ use crossterm::{
 
 use std::{
     fs::{self, File},
-    ops::{Deref, DerefMut},
+    io::{self, Write},
 };
 
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{
+    backend::{CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    layout::Size,
+    Frame, Terminal,
+};
+
+#[derive(Debug)]
+enum Backend {
+    Tty(Terminal<CrosstermBackend<File>>),
+    // renders into a buffer, see --self-test
+    Headless(Terminal<TestBackend>),
+}
+
+// the same call on whichever terminal we have
+macro_rules! with_terminal {
+    ($self:expr, $terminal:ident => $call:expr) => {
+        match &mut $self.backend {
+            Backend::Tty($terminal) => $call,
+            Backend::Headless($terminal) => $call,
+        }
+    };
+}
 
 #[derive(Debug)]
 pub struct TerminalWrapper {
-    terminal: Terminal<CrosstermBackend<File>>,
+    backend: Backend,
 }
 
 impl TerminalWrapper {
@@ -30,7 +54,13 @@ impl TerminalWrapper {
     fn init_terminal(file: File) -> Result<Terminal<CrosstermBackend<File>>> {
         println!("Initializing terminal");
         // No stdout after this point
-        execute!(&file, EnterAlternateScreen, cursor::Hide)?;
+        execute!(
+            &file,
+            EnterAlternateScreen,
+            EnableBracketedPaste,
+            EnableMouseCapture,
+            cursor::Hide
+        )?;
         enable_raw_mode()?;
         let mut terminal = Terminal::new(CrosstermBackend::new(file))?;
         terminal.clear()?;
@@ -40,13 +70,64 @@ impl TerminalWrapper {
     pub fn open_terminal() -> Result<Self> {
         let file = Self::tty_fd()?;
         let terminal = Self::init_terminal(file)?;
-        Ok(Self { terminal })
+        Ok(Self {
+            backend: Backend::Tty(terminal),
+        })
+    }
+
+    /// A terminal without a tty, frames are kept in a buffer
+    pub fn headless(width: u16, height: u16) -> Result<Self> {
+        let terminal = Terminal::new(TestBackend::new(width, height))?;
+        Ok(Self {
+            backend: Backend::Headless(terminal),
+        })
+    }
+
+    /// The last frame of a headless terminal
+    pub fn buffer(&self) -> Option<&Buffer> {
+        match &self.backend {
+            Backend::Tty(_) => None,
+            Backend::Headless(terminal) => Some(terminal.backend().buffer()),
+        }
+    }
+
+    pub fn draw<F: FnOnce(&mut Frame<'_>)>(&mut self, render: F) -> io::Result<()> {
+        with_terminal!(self, terminal => terminal.draw(render).map(|_| ()))
+    }
+
+    pub fn size(&self) -> io::Result<Size> {
+        match &self.backend {
+            Backend::Tty(terminal) => terminal.size(),
+            Backend::Headless(terminal) => terminal.size(),
+        }
+    }
+
+    pub fn autoresize(&mut self) -> io::Result<()> {
+        with_terminal!(self, terminal => terminal.autoresize())
+    }
+
+    pub fn clear(&mut self) -> io::Result<()> {
+        with_terminal!(self, terminal => terminal.clear())
+    }
+
+    pub fn show_cursor(&mut self) -> io::Result<()> {
+        with_terminal!(self, terminal => terminal.show_cursor())
+    }
+
+    pub fn hide_cursor(&mut self) -> io::Result<()> {
+        with_terminal!(self, terminal => terminal.hide_cursor())
     }
 
     pub fn close_terminal() -> Result<()> {
         if is_raw_mode_enabled()? {
             let mut file = Self::tty_fd()?;
-            execute!(file, LeaveAlternateScreen, cursor::Show)?;
+            execute!(
+                file,
+                DisableBracketedPaste,
+                DisableMouseCapture,
+                LeaveAlternateScreen,
+                cursor::Show
+            )?;
             execute!(file, Clear(crossterm::terminal::ClearType::All))?;
             disable_raw_mode()?;
         }
@@ -55,6 +136,19 @@ impl TerminalWrapper {
         Ok(())
     }
 
+    /// Write bytes to the terminal bypassing ratatui, e.g. the output of a
+    /// guest console
+    pub fn write_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        // nobody sees the bytes of a headless terminal
+        let Backend::Tty(terminal) = &mut self.backend else {
+            return Ok(());
+        };
+        let backend = terminal.backend_mut();
+        backend.write_all(bytes)?;
+        backend.flush()?;
+        Ok(())
+    }
+
     pub fn get_stream() -> crossterm::event::EventStream {
         crossterm::event::EventStream::new()
     }
@@ -62,21 +156,9 @@ impl TerminalWrapper {
 
 impl Drop for TerminalWrapper {
     fn drop(&mut self) {
-        let _ = self.terminal.clear();
-        let _ = Self::close_terminal();
-    }
-}
-
-impl Deref for TerminalWrapper {
-    type Target = Terminal<CrosstermBackend<File>>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.terminal
-    }
-}
-
-impl DerefMut for TerminalWrapper {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.terminal
+        if let Backend::Tty(terminal) = &mut self.backend {
+            let _ = terminal.clear();
+            let _ = Self::close_terminal();
+        }
     }
 }
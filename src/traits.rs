@@ -1,9 +1,11 @@
 use std::rc::Rc;
 
 use crate::events::Event;
-use crate::model::model::Model;
+use crate::model::model::{Model, MonitorModel};
 use crate::ui::action::{Action, UiActions};
 use crate::ui::activity::Activity;
+use crate::ui::command_palette::Command;
+use crate::ui::export::TableExport;
 use log::info;
 use ratatui::{layout::Rect, Frame};
 
@@ -32,6 +34,17 @@ pub trait IElementEventHandler {
     fn handle_key_event(&mut self, _key: crossterm::event::KeyEvent) -> Option<UiActions> {
         None
     }
+    fn handle_paste(&mut self, _text: &str) -> Option<UiActions> {
+        None
+    }
+    // `area` is where the widget was rendered, the event is inside it
+    fn handle_mouse_event(
+        &mut self,
+        _event: crossterm::event::MouseEvent,
+        _area: Rect,
+    ) -> Option<UiActions> {
+        None
+    }
     fn handle_tick(&mut self) -> Option<Activity> {
         None
     }
@@ -49,6 +62,14 @@ pub trait IWindow: IPresenter + IEventHandler {
         info!("Window received child action: {:?} from {}", action, source);
         None
     }
+    // table-based pages return their current content for "export current view"
+    fn export(&self, _model: &MonitorModel) -> Option<TableExport> {
+        None
+    }
+    // entries of the command palette, usually the shortcuts of the window
+    fn commands(&self) -> Vec<Command> {
+        Vec::new()
+    }
 }
 pub trait IWidget: IWidgetPresenter + IElementEventHandler {
     fn set_enabled(&mut self, _enabled: bool) {}
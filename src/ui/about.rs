@@ -0,0 +1,37 @@
+use chrono::DateTime;
+
+// EVE version file as seen from the monitor container. The first one that exists wins
+const EVE_RELEASE_FILES: &[&str] = &["/hostfs/etc/eve-release", "/run/eve-release"];
+
+pub fn eve_release() -> Option<String> {
+    EVE_RELEASE_FILES
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|release| release.trim().to_string())
+        .filter(|release| !release.is_empty())
+}
+
+fn build_date() -> Option<String> {
+    let timestamp = option_env!("BUILD_TIMESTAMP")?.parse().ok()?;
+    DateTime::from_timestamp(timestamp, 0).map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+}
+
+/// Build information to attach to bug reports
+pub fn about_text() -> String {
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    format!(
+        "EVE monitor {}\nGit version: {}\nBuild date: {}\nBuild: {} {}-{}\nEVE version: {}\n\n{}",
+        env!("CARGO_PKG_VERSION"),
+        option_env!("GIT_VERSION").unwrap_or("N/A"),
+        build_date().unwrap_or("N/A".to_string()),
+        profile,
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        eve_release().unwrap_or("N/A".to_string()),
+        env!("CARGO_PKG_REPOSITORY"),
+    )
+}
@@ -1,17 +1,102 @@
-use crate::{actions::MonActions, traits::IAction};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    actions::{AppCommand, MonActions},
+    config::RuntimeSettings,
+    traits::IAction,
+    ui::{
+        command_palette::Command,
+        export::{ExportFormat, TableExport},
+        summary_page::SummaryPanel,
+        ui::UiTabs,
+    },
+};
 #[derive(Debug, Clone, PartialEq)]
 pub enum UiActions {
     Quit,
     Redraw,
-    RadioGroup { selected: usize },
-    SpinBox { selected: usize },
-    Input { text: String },
+    RadioGroup {
+        selected: usize,
+    },
+    SpinBox {
+        selected: usize,
+    },
+    Input {
+        text: String,
+    },
+    ListChanged {
+        items: Vec<String>,
+    },
+    // indexes of the initial entries in the new order, enabled by initial index
+    #[cfg(feature = "efi")]
+    OrderChanged {
+        order: Vec<usize>,
+        enabled: Vec<bool>,
+    },
     ButtonClicked(String),
     DismissDialog,
     AppAction(MonActions),
     EditIfaceConfig(String),
     TabChanged(String, String),
     ChangeServer,
+    #[cfg(feature = "dmesg")]
+    SaveDmesg,
+    RestartCollector(String),
+    SupportBundle,
+    // the bundle the monitor creates without EVE
+    CreateBundle,
+    CloseBundleDialog,
+    ExportView(ExportFormat),
+    // lines selected in a detail pane
+    ExportLines(TableExport),
+    StartKeyCapture,
+    ShowProxyCerts(String),
+    // pick a CA certificate of the proxy on a USB stick
+    BrowseProxyCert,
+    ProxyCertFile(PathBuf),
+    // certificates of the picked file for the IP dialog
+    ProxyCertLoaded {
+        name: String,
+        pems: Vec<String>,
+    },
+    ShowDpcHistory,
+    #[cfg(feature = "efi")]
+    ShowEfiVariables,
+    #[cfg(feature = "efi")]
+    EditBootOrder,
+    // run the connectivity checks of an interface
+    Diagnose(String),
+    CloseDiagnostics,
+    // push an entry of the DPC history, by key and time priority, as the manual DPC
+    RollbackDpc(String, DateTime<Utc>),
+    RetryAttestation,
+    ViewGrubCfg,
+    ViewUnknownValues,
+    ShowMonitorLog,
+    SaveModelSnapshot,
+    ViewAppDetails(Uuid),
+    AttachConsole(Uuid),
+    AppCommand(AppCommand, Uuid),
+    SummarySettings,
+    SetSummaryPanels(Vec<SummaryPanel>),
+    ViewAppLogs(Uuid),
+    CloseAppLogs,
+    ChangeSettings(RuntimeSettings),
+    TakeSnapshot(String),
+    // the user agreed to an action the action bus asked about
+    Confirmed(Box<UiActions>),
+    // picked in the command palette
+    RunCommand(Box<Command>),
+    ShowTab(UiTabs),
+    ShowAbout,
+    SnapshotDialog,
+    CompareSnapshots,
+    // one alert by id or all of them
+    AcknowledgeAlerts(Option<u64>),
+    DismissAlerts(Option<u64>),
 }
 
 #[derive(Debug, Clone)]
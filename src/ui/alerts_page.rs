@@ -0,0 +1,175 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, HighlightSpacing, Padding, Row, StatefulWidget, Table},
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        alerts::{Alert, AlertSeverity},
+        model::{Model, MonitorModel},
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::{
+        action::{Action, UiActions},
+        command_palette::Command,
+    },
+};
+
+use super::{export::TableExport, selection::KeyedSelection, traits::ISelector};
+
+const HEADER: [&str; 5] = ["Time", "Severity", "Source", "Alert", "Seen"];
+
+fn cells(alert: &Alert) -> Vec<String> {
+    vec![
+        alert.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        alert.severity.to_string(),
+        alert.source.clone(),
+        alert.text.clone(),
+        if alert.acknowledged { "yes" } else { "" }.to_string(),
+    ]
+}
+
+/// All alerts, newest first. The user acknowledges the ones they have seen
+/// and dismisses the ones they no longer need
+#[derive(Debug, Default)]
+pub struct AlertsPage {
+    list: KeyedSelection<u64>,
+}
+
+impl AlertsPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn action(&self, action: UiActions) -> Option<Action> {
+        Some(Action::new("alerts", action))
+    }
+}
+
+impl IPresenter for AlertsPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let model = model.borrow();
+        let theme = model.theme;
+        let [table_rect, help_rect] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(*area);
+
+        self.list
+            .set_keys(model.alerts.iter().rev().map(|alert| alert.id).collect());
+        let mut rows: Vec<Row> = model
+            .alerts
+            .iter()
+            .rev()
+            .map(|alert| {
+                let row = Row::new(cells(alert));
+                match (alert.acknowledged, alert.severity) {
+                    (true, _) => row.fg(theme.muted),
+                    (false, AlertSeverity::Info) => row.fg(theme.text),
+                    (false, AlertSeverity::Warning) => row.fg(theme.warning),
+                    (false, AlertSeverity::Critical) => row.fg(theme.error).bold(),
+                }
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push(Row::new(vec!["", "", "", "No alerts"]).fg(theme.muted));
+        }
+        let new = model.new_alerts().count();
+        let block = Block::default()
+            .title(format!(" Alerts, {} new ", new))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        self.list.set_table_area(block.inner(table_rect), 1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+                Constraint::Length(4),
+            ],
+        )
+        .block(block)
+        .header(Row::new(HEADER.to_vec()).bold())
+        .row_highlight_style(Style::new().bg(theme.selection))
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_rect, frame.buffer_mut(), &mut self.list.state);
+
+        frame.render_widget(
+            Line::from(
+                " ▲▼ select, 'a' acknowledge, 'A' acknowledge all, 'd' dismiss, 'D' dismiss all",
+            )
+            .fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for AlertsPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+                return None;
+            }
+            _ => return None,
+        };
+        match key.code {
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down => self.list.select_next(),
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.list.select_first(),
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.list.select_last(),
+            KeyCode::Char('a') | KeyCode::Enter => {
+                let id = self.list.selected()?;
+                return self.action(UiActions::AcknowledgeAlerts(Some(id)));
+            }
+            KeyCode::Char('A') => return self.action(UiActions::AcknowledgeAlerts(None)),
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let id = self.list.selected()?;
+                return self.action(UiActions::DismissAlerts(Some(id)));
+            }
+            KeyCode::Char('D') => return self.action(UiActions::DismissAlerts(None)),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for AlertsPage {
+    fn commands(&self) -> Vec<Command> {
+        let mut commands = vec![
+            Command::new(
+                "Acknowledge all alerts",
+                "A",
+                UiActions::AcknowledgeAlerts(None),
+            ),
+            Command::new("Dismiss all alerts", "D", UiActions::DismissAlerts(None)),
+        ];
+        if let Some(id) = self.list.selected() {
+            commands.push(Command::new(
+                "Dismiss the selected alert",
+                "d",
+                UiActions::DismissAlerts(Some(id)),
+            ));
+        }
+        commands
+    }
+
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new("alerts", HEADER.to_vec());
+        for alert in model.alerts.iter().rev() {
+            export.add_row(cells(alert));
+        }
+        Some(export)
+    }
+}
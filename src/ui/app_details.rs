@@ -0,0 +1,323 @@
+use std::rc::Rc;
+
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use uuid::Uuid;
+
+use crate::{
+    ipc::eve_types::{AppInstanceStatus, Inprogress},
+    model::{
+        model::{AppInstance, Model},
+        sanitize::sanitize_line,
+        verification::{volume_name, Verifications},
+    },
+    traits::IWindow,
+    ui::{action::UiActions, theme::Theme},
+};
+
+use super::{action::Action, line_selection::LineSelection, window::Window};
+
+struct AppDetailsState {
+    uuid: Uuid,
+    scroll: usize,
+    page: usize,
+    // text of the lines at the last frame, key handlers have no model
+    text: Vec<String>,
+    selection: LineSelection,
+}
+
+fn time_text(time: &DateTime<Utc>) -> String {
+    // EVE sends the zero time for events that did not happen
+    if time.timestamp() <= 0 {
+        "never".to_string()
+    } else {
+        time.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+    }
+}
+
+fn progress_line(
+    what: &str,
+    progress: &Inprogress,
+    since: &DateTime<Utc>,
+    theme: &Theme,
+) -> Option<Line<'static>> {
+    match progress {
+        Inprogress::NotInprogress => None,
+        progress => Some(
+            Line::from(format!(
+                "{} in progress: {:?} since {}",
+                what,
+                progress,
+                time_text(since)
+            ))
+            .fg(theme.warning),
+        ),
+    }
+}
+
+fn section(title: &str) -> Line<'static> {
+    Line::from(title.to_string()).bold()
+}
+
+fn status_lines(
+    status: &AppInstanceStatus,
+    verifications: &Verifications,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    lines.extend(progress_line(
+        "Restart",
+        &status.restart_inprogress,
+        &status.restart_started_at,
+        theme,
+    ));
+    lines.extend(progress_line(
+        "Purge",
+        &status.purge_inprogress,
+        &status.purge_started_at,
+        theme,
+    ));
+    if status.missing_network {
+        lines.push(Line::from("Waiting for a network instance").fg(theme.warning));
+    }
+    if status.missing_memory {
+        lines.push(Line::from("Not enough memory to start").fg(theme.warning));
+    }
+
+    let error = &status.error_and_time_with_source;
+    if !error.error_description.error.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(section("Error"));
+        for line in error.error_description.error.lines() {
+            lines.push(Line::from(sanitize_line(line)).fg(theme.error));
+        }
+        lines.push(Line::from(format!(
+            "At {}, severity {:?}, source {}",
+            time_text(&error.error_description.error_time),
+            error.error_description.error_severity,
+            sanitize_line(&error.error_source_type)
+        )));
+        if !error.error_description.error_retry_condition.is_empty() {
+            lines.push(Line::from(format!(
+                "Retry: {}",
+                sanitize_line(&error.error_description.error_retry_condition)
+            )));
+        }
+    }
+
+    let vm = &status.fixed_resources;
+    lines.push(Line::from(""));
+    lines.push(section("Resources"));
+    lines.push(Line::from(format!(
+        "vCPUs: {} (max {}){}",
+        vm.vcpus,
+        vm.max_cpus,
+        if vm.cpus_pinned { ", pinned" } else { "" }
+    )));
+    // EVE reports memory in KiB
+    lines.push(Line::from(format!(
+        "Memory: {} MiB (max {} MiB, overhead {} MiB)",
+        vm.memory / 1024,
+        vm.max_mem / 1024,
+        status.mem_overhead / (1024 * 1024)
+    )));
+    lines.push(Line::from(format!(
+        "Virtualization: {:?}{}",
+        vm.virtualization_mode,
+        if vm.enable_vnc {
+            format!(", VNC display {}", vm.vnc_display)
+        } else {
+            String::new()
+        }
+    )));
+
+    lines.push(Line::from(""));
+    lines.push(section(&format!(
+        "Volumes ({})",
+        status.volume_ref_status_list.len()
+    )));
+    for volume in status.volume_ref_status_list.iter() {
+        let name = volume_name(volume);
+        let mismatch = verifications
+            .get(&name)
+            .and_then(|image| image.mismatch.as_ref());
+        let name = sanitize_line(&name);
+        lines.push(Line::from(format!(
+            "{}: {}, {:?} {:?}, {} MiB{}",
+            name,
+            volume.state,
+            volume.content_format,
+            volume.target,
+            volume.max_vol_size / (1024 * 1024),
+            if volume.read_only { ", read-only" } else { "" }
+        )));
+        let error = &volume.error_and_time_with_source.error_description.error;
+        if let Some(mismatch) = mismatch {
+            lines.push(Line::from("  Verification failed").fg(theme.error));
+            lines.push(
+                Line::from(format!("  expected SHA-256 {}", mismatch.expected)).fg(theme.error),
+            );
+            lines.push(
+                Line::from(format!("  computed SHA-256 {}", mismatch.computed)).fg(theme.error),
+            );
+        } else if !error.is_empty() {
+            lines.push(Line::from(format!("  {}", sanitize_line(error))).fg(theme.error));
+        }
+    }
+
+    // AppNetAdapterStatus and IoAdapter are not parsed yet
+    lines.push(Line::from(""));
+    lines.push(section("Adapters"));
+    lines.push(Line::from("Not reported by EVE to the monitor").fg(theme.muted));
+    lines
+}
+
+/// Everything EVE reported about the app, one item per line
+fn details_lines(
+    app: &AppInstance,
+    verifications: &Verifications,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let status = &app.status;
+    let mut lines = vec![
+        Line::from(format!("UUID: {}", app.uuid)),
+        Line::from(format!("Version: {}", sanitize_line(&app.version))),
+        Line::from(format!(
+            "State: {}{}",
+            status.state,
+            if status.activate_inprogress {
+                ", activating"
+            } else if status.activated {
+                ", activated"
+            } else {
+                ""
+            }
+        )),
+        Line::from(format!("Started: {}", time_text(&status.start_time))),
+        Line::from(format!("Booted: {}", time_text(&status.boot_time))),
+        Line::from(format!(
+            "Restarts: {}{}",
+            app.restarts.count(),
+            if app.restarts.is_crash_looping(Utc::now()) {
+                ", crash loop"
+            } else {
+                ""
+            }
+        )),
+    ];
+    lines.extend(status_lines(status, verifications, theme));
+    lines
+}
+
+fn do_layout(w: &mut Window<AppDetailsState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 2,
+        vertical: 1,
+    });
+    // borders
+    w.state.page = frame_rect.height.saturating_sub(2).max(1) as usize;
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<AppDetailsState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let model = model.borrow();
+    let state = &mut w.state;
+    let (title, lines) = match model.apps.get(&state.uuid) {
+        Some(app) => (
+            sanitize_line(&app.name),
+            details_lines(app, &model.verifications, &theme),
+        ),
+        // removed while the details are open
+        None => (
+            state.uuid.to_string(),
+            vec![Line::from("EVE no longer reports this app").fg(theme.warning)],
+        ),
+    };
+    state.text = lines.iter().map(|line| line.to_string()).collect();
+    state.scroll = state
+        .scroll
+        .min(state.text.len().saturating_sub(state.page));
+    let lines: Vec<Line> = lines
+        .into_iter()
+        .enumerate()
+        .skip(state.scroll)
+        .take(state.page)
+        .map(|(i, line)| state.selection.style_line(line, i, &theme))
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(format!(
+            " {} ('l' for logs, 'c' for console, 'v' to select, Esc to close) ",
+            title
+        ));
+
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+}
+
+fn on_key_event(w: &mut Window<AppDetailsState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    if let Some(action) = state.selection.handle_key(
+        key,
+        &mut state.scroll,
+        state.page,
+        state.text.len(),
+        "app-details",
+        &state.text,
+    ) {
+        return Some(Action::new(&w.name, action));
+    }
+    let last = state.text.len().saturating_sub(state.page);
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Char('l') => {
+            return Some(Action::new(&w.name, UiActions::ViewAppLogs(state.uuid)))
+        }
+        KeyCode::Char('c') => {
+            return Some(Action::new(&w.name, UiActions::AttachConsole(state.uuid)))
+        }
+        KeyCode::Up => state.scroll = state.scroll.saturating_sub(1),
+        KeyCode::Down => state.scroll = (state.scroll + 1).min(last),
+        KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(state.page),
+        KeyCode::PageDown => state.scroll = (state.scroll + state.page).min(last),
+        KeyCode::Home => state.scroll = 0,
+        KeyCode::End => state.scroll = last,
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+pub fn create_app_details(uuid: Uuid) -> impl IWindow {
+    Window::builder("app details")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(AppDetailsState {
+            uuid,
+            scroll: 0,
+            page: 1,
+            text: Vec::new(),
+            selection: LineSelection::default(),
+        })
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,146 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::Style,
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{model::model::Model, traits::IWindow, ui::action::UiActions};
+
+use super::{action::Action, window::Window};
+
+struct AppLogsViewerState {
+    scroll: usize,
+    page: usize,
+    // stick to the end as new lines arrive
+    follow: bool,
+    // number of lines at the last frame, key handlers have no model
+    len: usize,
+}
+
+impl AppLogsViewerState {
+    fn last(&self) -> usize {
+        self.len.saturating_sub(self.page)
+    }
+}
+
+fn do_layout(w: &mut Window<AppLogsViewerState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 2,
+        vertical: 1,
+    });
+    // borders
+    w.state.page = frame_rect.height.saturating_sub(2).max(1) as usize;
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<AppLogsViewerState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let model = model.borrow();
+    let Some(logs) = model.app_logs.as_ref() else {
+        return;
+    };
+    let state = &mut w.state;
+    state.len = logs.lines().len();
+    if state.follow {
+        state.scroll = state.last();
+    }
+    state.scroll = state.scroll.min(state.last());
+
+    let mut lines: Vec<Line> = logs
+        .lines()
+        .iter()
+        .skip(state.scroll)
+        .take(state.page)
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    if let Some(error) = &logs.error {
+        lines.push(Line::styled(
+            error.as_str(),
+            Style::default().fg(theme.error),
+        ));
+    } else if lines.is_empty() {
+        lines.push(Line::styled(
+            "Waiting for logs...",
+            Style::default().fg(theme.muted),
+        ));
+    }
+
+    let mut title = format!(
+        " Logs of {} ({} lines{}) ",
+        logs.app_name,
+        state.len,
+        if logs.dropped > 0 {
+            ", head dropped"
+        } else {
+            ""
+        }
+    );
+    title.push_str(if state.follow {
+        "[following, 'f' to stop, Esc to close] "
+    } else {
+        "['f' to follow, Esc to close] "
+    });
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(title);
+
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+}
+
+fn on_key_event(w: &mut Window<AppLogsViewerState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    let last = state.last();
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::CloseAppLogs)),
+        KeyCode::Char('f') => state.follow = !state.follow,
+        // moving away from the end stops following
+        KeyCode::Up => {
+            state.follow = false;
+            state.scroll = state.scroll.min(last).saturating_sub(1);
+        }
+        KeyCode::PageUp => {
+            state.follow = false;
+            state.scroll = state.scroll.min(last).saturating_sub(state.page);
+        }
+        KeyCode::Home => {
+            state.follow = false;
+            state.scroll = 0;
+        }
+        KeyCode::Down => state.scroll = (state.scroll + 1).min(last),
+        KeyCode::PageDown => state.scroll = (state.scroll + state.page).min(last),
+        KeyCode::End => state.follow = true,
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+pub fn create_app_logs_viewer() -> impl IWindow {
+    Window::builder("app logs")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(AppLogsViewerState {
+            scroll: 0,
+            page: 1,
+            follow: true,
+            len: 0,
+        })
+        .build()
+        .unwrap()
+}
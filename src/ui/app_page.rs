@@ -1,34 +1,38 @@
 use std::rc::Rc;
 
+use chrono::Utc;
+
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint, Rect},
-    style::{Color, Style, Stylize},
+    style::Style,
     text::Text,
     widgets::{
         Block, BorderType, Borders, Cell, HighlightSpacing, Padding, Row, StatefulWidget, Table,
-        TableState,
     },
     Frame,
 };
+use uuid::Uuid;
 
 use crate::{
+    actions::AppCommand,
     events::Event,
-    model::model::{AppInstance, AppInstanceState, Model},
+    model::model::{AppInstance, AppInstanceState, Model, MonitorModel},
     traits::{IEventHandler, IPresenter, IWindow},
 };
 
-use super::traits::ISelector;
-
-#[derive(Debug, Default)]
-struct ApplicationList {
-    state: TableState,
-    size: usize,
-}
+use super::{
+    action::{Action, UiActions},
+    command_palette::Command,
+    export::TableExport,
+    selection::KeyedSelection,
+    theme::Theme,
+    traits::ISelector,
+};
 
 #[derive(Debug, Default)]
 pub struct ApplicationsPage {
-    list: ApplicationList,
+    list: KeyedSelection<Uuid>,
 }
 
 impl ApplicationsPage {
@@ -38,22 +42,24 @@ impl ApplicationsPage {
         }
     }
     fn render_app_list(&mut self, model: &Rc<Model>, list_rect: Rect, frame: &mut Frame) {
+        let theme = model.borrow().theme;
         // create header for the table
         let header = Row::new(vec![
             Cell::from("Name").style(Style::default()),
             Cell::from("GUID").style(Style::default()),
             Cell::from("Status").style(Style::default()),
+            Cell::from("Restarts").style(Style::default()),
         ]);
 
         // create list items from the interface
-        let rows = model
-            .borrow()
-            .apps
+        let model = model.borrow();
+        let apps = model.sorted_apps();
+        let rows = apps
             .iter()
-            .map(|(_, app)| info_row_from_app(app))
+            .map(|app| info_row_from_app(app, &theme))
             .collect::<Vec<_>>();
-
-        self.list.size = rows.len();
+        self.list
+            .set_keys(apps.iter().map(|app| app.uuid).collect());
         // self.interface_names = model
         //     .borrow()
         //     .network
@@ -63,13 +69,16 @@ impl ApplicationsPage {
 
         // create a surrounding block for the list
         let block = Block::default()
-            .title(" Applications ")
+            .title(
+                " Applications (Enter details, 'l' logs, 'r' restart, 'p' purge, 'o' power off) ",
+            )
             .title_alignment(Alignment::Center)
             .borders(Borders::TOP)
             .border_type(BorderType::Plain)
             // .border_style(Style::default().fg(Color::White).bg(Color::Black))
             // .style(Style::default().bg(Color::Black));
             .padding(Padding::new(1, 1, 1, 1));
+        self.list.set_table_area(block.inner(list_rect), 1);
 
         let bar = " █ ";
 
@@ -80,10 +89,11 @@ impl ApplicationsPage {
                 Constraint::Max(20),
                 Constraint::Max(32),
                 Constraint::Fill(14),
+                Constraint::Max(20),
             ],
         )
         .block(block)
-        .row_highlight_style(Style::new().bg(Color::DarkGray))
+        .row_highlight_style(Style::new().bg(theme.selection))
         // .highlight_symbol(">")
         .highlight_symbol(Text::from(vec![
             // "".into(),
@@ -100,7 +110,75 @@ impl ApplicationsPage {
     }
 }
 
-impl IWindow for ApplicationsPage {}
+impl IWindow for ApplicationsPage {
+    fn commands(&self) -> Vec<Command> {
+        let Some(uuid) = self.selected() else {
+            return Vec::new();
+        };
+        vec![
+            Command::new(
+                "Show details of the selected application",
+                "Enter",
+                UiActions::ViewAppDetails(uuid),
+            ),
+            Command::new(
+                "Show logs of the selected application",
+                "l",
+                UiActions::ViewAppLogs(uuid),
+            ),
+            Command::new(
+                "Restart the selected application",
+                "r",
+                UiActions::AppCommand(AppCommand::Restart, uuid),
+            ),
+            Command::new(
+                "Purge the selected application",
+                "p",
+                UiActions::AppCommand(AppCommand::Purge, uuid),
+            ),
+            Command::new(
+                "Power off the selected application",
+                "o",
+                UiActions::AppCommand(AppCommand::PowerOff, uuid),
+            ),
+        ]
+    }
+
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new(
+            "apps",
+            vec![
+                "Name",
+                "GUID",
+                "Status",
+                "Error",
+                "Boot time",
+                "Restarts",
+                "Crash loop",
+            ],
+        );
+        let now = Utc::now();
+        for app in model.sorted_apps() {
+            let (state, error) = match &app.state {
+                AppInstanceState::Normal(st) => (st.to_string(), String::new()),
+                AppInstanceState::Error(st, err) => (st.to_string(), err.clone()),
+            };
+            export.add_row(vec![
+                app.name.clone(),
+                app.uuid.to_string(),
+                state,
+                error,
+                app.restarts
+                    .boot_time()
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default(),
+                app.restarts.count().to_string(),
+                app.restarts.is_crash_looping(now).to_string(),
+            ]);
+        }
+        Some(export)
+    }
+}
 
 impl IEventHandler for ApplicationsPage {
     fn handle_event(&mut self, event: Event) -> Option<super::action::Action> {
@@ -111,31 +189,53 @@ impl IEventHandler for ApplicationsPage {
                 KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.select_first(),
                 KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.select_last(),
                 KeyCode::Enter => {
-                    let _selected_iface = self.selected();
-                    // if let Some(selected) = _selected_iface {
-                    //     return Some(Action::new("net", UiActions::EditIfaceConfig(selected)));
-                    // }
+                    let uuid = self.selected()?;
+                    return Some(Action::new("apps", UiActions::ViewAppDetails(uuid)));
+                }
+                KeyCode::Char('l') => {
+                    let uuid = self.selected()?;
+                    return Some(Action::new("apps", UiActions::ViewAppLogs(uuid)));
+                }
+                KeyCode::Char(c @ ('r' | 'p' | 'o')) => {
+                    let uuid = self.selected()?;
+                    let command = match c {
+                        'r' => AppCommand::Restart,
+                        'p' => AppCommand::Purge,
+                        _ => AppCommand::PowerOff,
+                    };
+                    return Some(Action::new("apps", UiActions::AppCommand(command, uuid)));
                 }
                 _ => {}
             },
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+            }
             _ => {}
         }
         None
     }
 }
 
-fn info_row_from_app<'a, 'b>(app: &'a AppInstance) -> Row<'b> {
+fn info_row_from_app<'a, 'b>(app: &'a AppInstance, theme: &Theme) -> Row<'b> {
     let height = 1;
     // cells #1,2 IFace name and Link status
     let cells = vec![
         Cell::from(app.name.clone()),
         Cell::from(app.uuid.to_string()),
         match &app.state {
-            AppInstanceState::Normal(st) => Cell::from(st.to_string()).style(Style::new().green()),
+            AppInstanceState::Normal(st) => {
+                Cell::from(st.to_string()).style(Style::new().fg(theme.ok))
+            }
             AppInstanceState::Error(st, _err) => {
-                Cell::from(st.to_string()).style(Style::new().red())
+                Cell::from(st.to_string()).style(Style::new().fg(theme.error))
             }
         },
+        if app.restarts.is_crash_looping(Utc::now()) {
+            Cell::from(format!("{} crash loop", app.restarts.count()))
+                .style(Style::new().fg(theme.error))
+        } else {
+            Cell::from(app.restarts.count().to_string())
+        },
     ];
 
     // // collect IP addresses and add as multiline
@@ -180,38 +280,25 @@ impl IPresenter for ApplicationsPage {
 }
 
 impl ISelector for ApplicationsPage {
+    type Key = Uuid;
+
     fn select_next(&mut self) {
-        if let Some(selected) = self.list.state.selected() {
-            if selected < self.list.size - 1 {
-                self.list.state.select(Some(selected + 1));
-            }
-        } else {
-            self.list.state.select(Some(0));
-        }
+        self.list.select_next();
     }
 
     fn select_previous(&mut self) {
-        if let Some(selected) = self.list.state.selected() {
-            let index = selected.saturating_sub(1);
-            self.list.state.select(Some(index));
-        }
+        self.list.select_previous();
     }
 
     fn select_first(&mut self) {
-        self.list.state.select(Some(0));
+        self.list.select_first();
     }
 
     fn select_last(&mut self) {
-        let index = self.list.size.saturating_sub(1);
-
-        self.list.state.select(Some(index));
+        self.list.select_last();
     }
 
-    fn selected(&self) -> Option<String> {
-        // self.list
-        //     .state
-        //     .selected()
-        //     .map(|index| self[index].clone())
-        None
+    fn selected(&self) -> Option<Uuid> {
+        self.list.selected()
     }
 }
@@ -0,0 +1,178 @@
+use std::rc::Rc;
+
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, Borders, HighlightSpacing, Padding, Paragraph, Row, StatefulWidget,
+        Table, Wrap,
+    },
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        audit::AuditRecord,
+        model::{Model, MonitorModel},
+        sanitize::sanitize_line,
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::Action,
+};
+
+use super::{export::TableExport, selection::KeyedSelection, traits::ISelector};
+
+const HEADER: [&str; 6] = ["Time", "Action", "Target", "Old", "New", "Result"];
+
+fn value(value: &Option<String>) -> String {
+    value.as_deref().map(sanitize_line).unwrap_or_default()
+}
+
+fn cells(record: &AuditRecord) -> Vec<String> {
+    vec![
+        record
+            .time
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+        record.action.clone(),
+        sanitize_line(&record.target),
+        value(&record.old),
+        value(&record.new),
+        record.result(),
+    ]
+}
+
+/// Changes made from the monitor, newest first. The records come from the
+/// audit file so changes of earlier sessions are listed too
+#[derive(Debug, Default)]
+pub struct AuditPage {
+    list: KeyedSelection<u64>,
+}
+
+impl AuditPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IPresenter for AuditPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let model = model.borrow();
+        let theme = model.theme;
+        let [table_rect, details_rect, help_rect] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(5),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+
+        self.list
+            .set_keys(model.audit.iter().rev().map(|record| record.id).collect());
+        let mut rows: Vec<Row> = model
+            .audit
+            .iter()
+            .rev()
+            .map(|record| {
+                let row = Row::new(cells(record));
+                if record.accepted() {
+                    row.fg(theme.text)
+                } else {
+                    row.fg(theme.error)
+                }
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push(
+                Row::new(vec!["", "", "", "No changes made from the monitor"]).fg(theme.muted),
+            );
+        }
+        let block = Block::default()
+            .title(format!(" Audit, {} changes ", model.audit.len()))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        self.list.set_table_area(block.inner(table_rect), 1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Length(14),
+                Constraint::Length(16),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(16),
+            ],
+        )
+        .block(block)
+        .header(Row::new(HEADER.to_vec()).bold())
+        .row_highlight_style(Style::new().bg(theme.selection))
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_rect, frame.buffer_mut(), &mut self.list.state);
+
+        // the values do not fit the table, e.g. the whole port configuration
+        let selected = self
+            .list
+            .selected()
+            .and_then(|id| model.audit.iter().find(|record| record.id == id));
+        if let Some(record) = selected {
+            let details = Text::from(vec![
+                Line::from(format!("Old: {}", value(&record.old))).fg(theme.muted),
+                Line::from(format!("New: {}", value(&record.new))).fg(theme.text),
+                Line::from(record.result()).fg(if record.accepted() {
+                    theme.ok
+                } else {
+                    theme.error
+                }),
+            ]);
+            frame.render_widget(
+                Paragraph::new(details)
+                    .wrap(Wrap { trim: false })
+                    .block(Block::default().borders(Borders::TOP)),
+                details_rect,
+            );
+        }
+
+        frame.render_widget(
+            Line::from(" ▲▼ select, ctrl + Home/End first/last").fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for AuditPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+                return None;
+            }
+            _ => return None,
+        };
+        match key.code {
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down => self.list.select_next(),
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.list.select_first(),
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.list.select_last(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for AuditPage {
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new("audit", HEADER.to_vec());
+        for record in model.audit.iter().rev() {
+            export.add_row(cells(record));
+        }
+        Some(export)
+    }
+}
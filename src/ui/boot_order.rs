@@ -0,0 +1,219 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use log::debug;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear},
+    Frame,
+};
+
+use crate::{
+    actions::MonActions,
+    ipc::eve_types::EfiBootOrderUpdate,
+    model::{
+        device::efi::{
+            boot_order_bytes, efi_boot_current, efi_boot_entries, efi_boot_order, EfiBootEntry,
+        },
+        model::Model,
+        sanitize::sanitize_line,
+    },
+    traits::IWindow,
+    ui::action::UiActions,
+};
+
+use super::{
+    action::Action,
+    widgets::{button::ButtonElement, reorder_list::ReorderListElement},
+    window::Window,
+};
+
+struct BootOrderState {
+    // BootOrder as the firmware has it
+    previous: Vec<u16>,
+    // indexes into previous in the edited order
+    order: Vec<usize>,
+    // by index into previous
+    enabled: Vec<bool>,
+    initially_enabled: Vec<bool>,
+}
+
+impl BootOrderState {
+    fn update(&self) -> Option<EfiBootOrderUpdate> {
+        let order: Vec<u16> = self.order.iter().map(|i| self.previous[*i]).collect();
+        if order == self.previous && self.enabled == self.initially_enabled {
+            return None;
+        }
+        Some(EfiBootOrderUpdate {
+            boot_order: boot_order_bytes(&order),
+            inactive: self
+                .previous
+                .iter()
+                .zip(self.enabled.iter())
+                .filter(|(_, enabled)| !**enabled)
+                .map(|(number, _)| *number)
+                .collect(),
+        })
+    }
+}
+
+fn entry_label(number: u16, entry: Option<&EfiBootEntry>, current: Option<u16>) -> String {
+    let booted = if current == Some(number) {
+        " (booted)"
+    } else {
+        ""
+    };
+    match entry {
+        Some(entry) => format!(
+            "Boot{:04X} {}{}",
+            number,
+            sanitize_line(&entry.description),
+            booted
+        ),
+        None => format!("Boot{:04X} (no such entry){}", number, booted),
+    }
+}
+
+fn do_layout(w: &mut Window<BootOrderState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = crate::ui::tools::centered_rect_fixed(72, 20, *rect);
+    let inner = frame_rect.inner(Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+    let [hint, list, buttons] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Fill(1),
+        Constraint::Length(3),
+    ])
+    .areas(inner);
+    let [apply, cancel] = Layout::horizontal([Constraint::Length(9), Constraint::Length(10)])
+        .flex(Flex::End)
+        .areas(buttons);
+    w.update_layout("frame", frame_rect);
+    w.update_layout("hint", hint);
+    w.update_layout("list", list);
+    w.update_layout("apply", apply);
+    w.update_layout("cancel", cancel);
+}
+
+fn do_render(
+    w: &mut Window<BootOrderState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    let hint = w.get_layout("hint");
+    frame.render_widget(Clear {}, frame_rect);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(" Boot order ");
+    frame.render_widget(block, frame_rect);
+    frame.render_widget(
+        Line::from("EVE writes the new order to the firmware, it is used on the next boot")
+            .fg(theme.muted),
+        hint,
+    );
+}
+
+fn on_key_event(w: &mut Window<BootOrderState>, key: KeyEvent) -> Option<Action> {
+    if key.code == KeyCode::Esc {
+        return Some(Action::new(&w.name, UiActions::DismissDialog));
+    }
+    None
+}
+
+fn on_child_ui_action(
+    w: &mut Window<BootOrderState>,
+    source: &String,
+    action: &UiActions,
+) -> Option<Action> {
+    debug!("boot order: {}:{:?}", source, action);
+    match action {
+        UiActions::OrderChanged { order, enabled } => {
+            w.state.order = order.clone();
+            w.state.enabled = enabled.clone();
+            Some(Action::new(&w.name, UiActions::Redraw))
+        }
+        UiActions::ButtonClicked(name) if name == "apply" => match w.state.update() {
+            Some(update) => Some(Action::new(
+                &w.name,
+                UiActions::AppAction(MonActions::SetBootOrder {
+                    update,
+                    previous: w.state.previous.clone(),
+                }),
+            )),
+            // nothing changed
+            None => Some(Action::new(&w.name, UiActions::DismissDialog)),
+        },
+        UiActions::ButtonClicked(_) => Some(Action::new(&w.name, UiActions::DismissDialog)),
+        _ => None,
+    }
+}
+
+pub fn create_boot_order_editor() -> impl IWindow {
+    let previous = efi_boot_order();
+    let entries = efi_boot_entries();
+    let current = efi_boot_current();
+    let items: Vec<(String, bool)> = previous
+        .iter()
+        .map(|number| {
+            let entry = entries.iter().find(|e| e.number == *number);
+            (
+                entry_label(*number, entry, current),
+                entry.is_none_or(|e| e.active),
+            )
+        })
+        .collect();
+    let enabled: Vec<bool> = items.iter().map(|(_, enabled)| *enabled).collect();
+
+    Window::builder("boot order")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_on_child_ui_action(on_child_ui_action)
+        .widget("list", ReorderListElement::new("Entries", items))
+        .widget("apply", ButtonElement::new("apply"))
+        .widget("cancel", ButtonElement::new("cancel"))
+        .with_taborder(vec!["list".into(), "apply".into(), "cancel".into()])
+        .with_focused_view("list")
+        .with_state(BootOrderState {
+            order: (0..previous.len()).collect(),
+            previous,
+            initially_enabled: enabled.clone(),
+            enabled,
+        })
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boot_order_update() {
+        let mut state = BootOrderState {
+            previous: vec![1, 2, 3],
+            order: vec![0, 1, 2],
+            enabled: vec![true, true, false],
+            initially_enabled: vec![true, true, false],
+        };
+        assert_eq!(state.update(), None);
+        state.order = vec![2, 0, 1];
+        state.enabled[1] = false;
+        assert_eq!(
+            state.update(),
+            Some(EfiBootOrderUpdate {
+                boot_order: vec![3, 0, 1, 0, 2, 0],
+                inactive: vec![2, 3],
+            })
+        );
+    }
+}
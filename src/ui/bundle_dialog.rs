@@ -0,0 +1,107 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Gauge, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    model::{bundle::BundleProgress, model::Model},
+    traits::IWindow,
+    ui::action::UiActions,
+};
+
+use super::{action::Action, window::Window};
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 8;
+
+fn do_layout(w: &mut Window<()>, rect: &Rect, _model: &Rc<Model>) {
+    let [frame_rect] = Layout::horizontal([Constraint::Length(WIDTH)])
+        .flex(Flex::Center)
+        .areas(*rect);
+    let [frame_rect] = Layout::vertical([Constraint::Length(HEIGHT)])
+        .flex(Flex::Center)
+        .areas(frame_rect);
+    let [gauge_rect, text_rect] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+        .spacing(1)
+        .areas(frame_rect.inner(Margin {
+            horizontal: 2,
+            vertical: 1,
+        }));
+    w.update_layout("frame", frame_rect);
+    w.update_layout("gauge", gauge_rect);
+    w.update_layout("text", text_rect);
+}
+
+fn do_render(w: &mut Window<()>, _rect: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>) {
+    let model = model.borrow();
+    let theme = model.theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    // the bundler keeps running when the dialog is hidden
+    let title = if model.bundle.as_ref().is_some_and(|b| b.is_running()) {
+        " Support bundle (Esc to hide) "
+    } else {
+        " Support bundle (Esc to close) "
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(title);
+    frame.render_widget(block, frame_rect);
+
+    let (ratio, label, text) = match &model.bundle {
+        Some(BundleProgress::Running { step, done, total }) => (
+            *done as f64 / (*total).max(1) as f64,
+            format!("{}/{}", done, total),
+            Line::from(format!("{}...", step)).fg(theme.text),
+        ),
+        Some(BundleProgress::Completed(path)) => (
+            1.0,
+            "done".to_string(),
+            Line::from(format!("Saved to {}", path.display())).fg(theme.ok),
+        ),
+        Some(BundleProgress::Failed(error)) => (
+            0.0,
+            "failed".to_string(),
+            Line::from(error.clone()).fg(theme.error),
+        ),
+        None => return,
+    };
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(Style::new().fg(theme.accent).bg(theme.surface))
+            .ratio(ratio.clamp(0.0, 1.0))
+            .label(label),
+        w.get_layout("gauge"),
+    );
+    frame.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: false }),
+        w.get_layout("text"),
+    );
+}
+
+fn on_key_event(w: &mut Window<()>, key: KeyEvent) -> Option<Action> {
+    match key.code {
+        KeyCode::Esc | KeyCode::Enter => Some(Action::new(&w.name, UiActions::CloseBundleDialog)),
+        _ => None,
+    }
+}
+
+pub fn create_bundle_dialog() -> impl IWindow {
+    Window::builder("bundle")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(())
+        .build()
+        .unwrap()
+}
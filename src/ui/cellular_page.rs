@@ -0,0 +1,335 @@
+use std::rc::Rc;
+
+use chrono::DateTime;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, Borders, Padding, Paragraph},
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    ipc::eve_types::{SimType, WwanProvider, WwanRAT, WwanSimCard},
+    model::{
+        device::network::{CellularStatus, NetworkType},
+        model::Model,
+        sanitize::sanitize_line,
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::Action,
+};
+
+use super::theme::Theme;
+
+/// State of the LTE/5G modems of cellular ports as reported by EVE
+#[derive(Debug, Default)]
+pub struct CellularPage {
+    scroll: u16,
+    // lines and height at the last frame to stop scrolling at the end
+    lines: u16,
+    page: u16,
+}
+
+impl CellularPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.lines.saturating_sub(self.page)
+    }
+}
+
+fn rat_name(rat: &WwanRAT) -> &'static str {
+    match rat {
+        WwanRAT::WwanRATUnspecified => "unspecified",
+        WwanRAT::WwanRATGSM => "GSM",
+        WwanRAT::WwanRATUMTS => "UMTS",
+        WwanRAT::WwanRATLTE => "LTE",
+        WwanRAT::WwanRAT5GNR => "5G NR",
+        WwanRAT::Unrecognized => "unknown",
+    }
+}
+
+fn or_na(text: &str) -> String {
+    if text.is_empty() {
+        "N/A".to_string()
+    } else {
+        sanitize_line(text)
+    }
+}
+
+fn colored_field(label: &str, value: String, color: Color, theme: &Theme) -> Line<'static> {
+    Line::from(vec![
+        Span::from(format!("  {:<12}", label)).fg(theme.warning),
+        Span::from(value).fg(color),
+    ])
+}
+
+fn field(label: &str, value: String, theme: &Theme) -> Line<'static> {
+    colored_field(label, value, theme.text, theme)
+}
+
+fn provider_text(provider: &WwanProvider) -> String {
+    let mut text = match (provider.description.as_str(), provider.plmn.as_str()) {
+        ("", "") => return "N/A".to_string(),
+        (name, "") => sanitize_line(name),
+        ("", plmn) => sanitize_line(plmn),
+        (name, plmn) => format!("{} ({})", sanitize_line(name), sanitize_line(plmn)),
+    };
+    if provider.roaming {
+        text.push_str(", roaming");
+    }
+    if provider.forbidden {
+        text.push_str(", forbidden");
+    }
+    text
+}
+
+fn sim_line(sim: &WwanSimCard, apn: Option<&str>, theme: &Theme) -> Line<'static> {
+    let sim_type = match sim.sim_type {
+        SimType::Physical => "physical",
+        SimType::Embedded => "eSIM",
+        SimType::Unspecified | SimType::Unrecognized => "SIM",
+    };
+    let mut text = format!("{} {}", sim_type, or_na(&sim.state));
+    if !sim.iccid.is_empty() {
+        text.push_str(&format!(", ICCID {}", sanitize_line(&sim.iccid)));
+    }
+    if !sim.imsi.is_empty() {
+        text.push_str(&format!(", IMSI {}", sanitize_line(&sim.imsi)));
+    }
+    if let Some(apn) = apn {
+        text.push_str(&format!(", APN {}", or_na(apn)));
+    }
+    let label = format!("SIM slot {}", sim.slot_number);
+    let line = field(&label, text, theme);
+    if sim.slot_activated {
+        line.bold()
+    } else {
+        line
+    }
+}
+
+fn modem_lines(name: &str, cellular: &CellularStatus, theme: &Theme) -> Vec<Line<'static>> {
+    let modem = &cellular.modem;
+    let module = &modem.module;
+    let mut lines = vec![Line::from(format!(
+        "{} {}",
+        sanitize_line(name),
+        sanitize_line(&modem.logical_label)
+    ))
+    .fg(theme.accent)
+    .bold()];
+
+    let state_color = match module.op_mode.as_str() {
+        "online-and-connected" => theme.ok,
+        "" | "offline" | "radio-off" => theme.error,
+        _ => theme.warning,
+    };
+    let mut state = or_na(&module.op_mode);
+    if let Some(since) =
+        DateTime::from_timestamp(modem.connected_at as i64, 0).filter(|_| modem.connected_at > 0)
+    {
+        state.push_str(&format!(
+            ", connected since {}",
+            since.format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+    lines.push(colored_field("State", state, state_color, theme));
+
+    let product = [&module.manufacturer, &module.model]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| sanitize_line(s))
+        .collect::<Vec<_>>()
+        .join(" ");
+    lines.push(field(
+        "Modem",
+        format!(
+            "{}, firmware {}, {}",
+            or_na(&product),
+            or_na(&module.revision),
+            or_na(&module.control_protocol).to_uppercase()
+        ),
+        theme,
+    ));
+    lines.push(field("IMEI", or_na(&module.imei), theme));
+    let addrs = &modem.phys_addrs;
+    lines.push(field(
+        "Device",
+        format!(
+            "{} USB {} PCI {} {}",
+            or_na(&addrs.interface),
+            or_na(&addrs.usb),
+            or_na(&addrs.pci),
+            sanitize_line(&addrs.dev)
+        ),
+        theme,
+    ));
+
+    lines.push(field(
+        "Provider",
+        provider_text(&modem.current_provider),
+        theme,
+    ));
+    let rats = modem
+        .current_rats
+        .iter()
+        .flatten()
+        .map(rat_name)
+        .collect::<Vec<_>>();
+    lines.push(field(
+        "RAT",
+        if rats.is_empty() {
+            "N/A".to_string()
+        } else {
+            rats.join(", ")
+        },
+        theme,
+    ));
+
+    let ip = &modem.ip_settings;
+    lines.push(field(
+        "Address",
+        ip.address.map_or("N/A".to_string(), |a| a.to_string()),
+        theme,
+    ));
+    lines.push(field(
+        "Gateway",
+        ip.gateway.map_or("N/A".to_string(), |gw| gw.to_string()),
+        theme,
+    ));
+    let dns = ip
+        .dns_servers
+        .iter()
+        .flatten()
+        .map(|dns| dns.to_string())
+        .collect::<Vec<_>>();
+    lines.push(field(
+        "DNS",
+        if dns.is_empty() {
+            "N/A".to_string()
+        } else {
+            dns.join(", ")
+        },
+        theme,
+    ));
+    lines.push(field("MTU", ip.mtu.to_string(), theme));
+
+    // the SIM cards the modem sees with the APN configured for their slot
+    for sim in modem.sim_cards.iter().flatten() {
+        let apn = cellular
+            .sims
+            .iter()
+            .find(|ap| {
+                ap.slot == u32::from(sim.slot_number) || (ap.slot == 0 && sim.slot_activated)
+            })
+            .map(|ap| ap.apn.as_str());
+        lines.push(sim_line(sim, apn, theme));
+    }
+    if modem.sim_cards.as_ref().is_none_or(|sims| sims.is_empty()) {
+        lines.push(colored_field(
+            "SIM",
+            "no SIM card detected".to_string(),
+            theme.warning,
+            theme,
+        ));
+    }
+
+    let visible = modem
+        .visible_providers
+        .iter()
+        .flatten()
+        .filter(|p| !p.current_serving)
+        .map(provider_text)
+        .collect::<Vec<_>>();
+    if !visible.is_empty() {
+        lines.push(field("Visible", visible.join("; "), theme));
+    }
+
+    for (label, error) in [
+        ("Config error", &modem.config_error),
+        ("Probe error", &modem.probe_error),
+    ] {
+        if !error.is_empty() {
+            lines.push(colored_field(
+                label,
+                sanitize_line(error),
+                theme.error,
+                theme,
+            ));
+        }
+    }
+    lines.push(Line::from(""));
+    lines
+}
+
+impl IPresenter for CellularPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let model = model.borrow();
+        let theme = model.theme;
+        let [body_rect, help_rect] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(*area);
+
+        let mut lines: Vec<Line> = model
+            .network
+            .iter()
+            .filter_map(|iface| match &iface.media {
+                NetworkType::Cellular(cellular) => Some(modem_lines(&iface.name, cellular, &theme)),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("There are no cellular ports on this device").fg(theme.muted));
+        }
+
+        let block = Block::default()
+            .title(" Cellular ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        self.lines = lines.len() as u16;
+        self.page = block.inner(body_rect).height;
+        self.scroll = self.scroll.min(self.max_scroll());
+        frame.render_widget(
+            Paragraph::new(Text::from(lines))
+                .scroll((self.scroll, 0))
+                .block(block),
+            body_rect,
+        );
+        frame.render_widget(
+            Line::from(" ▲▼ scroll. The active SIM slot is shown in bold").fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for CellularPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match key.code {
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(self.max_scroll()),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(self.page),
+            KeyCode::PageDown => {
+                self.scroll = self.scroll.saturating_add(self.page).min(self.max_scroll())
+            }
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.scroll = 0,
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => {
+                self.scroll = self.max_scroll()
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for CellularPage {}
@@ -0,0 +1,247 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{model::model::Model, traits::IWindow, ui::action::UiActions};
+
+use super::{action::Action, ui::UiTabs, window::Window};
+
+/// An entry of the command palette. Pages and windows contribute the commands
+/// they support, `keys` is the shortcut that does the same
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub title: String,
+    pub keys: String,
+    pub action: UiActions,
+    // the tab to switch to before the action runs, set for commands of other tabs
+    pub tab: Option<UiTabs>,
+}
+
+impl Command {
+    pub fn new<S: Into<String>, K: Into<String>>(title: S, keys: K, action: UiActions) -> Self {
+        Self {
+            title: title.into(),
+            keys: keys.into(),
+            action,
+            tab: None,
+        }
+    }
+}
+
+/// Positions of the characters of `query` in `text` in order, ignoring case,
+/// and a score that is higher for matches at word starts and for runs of
+/// characters. None if `text` does not contain them all
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let text: Vec<char> = text.chars().collect();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut from = 0;
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let q = q.to_lowercase().next()?;
+        let pos = from
+            + text[from..]
+                .iter()
+                .position(|c| c.to_lowercase().next() == Some(q))?;
+        let word_start = pos == 0 || !text[pos - 1].is_alphanumeric();
+        let follows = positions.last().is_some_and(|&last| last + 1 == pos);
+        score += match (word_start, follows) {
+            (true, _) => 10,
+            (false, true) => 5,
+            (false, false) => 1,
+        };
+        positions.push(pos);
+        from = pos + 1;
+    }
+    // shorter titles first among equal matches
+    Some((score * 100 - text.len() as i32, positions))
+}
+
+struct CommandPaletteState {
+    commands: Vec<Command>,
+    query: String,
+    // indices of the matching commands, best first, with matched characters
+    matches: Vec<(usize, Vec<usize>)>,
+    selected: usize,
+}
+
+impl CommandPaletteState {
+    fn update_matches(&mut self) {
+        let mut matches: Vec<(i32, usize, Vec<usize>)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, command)| {
+                let (score, positions) = fuzzy_match(&self.query, &command.title)?;
+                Some((score, i, positions))
+            })
+            .collect();
+        // all commands in their order until something is typed. The sort is
+        // stable, commands with equal score keep their order too
+        if !self.query.trim().is_empty() {
+            matches.sort_by_key(|(score, _, _)| -score);
+        }
+        self.matches = matches
+            .into_iter()
+            .map(|(_, i, positions)| (i, positions))
+            .collect();
+        self.selected = 0;
+    }
+}
+
+fn title_line(command: &Command, positions: &[usize], width: usize) -> Line<'static> {
+    let mut spans: Vec<Span> = command
+        .title
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if positions.contains(&i) {
+                Span::from(c.to_string()).bold().underlined()
+            } else {
+                Span::from(c.to_string())
+            }
+        })
+        .collect();
+    // shortcut at the right edge
+    let pad = width.saturating_sub(command.title.chars().count() + command.keys.len() + 2);
+    spans.push(Span::from(" ".repeat(pad + 1)));
+    spans.push(Span::from(command.keys.clone()).italic());
+    Line::from(spans)
+}
+
+fn do_layout(w: &mut Window<CommandPaletteState>, rect: &Rect, _model: &Rc<Model>) {
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(*rect);
+    // input line, up to 12 commands and the borders
+    let height = (w.state.commands.len() as u16).min(12) + 4;
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Start)
+        .areas(area);
+    w.update_layout("frame", area);
+}
+
+fn do_render(
+    w: &mut Window<CommandPaletteState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(" Commands ")
+        .title_bottom(" ▲▼ select, Enter run, Esc close ");
+    let inner = block.inner(frame_rect);
+    let width = inner.width as usize;
+    let page = inner.height.saturating_sub(2) as usize;
+
+    let state = &w.state;
+    let mut lines = vec![
+        Line::from(vec![
+            Span::from("> ").fg(theme.accent),
+            Span::from(state.query.clone()).fg(theme.text),
+        ]),
+        Line::from("─".repeat(width)).fg(theme.border),
+    ];
+    if state.matches.is_empty() {
+        lines.push(Line::from("No matching commands").fg(theme.muted));
+    }
+    // keep the selection on the page
+    let first = (state.selected + 1).saturating_sub(page);
+    lines.extend(state.matches.iter().enumerate().skip(first).take(page).map(
+        |(i, (index, positions))| {
+            let line = title_line(&state.commands[*index], positions, width).fg(theme.text);
+            if i == state.selected {
+                line.bg(theme.selection)
+            } else {
+                line
+            }
+        },
+    ));
+
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+    frame.set_cursor_position((inner.x + 2 + state.query.chars().count() as u16, inner.y));
+}
+
+fn on_key_event(w: &mut Window<CommandPaletteState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    let last = state.matches.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Enter => {
+            let (index, _) = state.matches.get(state.selected)?;
+            let command = state.commands[*index].clone();
+            return Some(Action::new(
+                &w.name,
+                UiActions::RunCommand(Box::new(command)),
+            ));
+        }
+        KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Down => state.selected = (state.selected + 1).min(last),
+        KeyCode::Backspace => {
+            state.query.pop();
+            state.update_matches();
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            state.query.push(c);
+            state.update_matches();
+        }
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+pub fn create_command_palette(commands: Vec<Command>) -> impl IWindow {
+    let mut state = CommandPaletteState {
+        commands,
+        query: String::new(),
+        matches: Vec::new(),
+        selected: 0,
+    };
+    state.update_matches();
+    Window::builder("command palette")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(state)
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("", "Change server"), Some((-13, vec![])));
+        assert_eq!(
+            fuzzy_match("chs", "Change server").map(|(_, p)| p),
+            Some(vec![0, 1, 7])
+        );
+        assert_eq!(fuzzy_match("sc", "Change server"), None);
+
+        // word starts and runs rank higher than scattered characters
+        let score = |query, text| fuzzy_match(query, text).unwrap().0;
+        assert!(score("nt", "Go to Network") > score("nt", "Print"));
+        assert!(score("ex", "Export view") > score("ex", "Next export"));
+        // blanks in the query are ignored
+        assert_eq!(
+            fuzzy_match("as csv", "Export view as CSV").map(|(_, p)| p),
+            Some(vec![12, 13, 15, 16, 17])
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use log::debug;
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::Style,
+    widgets::{Block, BorderType, Borders, Clear},
+    Frame,
+};
+
+use crate::{model::model::Model, traits::IWindow, ui::action::UiActions};
+
+use super::{
+    action::Action,
+    widgets::{button::ButtonElement, label::LabelElement},
+    window::Window,
+};
+
+struct ConfirmDialogState {
+    content: String,
+    // sent to the application when the user presses "yes"
+    on_yes: UiActions,
+}
+
+fn on_init(w: &mut Window<ConfirmDialogState>) {
+    w.add_widget("label", LabelElement::new(w.state.content.clone()));
+    // buttons
+    w.add_widget("yes", ButtonElement::new("yes"));
+    w.add_widget("no", ButtonElement::new("no"));
+
+    w.set_focus_tracker_tab_order(vec!["no", "yes"]);
+}
+
+fn do_render(
+    w: &mut Window<ConfirmDialogState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+
+    // clear area under the dialog
+    let clear = Clear {};
+    frame.render_widget(clear, frame_rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(w.name.clone());
+
+    frame.render_widget(block, frame_rect);
+}
+
+fn do_layout(w: &mut Window<ConfirmDialogState>, rect: &Rect, _model: &Rc<Model>) {
+    let rect = crate::ui::tools::centered_rect_fixed(50, 10, *rect);
+    let content_with_buttons = rect.inner(Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+
+    w.update_layout("frame", rect);
+
+    let [dialog_content, buttons] =
+        Layout::vertical(vec![Constraint::Fill(1), Constraint::Length(3)])
+            .flex(Flex::End)
+            .areas(content_with_buttons);
+    w.update_layout("label", dialog_content);
+
+    let [yes, no] = Layout::horizontal(vec![Constraint::Length(7), Constraint::Length(6)])
+        .flex(Flex::End)
+        .areas(buttons);
+    w.update_layout("yes", yes);
+    w.update_layout("no", no);
+}
+
+fn on_key_event(w: &mut Window<ConfirmDialogState>, key: KeyEvent) -> Option<Action> {
+    if key.code == KeyCode::Esc {
+        return Some(Action::new(&w.name, UiActions::DismissDialog));
+    }
+    None
+}
+
+fn on_child_ui_action(
+    w: &mut Window<ConfirmDialogState>,
+    source: &String,
+    action: &UiActions,
+) -> Option<Action> {
+    debug!("on_child_ui_action: {}:{:?}", source, action);
+    match action {
+        UiActions::ButtonClicked(name) => match name.as_str() {
+            "yes" => Some(Action::new(&w.name, w.state.on_yes.clone())),
+            _ => Some(Action::new(&w.name, UiActions::DismissDialog)),
+        },
+        _ => None,
+    }
+}
+
+/// Yes/No question. "yes" sends `on_yes` to the application which must close the dialog
+pub fn create_confirm_dialog(
+    window_caption: &str,
+    content: &str,
+    on_yes: UiActions,
+) -> impl IWindow {
+    Window::builder(window_caption)
+        .with_on_init(on_init)
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_on_child_ui_action(on_child_ui_action)
+        .with_state(ConfirmDialogState {
+            content: content.to_string(),
+            on_yes,
+        })
+        .build()
+        .unwrap()
+}
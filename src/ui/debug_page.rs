@@ -0,0 +1,321 @@
+use std::{collections::HashSet, rc::Rc};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, Borders, Cell, HighlightSpacing, Padding, Paragraph, Row,
+        StatefulWidget, Table,
+    },
+    Frame,
+};
+
+use crate::{
+    collectors::{CollectorHealth, CollectorStatus},
+    events::Event,
+    ipc::lenient::UnknownKind,
+    model::model::{Model, MonitorModel},
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::{Action, UiActions},
+};
+
+use super::{
+    command_palette::Command, export::TableExport, selection::KeyedSelection, theme::Theme,
+    traits::ISelector,
+};
+
+#[derive(Debug, Default)]
+pub struct DebugPage {
+    list: KeyedSelection<&'static str>,
+}
+
+impl DebugPage {
+    pub fn new() -> Self {
+        DebugPage {
+            ..Default::default()
+        }
+    }
+
+    fn render_collectors(&mut self, model: &Rc<Model>, rect: Rect, frame: &mut Frame) {
+        let theme = model.borrow().theme;
+        let header = Row::new(vec![
+            Cell::from("Collector"),
+            Cell::from("Events"),
+            Cell::from("Restarts"),
+            Cell::from("Health"),
+        ]);
+
+        let collectors = &model.borrow().collectors;
+        let rows = collectors
+            .iter()
+            .map(|c| row_from_collector(c, &theme))
+            .collect::<Vec<_>>();
+        self.list
+            .set_keys(collectors.iter().map(|c| c.name).collect());
+
+        let block = Block::default()
+            .title(" Collectors ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::new(1, 1, 1, 1));
+        self.list.set_table_area(block.inner(rect), 1);
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ],
+        )
+        .block(block)
+        .row_highlight_style(Style::new().bg(theme.selection))
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always)
+        .header(header);
+
+        StatefulWidget::render(table, rect, frame.buffer_mut(), &mut self.list.state);
+    }
+}
+
+fn message_stats_text(model: &MonitorModel) -> String {
+    let stats = &model.ipc_message_stats;
+    let received: u64 = stats.values().map(|s| s.received).sum();
+    let duplicates: u64 = stats.values().map(|s| s.duplicates).sum();
+    let per_type = stats
+        .iter()
+        .filter(|(_, s)| s.duplicates > 0)
+        .map(|(message_type, s)| format!("{} {}/{}", message_type, s.duplicates, s.received))
+        .collect::<Vec<_>>();
+    let mut text = format!(
+        "Messages: {} received, {} unchanged skipped",
+        received, duplicates
+    );
+    if !per_type.is_empty() {
+        text.push_str(&format!(" ({})", per_type.join(", ")));
+    }
+    text
+}
+
+fn action_stats_text(model: &MonitorModel) -> String {
+    let stats = &model.action_stats;
+    let mut busiest: Vec<(&String, &u64)> = stats.handled.iter().collect();
+    busiest.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+    let busiest = busiest
+        .iter()
+        .take(3)
+        .map(|(kind, count)| format!("{} {}", kind, count))
+        .collect::<Vec<_>>();
+    let mut text = format!(
+        "Actions: {} dispatched, {} refused",
+        stats.total(),
+        stats.blocked
+    );
+    if !busiest.is_empty() {
+        text.push_str(&format!(" ({})", busiest.join(", ")));
+    }
+    text
+}
+
+fn render_protocol(model: &MonitorModel, rect: Rect, frame: &mut Frame) {
+    let theme = model.theme;
+    let mut lines = vec![
+        Line::from(format!("Protocol: {}", model.protocol)),
+        Line::from(message_stats_text(model)),
+        Line::from(action_stats_text(model)),
+    ];
+    for (message_type, unsupported) in model.unsupported_messages.iter() {
+        let schema = unsupported
+            .schema
+            .map_or("unversioned".to_string(), |s| format!("schema {}", s));
+        lines.push(
+            Line::from(format!(
+                "{} ({}): {} messages not shown, {}",
+                message_type, schema, unsupported.count, unsupported.error
+            ))
+            .fg(theme.warning),
+        );
+    }
+    let unknown: usize = model.unknown_values.values().map(Vec::len).sum();
+    if unknown > 0 {
+        lines.push(Line::from(format!(
+            "{} values in {} message types are not understood, press 'u' to list them",
+            unknown,
+            model.unknown_values.len()
+        )));
+    }
+    let block = Block::default()
+        .title(" IPC protocol ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::TOP)
+        .border_type(BorderType::Plain)
+        .padding(Padding::horizontal(1));
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), rect);
+}
+
+/// Values EVE sent that are not understood, one per line. Unknown enum values
+/// are highlighted, they are the ones that may change how the data is shown
+pub fn unknown_values_text(model: &MonitorModel) -> (String, HashSet<usize>) {
+    let mut lines = Vec::new();
+    let mut highlight = HashSet::new();
+    for (message_type, values) in model.unknown_values.iter() {
+        lines.push(format!("{}:", message_type));
+        for value in values {
+            if value.kind == UnknownKind::EnumValue {
+                highlight.insert(lines.len());
+            }
+            lines.push(format!("  {} = {}", value.path, value.value));
+        }
+    }
+    if lines.is_empty() {
+        lines.push("All values sent by EVE are understood".to_string());
+    }
+    (lines.join("\n"), highlight)
+}
+
+fn row_from_collector(status: &CollectorStatus, theme: &Theme) -> Row<'static> {
+    let health_style = match status.health {
+        CollectorHealth::Running => Style::default().fg(theme.ok),
+        CollectorHealth::Stopped => Style::default().fg(theme.warning),
+        CollectorHealth::Failed(_) => Style::default().fg(theme.error),
+    };
+    Row::new(vec![
+        Cell::from(status.name),
+        Cell::from(status.events.to_string()),
+        Cell::from(status.restarts.to_string()),
+        Cell::from(status.health.to_string()).style(health_style),
+    ])
+}
+
+impl IPresenter for DebugPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let theme = model.borrow().theme;
+        // title, protocol state and a line per unsupported message type
+        let protocol_height = 5 + model.borrow().unsupported_messages.len() as u16;
+        let [collectors_rect, protocol_rect, help_rect] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(protocol_height),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+        self.render_collectors(model, collectors_rect, frame);
+        render_protocol(&model.borrow(), protocol_rect, frame);
+        frame.render_widget(
+            Line::from( " Press 'r' to restart the selected collector, 'k' to capture keys, 'u' to list unknown values, 'm' for the monitor log, 's' to save a model snapshot")
+                .fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for DebugPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+                return None;
+            }
+            _ => return None,
+        };
+        match key.code {
+            KeyCode::Up => self.select_previous(),
+            KeyCode::Down => self.select_next(),
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.select_first(),
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.select_last(),
+            KeyCode::Char('r') => {
+                let name = self.selected()?;
+                return Some(Action::new(
+                    "debug",
+                    UiActions::RestartCollector(name.to_string()),
+                ));
+            }
+            KeyCode::Char('k') => {
+                return Some(Action::new("debug", UiActions::StartKeyCapture));
+            }
+            KeyCode::Char('u') => {
+                return Some(Action::new("debug", UiActions::ViewUnknownValues));
+            }
+            KeyCode::Char('m') => {
+                return Some(Action::new("debug", UiActions::ShowMonitorLog));
+            }
+            KeyCode::Char('s') => {
+                return Some(Action::new("debug", UiActions::SaveModelSnapshot));
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for DebugPage {
+    fn commands(&self) -> Vec<Command> {
+        let mut commands = Vec::new();
+        if let Some(name) = self.selected() {
+            commands.push(Command::new(
+                format!("Restart collector {}", name),
+                "r",
+                UiActions::RestartCollector(name.to_string()),
+            ));
+        }
+        commands.extend([
+            Command::new(
+                "Record keys from the console",
+                "k",
+                UiActions::StartKeyCapture,
+            ),
+            Command::new("Show unknown values", "u", UiActions::ViewUnknownValues),
+            Command::new("Show the monitor log", "m", UiActions::ShowMonitorLog),
+            Command::new(
+                "Save a model snapshot for --replay",
+                "s",
+                UiActions::SaveModelSnapshot,
+            ),
+        ]);
+        commands
+    }
+
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new(
+            "collectors",
+            vec!["Collector", "Events", "Restarts", "Health"],
+        );
+        for c in model.collectors.iter() {
+            export.add_row(vec![
+                c.name.to_string(),
+                c.events.to_string(),
+                c.restarts.to_string(),
+                c.health.to_string(),
+            ]);
+        }
+        Some(export)
+    }
+}
+
+impl ISelector for DebugPage {
+    type Key = &'static str;
+
+    fn select_next(&mut self) {
+        self.list.select_next();
+    }
+
+    fn select_previous(&mut self) {
+        self.list.select_previous();
+    }
+
+    fn select_first(&mut self) {
+        self.list.select_first();
+    }
+
+    fn select_last(&mut self) {
+        self.list.select_last();
+    }
+
+    fn selected(&self) -> Option<&'static str> {
+        self.list.selected()
+    }
+}
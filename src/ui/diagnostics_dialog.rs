@@ -0,0 +1,124 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    model::{diagnostics::StepState, model::Model, sanitize::sanitize_line},
+    traits::IWindow,
+    ui::{action::UiActions, theme::Theme},
+};
+
+use super::{action::Action, window::Window};
+
+const WIDTH: u16 = 90;
+const HEIGHT: u16 = 14;
+
+struct DiagnosticsState {
+    iface: String,
+}
+
+fn step_lines(name: &str, state: &StepState, theme: &Theme) -> Vec<Line<'static>> {
+    let (mark, detail, color) = match state {
+        StepState::Pending => ("[    ]", None, theme.muted),
+        StepState::Running => ("[ .. ]", None, theme.accent),
+        StepState::Passed(detail) => ("[ OK ]", Some(detail), theme.ok),
+        StepState::Failed(detail) => ("[FAIL]", Some(detail), theme.error),
+        StepState::Skipped(detail) => ("[SKIP]", Some(detail), theme.muted),
+    };
+    let mut lines = vec![Line::from(vec![
+        format!(" {} ", mark).fg(color).bold(),
+        name.to_string().fg(theme.text),
+    ])];
+    if let Some(detail) = detail {
+        lines.push(Line::from(format!("        {}", sanitize_line(detail))).fg(theme.muted));
+    }
+    lines
+}
+
+fn do_layout(w: &mut Window<DiagnosticsState>, rect: &Rect, _model: &Rc<Model>) {
+    let [frame_rect] = Layout::horizontal([Constraint::Length(WIDTH)])
+        .flex(Flex::Center)
+        .areas(*rect);
+    let [frame_rect] = Layout::vertical([Constraint::Length(HEIGHT)])
+        .flex(Flex::Center)
+        .areas(frame_rect);
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<DiagnosticsState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let model = model.borrow();
+    let mut lines = Vec::new();
+    let mut summary = None;
+    if let Some(report) = model.diagnostics.as_ref() {
+        for step in report.steps.iter() {
+            lines.extend(step_lines(&step.name, &step.state, &theme));
+        }
+        if report.is_finished() {
+            summary = Some(match report.failures() {
+                0 => Line::from(" All checks passed").fg(theme.ok),
+                n => Line::from(format!(" {} of {} checks failed", n, report.steps.len()))
+                    .fg(theme.error),
+            });
+        }
+    }
+    if let Some(summary) = summary {
+        lines.push(Line::from(""));
+        lines.push(summary);
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(format!(
+            " Diagnostics of {} (r to run again, Esc to close) ",
+            w.state.iface
+        ));
+
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(block),
+        frame_rect,
+    );
+}
+
+fn on_key_event(w: &mut Window<DiagnosticsState>, key: KeyEvent) -> Option<Action> {
+    match key.code {
+        KeyCode::Esc => Some(Action::new(&w.name, UiActions::CloseDiagnostics)),
+        KeyCode::Char('r') => Some(Action::new(
+            &w.name,
+            UiActions::Diagnose(w.state.iface.clone()),
+        )),
+        _ => None,
+    }
+}
+
+pub fn create_diagnostics_dialog(iface: &str) -> impl IWindow {
+    Window::builder("diagnostics")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(DiagnosticsState {
+            iface: iface.to_string(),
+        })
+        .build()
+        .unwrap()
+}
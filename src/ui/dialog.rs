@@ -11,7 +11,7 @@ use crossterm::event::KeyEvent;
 use log::{info, trace};
 use ratatui::{
     layout::{self, Constraint, Flex, Rect},
-    style::{Color, Style},
+    style::Style,
     widgets::{Block, BorderType, Borders, Widget},
     Frame,
 };
@@ -126,9 +126,10 @@ impl<D: 'static> IPresenter for Dialog<D> {
         &mut self,
         area: &Rect,
         frame: &mut Frame<'_>,
-        _model: &Rc<Model>,
+        model: &Rc<Model>,
         dialog_focused: bool,
     ) {
+        let theme = model.borrow().theme;
         trace!("Rendering dialog: {}", self.name);
         self.do_layout(area);
 
@@ -141,8 +142,8 @@ impl<D: 'static> IPresenter for Dialog<D> {
             //FIXME: need new Font
             //.border_type(BorderType::Thick)
             .border_type(BorderType::Double)
-            .border_style(Style::default().fg(Color::White))
-            .style(Style::default().bg(Color::Black))
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.surface))
             .title(self.name.as_str());
 
         block.render(*frame_rect, frame.buffer_mut());
@@ -0,0 +1,171 @@
+use std::rc::Rc;
+
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    ipc::eve_types::DevicePortConfig,
+    model::{model::Model, sanitize::sanitize_line},
+    traits::IWindow,
+    ui::{action::UiActions, theme::Theme},
+};
+
+use super::{action::Action, window::Window};
+
+struct DpcHistoryState {
+    selected: usize,
+    // entries at the last frame, key handlers have no model
+    entries: Vec<(String, DateTime<Utc>)>,
+}
+
+fn time_text(time: &DateTime<Utc>) -> String {
+    // EVE sends the zero time for tests that did not happen
+    if time.timestamp() <= 0 {
+        "never".to_string()
+    } else {
+        time.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+fn entry_line(dpc: &DevicePortConfig, current: bool, theme: &Theme) -> Line<'static> {
+    let results = &dpc.test_results;
+    let (test, color) = if dpc.last_test_failed() {
+        (
+            format!("failed {}", time_text(&results.last_failed)),
+            theme.error,
+        )
+    } else {
+        (
+            format!("OK {}", time_text(&results.last_succeeded)),
+            theme.ok,
+        )
+    };
+    Line::from(vec![
+        format!(
+            " {} {:<10} {} {:<16} ",
+            if current { "*" } else { " " },
+            sanitize_line(&dpc.key),
+            time_text(&dpc.time_priority),
+            format!("{:?}", dpc.state),
+        )
+        .fg(theme.text),
+        test.fg(color),
+    ])
+}
+
+fn do_layout(w: &mut Window<DpcHistoryState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 4,
+        vertical: 2,
+    });
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<DpcHistoryState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let model = model.borrow();
+    let state = &mut w.state;
+    let list = model.get_dpc_list();
+    let dpcs = list
+        .and_then(|list| list.port_config_list.as_ref())
+        .map(|dpcs| dpcs.as_slice())
+        .unwrap_or_default();
+    let current = list.map_or(-1, |list| list.current_index);
+
+    state.entries = dpcs
+        .iter()
+        .map(|dpc| (dpc.key.clone(), dpc.time_priority))
+        .collect();
+    state.selected = state.selected.min(dpcs.len().saturating_sub(1));
+
+    let mut lines: Vec<Line> = dpcs
+        .iter()
+        .enumerate()
+        .map(|(i, dpc)| {
+            let line = entry_line(dpc, i as i32 == current, &theme);
+            if i == state.selected {
+                line.reversed()
+            } else {
+                line
+            }
+        })
+        .collect();
+    if let Some(dpc) = dpcs.get(state.selected) {
+        if !dpc.test_results.last_error.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(format!(
+                    "Last error: {}",
+                    sanitize_line(&dpc.test_results.last_error)
+                ))
+                .fg(theme.error),
+            );
+        }
+    } else {
+        lines.push(Line::from("EVE did not report any network configuration").fg(theme.muted));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(" Network configurations (* in use, Enter to apply again, Esc to close) ");
+
+    frame.render_widget(
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(block),
+        frame_rect,
+    );
+}
+
+fn on_key_event(w: &mut Window<DpcHistoryState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Enter => {
+            let (key, time_priority) = state.entries.get(state.selected)?.clone();
+            return Some(Action::new(
+                &w.name,
+                UiActions::RollbackDpc(key, time_priority),
+            ));
+        }
+        KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Down => {
+            state.selected = (state.selected + 1).min(state.entries.len().saturating_sub(1))
+        }
+        KeyCode::Home => state.selected = 0,
+        KeyCode::End => state.selected = state.entries.len().saturating_sub(1),
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+pub fn create_dpc_history() -> impl IWindow {
+    Window::builder("dpc history")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(DpcHistoryState {
+            selected: 0,
+            entries: Vec::new(),
+        })
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,169 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::{
+        device::efi::{efi_variable_names, read_efi_variable, EfiVariable},
+        model::Model,
+    },
+    traits::IWindow,
+    ui::action::UiActions,
+};
+
+use super::{action::Action, widgets::hexdump::HexDumpElement, window::Window};
+
+const LIST_WIDTH: u16 = 32;
+
+struct EfiVariablesState {
+    // efivarfs file names, the name and the vendor GUID
+    names: Vec<String>,
+    selected: usize,
+    variable: Option<EfiVariable>,
+}
+
+// read the selected variable and show its data
+fn load_selected(w: &mut Window<EfiVariablesState>) {
+    let variable = w
+        .state
+        .names
+        .get(w.state.selected)
+        .and_then(|name| read_efi_variable(name));
+    let data = variable
+        .as_ref()
+        .map(|v| v.data.clone())
+        .unwrap_or_default();
+    w.state.variable = variable;
+    w.add_widget("hexdump", HexDumpElement::new(data));
+}
+
+fn do_layout(w: &mut Window<EfiVariablesState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 4,
+        vertical: 2,
+    });
+    let inner = frame_rect.inner(Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+    let [list_rect, data_rect] =
+        Layout::horizontal([Constraint::Length(LIST_WIDTH), Constraint::Fill(1)])
+            .spacing(1)
+            .areas(inner);
+    let [header_rect, hexdump_rect] =
+        Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(data_rect);
+    w.update_layout("frame", frame_rect);
+    w.update_layout("list", list_rect);
+    w.update_layout("header", header_rect);
+    w.update_layout("hexdump", hexdump_rect);
+}
+
+fn do_render(
+    w: &mut Window<EfiVariablesState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    let list_rect = w.get_layout("list");
+    let header_rect = w.get_layout("header");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(" EFI variables (▲▼ select, PgUp/PgDn scroll the data, Esc to close) ");
+    frame.render_widget(block, frame_rect);
+
+    let state = &w.state;
+    if state.names.is_empty() {
+        frame.render_widget(
+            Line::from("No EFI variables, the system did not boot with EFI").fg(theme.muted),
+            list_rect,
+        );
+        return;
+    }
+
+    // keep the selected variable in view
+    let rows = list_rect.height as usize;
+    let first = state.selected.saturating_sub(rows.saturating_sub(1));
+    let lines: Vec<Line> = state
+        .names
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(rows)
+        .map(|(i, name)| {
+            // without the vendor GUID
+            let short = name.get(..name.len().saturating_sub(37)).unwrap_or(name);
+            let line = Line::from(short.to_string()).fg(theme.text);
+            if i == state.selected {
+                line.reversed()
+            } else {
+                line
+            }
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(Text::from(lines)), list_rect);
+
+    let header = match &state.variable {
+        Some(var) => vec![
+            Line::from(var.name.clone()).fg(theme.text).bold(),
+            Line::from(format!("Vendor {}", var.vendor)).fg(theme.muted),
+            Line::from(format!(
+                "Attributes {:#x} {}",
+                var.attributes,
+                var.attribute_names().join(" ")
+            ))
+            .fg(theme.muted),
+        ],
+        None => vec![Line::from("Cannot read the variable").fg(theme.error)],
+    };
+    frame.render_widget(Paragraph::new(Text::from(header)), header_rect);
+}
+
+fn on_key_event(w: &mut Window<EfiVariablesState>, key: KeyEvent) -> Option<Action> {
+    let last = w.state.names.len().saturating_sub(1);
+    let selected = match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Up => w.state.selected.saturating_sub(1),
+        KeyCode::Down => (w.state.selected + 1).min(last),
+        KeyCode::Home => 0,
+        KeyCode::End => last,
+        // the rest scrolls the data
+        _ => return None,
+    };
+    if selected != w.state.selected {
+        w.state.selected = selected;
+        load_selected(w);
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+pub fn create_efi_variables() -> impl IWindow {
+    let mut w = Window::builder("efi variables")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .widget("hexdump", HexDumpElement::new(Vec::new()))
+        .with_focused_view("hexdump")
+        .with_state(EfiVariablesState {
+            names: efi_variable_names(),
+            selected: 0,
+            variable: None,
+        })
+        .build()
+        .unwrap();
+    load_selected(&mut w);
+    w
+}
@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+
+use crate::application::Application;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    // aligned columns for the terminal
+    Text,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Text => "txt",
+        }
+    }
+}
+
+/// Content of a table-based page as plain strings
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableExport {
+    // short page name used in the file name e.g. "network"
+    pub name: &'static str,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl TableExport {
+    pub fn new<S: Into<String>>(name: &'static str, header: Vec<S>) -> Self {
+        Self {
+            name,
+            header: header.into_iter().map(|h| h.into()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    fn to_csv(&self) -> String {
+        std::iter::once(&self.header)
+            .chain(self.rows.iter())
+            .map(|row| {
+                let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+                fields.join(",") + "\n"
+            })
+            .collect()
+    }
+
+    // array of objects keyed by the column names
+    fn to_json(&self) -> String {
+        let rows: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = self
+                    .header
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(|f| Value::String(f.clone())))
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+        serde_json::to_string_pretty(&rows).unwrap_or_default()
+    }
+
+    fn to_text(&self) -> String {
+        let widths: Vec<usize> = self
+            .header
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|f| f.chars().count())
+                    .chain(std::iter::once(h.chars().count()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        std::iter::once(&self.header)
+            .chain(self.rows.iter())
+            .map(|row| {
+                let fields: Vec<String> = row
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(f, width)| format!("{:<width$}", f, width = width))
+                    .collect();
+                fields.join("  ").trim_end().to_string() + "\n"
+            })
+            .collect()
+    }
+
+    pub fn serialize(&self, format: ExportFormat) -> Vec<u8> {
+        match format {
+            ExportFormat::Csv => self.to_csv(),
+            ExportFormat::Json => self.to_json(),
+            ExportFormat::Text => self.to_text(),
+        }
+        .into_bytes()
+    }
+
+    /// <export dir>/<page>-<timestamp>.<csv|json>
+    pub fn file_path(&self, format: ExportFormat) -> PathBuf {
+        let file_name = format!(
+            "{}-{}.{}",
+            self.name,
+            chrono::Local::now().format("%Y-%m-%d-%H-%M-%S"),
+            format.extension()
+        );
+        Application::monitor_data_dir("exports").join(file_name)
+    }
+}
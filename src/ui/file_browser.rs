@@ -0,0 +1,274 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::{
+        device::removable::{usb_mounts, UsbMount},
+        model::Model,
+        sanitize::sanitize_line,
+    },
+    traits::IWindow,
+    ui::action::UiActions,
+};
+
+use super::{action::Action, window::Window};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Entry {
+    Mount(UsbMount),
+    // the parent directory, the list of mounts above a mount point
+    Up,
+    Dir(PathBuf),
+    File(PathBuf),
+}
+
+struct FileBrowserState {
+    // None while the mounts are listed
+    root: Option<PathBuf>,
+    dir: PathBuf,
+    entries: Vec<Entry>,
+    selected: usize,
+    scroll: usize,
+    page: usize,
+    // lowercase, without the dot
+    extensions: &'static [&'static str],
+    on_select: fn(PathBuf) -> UiActions,
+    error: Option<String>,
+}
+
+fn file_name(path: &Path) -> String {
+    sanitize_line(&path.file_name().unwrap_or_default().to_string_lossy())
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| extensions.contains(&ext.as_str()))
+}
+
+// directories first, hidden entries are skipped
+fn read_entries(dir: &Path, extensions: &[&str]) -> std::io::Result<Vec<Entry>> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if file_name(&path).starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            dirs.push(path);
+        } else if has_extension(&path, extensions) {
+            files.push(path);
+        }
+    }
+    dirs.sort();
+    files.sort();
+    let mut entries = vec![Entry::Up];
+    entries.extend(dirs.into_iter().map(Entry::Dir));
+    entries.extend(files.into_iter().map(Entry::File));
+    Ok(entries)
+}
+
+impl FileBrowserState {
+    fn list_mounts(&mut self) {
+        self.root = None;
+        self.entries = usb_mounts().into_iter().map(Entry::Mount).collect();
+        self.selected = 0;
+        self.error = None;
+    }
+
+    fn open_dir(&mut self, dir: PathBuf) {
+        match read_entries(&dir, self.extensions) {
+            Ok(entries) => {
+                self.entries = entries;
+                self.dir = dir;
+                self.selected = 0;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Cannot read {}: {}", dir.display(), e)),
+        }
+    }
+
+    fn go_up(&mut self) {
+        match (&self.root, self.dir.parent()) {
+            (Some(root), Some(parent)) if self.dir != *root => self.open_dir(parent.to_path_buf()),
+            _ => self.list_mounts(),
+        }
+    }
+}
+
+fn do_layout(w: &mut Window<FileBrowserState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 4,
+        vertical: 2,
+    });
+    // borders and the line of the current directory
+    w.state.page = frame_rect.height.saturating_sub(4).max(1) as usize;
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<FileBrowserState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let state = &mut w.state;
+    let location = match &state.root {
+        Some(_) => sanitize_line(&state.dir.to_string_lossy()),
+        None => "USB storage".to_string(),
+    };
+    let mut lines = vec![Line::from(location).fg(theme.muted), Line::from("")];
+
+    if state.entries.is_empty() && state.root.is_none() {
+        lines.push(
+            Line::from("No USB storage is mounted. Plug it in and press r to look again")
+                .fg(theme.muted),
+        );
+    }
+    // keep the selection on the page
+    if state.selected < state.scroll {
+        state.scroll = state.selected;
+    } else if state.selected >= state.scroll + state.page {
+        state.scroll = state.selected + 1 - state.page;
+    }
+    lines.extend(
+        state
+            .entries
+            .iter()
+            .enumerate()
+            .skip(state.scroll)
+            .take(state.page)
+            .map(|(i, entry)| {
+                let line = match entry {
+                    Entry::Mount(mount) => Line::from(format!(
+                        " {} on {}",
+                        sanitize_line(&mount.device),
+                        sanitize_line(&mount.mount_point.to_string_lossy())
+                    ))
+                    .fg(theme.text),
+                    Entry::Up => Line::from(" ..").fg(theme.text),
+                    Entry::Dir(path) => Line::from(format!(" {}/", file_name(path))).fg(theme.text),
+                    Entry::File(path) => Line::from(format!(" {}", file_name(path))).fg(theme.ok),
+                };
+                if i == state.selected {
+                    line.reversed()
+                } else {
+                    line
+                }
+            }),
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(format!(
+            " Select a file ({}), Backspace to go up, Esc to close ",
+            state.extensions.join(", ")
+        ));
+    let block = match &state.error {
+        Some(error) => block.title_bottom(format!(" {} ", sanitize_line(error)).fg(theme.error)),
+        None => block,
+    };
+
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+}
+
+fn on_key_event(w: &mut Window<FileBrowserState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    let last = state.entries.len().saturating_sub(1);
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Enter => match state.entries.get(state.selected)?.clone() {
+            Entry::Mount(mount) => {
+                state.root = Some(mount.mount_point.clone());
+                state.open_dir(mount.mount_point);
+                if state.error.is_some() {
+                    state.root = None;
+                }
+            }
+            Entry::Up => state.go_up(),
+            Entry::Dir(dir) => state.open_dir(dir),
+            Entry::File(path) => return Some(Action::new(&w.name, (state.on_select)(path))),
+        },
+        KeyCode::Backspace | KeyCode::Left if state.root.is_some() => state.go_up(),
+        KeyCode::Char('r') if state.root.is_none() => state.list_mounts(),
+        KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Down => state.selected = (state.selected + 1).min(last),
+        KeyCode::PageUp => state.selected = state.selected.saturating_sub(state.page),
+        KeyCode::PageDown => state.selected = (state.selected + state.page).min(last),
+        KeyCode::Home => state.selected = 0,
+        KeyCode::End => state.selected = last,
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+/// Pick a file with one of `extensions` on mounted USB storage
+pub fn create_file_browser(
+    extensions: &'static [&'static str],
+    on_select: fn(PathBuf) -> UiActions,
+) -> impl IWindow {
+    let mut state = FileBrowserState {
+        root: None,
+        dir: PathBuf::new(),
+        entries: Vec::new(),
+        selected: 0,
+        scroll: 0,
+        page: 1,
+        extensions,
+        on_select,
+        error: None,
+    };
+    state.list_mounts();
+    Window::builder("file browser")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(state)
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_entries() {
+        let dir = std::env::temp_dir().join(format!("monitor-browser-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("certs")).unwrap();
+        std::fs::create_dir_all(dir.join(".hidden")).unwrap();
+        for name in ["proxy.PEM", "ca.crt", "notes.txt", ".old.pem"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        let entries = read_entries(&dir, &["pem", "crt"]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                Entry::Up,
+                Entry::Dir(dir.join("certs")),
+                Entry::File(dir.join("ca.crt")),
+                Entry::File(dir.join("proxy.PEM")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::{model::Model, sanitize::sanitize_line},
+    traits::IWindow,
+    ui::action::UiActions,
+};
+
+use super::{action::Action, line_selection::LineSelection, window::Window};
+
+struct FileViewerState {
+    lines: Vec<String>,
+    // indexes of the lines to highlight
+    highlight: HashSet<usize>,
+    scroll: usize,
+    page: usize,
+    selection: LineSelection,
+}
+
+fn do_layout(w: &mut Window<FileViewerState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 2,
+        vertical: 1,
+    });
+    // borders
+    w.state.page = frame_rect.height.saturating_sub(2).max(1) as usize;
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<FileViewerState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let state = &w.state;
+    let number_width = state.lines.len().to_string().len();
+    let lines: Vec<Line> = state
+        .lines
+        .iter()
+        .enumerate()
+        .skip(state.scroll)
+        .take(state.page)
+        .map(|(i, line)| {
+            let style = if state.selection.contains(i) {
+                Style::default().fg(theme.text).bg(theme.selection)
+            } else if state.highlight.contains(&i) {
+                Style::default().fg(theme.surface).bg(theme.warning)
+            } else {
+                Style::default().fg(theme.text)
+            };
+            let style = if state.selection.is_cursor(i) {
+                style.bold()
+            } else {
+                style
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>width$} ", i + 1, width = number_width),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(line.clone(), style),
+            ])
+        })
+        .collect();
+
+    let title = format!(
+        "{} (read-only, {} highlighted lines, 'v' to select, Esc to close)",
+        w.name,
+        state.highlight.len()
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(title);
+
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+}
+
+fn on_key_event(w: &mut Window<FileViewerState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    if let Some(action) = state.selection.handle_key(
+        key,
+        &mut state.scroll,
+        state.page,
+        state.lines.len(),
+        "selection",
+        &state.lines,
+    ) {
+        return Some(Action::new(&w.name, action));
+    }
+    let last = state.lines.len().saturating_sub(state.page);
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Up => state.scroll = state.scroll.saturating_sub(1),
+        KeyCode::Down => state.scroll = (state.scroll + 1).min(last),
+        KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(state.page),
+        KeyCode::PageDown => state.scroll = (state.scroll + state.page).min(last),
+        KeyCode::Home => state.scroll = 0,
+        KeyCode::End => state.scroll = last,
+        // jump to the next highlighted line
+        KeyCode::Char('n') => {
+            if let Some(next) =
+                (state.scroll + 1..state.lines.len()).find(|i| state.highlight.contains(i))
+            {
+                state.scroll = next.min(last);
+            }
+        }
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+pub fn create_file_viewer(path: &str, content: &str, highlight: HashSet<usize>) -> impl IWindow {
+    Window::builder(path)
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(FileViewerState {
+            lines: content.lines().map(sanitize_line).collect(),
+            highlight,
+            scroll: 0,
+            page: 1,
+            selection: LineSelection::default(),
+        })
+        .build()
+        .unwrap()
+}
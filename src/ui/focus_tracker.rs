@@ -81,7 +81,8 @@ impl FocusTracker {
     }
 
     pub fn focus_next(&mut self) -> Option<String> {
-        if self.too_late {
+        // windows without widgets get Tab too
+        if self.too_late || self.tab_order.is_empty() {
             return None;
         }
         if self.focused_view + 1 < self.tab_order.len() {
@@ -99,7 +100,8 @@ impl FocusTracker {
     }
 
     pub fn focus_prev(&mut self) -> Option<String> {
-        if self.too_late {
+        // windows without widgets get Tab too
+        if self.too_late || self.tab_order.is_empty() {
             return None;
         }
         if self.focused_view > 0 {
@@ -116,6 +118,17 @@ impl FocusTracker {
         Some(self.tab_order[self.focused_view].clone())
     }
 
+    // false if the view is not in the tab order
+    pub fn focus_view(&mut self, name: &str) -> bool {
+        match self.tab_order.iter().position(|n| n == name) {
+            Some(index) => {
+                self.focused_view = index;
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn clear_focus(&mut self) {
         self.focused_view = 0;
     }
@@ -0,0 +1,180 @@
+// Pages and dialogs rendered into a headless terminal and compared with the
+// text stored in fixtures/golden/<name>.txt. Only the symbols are compared,
+// colors come from the theme and are not worth pinning down.
+// Run with UPDATE_GOLDEN=1 to write the files after an intended change
+use std::{cell::RefCell, path::PathBuf, rc::Rc, sync::LazyLock};
+
+use ratatui::layout::Rect;
+use regex::Regex;
+
+use crate::{
+    ipc::fixtures::fixtures,
+    model::{
+        bundle::BundleProgress,
+        device::identity::DeviceIdentity,
+        model::{Model, MonitorModel},
+    },
+    terminal::TerminalWrapper,
+    traits::IWindow,
+    ui::{
+        bundle_dialog::create_bundle_dialog,
+        confirm_dialog::create_confirm_dialog,
+        ipdialog::create_ip_dialog,
+        message_box::create_message_box,
+        networkpage::create_network_page,
+        summary_page::{SummaryPage, SummaryPanel},
+    },
+};
+
+use super::action::UiActions;
+
+const GOLDEN_DIR: &str = "./fixtures/golden";
+const SIZE: (u16, u16) = (120, 40);
+
+// the model takes the local time when a message is applied
+static TIMESTAMP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{4}-\d{2}-\d{2} )?\d{2}:\d{2}:\d{2}").unwrap());
+
+// the model after all messages of a capture
+fn fixture_model(capture: &str) -> Rc<Model> {
+    let fixtures = fixtures!(capture: capture);
+    assert!(!fixtures.is_empty(), "no fixtures for {}", capture);
+    let mut model = MonitorModel::default();
+    for fixture in fixtures {
+        model.apply_ipc_message(fixture.ipc_message());
+    }
+    // the identity is read from /config of the host, also on OnboardingStatus
+    model.identity = DeviceIdentity::default();
+    Rc::new(RefCell::new(model))
+}
+
+// nothing was received from EVE yet
+fn empty_model() -> Rc<Model> {
+    let mut model = MonitorModel::default();
+    model.identity = DeviceIdentity::default();
+    Rc::new(RefCell::new(model))
+}
+
+// windows are drawn twice, some of them lay out on the first frame
+fn render(window: &mut dyn IWindow, model: &Rc<Model>) -> String {
+    let (width, height) = SIZE;
+    let mut terminal = TerminalWrapper::headless(width, height).unwrap();
+    for _ in 0..2 {
+        terminal
+            .draw(|frame| window.render(&Rect::new(0, 0, width, height), frame, model, true))
+            .unwrap();
+    }
+    let buffer = terminal.buffer().unwrap();
+    let lines: Vec<String> = buffer
+        .content()
+        .chunks(width as usize)
+        .map(|row| {
+            let line: String = row.iter().map(|cell| cell.symbol()).collect();
+            line.trim_end().to_string()
+        })
+        .collect();
+    // same width, the layout must not change
+    let screen = lines.join("\n") + "\n";
+    TIMESTAMP
+        .replace_all(&screen, |caps: &regex::Captures| {
+            if caps.get(1).is_some() {
+                "YYYY-MM-DD hh:mm:ss"
+            } else {
+                "hh:mm:ss"
+            }
+        })
+        .into_owned()
+}
+
+fn assert_golden(name: &str, screen: &str) {
+    let path = PathBuf::from(GOLDEN_DIR).join(format!("{}.txt", name));
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::create_dir_all(GOLDEN_DIR).unwrap();
+        std::fs::write(&path, screen).unwrap();
+        return;
+    }
+    let golden = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {}. Run with UPDATE_GOLDEN=1 to create it",
+            path.display(),
+            e
+        )
+    });
+    assert!(
+        golden == screen,
+        "{} differs, run with UPDATE_GOLDEN=1 if the change is intended.\nExpected:\n{}\nGot:\n{}",
+        path.display(),
+        golden,
+        screen
+    );
+}
+
+#[test]
+fn test_golden_pages() {
+    let model = fixture_model("unknown");
+    let cases: Vec<(&str, Rc<Model>, Box<dyn IWindow>)> = vec![
+        (
+            "summary",
+            model.clone(),
+            Box::new(SummaryPage::new(SummaryPanel::defaults())),
+        ),
+        (
+            "summary-empty",
+            empty_model(),
+            Box::new(SummaryPage::new(SummaryPanel::defaults())),
+        ),
+        (
+            "vault",
+            model.clone(),
+            Box::new(SummaryPage::new(vec![SummaryPanel::Vault])),
+        ),
+        (
+            "vault-errors",
+            fixture_model("unknown-errors"),
+            Box::new(SummaryPage::new(vec![SummaryPanel::Vault])),
+        ),
+        ("network", model.clone(), Box::new(create_network_page())),
+        (
+            "network-empty",
+            empty_model(),
+            Box::new(create_network_page()),
+        ),
+    ];
+    for (name, model, mut window) in cases {
+        assert_golden(name, &render(window.as_mut(), &model));
+    }
+}
+
+#[test]
+fn test_golden_dialogs() {
+    let model = fixture_model("unknown");
+    let iface = model.borrow().network[0].clone();
+    assert_golden("dialog-ip", &render(&mut create_ip_dialog(&iface), &model));
+    assert_golden(
+        "dialog-message-box",
+        &render(
+            &mut create_message_box("ERROR", "Cannot write the config file"),
+            &model,
+        ),
+    );
+    assert_golden(
+        "dialog-confirm",
+        &render(
+            &mut create_confirm_dialog(
+                "Support bundle",
+                "Generate a support bundle?",
+                UiActions::SupportBundle,
+            ),
+            &model,
+        ),
+    );
+    model.borrow_mut().bundle = Some(BundleProgress::Running {
+        step: "Compressing",
+        done: 2,
+        total: 4,
+    });
+    assert_golden(
+        "dialog-bundle",
+        &render(&mut create_bundle_dialog(), &model),
+    );
+}
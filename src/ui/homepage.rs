@@ -1,5 +1,10 @@
-use crate::ipc::eve_types::DownloaderStatus;
+use crate::ipc::eve_types::SwState;
 use crate::model::device::summary::DeviceSummary;
+use crate::model::model::MonitorModel;
+use crate::model::sanitize::sanitize_line;
+use crate::model::verification::ImageVerification;
+use crate::ui::theme::Theme;
+use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Text;
 use ratatui::widgets::Block;
@@ -12,7 +17,9 @@ use crate::model::model::Model;
 use crate::traits::{IEventHandler, IPresenter, IWindow};
 use crate::ui::action::Action;
 use crate::ui::window::LayoutMap;
+use crate::ui::window::Window;
 use log::debug;
+use ratatui::prelude::Alignment;
 use ratatui::prelude::Constraint;
 use ratatui::prelude::Layout;
 use ratatui::prelude::Rect;
@@ -39,7 +46,7 @@ impl HomePage {
             Layout::horizontal([Constraint::Ratio(1, 3), Constraint::Ratio(2, 3)]).areas(*area);
 
         let [details, download] =
-            Layout::vertical([Constraint::Fill(0), Constraint::Length(5)]).areas(left);
+            Layout::vertical([Constraint::Fill(0), Constraint::Length(9)]).areas(left);
 
         let [usb, pci] =
             Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(right);
@@ -70,7 +77,7 @@ impl HomePage {
         .block(Block::bordered().title("Device Summary"));
         frame.render_widget(left, layout["summary"]);
 
-        self.render_download(layout["download"], frame, &model.borrow().downloader);
+        self.render_download(layout["download"], frame, &model.borrow());
 
         let usb = Paragraph::new(Text::from(self.state.usb_devices.join("\n")))
             .wrap(Wrap { trim: true })
@@ -83,23 +90,33 @@ impl HomePage {
         frame.render_widget(pci, layout["pci"]);
     }
 
-    fn render_download(&self, area: Rect, frame: &mut Frame<'_>, model: &Option<DownloaderStatus>) {
+    fn render_download(&self, area: Rect, frame: &mut Frame<'_>, model: &MonitorModel) {
         let download = Block::bordered().title("Download status");
         frame.render_widget(&download, area);
 
-        let Some(model) = model else {
-            frame.render_widget(Line::raw("No download in progress"), download.inner(area));
-            return;
-        };
-
         let contents = download.inner(area);
-        let [area_status, area_name, area_progress] = Layout::vertical([
+        let [area_status, area_name, area_progress, area_images] = Layout::vertical([
             Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(1),
+            Constraint::Fill(1),
         ])
         .areas(contents);
 
+        // the images of all volumes, a failed verification would hide in app errors
+        let images: Vec<Line> = model
+            .verifications
+            .recent()
+            .into_iter()
+            .map(|(name, image)| image_line(name, image, &model.theme))
+            .collect();
+        frame.render_widget(Paragraph::new(Text::from(images)), area_images);
+
+        let Some(model) = &model.downloader else {
+            frame.render_widget(Line::raw("No download in progress"), area_status);
+            return;
+        };
+
         let download_progress = Gauge::default().percent(model.progress as u16);
         frame.render_widget(Line::raw(format!("State: {}", model.state)), area_status);
         frame.render_widget(Line::raw(format!("File: {}", &model.name)), area_name);
@@ -107,6 +124,20 @@ impl HomePage {
     }
 }
 
+fn image_line(name: &str, image: &ImageVerification, theme: &Theme) -> Line<'static> {
+    let name = sanitize_line(name);
+    match (&image.mismatch, image.progress) {
+        (Some(_), _) => Line::from(format!("{}: SHA-256 mismatch", name)).fg(theme.error),
+        (None, Some(progress)) if image.state == SwState::Downloading => {
+            Line::from(format!("{}: downloading {}%", name, progress)).fg(theme.warning)
+        }
+        _ if image.is_verifying() => {
+            Line::from(format!("{}: {}", name, image.state)).fg(theme.warning)
+        }
+        _ => Line::from(format!("{}: {}", name, image.state)),
+    }
+}
+
 impl IPresenter for HomePage {
     // add code here
     fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _: bool) {
@@ -125,3 +156,17 @@ impl IEventHandler for HomePage {
 }
 
 impl IWindow for HomePage {}
+
+// placeholder shown instead of the HomePage in low-memory mode
+pub fn create_disabled_home_page() -> Window<()> {
+    Window::builder("HomePage")
+        .with_state(())
+        .with_render(|_w, rect, frame, _model| {
+            let text = Paragraph::new("Device summary is disabled in low-memory mode")
+                .alignment(Alignment::Center)
+                .block(Block::bordered().title("Device Summary"));
+            frame.render_widget(text, *rect);
+        })
+        .build()
+        .unwrap()
+}
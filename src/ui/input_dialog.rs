@@ -4,12 +4,14 @@ use crossterm::event::{KeyCode, KeyEvent};
 use log::debug;
 use ratatui::{
     layout::{Constraint, Flex, Layout, Margin, Rect},
-    style::{Color, Style},
-    widgets::{Block, BorderType, Borders, Clear},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
     Frame,
 };
+use url::Url;
 
-use crate::{actions::MonActions, model::model::Model, traits::IWindow, ui::action::UiActions};
+use crate::{model::model::Model, traits::IWindow, ui::action::UiActions};
 
 use super::{
     action::Action,
@@ -17,31 +19,80 @@ use super::{
     window::Window,
 };
 
+// checks the entered text, Err is the message for the user
+pub type Validator = fn(&str) -> Result<(), String>;
+
 struct InputDialogState {
     caption: String,
     content: String,
     hint: String,
+    // the action for the entered text
+    on_ok: fn(String) -> UiActions,
+    // the value the dialog was opened with, 'revert' goes back to it
+    original: String,
+    validate: Option<Validator>,
+    // why the current content is not accepted
+    error: Option<String>,
+}
+
+// EVE talks to the controller over https, http is only for test setups
+const SERVER_URL_SCHEMES: [&str; 2] = ["https", "http"];
+
+/// A controller address as EVE takes it: a host with an optional port or a
+/// full URL with one of the allowed schemes
+pub fn validate_server_url(text: &str) -> Result<(), String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("The server URL is empty".to_string());
+    }
+    if text.contains(char::is_whitespace) {
+        return Err("The server URL contains spaces".to_string());
+    }
+    let url = if text.contains("://") {
+        Url::parse(text)
+    } else {
+        Url::parse(&format!("https://{}", text))
+    }
+    .map_err(|e| format!("Not a valid URL: {}", e))?;
+    if !SERVER_URL_SCHEMES.contains(&url.scheme()) {
+        return Err(format!(
+            "Unsupported scheme '{}', use {}",
+            url.scheme(),
+            SERVER_URL_SCHEMES.join(" or ")
+        ));
+    }
+    if url.host_str().is_none_or(|h| h.is_empty()) {
+        return Err("The server URL has no host".to_string());
+    }
+    Ok(())
+}
+
+fn input_field(state: &InputDialogState, content: &str) -> InputFieldElement {
+    InputFieldElement::new(state.caption.clone(), Some(content.to_string()))
+        .with_text_hint(state.hint.clone())
 }
 
 fn on_init(w: &mut Window<InputDialogState>) {
-    w.add_widget(
-        "input",
-        InputFieldElement::new(w.state.caption.clone(), Some(w.state.content.clone()))
-            .with_text_hint(w.state.hint.clone()),
-    );
+    w.add_widget("input", input_field(&w.state, &w.state.content));
     // buttons
     w.add_widget("ok", ButtonElement::new("ok"));
     w.add_widget("cancel", ButtonElement::new("cancel"));
 
-    w.set_focus_tracker_tab_order(vec!["input", "ok", "cancel"]);
+    if w.state.validate.is_some() {
+        w.add_widget("revert", ButtonElement::new("revert"));
+        w.set_focus_tracker_tab_order(vec!["input", "ok", "revert", "cancel"]);
+    } else {
+        w.set_focus_tracker_tab_order(vec!["input", "ok", "cancel"]);
+    }
 }
 
 fn do_render(
     w: &mut Window<InputDialogState>,
     _rect: &Rect,
     frame: &mut Frame<'_>,
-    _model: &Rc<Model>,
+    model: &Rc<Model>,
 ) {
+    let theme = model.borrow().theme;
     // render frame
     let frame_rect = w.get_layout("frame");
 
@@ -52,17 +103,41 @@ fn do_render(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Double)
-        .border_style(Style::default().fg(Color::White))
-        .style(Style::default().bg(Color::Black))
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
         .title(w.name.clone());
 
     frame.render_widget(block, frame_rect);
+
+    if w.state.validate.is_none() {
+        return;
+    }
+    let current = if w.state.original.is_empty() {
+        "not set".to_string()
+    } else {
+        w.state.original.clone()
+    };
+    let status = match w.state.error.as_ref() {
+        Some(error) => Line::from(error.clone()).fg(theme.error),
+        None => Line::from("Valid").fg(theme.ok),
+    };
+    let lines = vec![
+        Line::from(format!("Current: {}", current)).fg(theme.muted),
+        status,
+    ];
+    frame.render_widget(Paragraph::new(lines), w.get_layout("status"));
 }
 
 fn do_layout(w: &mut Window<InputDialogState>, rect: &Rect, _model: &Rc<Model>) {
     debug!("do_layout. selected tab");
 
-    let rect = crate::ui::tools::centered_rect_fixed(40, 10, *rect);
+    // URLs are long, use the width when there is one
+    let width = if w.state.validate.is_some() {
+        rect.width.saturating_sub(4).clamp(40, 80)
+    } else {
+        40
+    };
+    let rect = crate::ui::tools::centered_rect_fixed(width, 10, *rect);
     let content_with_buttons = rect.inner(Margin {
         horizontal: 1,
         vertical: 1,
@@ -77,15 +152,21 @@ fn do_layout(w: &mut Window<InputDialogState>, rect: &Rect, _model: &Rc<Model>)
             .areas(content_with_buttons);
 
     // split dialog content area. Top - Input widget
-    let [tabs, _dialog_content_rect] =
+    let [tabs, status] =
         Layout::vertical(vec![Constraint::Length(3), Constraint::Fill(1)]).areas(dialog_content);
     w.update_layout("input", tabs);
+    w.update_layout("status", status);
 
     // buttons
-    let [ok, cancel] = Layout::horizontal(vec![Constraint::Length(6), Constraint::Length(10)])
-        .flex(Flex::End)
-        .areas(buttons);
+    let [ok, revert, cancel] = Layout::horizontal(vec![
+        Constraint::Length(6),
+        Constraint::Length(if w.state.validate.is_some() { 10 } else { 0 }),
+        Constraint::Length(10),
+    ])
+    .flex(Flex::End)
+    .areas(buttons);
     w.update_layout("ok", ok);
+    w.update_layout("revert", revert);
     w.update_layout("cancel", cancel);
 }
 
@@ -107,15 +188,26 @@ fn on_child_ui_action(
     match action {
         UiActions::ButtonClicked(name) => match name.as_str() {
             "cancel" => Some(Action::new(&w.name, UiActions::DismissDialog)),
+            // the error is on the screen already
+            "ok" if w.state.error.is_some() => None,
             "ok" => Some(Action::new(
                 &w.name,
-                UiActions::AppAction(MonActions::ServerUpdated(w.state.content.clone())),
+                (w.state.on_ok)(w.state.content.trim().to_string()),
             )),
+            "revert" => {
+                w.state.content = w.state.original.clone();
+                w.add_widget("input", input_field(&w.state, &w.state.original));
+                w.state.error = w.state.validate.and_then(|v| v(&w.state.content).err());
+                Some(Action::new(&w.name, UiActions::Redraw))
+            }
             _ => None,
         },
         UiActions::Input { text } => {
             match source.as_str() {
-                "input" => w.state.content = text.clone(),
+                "input" => {
+                    w.state.content = text.clone();
+                    w.state.error = w.state.validate.and_then(|v| v(text).err());
+                }
                 _ => {}
             }
             None
@@ -129,6 +221,38 @@ pub fn create_input_dialog(
     caption: &str,
     content: &str,
     hint: &str,
+    on_ok: fn(String) -> UiActions,
+) -> impl IWindow {
+    build_input_dialog(window_caption, caption, content, hint, None, on_ok)
+}
+
+/// Input dialog that checks the text as it is typed or pasted, shows the
+/// value it was opened with and can revert to it
+pub fn create_validated_input_dialog(
+    window_caption: &str,
+    caption: &str,
+    content: &str,
+    hint: &str,
+    validate: Validator,
+    on_ok: fn(String) -> UiActions,
+) -> impl IWindow {
+    build_input_dialog(
+        window_caption,
+        caption,
+        content,
+        hint,
+        Some(validate),
+        on_ok,
+    )
+}
+
+fn build_input_dialog(
+    window_caption: &str,
+    caption: &str,
+    content: &str,
+    hint: &str,
+    validate: Option<Validator>,
+    on_ok: fn(String) -> UiActions,
 ) -> impl IWindow {
     let w = Window::builder(window_caption)
         .with_on_init(on_init)
@@ -140,8 +264,31 @@ pub fn create_input_dialog(
             caption: caption.to_string(),
             content: content.to_string(),
             hint: hint.to_string(),
+            on_ok,
+            original: content.to_string(),
+            validate,
+            error: validate.and_then(|v| v(content).err()),
         })
         .build()
         .unwrap();
     w
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_server_url() {
+        assert!(validate_server_url("zedcloud.example.net").is_ok());
+        assert!(validate_server_url("zedcloud.example.net:8443").is_ok());
+        assert!(validate_server_url("https://prod.zedcontrol.zededa.net").is_ok());
+        assert!(validate_server_url(" http://10.0.0.1:8080 ").is_ok());
+        assert!(validate_server_url("").is_err());
+        assert!(validate_server_url("zed cloud.net").is_err());
+        assert!(validate_server_url("ftp://zedcloud.example.net")
+            .unwrap_err()
+            .contains("Unsupported scheme"));
+        assert!(validate_server_url("https://").is_err());
+    }
+}
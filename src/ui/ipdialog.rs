@@ -1,18 +1,21 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, net::IpAddr, rc::Rc};
 
 use crossterm::event::{KeyCode, KeyEvent};
+use ipnet::IpNet;
 use log::debug;
 use ratatui::{
     layout::{Constraint, Flex, Layout, Margin, Rect},
-    style::{Color, Style},
+    style::{Style, Stylize},
     widgets::{Block, BorderType, Borders, Clear},
     Frame,
 };
+use url::Url;
 
 use crate::{
     actions::MonActions,
+    ipc::eve_types::{self, LargeBlob, NetworkProxyType, NetworkType, ProxyEntry},
     model::{
-        device::network::{NetworkInterfaceStatus, ProxyConfig},
+        device::network::{IpV6LinikLocal, NetworkInterfaceStatus, ProxyConfig},
         model::Model,
     },
     traits::IWindow,
@@ -22,8 +25,8 @@ use super::{
     action::{Action, UiActions},
     tools::centered_rect,
     widgets::{
-        button::ButtonElement, input_field::InputFieldElement, spin_box::SpinBoxElement,
-        tab::TabElement,
+        button::ButtonElement, input_field::InputFieldElement, list_editor::ListEditorElement,
+        spin_box::SpinBoxElement, tab::TabElement,
     },
     window::Window,
 };
@@ -41,12 +44,13 @@ pub struct InterfaceState {
     pub iface_name: String,
     pub ip_dhcp: bool,
     pub proxy_type: ProxyType,
-    pub ipv4: String,
-    pub ipv6: String,
-    pub mask: String,
+    // address/prefix, IPv4 and IPv6
+    pub addresses: Vec<String>,
     pub gw: String,
     pub proxy_url: String,
     pub proxy_certificate: String,
+    // CA certificates of the proxy imported from a file, PEM encoded
+    pub proxy_cert_pems: Vec<String>,
     pub pac_file: String,
     pub domain: String,
     pub dns: String,
@@ -56,12 +60,286 @@ pub struct InterfaceState {
     pub proxy_https: String,
     pub proxy_ftp: String,
     pub proxy_socks: String,
+    // hosts reached without the proxy, comma separated
+    pub proxy_exceptions: String,
+}
+
+/// Static configuration of a port as EVE takes it, parsed from the dialog fields
+#[derive(Clone, Debug, PartialEq)]
+pub struct StaticIpConfig {
+    pub addr_subnets: Vec<IpNet>,
+    pub network_type: NetworkType,
+    pub gateway: Option<IpAddr>,
+    pub dns: Vec<IpAddr>,
+    pub ntp: Vec<String>,
+}
+
+// comma separated, blanks around the items are ignored
+fn split_list(text: &str) -> impl Iterator<Item = &str> {
+    text.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Parses an address of the static configuration, e.g. 192.168.1.10/24
+pub fn parse_address(text: &str) -> Result<IpNet, String> {
+    let text = text.trim();
+    let net: IpNet = text.parse().map_err(|_| match text.parse::<IpAddr>() {
+        Ok(IpAddr::V4(_)) => format!("Add the prefix to {}, e.g. {}/24", text, text),
+        Ok(IpAddr::V6(_)) => format!("Add the prefix to {}, e.g. {}/64", text, text),
+        Err(_) => format!("Invalid address '{}', use address/prefix", text),
+    })?;
+    match net.addr() {
+        IpAddr::V6(addr) if addr.is_link_local() => {
+            Err(format!("{} is link-local, it cannot be static", addr))
+        }
+        addr if addr.is_unspecified() || addr.is_multicast() => {
+            Err(format!("{} cannot be the address of a port", addr))
+        }
+        _ => Ok(net),
+    }
+}
+
+// EVE fetches the PAC file itself, over http or https
+const PAC_URL_SCHEMES: [&str; 2] = ["http", "https"];
+
+fn validate_pac_url(text: &str) -> Result<(), String> {
+    if text.is_empty() {
+        return Err("Enter the URL of the PAC file".to_string());
+    }
+    let url = Url::parse(text).map_err(|e| format!("Invalid PAC file URL: {}", e))?;
+    if !PAC_URL_SCHEMES.contains(&url.scheme()) {
+        return Err(format!(
+            "Unsupported scheme '{}' of the PAC file URL, use {}",
+            url.scheme(),
+            PAC_URL_SCHEMES.join(" or ")
+        ));
+    }
+    if url.host_str().is_none_or(|h| h.is_empty()) {
+        return Err("The PAC file URL has no host".to_string());
+    }
+    Ok(())
+}
+
+fn is_host_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+/// An entry of the proxy exceptions: a host, a domain as .example.com or
+/// *.example.com, an address or a subnet
+pub fn validate_proxy_exception(text: &str) -> Result<(), String> {
+    if text.parse::<IpAddr>().is_ok() || text.parse::<IpNet>().is_ok() {
+        return Ok(());
+    }
+    let name = text
+        .strip_prefix("*.")
+        .or_else(|| text.strip_prefix('.'))
+        .unwrap_or(text);
+    if is_host_name(name) {
+        Ok(())
+    } else {
+        Err(format!("Invalid proxy exception '{}'", text))
+    }
+}
+
+// host:port of a manual proxy, the scheme is optional. None if the field is empty
+fn proxy_entry(proxy_type: NetworkProxyType, text: &str) -> Result<Option<ProxyEntry>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || format!("Invalid {:?} proxy '{}', use host:port", proxy_type, text);
+    let host_port = text.split_once("://").map_or(text, |(_, rest)| rest);
+    let host_port = host_port.trim_end_matches('/');
+    let (server, port) = host_port.rsplit_once(':').ok_or_else(invalid)?;
+    let port: u16 = port.parse().map_err(|_| invalid())?;
+    // IPv6 addresses are written in brackets
+    let bare = server.trim_start_matches('[').trim_end_matches(']');
+    if port == 0 || !(is_host_name(bare) || bare.parse::<IpAddr>().is_ok()) {
+        return Err(invalid());
+    }
+    Ok(Some(ProxyEntry {
+        proxy_type,
+        server: bare.to_string(),
+        port: port.into(),
+    }))
 }
 
 impl InterfaceState {
     pub fn is_dhcp(&self) -> bool {
         self.ip_dhcp
     }
+
+    pub fn same_proxy(&self, other: &InterfaceState) -> bool {
+        self.proxy_type == other.proxy_type
+            && self.pac_file == other.pac_file
+            && self.proxy_http == other.proxy_http
+            && self.proxy_https == other.proxy_https
+            && self.proxy_ftp == other.proxy_ftp
+            && self.proxy_socks == other.proxy_socks
+            && self.proxy_exceptions == other.proxy_exceptions
+            && self.proxy_cert_pems == other.proxy_cert_pems
+    }
+
+    /// One line for the audit log, e.g. "static 10.0.0.2/24 gw 10.0.0.1 dns 8.8.8.8, proxy PAC"
+    pub fn summary(&self) -> String {
+        let mut summary = if self.ip_dhcp {
+            "DHCP".to_string()
+        } else {
+            format!("static {} gw {}", self.addresses.join(" "), self.gw)
+        };
+        for (name, value) in [("dns", &self.dns), ("ntp", &self.ntp)] {
+            let value = split_list(value).collect::<Vec<_>>().join(" ");
+            if !self.ip_dhcp && !value.is_empty() {
+                summary.push_str(&format!(" {} {}", name, value));
+            }
+        }
+        let proxy = match self.proxy_type {
+            ProxyType::None => None,
+            ProxyType::Manual => Some(
+                [
+                    &self.proxy_http,
+                    &self.proxy_https,
+                    &self.proxy_ftp,
+                    &self.proxy_socks,
+                ]
+                .into_iter()
+                .filter(|p| !p.is_empty())
+                .map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            ),
+            ProxyType::Pac => Some(format!("PAC {}", self.pac_file)),
+            ProxyType::Wad => Some("WPAD".to_string()),
+        };
+        if let Some(proxy) = proxy {
+            summary.push_str(&format!(", proxy {}", proxy));
+        }
+        summary
+    }
+
+    /// The proxy part of the port configuration, based on the `current` one
+    /// so the certificates and WPAD settings are kept. The error is shown to
+    /// the user as is
+    pub fn create_proxy_config(
+        &self,
+        current: eve_types::ProxyConfig,
+    ) -> Result<eve_types::ProxyConfig, String> {
+        let exceptions = split_list(&self.proxy_exceptions)
+            .map(|host| validate_proxy_exception(host).map(|_| host))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(",");
+        let mut config = eve_types::ProxyConfig {
+            exceptions,
+            ..current
+        };
+        match self.proxy_type {
+            ProxyType::None => {
+                config.proxies = None;
+                config.pacfile = String::new();
+                config.exceptions = String::new();
+                config.network_proxy_enable = false;
+            }
+            ProxyType::Manual => {
+                let proxies = [
+                    (NetworkProxyType::HTTP, &self.proxy_http),
+                    (NetworkProxyType::HTTPS, &self.proxy_https),
+                    (NetworkProxyType::FTP, &self.proxy_ftp),
+                    (NetworkProxyType::SOCKS, &self.proxy_socks),
+                ]
+                .into_iter()
+                .filter_map(|(proxy_type, text)| proxy_entry(proxy_type, text).transpose())
+                .collect::<Result<Vec<_>, _>>()?;
+                if proxies.is_empty() {
+                    return Err("Enter at least one proxy".to_string());
+                }
+                config.proxies = Some(proxies);
+                config.pacfile = String::new();
+                config.network_proxy_enable = false;
+            }
+            ProxyType::Pac => {
+                let url = self.pac_file.trim();
+                validate_pac_url(url)?;
+                config.proxies = None;
+                config.pacfile = url.to_string();
+                config.network_proxy_enable = false;
+            }
+            // set by the controller, the dialog cannot change it
+            ProxyType::Wad => {}
+        }
+        if !self.proxy_cert_pems.is_empty() {
+            let certs = config.proxy_cert_pem.get_or_insert_with(Vec::new);
+            for pem in self.proxy_cert_pems.iter() {
+                let cert = LargeBlob::Inline(pem.as_bytes().to_vec());
+                if !certs.contains(&cert) {
+                    certs.push(cert);
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Checks the static fields, the error is shown to the user as is
+    pub fn static_config(&self) -> Result<StaticIpConfig, String> {
+        let addr_subnets = self
+            .addresses
+            .iter()
+            .map(|addr| parse_address(addr))
+            .collect::<Result<Vec<_>, _>>()?;
+        if addr_subnets.is_empty() {
+            return Err("Add at least one address".to_string());
+        }
+        let has_ipv4 = addr_subnets.iter().any(|net| matches!(net, IpNet::V4(_)));
+        let has_ipv6 = addr_subnets.iter().any(|net| matches!(net, IpNet::V6(_)));
+
+        let gateway = match self.gw.trim() {
+            "" => None,
+            gw => Some(
+                gw.parse::<IpAddr>()
+                    .map_err(|_| format!("Invalid gateway '{}'", gw))?,
+            ),
+        };
+        match gateway {
+            Some(gw @ IpAddr::V4(_)) if !has_ipv4 => {
+                return Err(format!("Gateway {} needs an IPv4 address", gw))
+            }
+            Some(gw @ IpAddr::V4(_)) if !addr_subnets.iter().any(|net| net.contains(&gw)) => {
+                return Err(format!("Gateway {} is not in the subnet of an address", gw))
+            }
+            // usually the link-local address of the router, not in the subnet
+            Some(gw @ IpAddr::V6(_)) if !has_ipv6 => {
+                return Err(format!("Gateway {} needs an IPv6 address", gw))
+            }
+            _ => {}
+        }
+
+        // DNS servers may be of the other family, e.g. IPv6 resolvers for an IPv4 port
+        let dns = split_list(&self.dns)
+            .map(|s| {
+                s.parse::<IpAddr>()
+                    .map_err(|_| format!("Invalid DNS server '{}'", s))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(StaticIpConfig {
+            addr_subnets,
+            network_type: match (has_ipv4, has_ipv6) {
+                (true, true) => NetworkType::DualStack,
+                (false, true) => NetworkType::IPV6,
+                _ => NetworkType::IPv4,
+            },
+            gateway,
+            dns,
+            // names or addresses
+            ntp: split_list(&self.ntp).map(str::to_string).collect(),
+        })
+    }
 }
 
 // here we deal with Strings because we update them from InputFiled
@@ -71,9 +349,24 @@ pub struct IpDialogState {
     focus_tarcker_state: HashMap<String, usize>,
     pub new_iface_state: InterfaceState,
     pub old_iface_state: InterfaceState,
+    // why OK did not close the dialog
+    error: Option<String>,
 }
 
 impl IpDialogState {
+    // only the fields the user changed, a broken configuration from the
+    // controller does not block an unrelated change
+    fn check(&self) -> Result<(), String> {
+        let (new, old) = (&self.new_iface_state, &self.old_iface_state);
+        if !new.ip_dhcp {
+            new.static_config()?;
+        }
+        if !new.same_proxy(old) {
+            new.create_proxy_config(eve_types::ProxyConfig::default())?;
+        }
+        Ok(())
+    }
+
     pub fn get_focused_view(&self) -> Option<usize> {
         self.focus_tarcker_state.get(&self.selected_tab).copied()
     }
@@ -83,16 +376,7 @@ impl IpDialogState {
                 if self.new_iface_state.ip_dhcp {
                     vec!["ip_spinner"]
                 } else {
-                    vec![
-                        "ip_spinner",
-                        "ipv4",
-                        "ipv6",
-                        "mask",
-                        "gw",
-                        "domain",
-                        "dns",
-                        "ntp",
-                    ]
+                    vec!["ip_spinner", "addresses", "gw", "domain", "dns", "ntp"]
                 }
             }
             "Proxy" => match self.new_iface_state.proxy_type {
@@ -104,12 +388,13 @@ impl IpDialogState {
                         "https",
                         "ftp",
                         "socks",
+                        "exceptions",
                         "certificate",
                         "upload",
                     ]
                 }
                 ProxyType::Wad => vec!["proxy_spinner"],
-                ProxyType::Pac => vec!["proxy_spinner", "pac_file", "upload"],
+                ProxyType::Pac => vec!["proxy_spinner", "pac_file", "exceptions"],
             },
             _ => vec![],
         };
@@ -165,21 +450,10 @@ fn create_widgets(w: &mut Window<IpDialogState>) {
     );
 
     w.add_widget(
-        "ipv4",
-        InputFieldElement::new("IPv4", Some(w.state.new_iface_state.ipv4.as_str()))
-            .with_text_hint("e.g. 192.168.0.1"),
-    );
-
-    w.add_widget(
-        "ipv6",
-        InputFieldElement::new("IPv6", Some(w.state.new_iface_state.ipv6.as_str()))
-            .with_text_hint("e.g. c820::1"),
-    );
-
-    w.add_widget(
-        "mask",
-        InputFieldElement::new("Mask", Some(w.state.new_iface_state.mask.as_str()))
-            .with_text_hint("w.g. 255.255.255.0"),
+        "addresses",
+        ListEditorElement::new("Addresses", w.state.new_iface_state.addresses.clone())
+            .with_validator(|text| parse_address(text).map(|net| net.to_string()))
+            .with_text_hint("e.g. 192.168.0.10/24, 2001:db8::10/64"),
     );
     w.add_widget(
         "gw",
@@ -203,9 +477,14 @@ fn create_widgets(w: &mut Window<IpDialogState>) {
     );
 
     // proxy widgets
+    let index = match w.state.new_iface_state.proxy_type {
+        ProxyType::Manual => 1,
+        ProxyType::Pac => 2,
+        ProxyType::None | ProxyType::Wad => 0,
+    };
     w.add_widget(
         "proxy_spinner",
-        SpinBoxElement::new(vec!["None", "Manual", "Pac"]),
+        SpinBoxElement::new(vec!["None", "Manual", "Pac"]).selected(index),
     );
     w.add_widget(
         "http",
@@ -225,18 +504,35 @@ fn create_widgets(w: &mut Window<IpDialogState>) {
     );
     w.add_widget(
         "pac_file",
-        InputFieldElement::new("PAC file", Some(&w.state.new_iface_state.pac_file.as_str()))
-            .enabled(false),
+        InputFieldElement::new(
+            "PAC file URL",
+            Some(w.state.new_iface_state.pac_file.as_str()),
+        )
+        .with_text_hint("e.g. http://proxy.example.com/proxy.pac"),
+    );
+    w.add_widget(
+        "exceptions",
+        InputFieldElement::new(
+            "Exceptions",
+            Some(w.state.new_iface_state.proxy_exceptions.as_str()),
+        )
+        .with_text_hint("e.g. .example.com, 10.0.0.0/8"),
     );
+    add_certificate_widget(w);
+    w.add_widget("upload", ButtonElement::new("Upload"));
+}
+
+// also called to show the name of an imported file, the field has no setter
+fn add_certificate_widget(w: &mut Window<IpDialogState>) {
     w.add_widget(
         "certificate",
         InputFieldElement::new(
-            "Proxy Certificcate",
-            Some(&w.state.new_iface_state.proxy_certificate.as_str()),
+            "Proxy Certificate",
+            Some(w.state.new_iface_state.proxy_certificate.as_str()),
         )
+        .with_text_hint("Upload a .pem or .crt file from a USB stick")
         .enabled(false),
     );
-    w.add_widget("upload", ButtonElement::new("Upload"));
 }
 
 fn update_ip_layout(w: &mut Window<IpDialogState>, rect: &Rect) {
@@ -248,10 +544,10 @@ fn update_ip_layout(w: &mut Window<IpDialogState>, rect: &Rect) {
     w.update_layout("ip_spinner", spinner_rect);
 
     if !w.state.new_iface_state.ip_dhcp {
-        let [ip, ipv6, mask, gw, domain, dns, ntp] = Layout::vertical(vec![
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
+        // the addresses and a line for a new one
+        let addresses_height = w.state.new_iface_state.addresses.len() as u16 + 3;
+        let [addresses, gw, domain, dns, ntp] = Layout::vertical(vec![
+            Constraint::Length(addresses_height.min(8)),
             Constraint::Length(3),
             Constraint::Length(3),
             Constraint::Length(3),
@@ -259,10 +555,8 @@ fn update_ip_layout(w: &mut Window<IpDialogState>, rect: &Rect) {
         ])
         .areas(input_rect);
 
-        w.update_layout("ipv4", ip);
-        w.update_layout("mask", mask);
+        w.update_layout("addresses", addresses);
         w.update_layout("gw", gw);
-        w.update_layout("ipv6", ipv6);
         w.update_layout("domain", domain);
         w.update_layout("dns", dns);
         w.update_layout("ntp", ntp);
@@ -278,7 +572,8 @@ fn update_proxy_layout(w: &mut Window<IpDialogState>, rect: &Rect) {
     match w.state.new_iface_state.proxy_type {
         ProxyType::None => {}
         ProxyType::Manual => {
-            let [http, https, ftp, socks, certificate] = Layout::vertical(vec![
+            let [http, https, ftp, socks, exceptions, certificate] = Layout::vertical(vec![
+                Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
                 Constraint::Length(3),
@@ -296,17 +591,16 @@ fn update_proxy_layout(w: &mut Window<IpDialogState>, rect: &Rect) {
             w.update_layout("https", https);
             w.update_layout("ftp", ftp);
             w.update_layout("socks", socks);
+            w.update_layout("exceptions", exceptions);
             w.update_layout("certificate", cert_str);
             w.update_layout("upload", upload_button);
         }
         ProxyType::Pac => {
-            let [pac_file_area] = Layout::vertical(vec![Constraint::Length(3)]).areas(input_rect);
-            let [pac_url, upload] =
-                Layout::horizontal(vec![Constraint::Fill(1), Constraint::Length(10)])
-                    .flex(Flex::SpaceBetween)
-                    .areas(pac_file_area);
+            let [pac_url, exceptions] =
+                Layout::vertical(vec![Constraint::Length(3), Constraint::Length(3)])
+                    .areas(input_rect);
             w.update_layout("pac_file", pac_url);
-            w.update_layout("upload", upload);
+            w.update_layout("exceptions", exceptions);
         }
         ProxyType::Wad => {}
     }
@@ -361,8 +655,10 @@ fn ip_dialog_render(
     w: &mut Window<IpDialogState>,
     _rect: &Rect,
     frame: &mut Frame<'_>,
-    _model: &Rc<Model>,
+    model: &Rc<Model>,
 ) {
+    let theme = model.borrow().theme;
+    let read_only = model.borrow().read_only_reason().is_some();
     // render frame
     let frame_rect = w.get_layout("frame");
 
@@ -373,9 +669,18 @@ fn ip_dialog_render(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Double)
-        .border_style(Style::default().fg(Color::White))
-        .style(Style::default().bg(Color::Black))
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
         .title(w.state.new_iface_state.iface_name.as_str());
+    let block = if read_only {
+        block.title(" read-only, changes cannot be applied ".fg(theme.warning))
+    } else {
+        block
+    };
+    let block = match &w.state.error {
+        Some(error) => block.title_bottom(format!(" {} ", error).fg(theme.error)),
+        None => block,
+    };
 
     frame.render_widget(block, frame_rect);
 }
@@ -399,6 +704,9 @@ fn on_child_ui_action(
     action: &UiActions,
 ) -> Option<Action> {
     debug!("on_child_ui_action: {}:{:?}", source, action);
+    if !matches!(action, UiActions::ButtonClicked(_) | UiActions::Redraw) {
+        w.state.error = None;
+    }
     match action {
         UiActions::TabChanged(old_tab, selected_tab) => {
             save_restore_ft_state(w, old_tab, selected_tab);
@@ -424,20 +732,40 @@ fn on_child_ui_action(
         },
         UiActions::ButtonClicked(name) => match name.as_str() {
             "cancel" => Some(Action::new(&w.name, UiActions::DismissDialog)),
-            "ok" => Some(Action::new(
-                &w.name,
-                UiActions::AppAction(MonActions::NetworkInterfaceUpdated(
-                    w.state.old_iface_state.clone(),
-                    w.state.new_iface_state.clone(),
-                )),
-            )),
+            "Upload" => Some(Action::new(&w.name, UiActions::BrowseProxyCert)),
+            "ok" => {
+                if let Err(e) = w.state.check() {
+                    w.state.error = Some(e);
+                    return Some(Action::new(&w.name, UiActions::Redraw));
+                }
+                Some(Action::new(
+                    &w.name,
+                    UiActions::AppAction(MonActions::NetworkInterfaceUpdated(
+                        w.state.old_iface_state.clone(),
+                        w.state.new_iface_state.clone(),
+                    )),
+                ))
+            }
             _ => None,
         },
+        UiActions::ProxyCertLoaded { name, pems } => {
+            w.state.new_iface_state.proxy_certificate = match pems.len() {
+                1 => name.clone(),
+                n => format!("{} ({} certificates)", name, n),
+            };
+            w.state.new_iface_state.proxy_cert_pems = pems.clone();
+            add_certificate_widget(w);
+            Some(Action::new(&w.name, UiActions::Redraw))
+        }
+        UiActions::ListChanged { items } => {
+            if source == "addresses" {
+                w.state.new_iface_state.addresses = items.clone();
+            }
+            // the list may have grown
+            Some(Action::new(source, UiActions::Redraw))
+        }
         UiActions::Input { text } => {
             match source.as_str() {
-                "ipv4" => w.state.new_iface_state.ipv4 = text.clone(),
-                "ipv6" => w.state.new_iface_state.ipv6 = text.clone(),
-                "mask" => w.state.new_iface_state.mask = text.clone(),
                 "gw" => w.state.new_iface_state.gw = text.clone(),
                 "dns" => w.state.new_iface_state.dns = text.clone(),
                 "domain" => w.state.new_iface_state.domain = text.clone(),
@@ -445,6 +773,8 @@ fn on_child_ui_action(
                 "https" => w.state.new_iface_state.proxy_https = text.clone(),
                 "ftp" => w.state.new_iface_state.proxy_ftp = text.clone(),
                 "socks" => w.state.new_iface_state.proxy_socks = text.clone(),
+                "pac_file" => w.state.new_iface_state.pac_file = text.clone(),
+                "exceptions" => w.state.new_iface_state.proxy_exceptions = text.clone(),
                 "ntp" => w.state.new_iface_state.ntp = text.clone(),
                 _ => {}
             }
@@ -484,26 +814,26 @@ fn update_tab_order(w: &mut Window<IpDialogState>) {
 
 impl From<&NetworkInterfaceStatus> for IpDialogState {
     fn from(iface: &NetworkInterfaceStatus) -> Self {
-        // take only the first ipv4  and ipv6 address
-        // TODO: per Milan, we may get local IPs on interfaces
-        // need to find out how to filter them out
-        // but those are just to fill the dialog, so it's not a big deal
-        // user will change them anyway
-        let ipv4 = iface
+        // the prefix is known only for addresses in the subnet of the port. Link-local
+        // addresses cannot be static
+        let with_prefix = |addr: IpAddr| match iface.subnet {
+            Some(net) if net.contains(&addr) => format!("{}/{}", addr, net.prefix_len()),
+            _ => addr.to_string(),
+        };
+        let addresses = iface
             .ipv4
-            .as_ref()
-            .map(|ipv4: &Vec<std::net::Ipv4Addr>| ipv4.first().cloned())
-            .flatten()
-            .map(|addr| addr.to_string())
-            .unwrap_or_default();
-
-        let ipv6 = iface
-            .ipv6
-            .as_ref()
-            .map(|ipv6: &Vec<std::net::Ipv6Addr>| ipv6.first().cloned())
+            .iter()
             .flatten()
-            .map(|addr| addr.to_string())
-            .unwrap_or_default();
+            .map(|addr| with_prefix(IpAddr::V4(*addr)))
+            .chain(
+                iface
+                    .ipv6
+                    .iter()
+                    .flatten()
+                    .filter(|addr| !addr.is_link_local())
+                    .map(|addr| with_prefix(IpAddr::V6(*addr))),
+            )
+            .collect();
 
         let proxy_type = match iface.proxy_config {
             ProxyConfig::None => ProxyType::None,
@@ -565,16 +895,12 @@ impl From<&NetworkInterfaceStatus> for IpDialogState {
         let new_iface_state = InterfaceState {
             iface_name: iface.name.clone(),
             ip_dhcp: iface.is_dhcp,
-            ipv4: ipv4.clone(),
-            ipv6: ipv6.clone(),
+            addresses,
             proxy_type,
-            mask: iface
-                .subnet
-                .map(|ip| ip.netmask().to_string())
-                .unwrap_or_default(),
             gw: iface.gw.map(|ip| ip.to_string()).unwrap_or_default(),
             proxy_url,
             proxy_certificate: "".to_string(),
+            proxy_cert_pems: Vec::new(),
             pac_file,
             domain,
             dns,
@@ -583,6 +909,7 @@ impl From<&NetworkInterfaceStatus> for IpDialogState {
             proxy_http,
             proxy_https,
             proxy_socks,
+            proxy_exceptions: iface.proxy_exceptions.join(","),
         };
 
         let old_iface_state = new_iface_state.clone();
@@ -592,6 +919,7 @@ impl From<&NetworkInterfaceStatus> for IpDialogState {
             focus_tarcker_state: HashMap::new(),
             new_iface_state,
             old_iface_state,
+            error: None,
         }
     }
 }
@@ -609,3 +937,150 @@ pub fn create_ip_dialog(iface: &NetworkInterfaceStatus) -> impl IWindow {
         .unwrap();
     w
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(addresses: &str, gw: &str, dns: &str) -> InterfaceState {
+        InterfaceState {
+            iface_name: "eth0".to_string(),
+            ip_dhcp: false,
+            proxy_type: ProxyType::None,
+            addresses: split_list(addresses).map(str::to_string).collect(),
+            gw: gw.to_string(),
+            proxy_url: String::new(),
+            proxy_certificate: String::new(),
+            proxy_cert_pems: Vec::new(),
+            pac_file: String::new(),
+            domain: String::new(),
+            dns: dns.to_string(),
+            ntp: " pool.ntp.org, ".to_string(),
+            proxy_http: String::new(),
+            proxy_https: String::new(),
+            proxy_ftp: String::new(),
+            proxy_socks: String::new(),
+            proxy_exceptions: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut iface = state("192.168.1.10/24", "192.168.1.1", "");
+        assert_eq!(
+            iface.summary(),
+            "static 192.168.1.10/24 gw 192.168.1.1 ntp pool.ntp.org"
+        );
+        iface.ip_dhcp = true;
+        iface.proxy_type = ProxyType::Pac;
+        iface.pac_file = "http://wpad/proxy.pac".to_string();
+        assert_eq!(iface.summary(), "DHCP, proxy PAC http://wpad/proxy.pac");
+    }
+
+    #[test]
+    fn test_static_config() {
+        let config = state("192.168.1.10/24", "192.168.1.1", "")
+            .static_config()
+            .unwrap();
+        assert_eq!(
+            config.addr_subnets,
+            vec!["192.168.1.10/24".parse().unwrap()]
+        );
+        assert_eq!(config.network_type, NetworkType::IPv4);
+        assert_eq!(config.ntp, vec!["pool.ntp.org"]);
+
+        // resolvers of both families
+        let config = state("2001:db8::10/64", "fe80::1", "1.1.1.1, 2606:4700::1111")
+            .static_config()
+            .unwrap();
+        assert_eq!(config.network_type, NetworkType::IPV6);
+        assert_eq!(config.gateway, Some("fe80::1".parse().unwrap()));
+        assert_eq!(config.dns.len(), 2);
+
+        // the gateway may be in the subnet of any address
+        let config = state(
+            "10.0.0.5/8, 192.168.1.10/24, 2001:db8::10/64",
+            "192.168.1.1",
+            "",
+        )
+        .static_config()
+        .unwrap();
+        assert_eq!(config.addr_subnets.len(), 3);
+        assert_eq!(config.network_type, NetworkType::DualStack);
+
+        for (addresses, gw, dns) in [
+            ("", "", ""),
+            ("192.168.1.10", "", ""),
+            ("192.168.1.10/33", "", ""),
+            ("192.168.1.10/24", "192.168.2.1", ""),
+            ("192.168.1.10/24", "fe80::1", ""),
+            ("2001:db8::10/64", "192.168.1.1", ""),
+            ("2001:db8::10", "", ""),
+            ("fe80::10/64", "", ""),
+            ("0.0.0.0/0", "", ""),
+            ("2001:db8::10/64", "", "1.1.1"),
+        ] {
+            assert!(state(addresses, gw, dns).static_config().is_err());
+        }
+    }
+
+    #[test]
+    fn test_create_proxy_config() {
+        let current = eve_types::ProxyConfig {
+            network_proxy_url: "http://wpad.example.com".to_string(),
+            ..Default::default()
+        };
+        let mut manual = state("", "", "");
+        manual.proxy_type = ProxyType::Manual;
+        manual.proxy_http = "http://proxy.example.com:3128/".to_string();
+        manual.proxy_socks = "[2001:db8::1]:1080".to_string();
+        manual.proxy_exceptions = " .example.com, 10.0.0.0/8,*.local ".to_string();
+        let config = manual.create_proxy_config(current.clone()).unwrap();
+        let proxies = config.proxies.unwrap();
+        assert_eq!(proxies.len(), 2);
+        assert_eq!(proxies[0].server, "proxy.example.com");
+        assert_eq!(proxies[0].port, 3128);
+        assert_eq!(proxies[1].proxy_type, NetworkProxyType::SOCKS);
+        assert_eq!(config.exceptions, ".example.com,10.0.0.0/8,*.local");
+        assert_eq!(config.network_proxy_url, current.network_proxy_url);
+
+        let mut pac = state("", "", "");
+        pac.proxy_type = ProxyType::Pac;
+        pac.pac_file = "https://proxy.example.com/proxy.pac".to_string();
+        let config = pac.create_proxy_config(current.clone()).unwrap();
+        assert_eq!(config.pacfile, pac.pac_file);
+        assert!(config.proxies.is_none());
+
+        // imported certificates are added once to the ones EVE has
+        let cert = LargeBlob::Inline(b"first".to_vec());
+        pac.proxy_cert_pems = vec!["first".to_string(), "second".to_string()];
+        let config = pac
+            .create_proxy_config(eve_types::ProxyConfig {
+                proxy_cert_pem: Some(vec![cert.clone()]),
+                ..current.clone()
+            })
+            .unwrap();
+        assert_eq!(
+            config.proxy_cert_pem,
+            Some(vec![cert, LargeBlob::Inline(b"second".to_vec())])
+        );
+
+        for (proxy_type, http, pac_file, exceptions) in [
+            (ProxyType::Manual, "", "", ""),
+            (ProxyType::Manual, "proxy.example.com", "", ""),
+            (ProxyType::Manual, "proxy.example.com:0", "", ""),
+            (ProxyType::Manual, "proxy_example.com:80", "", ""),
+            (ProxyType::Manual, "proxy.example.com:80", "", "bad host"),
+            (ProxyType::Pac, "", "", ""),
+            (ProxyType::Pac, "", "ftp://proxy.example.com/proxy.pac", ""),
+            (ProxyType::Pac, "", "proxy.example.com/proxy.pac", ""),
+        ] {
+            let mut state = state("", "", "");
+            state.proxy_type = proxy_type;
+            state.proxy_http = http.to_string();
+            state.pac_file = pac_file.to_string();
+            state.proxy_exceptions = exceptions.to_string();
+            assert!(state.create_proxy_config(current.clone()).is_err());
+        }
+    }
+}
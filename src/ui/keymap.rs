@@ -0,0 +1,242 @@
+use std::collections::{HashMap, VecDeque};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use log::{debug, info, warn};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::config::KeymapConfig;
+
+use super::theme::Theme;
+
+// number of keys shown in the capture window
+const CAPTURE_HISTORY: usize = 10;
+
+/// Parse a key in the keymap notation e.g. "ctrl+e", "alt+1", "ctrl+left", "f5"
+pub fn parse_key(s: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = s.split('+').collect();
+    // "+" itself or "ctrl++"
+    if s.ends_with("++") || s == "+" {
+        parts.truncate(parts.len() - 2);
+        parts.push("+");
+    }
+    let (key, mods) = parts.split_last()?;
+    for m in mods {
+        modifiers |= match m.to_lowercase().as_str() {
+            "ctrl" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    let code = match key.to_lowercase().as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        f if f.len() > 1 && f.starts_with('f') => KeyCode::F(f[1..].parse().ok()?),
+        _ => {
+            let mut chars = key.chars();
+            let c = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            KeyCode::Char(c)
+        }
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+/// Format a key in the keymap notation so captured keys can be copied to the config
+pub fn key_to_string(key: &KeyEvent) -> String {
+    let mut s = String::new();
+    for (modifier, name) in [
+        (KeyModifiers::CONTROL, "ctrl+"),
+        (KeyModifiers::ALT, "alt+"),
+        (KeyModifiers::SHIFT, "shift+"),
+    ] {
+        if key.modifiers.contains(modifier) {
+            s.push_str(name);
+        }
+    }
+    match key.code {
+        KeyCode::Char(' ') => s.push_str("space"),
+        KeyCode::Char(c) => s.push(c),
+        KeyCode::F(n) => s.push_str(&format!("f{}", n)),
+        code => s.push_str(&format!("{:?}", code).to_lowercase()),
+    }
+    s
+}
+
+// modifiers may come with extra state bits we do not care about
+fn same_key(a: &KeyEvent, b: &KeyEvent) -> bool {
+    a.code == b.code && a.modifiers == b.modifiers
+}
+
+/// Translates two-key sequences (leader key + key) into the mapped keys for
+/// consoles that do not deliver modifier keys
+#[derive(Debug, Default)]
+pub struct KeyChords {
+    enabled: bool,
+    leader: Option<KeyEvent>,
+    chords: HashMap<KeyCode, KeyEvent>,
+    pending: bool,
+}
+
+impl KeyChords {
+    pub fn new(config: &KeymapConfig) -> Self {
+        let leader = match config.leader.as_str() {
+            "" => None,
+            leader => parse_key(leader).or_else(|| {
+                warn!("Invalid keymap leader '{}', chords disabled", leader);
+                None
+            }),
+        };
+        let mut chords = HashMap::new();
+        for (from, to) in config.chords.iter() {
+            match (parse_key(from), parse_key(to)) {
+                (Some(from), Some(to)) => {
+                    chords.insert(from.code, to);
+                }
+                _ => warn!("Invalid key chord '{}' -> '{}'", from, to),
+            }
+        }
+        Self {
+            enabled: config.enabled,
+            leader,
+            chords,
+            pending: false,
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.pending = false;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Returns the key to handle or None if the key is a part of an unfinished chord.
+    /// The leader key pressed twice is passed through as is
+    pub fn translate(&mut self, key: KeyEvent) -> Option<KeyEvent> {
+        let Some(leader) = self.leader.filter(|_| self.enabled) else {
+            return Some(key);
+        };
+        if self.pending {
+            self.pending = false;
+            if same_key(&key, &leader) {
+                return Some(key);
+            }
+            let mapped = self.chords.get(&key.code).copied();
+            if mapped.is_none() {
+                debug!("No chord for {}", key_to_string(&key));
+            }
+            return mapped;
+        }
+        if same_key(&key, &leader) {
+            self.pending = true;
+            return None;
+        }
+        Some(key)
+    }
+}
+
+/// Records keys exactly as they arrive from the console. Helps to find out what
+/// a KVM console sends instead of the expected key combinations
+#[derive(Debug, Default)]
+pub struct KeyCapture {
+    keys: VecDeque<KeyEvent>,
+}
+
+impl KeyCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, key: KeyEvent) {
+        info!("Key capture: {} {:?}", key_to_string(&key), key);
+        if self.keys.len() == CAPTURE_HISTORY {
+            self.keys.pop_front();
+        }
+        self.keys.push_back(key);
+    }
+
+    pub fn render(&self, area: &Rect, frame: &mut Frame<'_>, theme: &Theme) {
+        let rect = super::tools::centered_rect_fixed(60, CAPTURE_HISTORY as u16 + 4, *area);
+        let mut lines = vec![Line::from("Press keys to see what the console sends").dark_gray()];
+        lines.extend(self.keys.iter().map(|key| {
+            Line::from(format!(
+                "{:<16} {:?} {:?}",
+                key_to_string(key),
+                key.code,
+                key.modifiers
+            ))
+        }));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.surface))
+            .title(" Key capture. Esc to close ");
+        frame.render_widget(Clear, rect);
+        frame.render_widget(Paragraph::new(lines).block(block), rect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        let ctrl_e = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL);
+        assert_eq!(parse_key("ctrl+e"), Some(ctrl_e));
+        assert_eq!(key_to_string(&ctrl_e), "ctrl+e");
+        assert_eq!(
+            parse_key("Ctrl+Left"),
+            Some(KeyEvent::new(KeyCode::Left, KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            parse_key("alt++"),
+            Some(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::ALT))
+        );
+        assert_eq!(parse_key("f5"), Some(KeyEvent::from(KeyCode::F(5))));
+        assert_eq!(parse_key("f"), Some(KeyEvent::from(KeyCode::Char('f'))));
+        assert_eq!(parse_key("hyper+x"), None);
+        assert_eq!(parse_key("xy"), None);
+    }
+
+    #[test]
+    fn test_chords() {
+        let config = KeymapConfig::default();
+        let mut chords = KeyChords::new(&config);
+        let leader = parse_key(&config.leader).unwrap();
+        let e = KeyEvent::from(KeyCode::Char('e'));
+        assert_eq!(chords.translate(e), Some(e));
+        assert_eq!(chords.translate(leader), None);
+        assert!(chords.is_pending());
+        assert_eq!(chords.translate(e), parse_key("ctrl+e"));
+        assert_eq!(chords.translate(leader), None);
+        assert_eq!(chords.translate(leader), Some(leader));
+        assert!(!chords.is_pending());
+    }
+}
@@ -12,9 +12,23 @@ impl LayerStack {
         // clear focus on current top layer
         self.layers.push(layer);
     }
+    /// Replace the page at the bottom of the stack, the dialogs on top stay
+    pub fn set_base(&mut self, layer: Box<dyn IWindow>) {
+        match self.layers.first_mut() {
+            Some(base) => *base = layer,
+            None => self.layers.push(layer),
+        }
+    }
     pub fn pop(&mut self) -> Option<Box<dyn IWindow>> {
         self.layers.pop()
     }
+    // the page of the tab
+    pub fn first(&self) -> Option<&dyn IWindow> {
+        self.layers.first().map(|layer| layer.as_ref())
+    }
+    pub fn last(&self) -> Option<&dyn IWindow> {
+        self.layers.last().map(|layer| layer.as_ref())
+    }
     pub fn last_mut(&mut self) -> Option<&mut Box<dyn IWindow>> {
         self.layers.last_mut()
     }
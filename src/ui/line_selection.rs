@@ -0,0 +1,143 @@
+use std::ops::RangeInclusive;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{style::Stylize, text::Line};
+
+use super::{action::UiActions, export::TableExport, theme::Theme};
+
+/// Line cursor for paragraph panes so a part of the text, e.g. an error or
+/// a URL, can be exported on its own. 'v' starts at the top visible line,
+/// ▲▼ extend the selection, 'x' or Enter exports it and Esc leaves the mode
+#[derive(Debug, Default)]
+pub struct LineSelection {
+    // where the selection started, None when not selecting
+    anchor: Option<usize>,
+    cursor: usize,
+}
+
+impl LineSelection {
+    pub fn is_active(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    pub fn range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.anchor?;
+        Some(anchor.min(self.cursor)..=anchor.max(self.cursor))
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.range().is_some_and(|r| r.contains(&index))
+    }
+
+    pub fn is_cursor(&self, index: usize) -> bool {
+        self.is_active() && self.cursor == index
+    }
+
+    /// `line` at `index` marked if it is selected
+    pub fn style_line<'a>(&self, line: Line<'a>, index: usize, theme: &Theme) -> Line<'a> {
+        let line = if self.contains(index) {
+            line.bg(theme.selection)
+        } else {
+            line
+        };
+        if self.is_cursor(index) {
+            line.bold()
+        } else {
+            line
+        }
+    }
+
+    /// Handle a key of a pane with `len` lines scrolled to `scroll`. Returns
+    /// None for keys that are not about the selection
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        scroll: &mut usize,
+        page: usize,
+        len: usize,
+        name: &'static str,
+        lines: &[String],
+    ) -> Option<UiActions> {
+        if !self.is_active() {
+            if key.code == KeyCode::Char('v') && len > 0 {
+                self.cursor = (*scroll).min(len - 1);
+                self.anchor = Some(self.cursor);
+                return Some(UiActions::Redraw);
+            }
+            return None;
+        }
+        let last = len.saturating_sub(1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('v') => self.anchor = None,
+            KeyCode::Up => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down => self.cursor = (self.cursor + 1).min(last),
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(page),
+            KeyCode::PageDown => self.cursor = (self.cursor + page).min(last),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = last,
+            KeyCode::Char('x') | KeyCode::Enter => {
+                let export = self.export(name, lines)?;
+                self.anchor = None;
+                return Some(UiActions::ExportLines(export));
+            }
+            _ => return None,
+        }
+        // keep the cursor on the screen
+        if self.cursor < *scroll {
+            *scroll = self.cursor;
+        } else if self.cursor >= *scroll + page {
+            *scroll = self.cursor + 1 - page;
+        }
+        Some(UiActions::Redraw)
+    }
+
+    /// The selected lines with their numbers, counted from 1
+    pub fn export(&self, name: &'static str, lines: &[String]) -> Option<TableExport> {
+        let range = self.range()?;
+        let mut export = TableExport::new(name, vec!["Line", "Text"]);
+        let (start, end) = (*range.start(), *range.end());
+        for (i, line) in lines.iter().enumerate().skip(start).take(end + 1 - start) {
+            export.add_row(vec![(i + 1).to_string(), line.clone()]);
+        }
+        Some(export)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_line_selection() {
+        let lines: Vec<String> = (0..10).map(|i| format!("line {}", i)).collect();
+        let mut selection = LineSelection::default();
+        let mut scroll = 2;
+        let mut handle = |selection: &mut LineSelection, code| {
+            selection.handle_key(key(code), &mut scroll, 3, lines.len(), "test", &lines)
+        };
+        assert_eq!(handle(&mut selection, KeyCode::Down), None);
+        assert_eq!(
+            handle(&mut selection, KeyCode::Char('v')),
+            Some(UiActions::Redraw)
+        );
+        for _ in 0..3 {
+            handle(&mut selection, KeyCode::Down);
+        }
+        assert_eq!(selection.range(), Some(2..=5));
+        let Some(UiActions::ExportLines(export)) = handle(&mut selection, KeyCode::Char('x'))
+        else {
+            panic!("selection not exported");
+        };
+        assert_eq!(export.rows.len(), 4);
+        assert_eq!(export.rows[0], vec!["3", "line 2"]);
+        assert!(!selection.is_active());
+        // the cursor stayed visible
+        assert_eq!(scroll, 3);
+    }
+}
@@ -4,7 +4,7 @@ use crossterm::event::{KeyCode, KeyEvent};
 use log::debug;
 use ratatui::{
     layout::{Constraint, Flex, Layout, Margin, Rect},
-    style::{Color, Style},
+    style::Style,
     widgets::{Block, BorderType, Borders, Clear},
     Frame,
 };
@@ -34,8 +34,9 @@ fn do_render(
     w: &mut Window<MessageBoxState>,
     _rect: &Rect,
     frame: &mut Frame<'_>,
-    _model: &Rc<Model>,
+    model: &Rc<Model>,
 ) {
+    let theme = model.borrow().theme;
     // render frame
     let frame_rect = w.get_layout("frame");
 
@@ -46,8 +47,8 @@ fn do_render(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Double)
-        .border_style(Style::default().fg(Color::White))
-        .style(Style::default().bg(Color::Black))
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
         .title(w.name.clone());
 
     frame.render_widget(block, frame_rect);
@@ -56,7 +57,23 @@ fn do_render(
 fn do_layout(w: &mut Window<MessageBoxState>, rect: &Rect, _model: &Rc<Model>) {
     debug!("do_layout. selected tab");
 
-    let rect = crate::ui::tools::centered_rect_fixed(40, 10, *rect);
+    // grow with the content: longer lines make the box wider, then taller
+    let longest = w
+        .state
+        .content
+        .lines()
+        .map(|l| l.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = (longest as u16 + 4).clamp(40, 80);
+    let label_height: u16 = w
+        .state
+        .content
+        .lines()
+        .map(|l| (l.chars().count() as u16).div_ceil(width - 4).max(1))
+        .sum::<u16>()
+        .max(3);
+    let rect = crate::ui::tools::centered_rect_fixed(width, label_height + 7, *rect);
     let content_with_buttons = rect.inner(Margin {
         horizontal: 1,
         vertical: 1,
@@ -72,7 +89,8 @@ fn do_layout(w: &mut Window<MessageBoxState>, rect: &Rect, _model: &Rc<Model>) {
 
     // split dialog content area. Top - Input widget
     let [tabs, _dialog_content_rect] =
-        Layout::vertical(vec![Constraint::Length(3), Constraint::Fill(1)]).areas(dialog_content);
+        Layout::vertical(vec![Constraint::Length(label_height), Constraint::Fill(1)])
+            .areas(dialog_content);
     w.update_layout("label", tabs);
 
     // buttons
@@ -1,19 +1,57 @@
+pub mod about;
 pub mod action;
 pub mod activity;
+pub mod alerts_page;
+pub mod app_details;
+pub mod app_logs_viewer;
 pub mod app_page;
+pub mod audit_page;
+#[cfg(feature = "efi")]
+pub mod boot_order;
+pub mod bundle_dialog;
+pub mod cellular_page;
+pub mod command_palette;
+pub mod confirm_dialog;
+pub mod debug_page;
+pub mod diagnostics_dialog;
 pub mod dialog;
+pub mod dpc_history;
+#[cfg(feature = "efi")]
+pub mod efi_variables;
+pub mod export;
+pub mod file_browser;
+pub mod file_viewer;
 pub mod focus_tracker;
-#[cfg(debug_assertions)]
+#[cfg(test)]
+mod golden_tests;
+#[cfg(any(debug_assertions, feature = "home-page"))]
 pub mod homepage;
 pub mod input_dialog;
 pub mod ipdialog;
+pub mod keymap;
 pub mod layer_stack;
+pub mod line_selection;
 pub mod message_box;
+pub mod monitor_log_viewer;
 pub mod networkpage;
+pub mod resources_page;
+pub mod search;
+pub mod selection;
+pub mod selftest_splash;
+pub mod settings_page;
+pub mod snapshot_compare;
 pub mod statusbar;
+#[cfg(feature = "efi")]
+pub mod storage_page;
 pub mod summary_page;
+pub mod summary_settings;
+pub mod theme;
+pub mod toasts;
 pub mod tools;
+pub mod traffic_page;
 pub mod traits;
 pub mod ui;
+#[cfg(feature = "efi")]
+pub mod usb_page;
 pub mod widgets;
 pub mod window;
@@ -0,0 +1,391 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEventKind};
+use log::Level;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        model::Model,
+        monitor_log::{session_log_file, LogRecord, MonitorLogTail},
+        ring_buffer::RingBuffer,
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::{Action, UiActions},
+};
+
+use super::{
+    search::{highlight, matches},
+    theme::Theme,
+};
+
+// 'l' cycles through these
+const LEVEL_FILTERS: [Option<Level>; 3] = [None, Some(Level::Warn), Some(Level::Error)];
+
+// width of "[YYYY-MM-DD HH:MM:SS.mmm] WARN  "
+const PREFIX_WIDTH: usize = 32;
+
+/// The monitor's own log of the current session, to read it on a device
+/// without a shell. Follows the file, pages, filters by level and searches
+pub struct MonitorLogViewer {
+    tail: Option<MonitorLogTail>,
+    records: RingBuffer<LogRecord>,
+    error: Option<String>,
+    follow: bool,
+    // position in the shown records
+    position: usize,
+    page: usize,
+    // only records at least this severe are shown, all if None
+    min_level: Option<Level>,
+    search: String,
+    // the search string is being typed
+    searching: bool,
+}
+
+fn level_style(level: Option<Level>, theme: &Theme) -> Style {
+    match level {
+        Some(Level::Error) => Style::default().fg(theme.error),
+        Some(Level::Warn) => Style::default().fg(theme.warning),
+        Some(Level::Debug) | Some(Level::Trace) => Style::default().fg(theme.muted),
+        Some(Level::Info) | None => Style::default().fg(theme.text),
+    }
+}
+
+impl MonitorLogViewer {
+    pub fn new(capacity: usize) -> Self {
+        let mut viewer = Self {
+            tail: session_log_file().map(MonitorLogTail::new),
+            records: RingBuffer::new(capacity),
+            error: None,
+            follow: true,
+            position: 0,
+            page: 1,
+            min_level: None,
+            search: String::new(),
+            searching: false,
+        };
+        if viewer.tail.is_none() {
+            viewer.error = Some("Logging is not initialized".to_string());
+        }
+        viewer.read_log();
+        viewer
+    }
+
+    fn read_log(&mut self) {
+        let Some(tail) = self.tail.as_mut() else {
+            return;
+        };
+        match tail.read(&mut self.records) {
+            Ok(_) => self.error = None,
+            Err(e) => self.error = Some(format!("Cannot read {}: {}", tail.path().display(), e)),
+        }
+    }
+
+    // indexes of the records that pass the level filter
+    fn shown(&self) -> Vec<usize> {
+        self.records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| {
+                self.min_level
+                    .is_none_or(|min| record.level.unwrap_or(Level::Info) <= min)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn last_position(&self, shown: usize) -> usize {
+        shown.saturating_sub(self.page)
+    }
+
+    fn scroll_to(&mut self, position: usize, shown: usize) {
+        self.position = position.min(self.last_position(shown));
+        self.follow = self.position == self.last_position(shown);
+    }
+
+    fn record_lines(&self, record: &LogRecord, theme: &Theme) -> Vec<Line<'static>> {
+        let style = level_style(record.level, theme);
+        record
+            .message
+            .split('\n')
+            .enumerate()
+            .map(|(i, text)| {
+                let prefix = match (i, record.level) {
+                    (0, Some(level)) => format!("[{}] {:<5} ", record.time, level),
+                    _ => String::new(),
+                };
+                let mut spans = vec![Span::styled(
+                    format!("{:<PREFIX_WIDTH$}", prefix),
+                    Style::default().fg(theme.muted),
+                )];
+                spans.extend(highlight(text, &self.search, theme));
+                Line::from(spans).style(style)
+            })
+            .collect()
+    }
+
+    // the next or previous match from the top of the page, wraps around
+    fn find(&mut self, forward: bool) {
+        let shown = self.shown();
+        let targets: Vec<usize> = (0..shown.len())
+            .filter(|p| {
+                self.records
+                    .get(shown[*p])
+                    .is_some_and(|record| matches(&record.message, &self.search))
+            })
+            .collect();
+        let target = if forward {
+            targets
+                .iter()
+                .find(|p| **p > self.position)
+                .or(targets.first())
+        } else {
+            targets
+                .iter()
+                .rev()
+                .find(|p| **p < self.position)
+                .or(targets.last())
+        };
+        if let Some(position) = target.copied() {
+            self.position = position.min(self.last_position(shown.len()));
+            self.follow = false;
+        }
+    }
+
+    fn cycle_level_filter(&mut self) {
+        let current = LEVEL_FILTERS
+            .iter()
+            .position(|level| *level == self.min_level)
+            .unwrap_or(0);
+        self.min_level = LEVEL_FILTERS[(current + 1) % LEVEL_FILTERS.len()];
+        // positions are in the shown records, they all changed
+        self.follow = true;
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                self.searching = false;
+                if !self.search.is_empty() {
+                    // the newest match first
+                    self.position = self.shown().len();
+                    self.find(false);
+                }
+            }
+            KeyCode::Esc => {
+                self.searching = false;
+                self.search.clear();
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if self.searching {
+            self.handle_search_key(key);
+            return None;
+        }
+        let shown = self.shown().len();
+        let position = if self.follow {
+            self.last_position(shown)
+        } else {
+            self.position
+        };
+        match key.code {
+            KeyCode::Esc if !self.search.is_empty() => self.search.clear(),
+            KeyCode::Esc => return Some(Action::new("monitor log", UiActions::DismissDialog)),
+            KeyCode::Up => self.scroll_to(position.saturating_sub(1), shown),
+            KeyCode::Down => self.scroll_to(position + 1, shown),
+            KeyCode::PageUp => self.scroll_to(position.saturating_sub(self.page), shown),
+            KeyCode::PageDown => self.scroll_to(position + self.page, shown),
+            KeyCode::Home => self.scroll_to(0, shown),
+            KeyCode::End | KeyCode::Char(' ') => self.follow = true,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.search.clear();
+            }
+            KeyCode::Char('n') if !self.search.is_empty() => {
+                self.position = position;
+                self.find(true);
+            }
+            KeyCode::Char('N') if !self.search.is_empty() => {
+                self.position = position;
+                self.find(false);
+            }
+            KeyCode::Char('l') => self.cycle_level_filter(),
+            _ => {}
+        }
+        None
+    }
+
+    fn status_line(&self, shown: usize, theme: &Theme) -> Line<'static> {
+        let mode = if self.follow { " FOLLOW " } else { " SCROLL " };
+        let level = match self.min_level {
+            None => format!("all levels, {} records", self.records.len()),
+            Some(level) => format!("{} and worse, {} of {}", level, shown, self.records.len()),
+        };
+        let mut spans = vec![
+            Span::styled(mode, Style::default().fg(theme.surface).bg(theme.text)),
+            Span::raw(format!(" {} ", level)),
+        ];
+        if let Some(error) = &self.error {
+            spans.push(Span::raw(format!("{}  ", error)).fg(theme.error));
+        }
+        if self.searching {
+            spans.push(Span::raw(format!("/{}", self.search)).bold());
+            spans.push(Span::raw("_").slow_blink());
+            spans.push(Span::raw("  Enter to find, Esc to cancel").fg(theme.muted));
+        } else {
+            if !self.search.is_empty() {
+                spans.push(Span::raw(format!("/{}  ", self.search)).bold());
+            }
+            spans.push(
+                Span::raw("/ search, n/N next/prev, l level, space follow, Esc close")
+                    .fg(theme.muted),
+            );
+        }
+        Line::from(spans)
+    }
+}
+
+impl IPresenter for MonitorLogViewer {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let theme = model.borrow().theme;
+        frame.render_widget(Clear, *area);
+        let [title_area, log_area, status_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+        self.page = log_area.height as usize;
+
+        let shown = self.shown();
+        self.position = if self.follow {
+            self.last_position(shown.len())
+        } else {
+            // the filter or the page may have changed since the last key press
+            self.position.min(self.last_position(shown.len()))
+        };
+        let mut lines: Vec<Line> = shown
+            .iter()
+            .skip(self.position)
+            .take(self.page)
+            .filter_map(|index| self.records.get(*index))
+            .flat_map(|record| self.record_lines(record, &theme))
+            .collect();
+        // multiline records may not fit. Keep the newest lines visible when following
+        if self.follow {
+            lines.drain(..lines.len().saturating_sub(self.page));
+        }
+
+        let title = match self.tail.as_ref() {
+            Some(tail) => format!(" Monitor log {} ", tail.path().display()),
+            None => " Monitor log ".to_string(),
+        };
+        frame.render_widget(
+            Line::from(title).bold().fg(theme.accent).centered(),
+            title_area,
+        );
+        frame.render_widget(Paragraph::new(lines), log_area);
+        let mut scrollbar_state = ScrollbarState::new(shown.len()).position(self.position);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(Some("↑"))
+                .end_symbol(Some("↓")),
+            log_area,
+            &mut scrollbar_state,
+        );
+        frame.render_widget(self.status_line(shown.len(), &theme), status_area);
+    }
+}
+
+impl IEventHandler for MonitorLogViewer {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        match event {
+            Event::Tick => self.read_log(),
+            Event::Key(key) => return self.handle_key(key),
+            Event::Mouse(mouse) => {
+                let key = match mouse.kind {
+                    MouseEventKind::ScrollUp => KeyCode::Up,
+                    MouseEventKind::ScrollDown => KeyCode::Down,
+                    _ => return None,
+                };
+                return self.handle_key(KeyEvent::new(key, KeyModifiers::NONE));
+            }
+            Event::TerminalResize(_, _) | Event::Paste(_) => {}
+        }
+        None
+    }
+}
+
+impl IWindow for MonitorLogViewer {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: Level, message: &str) -> LogRecord {
+        LogRecord {
+            time: "2024-01-31 12:00:00.000".to_string(),
+            level: Some(level),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_filter_and_search() {
+        let mut viewer = MonitorLogViewer {
+            tail: None,
+            records: RingBuffer::new(10),
+            error: None,
+            follow: true,
+            position: 0,
+            page: 2,
+            min_level: None,
+            search: String::new(),
+            searching: false,
+        };
+        for (level, message) in [
+            (Level::Info, "ipc: connected"),
+            (Level::Warn, "ui: slow frame"),
+            (Level::Debug, "ipc: message"),
+            (Level::Error, "ipc: disconnected"),
+            (Level::Info, "main: tick"),
+        ] {
+            viewer.records.push(record(level, message));
+        }
+        viewer.cycle_level_filter();
+        assert_eq!(viewer.shown(), vec![1, 3]);
+        viewer.cycle_level_filter();
+        assert_eq!(viewer.shown(), vec![3]);
+        viewer.cycle_level_filter();
+        assert_eq!(viewer.shown().len(), 5);
+
+        // the newest match first, then backwards with wrap around
+        viewer.search = "IPC".to_string();
+        viewer.position = 5;
+        viewer.find(false);
+        assert_eq!(viewer.position, 3);
+        assert!(!viewer.follow);
+        viewer.find(false);
+        assert_eq!(viewer.position, 2);
+        viewer.find(false);
+        assert_eq!(viewer.position, 0);
+        viewer.find(true);
+        assert_eq!(viewer.position, 2);
+    }
+}
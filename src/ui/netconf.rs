@@ -218,8 +218,8 @@ impl NetworkDialog {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Double)
-            .border_style(Style::default().fg(Color::White))
-            .style(Style::default().bg(Color::Black))
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.surface))
             .title(self.interface_name.as_str());
 
         block.render(area, frame.buffer_mut());
@@ -355,7 +355,7 @@ impl IEventHandler for NetworkDialog {
                     other => Some(Action::new("edit network", other)),
                 }
             }
-            Event::Tick | Event::TerminalResize(_, _) => None,
+            Event::Tick | Event::TerminalResize(_, _) | Event::Paste(_) | Event::Mouse(_) => None,
         }
     }
 }
@@ -371,7 +371,7 @@ fn tabs() -> Tabs<'static> {
 impl NetworkTabs {
     fn to_tab_title(self) -> Line<'static> {
         let text = self.to_string();
-        format!(" {text} ").bg(Color::Black).into()
+        format!(" {text} ").bg(theme.surface).into()
     }
 
     /// Get the previous tab, if there is no previous tab return the current tab.
@@ -3,17 +3,18 @@ use std::{cell::RefCell, rc::Rc};
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Color, Style, Styled, Stylize},
+    style::{Style, Styled, Stylize},
     text::{Line, Span, Text},
     widgets::{
         Block, BorderType, Borders, Cell, HighlightSpacing, Padding, Paragraph, Row,
-        StatefulWidget, Table, TableState,
+        StatefulWidget, Table,
     },
     Frame,
 };
 
 use crate::{
     events::Event,
+    ipc::eve_types::BlobRef,
     model::device::network::{NetworkInterfaceStatus, NetworkType},
     model::model::{Model, MonitorModel},
     traits::{IEventHandler, IPresenter, IWindow},
@@ -21,52 +22,101 @@ use crate::{
 
 use super::{
     action::{Action, UiActions},
+    command_palette::Command,
+    export::TableExport,
+    selection::KeyedSelection,
+    theme::Theme,
     traits::ISelector,
 };
 
 const MAC_LENGTH: u16 = 17;
-const LINK_STATE_LENGTH: u16 = 4;
+const LINK_STATE_LENGTH: u16 = 5;
 const IPV6_AVERAGE_LENGTH: u16 = 25;
 const IFACE_LABEL_LENGTH: u16 = 10;
 
 #[derive(Default)]
 struct NetworkPage {
-    list: InterfaceList,
-    interface_names: Vec<String>,
+    list: KeyedSelection<String>,
 }
 
-struct InterfaceList {
-    state: TableState,
-    size: usize,
+fn join_list<T: ToString>(list: Option<&[T]>) -> String {
+    list.unwrap_or_default()
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-impl Default for InterfaceList {
-    fn default() -> Self {
-        Self {
-            state: TableState::default(),
-            size: 0,
+impl IWindow for NetworkPage {
+    fn commands(&self) -> Vec<Command> {
+        let mut commands = Vec::new();
+        if let Some(iface) = self.selected() {
+            commands.push(Command::new(
+                format!("Edit interface {}", iface),
+                "Enter",
+                UiActions::EditIfaceConfig(iface.clone()),
+            ));
+            commands.push(Command::new(
+                format!("Show proxy certificates of {}", iface),
+                "c",
+                UiActions::ShowProxyCerts(iface.clone()),
+            ));
+            commands.push(Command::new(
+                format!("Diagnose connectivity of {}", iface),
+                "d",
+                UiActions::Diagnose(iface),
+            ));
+        }
+        commands.push(Command::new(
+            "Show network configuration history",
+            "h",
+            UiActions::ShowDpcHistory,
+        ));
+        commands
+    }
+
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new(
+            "network",
+            vec!["Name", "Link", "IPv4", "IPv6", "MAC", "DNS", "Gateway"],
+        );
+        for iface in model.network.iter() {
+            export.add_row(vec![
+                iface.name.clone(),
+                if iface.up { "UP" } else { "DOWN" }.to_string(),
+                join_list(iface.ipv4.as_deref()),
+                join_list(iface.ipv6.as_deref()),
+                iface.mac.map(|mac| mac.to_string()).unwrap_or_default(),
+                join_list(iface.dns.as_deref()),
+                iface.gw.map(|gw| gw.to_string()).unwrap_or_default(),
+            ]);
         }
+        Some(export)
     }
 }
 
-impl IWindow for NetworkPage {}
+// a line per IP address
+fn iface_row_height(iface: &NetworkInterfaceStatus) -> u16 {
+    let ipv4_len = iface.ipv4.as_ref().map_or(0, |v| v.len());
+    let ipv6_len = iface.ipv6.as_ref().map_or(0, |v| v.len());
+    (ipv4_len + ipv6_len).max(1) as u16
+}
 
-fn info_row_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus) -> Row<'b> {
-    // cells #1,2 IFace name and Link status
+fn info_row_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus, theme: &Theme) -> Row<'b> {
+    // cells #1,2 IFace name and Link status. '*' marks a state seen by the
+    // kernel before EVE reported it
+    let mark = if iface.locally_observed { "*" } else { "" };
     let mut cells = vec![
         Cell::from(iface.name.clone()),
         if iface.up {
-            Cell::from("UP").style(Style::new().green())
+            Cell::from(format!("UP{}", mark)).style(Style::new().fg(theme.ok))
         } else {
-            Cell::from("DOWN").style(Style::new().red())
+            Cell::from(format!("DOWN{}", mark)).style(Style::new().fg(theme.error))
         },
     ];
 
     // collect IP addresses and add as multiline
-    let ipv4_len = iface.ipv4.as_ref().map_or(0, |v| v.len());
-    let ipv6_len = iface.ipv6.as_ref().map_or(0, |v| v.len());
-
-    let height = (ipv4_len + ipv6_len).max(1);
+    let height = iface_row_height(iface);
 
     // join Ipv4 and Ipv6 addresses and separate by newline
     let combined_ip_list_iter = iface
@@ -86,34 +136,38 @@ fn info_row_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus) -> Row<'b> {
 
     // cell #3 IP address list
     if height > 0 {
-        cells.push(Cell::from(combined_ip_list_iter).style(Style::new().white()));
+        cells.push(Cell::from(combined_ip_list_iter).style(Style::new().fg(theme.text)));
     } else {
-        cells.push(Cell::from("N/A").style(Style::new().red()));
+        cells.push(Cell::from("N/A").style(Style::new().fg(theme.error)));
     }
 
     // cell #4 MAC
     cells.push(
         Cell::from(iface.mac.map_or("N/A".to_string(), |e| e.to_string()))
-            .style(Style::new().yellow()),
+            .style(Style::new().fg(theme.warning)),
     );
 
-    Row::new(cells).height(height as u16)
+    Row::new(cells).height(height)
 }
 
-fn details_table_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus) -> Vec<Row<'b>> {
+fn details_table_from_iface<'a, 'b>(
+    iface: &'a NetworkInterfaceStatus,
+    proxy_certs: &[BlobRef],
+    theme: &Theme,
+) -> Vec<Row<'b>> {
     // Row 0: Interface type
     // //FIXME: doesn't work reliably
     let iface_type = iface.media.to_string();
     let iface_type_row = Row::new(vec![
-        Cell::from("Type").style(Style::new().yellow()),
-        Cell::from(iface_type).style(Style::new().white()),
+        Cell::from("Type").style(Style::new().fg(theme.warning)),
+        Cell::from(iface_type).style(Style::new().fg(theme.text)),
     ]);
 
     // IP type: DHCP/static
     let ip_source = if iface.is_dhcp { "DHCP" } else { "Static" };
     let ip_source_row = Row::new(vec![
-        Cell::from("IP source").style(Style::new().yellow()),
-        Cell::from(ip_source).style(Style::new().white()),
+        Cell::from("IP source").style(Style::new().fg(theme.warning)),
+        Cell::from(ip_source).style(Style::new().fg(theme.text)),
     ]);
 
     // Row 1: DNS
@@ -128,18 +182,56 @@ fn details_table_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus) -> Vec<Ro
     );
     let dns_row_height = iface.dns.as_ref().map_or(1, |v| v.len());
     let dns_row = Row::new(vec![
-        Cell::from("DNS").style(Style::new().yellow()),
-        Cell::from(dns).style(Style::new().white()),
+        Cell::from("DNS").style(Style::new().fg(theme.warning)),
+        Cell::from(dns).style(Style::new().fg(theme.text)),
     ])
     .height(dns_row_height as u16);
+    // Row 1a: resolver in use. Highlight servers EVE doesn't know about
+    let configured_dns = iface.dns.as_deref().unwrap_or_default();
+    let resolver = match &iface.resolver {
+        Some(resolver) => {
+            let mut lines: Vec<Line> = resolver
+                .nameservers
+                .iter()
+                .map(|ns| {
+                    let style = if configured_dns.contains(ns) {
+                        Style::new().fg(theme.text)
+                    } else {
+                        Style::new().fg(theme.error)
+                    };
+                    Line::styled(ns.to_string(), style)
+                })
+                .collect();
+            if !resolver.search.is_empty() {
+                lines.push(Line::styled(
+                    format!("search {}", resolver.search.join(" ")),
+                    Style::new().fg(theme.muted),
+                ));
+            }
+            if iface.dns_mismatch() {
+                lines.push(Line::styled(
+                    "Does not match DNS",
+                    Style::new().fg(theme.error).bold(),
+                ));
+            }
+            Text::from(lines)
+        }
+        None => Text::styled("N/A", Style::new().fg(theme.text)),
+    };
+    let resolver_row_height = resolver.height().max(1);
+    let resolver_row = Row::new(vec![
+        Cell::from("Resolver").style(Style::new().fg(theme.warning)),
+        Cell::from(resolver),
+    ])
+    .height(resolver_row_height as u16);
     // Row 2: Gateway
     let gateway = iface
         .gw
         .as_ref()
         .map_or("N/A".to_string(), |v| v.to_string());
     let gateway_row = Row::new(vec![
-        Cell::from("Gateway").style(Style::new().yellow()),
-        Cell::from(gateway).style(Style::new().white()),
+        Cell::from("Gateway").style(Style::new().fg(theme.warning)),
+        Cell::from(gateway).style(Style::new().fg(theme.text)),
     ]);
 
     // Row 3: NTP
@@ -154,12 +246,45 @@ fn details_table_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus) -> Vec<Ro
     );
     let ntp_row_height = iface.ntp_servers.as_ref().map_or(1, |v| v.len());
     let ntp_row = Row::new(vec![
-        Cell::from("NTP").style(Style::new().yellow()),
-        Cell::from(ntp).style(Style::new().white()),
+        Cell::from("NTP").style(Style::new().fg(theme.warning)),
+        Cell::from(ntp).style(Style::new().fg(theme.text)),
     ])
     .height(ntp_row_height as u16);
 
-    let mut table = vec![iface_type_row, ip_source_row, dns_row, gateway_row, ntp_row];
+    let source = if iface.locally_observed {
+        Cell::from("Kernel, not reported by EVE yet").style(Style::new().fg(theme.warning))
+    } else {
+        Cell::from("EVE").style(Style::new().fg(theme.text))
+    };
+    let source_row = Row::new(vec![
+        Cell::from("State from").style(Style::new().fg(theme.warning)),
+        source,
+    ]);
+
+    let mut table = vec![
+        source_row,
+        iface_type_row,
+        ip_source_row,
+        dns_row,
+        resolver_row,
+        gateway_row,
+        ntp_row,
+    ];
+
+    // only the metadata, the certificates are loaded when the user asks for them
+    if !proxy_certs.is_empty() {
+        let size: usize = proxy_certs.iter().map(|cert| cert.size).sum();
+        let proxy_certs_row = Row::new(vec![
+            Cell::from("Proxy CA").style(Style::new().fg(theme.warning)),
+            Cell::from(format!(
+                "{} certificate(s), {} bytes. Press 'c' for details",
+                proxy_certs.len(),
+                size
+            ))
+            .style(Style::new().fg(theme.text)),
+        ]);
+        table.push(proxy_certs_row);
+    }
 
     match &iface.media {
         NetworkType::Ethernet => {}
@@ -170,8 +295,8 @@ fn details_table_from_iface<'a, 'b>(iface: &'a NetworkInterfaceStatus) -> Vec<Ro
                 .as_ref()
                 .map_or("N/A".to_string(), |v| v.clone());
             let ssid_row = Row::new(vec![
-                Cell::from("SSID").style(Style::new().yellow()),
-                Cell::from(ssid).style(Style::new().white()),
+                Cell::from("SSID").style(Style::new().fg(theme.warning)),
+                Cell::from(ssid).style(Style::new().fg(theme.text)),
             ]);
             table.push(ssid_row);
         }
@@ -213,6 +338,7 @@ impl NetworkPage {
     }
 
     fn render_interface_details(&mut self, model: &Rc<Model>, rect: Rect, frame: &mut Frame) {
+        let theme = model.borrow().theme;
         let iface = self.get_selected_interface(model);
         if iface.is_none() {
             return;
@@ -220,19 +346,21 @@ impl NetworkPage {
         let iface = iface.unwrap();
         // create a table with the interface details. First column is the label, second column is the value
         // create header for the table
-        let rows = details_table_from_iface(&iface);
+        let proxy_certs = model.borrow().proxy_cert_refs(&iface.name);
+        let rows = details_table_from_iface(&iface, &proxy_certs, &theme);
         let table = Table::new(rows, [Constraint::Length(10), Constraint::Percentage(90)])
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .title(format!("{} Details", iface.name)),
             )
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .column_spacing(1);
 
         frame.render_widget(table, rect);
     }
     fn render_interface_list(&mut self, model: &Rc<Model>, list_rect: Rect, frame: &mut Frame) {
+        let theme = model.borrow().theme;
         // create header for the table
         let header = Row::new(vec![
             Cell::from("Name").style(Style::default()),
@@ -246,16 +374,25 @@ impl NetworkPage {
             .borrow()
             .network
             .iter()
-            .map(|iface| info_row_from_iface(iface))
+            .map(|iface| info_row_from_iface(iface, &theme))
             .collect::<Vec<_>>();
 
-        self.list.size = rows.len();
-        self.interface_names = model
-            .borrow()
-            .network
-            .iter()
-            .map(|iface| iface.name.clone())
-            .collect();
+        self.list.set_keys(
+            model
+                .borrow()
+                .network
+                .iter()
+                .map(|iface| iface.name.clone())
+                .collect(),
+        );
+        self.list.set_row_heights(
+            model
+                .borrow()
+                .network
+                .iter()
+                .map(iface_row_height)
+                .collect(),
+        );
 
         // create a surrounding block for the list
         let block = Block::default()
@@ -266,6 +403,7 @@ impl NetworkPage {
             // .border_style(Style::default().fg(Color::White).bg(Color::Black))
             // .style(Style::default().bg(Color::Black));
             .padding(Padding::new(1, 1, 1, 1));
+        self.list.set_table_area(block.inner(list_rect), 1);
 
         let bar = " █ ";
 
@@ -280,7 +418,7 @@ impl NetworkPage {
             ],
         )
         .block(block)
-        .row_highlight_style(Style::new().bg(Color::DarkGray))
+        .row_highlight_style(Style::new().bg(theme.selection))
         // .highlight_symbol(">")
         .highlight_symbol(Text::from(vec![
             // "".into(),
@@ -297,29 +435,38 @@ impl NetworkPage {
     }
 
     fn render_dpc_info(&mut self, model: &Rc<Model>, rect: Rect, frame: &mut Frame) {
+        let theme = model.borrow().theme;
         let dpc_key = model.borrow().dpc_key.clone().unwrap_or("N/A".to_string());
 
         let configuration_string = match dpc_key.as_str() {
-            "zedagent" => "From controller".green(),
-            "manual" => "Set by local user".yellow(),
-            s => s.red(),
+            "zedagent" => "From controller".fg(theme.ok),
+            "manual" => "Set by local user".fg(theme.warning),
+            s => s.fg(theme.error),
         };
 
         // convert DPC key into human readabel piece of information
         let dpc_info = Line::default().spans(vec![
-            "Current configuration: ".white(),
+            "Current configuration: ".fg(theme.text),
             configuration_string,
         ]);
 
         let mut text = Text::from(dpc_info);
 
         if dpc_key == "manual" {
-            text.push_line(vec!["WARNING: ".red(),"the configuratiion set locally will be overwritten by working configuration from the controller".white()]);
+            text.push_line(vec!["WARNING: ".fg(theme.error),"the configuratiion set locally will be overwritten by working configuration from the controller".fg(theme.text)]);
+        }
+
+        if model.borrow().maintenance_mode {
+            text.push_line(vec![
+                "MAINTENANCE: ".fg(theme.warning),
+                "configuration changes will not be applied until maintenance mode is cleared"
+                    .fg(theme.text),
+            ]);
         }
 
         // create paragraph with the DPC key
         let paragraph = Paragraph::new(text)
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(theme.text))
             .alignment(Alignment::Left);
 
         frame.render_widget(paragraph, rect);
@@ -340,8 +487,22 @@ impl IEventHandler for NetworkPage {
                         return Some(Action::new("net", UiActions::EditIfaceConfig(selected)));
                     }
                 }
+                KeyCode::Char('c') => {
+                    let selected = self.selected()?;
+                    return Some(Action::new("net", UiActions::ShowProxyCerts(selected)));
+                }
+                KeyCode::Char('d') => {
+                    let selected = self.selected()?;
+                    return Some(Action::new("net", UiActions::Diagnose(selected)));
+                }
+                KeyCode::Char('h') => {
+                    return Some(Action::new("net", UiActions::ShowDpcHistory));
+                }
                 _ => {}
             },
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+            }
             _ => {}
         }
         None
@@ -349,44 +510,29 @@ impl IEventHandler for NetworkPage {
 }
 
 impl ISelector for NetworkPage {
+    type Key = String;
+
     fn select_next(&mut self) {
-        if let Some(selected) = self.list.state.selected() {
-            if selected < self.list.size - 1 {
-                self.list.state.select(Some(selected + 1));
-            }
-        } else {
-            self.list.state.select(Some(0));
-        }
+        self.list.select_next();
     }
 
     fn select_previous(&mut self) {
-        if let Some(selected) = self.list.state.selected() {
-            let index = selected.saturating_sub(1);
-            self.list.state.select(Some(index));
-        }
+        self.list.select_previous();
     }
 
     fn select_first(&mut self) {
-        self.list.state.select(Some(0));
+        self.list.select_first();
     }
 
     fn select_last(&mut self) {
-        let index = self.list.size.saturating_sub(1);
-
-        self.list.state.select(Some(index));
+        self.list.select_last();
     }
 
     fn selected(&self) -> Option<String> {
-        self.list
-            .state
-            .selected()
-            .map(|index| self.interface_names[index].clone())
+        self.list.selected()
     }
 }
 
 pub fn create_network_page() -> impl IWindow {
-    NetworkPage {
-        list: InterfaceList::default(),
-        interface_names: vec![],
-    }
+    NetworkPage::default()
 }
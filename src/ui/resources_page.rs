@@ -0,0 +1,239 @@
+use std::rc::Rc;
+
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::Line,
+    widgets::{Block, BorderType, Borders, LineGauge, Padding, Paragraph, Sparkline},
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        device::{
+            resources::format_size,
+            sensors::{critical_celsius, sensor_level, SensorLevel},
+        },
+        model::{Model, MonitorModel},
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::Action,
+};
+
+use super::{export::TableExport, theme::Theme};
+
+// cores are shown in columns of this many bars
+const CORES_PER_COLUMN: usize = 16;
+
+/// Green, yellow above 75% and red above 90%
+pub fn usage_color(percent: u64, theme: &Theme) -> Color {
+    match percent {
+        90.. => theme.error,
+        75.. => theme.warning,
+        _ => theme.ok,
+    }
+}
+
+pub fn usage_gauge(label: String, percent: u64, theme: &Theme) -> LineGauge<'static> {
+    LineGauge::default()
+        .label(label)
+        .ratio(percent.min(100) as f64 / 100.0)
+        .filled_style(Style::new().fg(usage_color(percent, theme)))
+        .unfilled_style(Style::new().fg(theme.muted))
+        .line_set(symbols::line::THICK)
+}
+
+fn titled_block(title: String) -> Block<'static> {
+    Block::default()
+        .title(title)
+        .borders(Borders::TOP)
+        .border_type(BorderType::Plain)
+        .padding(Padding::horizontal(1))
+}
+
+/// CPU and memory of the host over the last minutes, every core and the load
+#[derive(Debug, Default)]
+pub struct ResourcesPage {}
+
+impl ResourcesPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn render_cores(&self, model: &MonitorModel, rect: Rect, frame: &mut Frame) {
+        let theme = model.theme;
+        let cores = &model.resources.cores;
+        let block = titled_block(format!(" {} cores ", cores.len()));
+        let inner = block.inner(rect);
+        frame.render_widget(block, rect);
+        let columns = cores.len().div_ceil(CORES_PER_COLUMN).max(1);
+        let column_rects = Layout::horizontal(vec![Constraint::Fill(1); columns]).split(inner);
+        for (c, (column, column_rect)) in cores
+            .chunks(CORES_PER_COLUMN)
+            .zip(column_rects.iter())
+            .enumerate()
+        {
+            let row_rects =
+                Layout::vertical(vec![Constraint::Length(1); column.len()]).split(*column_rect);
+            for ((i, percent), row_rect) in column.iter().enumerate().zip(row_rects.iter()) {
+                let core = c * CORES_PER_COLUMN + i;
+                frame.render_widget(
+                    usage_gauge(format!("{:>3} {:>3}%", core, percent), *percent, &theme),
+                    *row_rect,
+                );
+            }
+        }
+    }
+
+    fn render_sensors(&self, model: &MonitorModel, rect: Rect, frame: &mut Frame) {
+        let theme = model.theme;
+        let mut lines: Vec<Line> = model
+            .sensors
+            .iter()
+            .map(|reading| {
+                let color = match sensor_level(reading, &model.sensor_thresholds) {
+                    SensorLevel::Normal => theme.text,
+                    SensorLevel::Warning => theme.warning,
+                    SensorLevel::Critical => theme.error,
+                };
+                Line::from(format!(
+                    "{:<28} {:>6.1}°C  crit {:.0}°C",
+                    reading.key(),
+                    reading.celsius,
+                    critical_celsius(reading, &model.sensor_thresholds)
+                ))
+                .fg(color)
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("No temperature sensors").fg(theme.muted));
+        }
+        let block = titled_block(" Temperatures ".to_string());
+        frame.render_widget(Paragraph::new(lines).block(block), rect);
+    }
+}
+
+impl IPresenter for ResourcesPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let model = model.borrow();
+        let theme = model.theme;
+        let resources = &model.resources;
+
+        let [cpu_rect, memory_rect, cores_rect, load_rect] = Layout::vertical([
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+
+        let cpu = resources.cpu_percent();
+        let cpu_title = match cpu {
+            Some(percent) => format!(" CPU {}% ", percent),
+            None => " CPU ".to_string(),
+        };
+        // the newest samples on the right, as many as fit
+        let width = cpu_rect.width.saturating_sub(2) as usize;
+        let history: Vec<u64> = resources.cpu.iter().copied().collect();
+        let sparkline = Sparkline::default()
+            .block(titled_block(cpu_title))
+            .data(&history[history.len().saturating_sub(width)..])
+            .max(100)
+            .fg(usage_color(cpu.unwrap_or(0), &theme));
+        frame.render_widget(sparkline, cpu_rect);
+
+        let memory_title = match resources.meminfo {
+            Some(meminfo) => {
+                let mut title = format!(
+                    " Memory {} of {} used ",
+                    format_size(meminfo.used() * 1024),
+                    format_size(meminfo.total * 1024)
+                );
+                if meminfo.swap_total > 0 {
+                    title.push_str(&format!(
+                        "swap {} of {} used ",
+                        format_size(meminfo.swap_total.saturating_sub(meminfo.swap_free) * 1024),
+                        format_size(meminfo.swap_total * 1024)
+                    ));
+                }
+                title
+            }
+            None => " Memory ".to_string(),
+        };
+        let history: Vec<u64> = resources.memory.iter().copied().collect();
+        let sparkline = Sparkline::default()
+            .block(titled_block(memory_title))
+            .data(&history[history.len().saturating_sub(width)..])
+            .max(100)
+            .fg(usage_color(
+                resources.memory.back().copied().unwrap_or(0),
+                &theme,
+            ));
+        frame.render_widget(sparkline, memory_rect);
+
+        let [cores_rect, sensors_rect] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(50)]).areas(cores_rect);
+        self.render_cores(&model, cores_rect, frame);
+        self.render_sensors(&model, sensors_rect, frame);
+
+        let load = match resources.load {
+            Some(load) => format!(
+                " Load average {:.2} {:.2} {:.2}, one sample per second, {} kept",
+                load.one,
+                load.five,
+                load.fifteen,
+                resources.window()
+            ),
+            None => " No samples yet, is the resources collector on?".to_string(),
+        };
+        frame.render_widget(Line::from(load).fg(theme.muted), load_rect);
+    }
+}
+
+impl IEventHandler for ResourcesPage {
+    fn handle_event(&mut self, _event: Event) -> Option<Action> {
+        None
+    }
+}
+
+impl IWindow for ResourcesPage {
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let resources = &model.resources;
+        let mut export = TableExport::new("resources", vec!["Metric", "Value"]);
+        if let Some(percent) = resources.cpu_percent() {
+            export.add_row(vec!["cpu_percent".to_string(), percent.to_string()]);
+        }
+        for (core, percent) in resources.cores.iter().enumerate() {
+            export.add_row(vec![format!("cpu{}_percent", core), percent.to_string()]);
+        }
+        if let Some(meminfo) = resources.meminfo {
+            export.add_row(vec!["mem_total_kb".to_string(), meminfo.total.to_string()]);
+            export.add_row(vec![
+                "mem_available_kb".to_string(),
+                meminfo.available.to_string(),
+            ]);
+            export.add_row(vec![
+                "swap_total_kb".to_string(),
+                meminfo.swap_total.to_string(),
+            ]);
+            export.add_row(vec![
+                "swap_free_kb".to_string(),
+                meminfo.swap_free.to_string(),
+            ]);
+        }
+        if let Some(load) = resources.load {
+            export.add_row(vec!["load1".to_string(), load.one.to_string()]);
+            export.add_row(vec!["load5".to_string(), load.five.to_string()]);
+            export.add_row(vec!["load15".to_string(), load.fifteen.to_string()]);
+        }
+        for reading in model.sensors.iter() {
+            export.add_row(vec![
+                format!("{}_celsius", reading.key()),
+                reading.celsius.to_string(),
+            ]);
+        }
+        Some(export)
+    }
+}
@@ -0,0 +1,37 @@
+use ratatui::{style::Style, text::Span};
+
+use super::theme::Theme;
+
+// case-insensitive, ASCII case folding keeps byte offsets valid
+pub fn matches(text: &str, search: &str) -> bool {
+    !search.is_empty()
+        && text
+            .to_ascii_lowercase()
+            .contains(&search.to_ascii_lowercase())
+}
+
+// split the text into spans with the matches highlighted
+pub fn highlight(text: &str, search: &str, theme: &Theme) -> Vec<Span<'static>> {
+    if search.is_empty() {
+        return vec![Span::raw(text.to_string())];
+    }
+    let lower = text.to_ascii_lowercase();
+    let search = search.to_ascii_lowercase();
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (offset, found) in lower.match_indices(&search) {
+        if offset > start {
+            spans.push(Span::raw(text[start..offset].to_string()));
+        }
+        let end = offset + found.len();
+        spans.push(Span::styled(
+            text[offset..end].to_string(),
+            Style::default().fg(theme.surface).bg(theme.warning),
+        ));
+        start = end;
+    }
+    if start < text.len() {
+        spans.push(Span::raw(text[start..].to_string()));
+    }
+    spans
+}
@@ -0,0 +1,221 @@
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{layout::Rect, widgets::TableState};
+
+use super::traits::ISelector;
+
+/// Table selection that follows the selected row by its key so it does not
+/// jump when rows are added, removed or reordered. If the selected row goes
+/// away nothing is selected rather than the row that took its place, so an
+/// action never applies to an item the user did not pick
+#[derive(Debug)]
+pub struct KeyedSelection<K> {
+    pub state: TableState,
+    keys: Vec<K>,
+    selected: Option<K>,
+    // where the removed row was, navigation continues from there
+    removed_at: Option<usize>,
+    // where the rows were drawn at the last frame, for clicks
+    rows_area: Rect,
+    // rows are one line high if not set
+    row_heights: Vec<u16>,
+}
+
+impl<K> Default for KeyedSelection<K> {
+    fn default() -> Self {
+        Self {
+            state: TableState::default(),
+            keys: Vec::new(),
+            selected: None,
+            removed_at: None,
+            rows_area: Rect::default(),
+            row_heights: Vec::new(),
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> KeyedSelection<K> {
+    /// Set the keys of the rows in the order they are shown. Call before rendering
+    pub fn set_keys(&mut self, keys: Vec<K>) {
+        self.keys = keys;
+        let Some(key) = &self.selected else {
+            return;
+        };
+        match self.keys.iter().position(|k| k == key) {
+            Some(index) => self.state.select(Some(index)),
+            None => {
+                self.removed_at = self.state.selected();
+                self.select(None);
+            }
+        }
+    }
+
+    /// Remember where the table is rendered, `area` without borders and
+    /// `header_height` the lines of the header with its bottom margin
+    pub fn set_table_area(&mut self, area: Rect, header_height: u16) {
+        let header_height = header_height.min(area.height);
+        self.rows_area = Rect {
+            y: area.y + header_height,
+            height: area.height - header_height,
+            ..area
+        };
+    }
+
+    /// Heights of the rows in the order of the keys, for rows of more than one line
+    pub fn set_row_heights(&mut self, heights: Vec<u16>) {
+        self.row_heights = heights;
+    }
+
+    // the row drawn at `row` of the screen
+    fn row_at(&self, row: u16) -> Option<usize> {
+        let mut top = self.rows_area.y;
+        for index in self.state.offset()..self.len() {
+            top = top.saturating_add(*self.row_heights.get(index).unwrap_or(&1));
+            if row < top {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    /// A click selects the row under the pointer, the wheel moves the
+    /// selection. True if the event was for the table
+    pub fn handle_mouse(&mut self, event: MouseEvent) -> bool {
+        if !self.rows_area.contains((event.column, event.row).into()) {
+            return false;
+        }
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.row_at(event.row) {
+                    self.select(Some(index));
+                }
+            }
+            MouseEventKind::ScrollUp => self.select_previous(),
+            MouseEventKind::ScrollDown => self.select_next(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn select(&mut self, index: Option<usize>) {
+        self.state.select(index);
+        self.selected = index.and_then(|i| self.keys.get(i).cloned());
+        if index.is_some() {
+            self.removed_at = None;
+        }
+    }
+}
+
+impl<K: Clone + PartialEq> ISelector for KeyedSelection<K> {
+    type Key = K;
+
+    fn select_next(&mut self) {
+        match (self.state.selected(), self.removed_at) {
+            (Some(selected), _) if selected + 1 < self.len() => self.select(Some(selected + 1)),
+            (Some(_), _) => {}
+            // the row after the removed one took its place
+            (None, Some(removed)) if removed < self.len() => self.select(Some(removed)),
+            (None, _) if self.len() > 0 => self.select(Some(0)),
+            (None, _) => {}
+        }
+    }
+
+    fn select_previous(&mut self) {
+        match (self.state.selected(), self.removed_at) {
+            (Some(selected), _) => self.select(Some(selected.saturating_sub(1))),
+            (None, Some(removed)) if self.len() > 0 => {
+                self.select(Some(removed.saturating_sub(1).min(self.len() - 1)))
+            }
+            (None, _) => {}
+        }
+    }
+
+    fn select_first(&mut self) {
+        if self.len() > 0 {
+            self.select(Some(0));
+        }
+    }
+
+    fn select_last(&mut self) {
+        self.select(self.len().checked_sub(1));
+    }
+
+    fn selected(&self) -> Option<K> {
+        self.selected.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection_follows_key() {
+        let mut selection = KeyedSelection::default();
+        selection.set_keys(vec!["a", "b", "c"]);
+        assert_eq!(selection.selected(), None);
+        selection.select_next();
+        selection.select_next();
+        assert_eq!(selection.selected(), Some("b"));
+        // reordered
+        selection.set_keys(vec!["b", "c", "a"]);
+        assert_eq!(selection.state.selected(), Some(0));
+        assert_eq!(selection.selected(), Some("b"));
+        // removed, another row must not be picked for the user
+        selection.set_keys(vec!["c", "a"]);
+        assert_eq!(selection.selected(), None);
+        assert_eq!(selection.state.selected(), None);
+        // navigation continues where the row was
+        selection.select_next();
+        assert_eq!(selection.selected(), Some("c"));
+        selection.select_last();
+        selection.set_keys(Vec::new());
+        assert_eq!(selection.selected(), None);
+        selection.select_previous();
+        assert_eq!(selection.selected(), None);
+    }
+
+    #[test]
+    fn test_mouse_selection() {
+        use crossterm::event::KeyModifiers;
+
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        let click = |row| mouse(MouseEventKind::Down(MouseButton::Left), 5, row);
+
+        let mut selection = KeyedSelection::default();
+        selection.set_keys(vec!["a", "b", "c", "d"]);
+        selection.set_table_area(Rect::new(1, 2, 20, 4), 2);
+        // the header and outside the table
+        assert!(!selection.handle_mouse(click(3)));
+        assert!(!selection.handle_mouse(click(6)));
+        assert!(selection.handle_mouse(click(5)));
+        assert_eq!(selection.selected(), Some("b"));
+        // rows scrolled out of view
+        *selection.state.offset_mut() = 2;
+        assert!(selection.handle_mouse(click(4)));
+        assert_eq!(selection.selected(), Some("c"));
+        assert!(selection.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 4)));
+        assert_eq!(selection.selected(), Some("d"));
+        // below the last row
+        *selection.state.offset_mut() = 3;
+        assert!(selection.handle_mouse(click(5)));
+        assert_eq!(selection.selected(), Some("d"));
+
+        // the first row is 2 lines high
+        *selection.state.offset_mut() = 0;
+        selection.set_table_area(Rect::new(1, 2, 20, 6), 2);
+        selection.set_row_heights(vec![2, 1, 1, 1]);
+        selection.handle_mouse(click(5));
+        assert_eq!(selection.selected(), Some("a"));
+        selection.handle_mouse(click(7));
+        assert_eq!(selection.selected(), Some("c"));
+    }
+}
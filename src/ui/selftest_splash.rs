@@ -0,0 +1,149 @@
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::model::Model,
+    selftest::SelfTestResult,
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::{Action, UiActions},
+};
+
+// how long the splash stays on screen if all checks passed. Not counted in
+// ticks, the tick period is a setting
+const SPLASH_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub struct SelfTestSplash {
+    results: Vec<SelfTestResult>,
+    // None if the splash must be dismissed by the user
+    deadline: Option<Instant>,
+}
+
+impl SelfTestSplash {
+    pub fn new(results: Vec<SelfTestResult>) -> Self {
+        // keep failures on screen until the user acknowledges them
+        let deadline = results
+            .iter()
+            .all(|r| r.passed)
+            .then(|| Instant::now() + SPLASH_TIMEOUT);
+        Self { results, deadline }
+    }
+
+    // the line under the results, counts down to the deadline
+    fn hint(&self, now: Instant) -> String {
+        match self.deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(now);
+                // rounded up, "0s" is never shown
+                let secs = left.as_millis().div_ceil(1000).max(1);
+                format!("Closing in {}s, press any key to close now", secs)
+            }
+            None => "Press any key to continue".to_string(),
+        }
+    }
+}
+
+impl IPresenter for SelfTestSplash {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let theme = model.borrow().theme;
+        let mut lines: Vec<Line> = self
+            .results
+            .iter()
+            .map(|r| {
+                let status = if r.passed {
+                    Span::styled("[ OK ] ", Style::default().fg(theme.ok))
+                } else {
+                    Span::styled("[FAIL] ", Style::default().fg(theme.error))
+                };
+                Line::from(vec![
+                    status,
+                    Span::raw(format!("{:<10}", r.name)),
+                    Span::raw(r.details.clone()).fg(theme.muted),
+                ])
+            })
+            .collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from(self.hint(Instant::now())).centered());
+
+        let width = lines.iter().map(|l| l.width()).max().unwrap_or_default() as u16 + 4;
+        let height = lines.len() as u16 + 2;
+        let rect = crate::ui::tools::centered_rect_fixed(width, height, *area);
+
+        frame.render_widget(Clear, rect);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.surface))
+            .title(" Self-test ");
+        frame.render_widget(block, rect);
+        frame.render_widget(
+            Paragraph::new(lines),
+            rect.inner(Margin {
+                horizontal: 2,
+                vertical: 1,
+            }),
+        );
+    }
+
+    fn can_focus(&self) -> bool {
+        false
+    }
+}
+
+impl IEventHandler for SelfTestSplash {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        match event {
+            Event::Key(_) => Some(Action::new("selftest", UiActions::DismissDialog)),
+            Event::Tick => {
+                let deadline = self.deadline?;
+                (Instant::now() >= deadline)
+                    .then(|| Action::new("selftest", UiActions::DismissDialog))
+            }
+            Event::TerminalResize(_, _) | Event::Paste(_) | Event::Mouse(_) => None,
+        }
+    }
+}
+
+impl IWindow for SelfTestSplash {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hint() {
+        let passed = SelfTestResult {
+            name: "ipc",
+            passed: true,
+            details: String::new(),
+        };
+        let failed = SelfTestResult {
+            name: "ipc",
+            passed: false,
+            details: "no socket".to_string(),
+        };
+        let splash = SelfTestSplash::new(vec![passed]);
+        let deadline = splash.deadline.unwrap();
+        assert_eq!(
+            splash.hint(deadline - Duration::from_millis(2500)),
+            "Closing in 3s, press any key to close now"
+        );
+        assert_eq!(
+            splash.hint(deadline + Duration::from_secs(1)),
+            "Closing in 1s, press any key to close now"
+        );
+        let splash = SelfTestSplash::new(vec![failed]);
+        assert_eq!(splash.hint(Instant::now()), "Press any key to continue");
+    }
+}
@@ -0,0 +1,244 @@
+use std::rc::Rc;
+
+use crossterm::event::KeyCode;
+use log::LevelFilter;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Cell, HighlightSpacing, Paragraph, Row, Table},
+    Frame,
+};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::{
+    config::RuntimeSettings,
+    events::Event,
+    model::model::Model,
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::{Action, UiActions},
+};
+
+use super::theme::ThemeName;
+
+const LOG_LEVELS: [LevelFilter; 4] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+];
+const TICKS_MS: [u64; 4] = [250, 500, 1000, 2000];
+// seconds, 0 is off
+const IDLE_TIMEOUTS: [u64; 4] = [0, 60, 300, 900];
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumIter)]
+enum Setting {
+    #[strum(to_string = "Log level")]
+    LogLevel,
+    #[strum(to_string = "Refresh interval")]
+    Tick,
+    #[strum(to_string = "Idle timeout")]
+    IdleTimeout,
+    #[strum(to_string = "Key chords")]
+    KeyChords,
+    Theme,
+    #[cfg(feature = "dmesg")]
+    #[strum(to_string = "Kernel messages collector")]
+    Kmsg,
+    #[strum(to_string = "Netlink collector")]
+    Netlink,
+    #[strum(to_string = "Traffic collector")]
+    NetStats,
+    #[strum(to_string = "Resources collector")]
+    Metrics,
+    #[strum(to_string = "Sensors collector")]
+    Sensors,
+}
+
+fn on_off(value: bool) -> String {
+    if value { "on" } else { "off" }.to_string()
+}
+
+// the option after or before `current`. A value not in the list, e.g. set in
+// the config file, continues with the first option
+fn cycle<T: PartialEq + Copy>(options: &[T], current: T, forward: bool) -> T {
+    let next = match options.iter().position(|o| *o == current) {
+        Some(i) if forward => (i + 1) % options.len(),
+        Some(i) => (i + options.len() - 1) % options.len(),
+        None => 0,
+    };
+    options[next]
+}
+
+impl Setting {
+    fn value(self, settings: &RuntimeSettings) -> String {
+        match self {
+            Setting::LogLevel => settings.log_level.to_string().to_lowercase(),
+            Setting::Tick => format!("{} ms", settings.tick_ms),
+            Setting::IdleTimeout if settings.idle_timeout == 0 => "off".to_string(),
+            Setting::IdleTimeout => format!("{} s", settings.idle_timeout),
+            Setting::KeyChords => on_off(settings.key_chords),
+            Setting::Theme => settings.theme.to_string(),
+            #[cfg(feature = "dmesg")]
+            Setting::Kmsg => on_off(settings.kmsg),
+            Setting::Netlink => on_off(settings.netlink),
+            Setting::NetStats => on_off(settings.netstats),
+            Setting::Metrics => on_off(settings.metrics),
+            Setting::Sensors => on_off(settings.sensors),
+        }
+    }
+
+    fn change(self, settings: &RuntimeSettings, forward: bool) -> RuntimeSettings {
+        let mut settings = settings.clone();
+        match self {
+            Setting::LogLevel => {
+                settings.log_level = cycle(&LOG_LEVELS, settings.log_level, forward)
+            }
+            Setting::Tick => settings.tick_ms = cycle(&TICKS_MS, settings.tick_ms, forward),
+            Setting::IdleTimeout => {
+                settings.idle_timeout = cycle(&IDLE_TIMEOUTS, settings.idle_timeout, forward)
+            }
+            Setting::KeyChords => settings.key_chords ^= true,
+            Setting::Theme => {
+                let themes: Vec<ThemeName> = ThemeName::iter().collect();
+                settings.theme = cycle(&themes, settings.theme, forward)
+            }
+            #[cfg(feature = "dmesg")]
+            Setting::Kmsg => settings.kmsg ^= true,
+            Setting::Netlink => settings.netlink ^= true,
+            Setting::NetStats => settings.netstats ^= true,
+            Setting::Metrics => settings.metrics ^= true,
+            Setting::Sensors => settings.sensors ^= true,
+        }
+        settings
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Setting::LogLevel => "Messages written to monitor.log",
+            Setting::Tick => "How often the pages are refreshed",
+            Setting::IdleTimeout => "Refresh slower after no key was pressed for this long",
+            Setting::KeyChords => "Leader key sequences for consoles that lose Ctrl and Alt",
+            Setting::Theme => "Colors, theme_colors in config.json overrides single ones",
+            #[cfg(feature = "dmesg")]
+            Setting::Kmsg => "Read kernel messages for the Dmesg page and alerts",
+            Setting::Netlink => "Watch link and address changes between EVE reports",
+            Setting::NetStats => "Sample interface counters for the Traffic page",
+            Setting::Metrics => "Sample CPU, memory and load for the Resources page",
+            Setting::Sensors => "Read hwmon temperatures, warns when one gets critical",
+        }
+    }
+}
+
+/// Options that apply immediately and are saved to the config file
+#[derive(Debug, Default)]
+pub struct SettingsPage {
+    selected: usize,
+    // settings at the last frame, key handlers have no model
+    settings: RuntimeSettings,
+}
+
+impl SettingsPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn selected_setting(&self) -> Setting {
+        Setting::iter()
+            .nth(self.selected)
+            .unwrap_or(Setting::LogLevel)
+    }
+}
+
+impl IPresenter for SettingsPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        self.settings = model.borrow().settings.clone();
+        let theme = model.borrow().theme;
+
+        let rows: Vec<Row> = Setting::iter()
+            .enumerate()
+            .map(|(i, setting)| {
+                let row = Row::new(vec![
+                    Cell::from(setting.to_string()),
+                    Cell::from(setting.value(&self.settings)),
+                    Cell::from(setting.help()).fg(theme.muted),
+                ]);
+                if i == self.selected {
+                    row.reversed()
+                } else {
+                    row
+                }
+            })
+            .collect();
+        let block = Block::default()
+            .title(" Settings ")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain)
+            .border_style(Style::default().fg(theme.border));
+        let inner = block.inner(*area);
+        frame.render_widget(block, *area);
+
+        let [table_rect, help_rect] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(28),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ],
+        )
+        .highlight_spacing(HighlightSpacing::Never);
+        frame.render_widget(table, table_rect);
+        frame.render_widget(
+            Paragraph::new(Line::from(
+                " ▲▼ select, ◄► or Enter change. Changes apply now and are saved",
+            ))
+            .fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for SettingsPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let last = Setting::iter().count() - 1;
+        let forward = match key.code {
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                return None;
+            }
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(last);
+                return None;
+            }
+            KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => true,
+            KeyCode::Left => false,
+            _ => return None,
+        };
+        let settings = self.selected_setting().change(&self.settings, forward);
+        Some(Action::new("settings", UiActions::ChangeSettings(settings)))
+    }
+}
+
+impl IWindow for SettingsPage {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        assert_eq!(cycle(&TICKS_MS, 500, true), 1000);
+        assert_eq!(cycle(&TICKS_MS, 2000, true), 250);
+        assert_eq!(cycle(&TICKS_MS, 250, false), 2000);
+        // set by hand in the config file
+        assert_eq!(cycle(&TICKS_MS, 700, true), 250);
+        let settings = RuntimeSettings::default();
+        #[cfg(feature = "dmesg")]
+        assert!(!Setting::Kmsg.change(&settings, true).kmsg);
+    }
+}
@@ -0,0 +1,151 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    model::{diff::Change, model::Model, snapshot::SnapshotSection},
+    traits::IWindow,
+    ui::action::UiActions,
+};
+
+use super::{action::Action, line_selection::LineSelection, theme::Theme, window::Window};
+
+struct SnapshotCompareState {
+    scroll: usize,
+    page: usize,
+    // text of the lines at the last frame, key handlers have no model
+    text: Vec<String>,
+    selection: LineSelection,
+}
+
+fn change_line(change: &Change, theme: &Theme) -> Line<'static> {
+    let color = match change {
+        Change::Added(_) => theme.ok,
+        Change::Removed(_) => theme.error,
+        Change::Modified { .. } => theme.warning,
+    };
+    Line::from(format!("  {}", change)).fg(color)
+}
+
+fn do_layout(w: &mut Window<SnapshotCompareState>, rect: &Rect, _model: &Rc<Model>) {
+    let frame_rect = rect.inner(Margin {
+        horizontal: 2,
+        vertical: 1,
+    });
+    // borders
+    w.state.page = frame_rect.height.saturating_sub(2).max(1) as usize;
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<SnapshotCompareState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let model = model.borrow();
+    let theme = model.theme;
+    let (title, lines) = match model.snapshot.as_ref() {
+        Some(snapshot) => {
+            let changes = snapshot.compare(&model);
+            let mut lines = Vec::new();
+            if changes.is_empty() {
+                lines.push(Line::from("Nothing changed since the snapshot").fg(theme.muted));
+            }
+            let mut section: Option<SnapshotSection> = None;
+            for (change_section, change) in changes.iter() {
+                if section != Some(*change_section) {
+                    section = Some(*change_section);
+                    lines.push(Line::from(change_section.to_string()).bold());
+                }
+                lines.push(change_line(change, &theme));
+            }
+            (
+                format!(
+                    " Changes since '{}' taken at {} ",
+                    snapshot.name,
+                    snapshot.taken.format("%H:%M:%S")
+                ),
+                lines,
+            )
+        }
+        None => (
+            " Snapshot ".to_string(),
+            vec![Line::from("No snapshot, press Alt+s to take one").fg(theme.warning)],
+        ),
+    };
+
+    let state = &mut w.state;
+    state.text = lines.iter().map(|line| line.to_string()).collect();
+    state.scroll = state
+        .scroll
+        .min(state.text.len().saturating_sub(state.page));
+    let lines: Vec<Line> = lines
+        .into_iter()
+        .enumerate()
+        .skip(state.scroll)
+        .take(state.page)
+        .map(|(i, line)| state.selection.style_line(line, i, &theme))
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(title)
+        .title_bottom(" 'v' to select, Esc to close ");
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+}
+
+fn on_key_event(w: &mut Window<SnapshotCompareState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    if let Some(action) = state.selection.handle_key(
+        key,
+        &mut state.scroll,
+        state.page,
+        state.text.len(),
+        "snapshot-compare",
+        &state.text,
+    ) {
+        return Some(Action::new(&w.name, action));
+    }
+    let last = state.text.len().saturating_sub(state.page);
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Up => state.scroll = state.scroll.saturating_sub(1),
+        KeyCode::Down => state.scroll = (state.scroll + 1).min(last),
+        KeyCode::PageUp => state.scroll = state.scroll.saturating_sub(state.page),
+        KeyCode::PageDown => state.scroll = (state.scroll + state.page).min(last),
+        KeyCode::Home => state.scroll = 0,
+        KeyCode::End => state.scroll = last,
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+/// The live state compared with the snapshot, updated on every frame
+pub fn create_snapshot_compare() -> impl IWindow {
+    Window::builder("snapshot compare")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(SnapshotCompareState {
+            scroll: 0,
+            page: 1,
+            text: Vec::new(),
+            selection: LineSelection::default(),
+        })
+        .build()
+        .unwrap()
+}
@@ -1,44 +1,277 @@
+use std::{rc::Rc, time::Instant};
+
 use ratatui::{
-    layout::{Constraint, Flex, Layout, Margin},
-    style::{Color, Style},
+    layout::{Margin, Rect},
+    style::{Style, Stylize},
+    text::Span,
     widgets::{Block, BorderType, Borders, WidgetRef},
+    Frame,
+};
+
+use crate::{
+    config::MonitorConfig,
+    ipc::eve_types::SupportBundleState,
+    model::alerts::AlertSeverity,
+    model::model::{IpcConnectionState, Model, MonitorModel},
 };
 
 use super::{widgets::label::LabelElement, window::Window};
 
-pub struct StatusBarState {}
+const SEGMENT_SEPARATOR: &str = " │ ";
+
+type SegmentFn = Box<dyn Fn(&MonitorModel) -> Option<Span<'static>>>;
+
+enum SegmentContent {
+    // text computed from the model on every frame. None if there is nothing to show
+    Text(SegmentFn),
+    // a widget of the window that updates itself e.g. on timer tick
+    Widget { name: String, width: u16 },
+}
 
-pub fn create_status_bar() -> Window<StatusBarState> {
-    let clock = LabelElement::new("Clock").on_tick(|label| {
-        let now = chrono::Local::now();
-        let time = now.format("%H:%M:%S").to_string();
-        label.set_text(time);
+struct StatusBarSegment {
+    // segments with the lowest priority are hidden first when the bar is too narrow
+    priority: u8,
+    content: SegmentContent,
+}
+
+enum VisibleSegment {
+    Text(Span<'static>),
+    Widget(String),
+}
+
+pub struct StatusBarState {
+    // in order of appearance from left to right
+    segments: Vec<StatusBarSegment>,
+    // text segments and separators for the current frame. Updated in layout
+    visible: Vec<(Rect, Span<'static>)>,
+}
+
+impl StatusBarState {
+    fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            visible: Vec::new(),
+        }
+    }
+
+    fn add_text_segment<F>(&mut self, priority: u8, content: F)
+    where
+        F: Fn(&MonitorModel) -> Option<Span<'static>> + 'static,
+    {
+        self.segments.push(StatusBarSegment {
+            priority,
+            content: SegmentContent::Text(Box::new(content)),
+        });
+    }
+
+    fn add_widget_segment<S: Into<String>>(&mut self, priority: u8, name: S, width: u16) {
+        self.segments.push(StatusBarSegment {
+            priority,
+            content: SegmentContent::Widget {
+                name: name.into(),
+                width,
+            },
+        });
+    }
+}
+
+// place segments right-aligned in the inner area of the status bar
+fn do_layout(w: &mut Window<StatusBarState>, rect: &Rect, model: &Rc<Model>) {
+    let theme = model.borrow().theme;
+    let inner_rect = rect.inner(Margin {
+        horizontal: 1,
+        vertical: 1,
     });
 
-    let w = Window::builder("StatusBar")
-        .with_state(StatusBarState {})
-        .widget("Clock", clock)
-        .with_layout(|w, rect, _model| {
-            let inner_rect = rect.inner(Margin {
-                horizontal: 1,
-                vertical: 1,
-            });
-
-            let layout = Layout::horizontal([Constraint::Length(0), Constraint::Length(8)])
-                .flex(Flex::End)
-                .split(inner_rect);
-            w.update_layout("Clock", layout[1]);
-        })
-        .with_render(|_w, rect, frame, _model| {
-            let blk = Block::new()
-                //.border_type(BorderType::Rounded)
-                //FIXME: need new Font
-                .border_type(BorderType::Plain)
-                .borders(Borders::ALL)
-                .style(Style::default().bg(Color::Black));
-
-            blk.render_ref(*rect, frame.buffer_mut());
+    let mut segments: Vec<(u8, u16, VisibleSegment)> = w
+        .state
+        .segments
+        .iter()
+        .filter_map(|s| match &s.content {
+            SegmentContent::Text(content) => content(&model.borrow())
+                .map(|span| (s.priority, span.width() as u16, VisibleSegment::Text(span))),
+            SegmentContent::Widget { name, width } => {
+                Some((s.priority, *width, VisibleSegment::Widget(name.clone())))
+            }
         })
+        .collect();
+
+    let separator_width = SEGMENT_SEPARATOR.chars().count() as u16;
+    let total_width = |segments: &Vec<(u8, u16, VisibleSegment)>| {
+        let content: u16 = segments.iter().map(|(_, width, _)| width).sum();
+        content + segments.len().saturating_sub(1) as u16 * separator_width
+    };
+
+    // drop least important segments until the rest fits
+    while total_width(&segments) > inner_rect.width {
+        let Some(index) = segments
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (priority, _, _))| *priority)
+            .map(|(index, _)| index)
+        else {
+            break;
+        };
+        segments.remove(index);
+    }
+
+    // hidden widgets must not be rendered
+    w.clear_layout();
+    w.state.visible.clear();
+
+    let mut x = inner_rect.right().saturating_sub(total_width(&segments));
+    for (index, (_, width, segment)) in segments.into_iter().enumerate() {
+        if index > 0 {
+            let separator_rect = Rect::new(x, inner_rect.y, separator_width, 1);
+            let separator = Span::styled(SEGMENT_SEPARATOR, Style::default().fg(theme.muted));
+            w.state.visible.push((separator_rect, separator));
+            x += separator_width;
+        }
+        let segment_rect = Rect::new(x, inner_rect.y, width, 1).intersection(inner_rect);
+        match segment {
+            VisibleSegment::Text(span) => w.state.visible.push((segment_rect, span)),
+            VisibleSegment::Widget(name) => w.update_layout(name, segment_rect),
+        }
+        x += width;
+    }
+}
+
+fn do_render(
+    w: &mut Window<StatusBarState>,
+    rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let blk = Block::new()
+        //.border_type(BorderType::Rounded)
+        //FIXME: need new Font
+        .border_type(BorderType::Plain)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface));
+
+    blk.render_ref(*rect, frame.buffer_mut());
+
+    for (rect, span) in w.state.visible.iter() {
+        frame.render_widget(span, *rect);
+    }
+}
+
+fn ipc_state_segment(model: &MonitorModel) -> Option<Span<'static>> {
+    let theme = model.theme;
+    let span = match model.ipc_state {
+        IpcConnectionState::Disconnected => "IPC: disconnected".fg(theme.error),
+        IpcConnectionState::Connecting => "IPC: connecting".fg(theme.warning),
+        IpcConnectionState::Connected => "IPC: connected".fg(theme.ok),
+        IpcConnectionState::Replay => "IPC: replaying".fg(theme.warning),
+        IpcConnectionState::Reconnecting { attempt, retry_at } => {
+            let seconds = retry_at
+                .saturating_duration_since(Instant::now())
+                .as_secs_f32()
+                .ceil();
+            format!("IPC: lost, retry #{} in {}s", attempt, seconds).fg(theme.error)
+        }
+        IpcConnectionState::Failed {
+            restart_at: Some(restart_at),
+        } => {
+            let seconds = restart_at
+                .saturating_duration_since(Instant::now())
+                .as_secs_f32()
+                .ceil();
+            format!("IPC: failed, restart in {}s", seconds).fg(theme.error)
+        }
+        IpcConnectionState::Failed { restart_at: None } => {
+            "IPC: failed".fg(theme.text).bg(theme.error)
+        }
+    };
+    Some(span)
+}
+
+fn pending_requests_segment(model: &MonitorModel) -> Option<Span<'static>> {
+    let theme = model.theme;
+    match model.pending_requests {
+        0 => None,
+        n => Some(format!("Pending: {}", n).fg(theme.warning)),
+    }
+}
+
+fn support_bundle_segment(model: &MonitorModel) -> Option<Span<'static>> {
+    let theme = model.theme;
+    let status = model.support_bundle.as_ref()?;
+    let span = match status.state {
+        SupportBundleState::Generating => "Bundle: generating".fg(theme.warning),
+        SupportBundleState::Ready => "Bundle: ready".fg(theme.ok),
+        SupportBundleState::Uploading => {
+            format!("Bundle: uploading {}%", status.progress).fg(theme.warning)
+        }
+        SupportBundleState::Uploaded => "Bundle: uploaded".fg(theme.ok),
+        SupportBundleState::Failed => "Bundle: failed".fg(theme.error),
+        SupportBundleState::Unrecognized => "Bundle: unknown state".fg(theme.warning),
+    };
+    Some(span)
+}
+
+fn sensors_segment(model: &MonitorModel) -> Option<Span<'static>> {
+    let theme = model.theme;
+    let reading = model.critical_sensor()?;
+    Some(
+        format!("Temp: {} {:.0}°C", reading.label, reading.celsius)
+            .fg(theme.text)
+            .bg(theme.error),
+    )
+}
+
+fn read_only_segment(model: &MonitorModel) -> Option<Span<'static>> {
+    model.read_only_reason()?;
+    Some("Read-only".fg(model.theme.warning))
+}
+
+fn alerts_segment(model: &MonitorModel) -> Option<Span<'static>> {
+    let theme = model.theme;
+    let alert = model.new_alerts().last()?;
+    let text = format!(
+        "Alerts: {} new (last {} {})",
+        model.new_alerts().count(),
+        alert.time.format("%H:%M:%S"),
+        alert.source
+    );
+    let span = match alert.severity {
+        AlertSeverity::Info => text.into(),
+        AlertSeverity::Warning => text.fg(theme.warning),
+        AlertSeverity::Critical => text.fg(theme.error),
+    };
+    Some(span)
+}
+
+pub fn create_status_bar(config: &MonitorConfig) -> Window<StatusBarState> {
+    let mut state = StatusBarState::new();
+
+    state.add_text_segment(2, ipc_state_segment);
+    state.add_text_segment(2, read_only_segment);
+    state.add_text_segment(2, alerts_segment);
+    state.add_text_segment(2, sensors_segment);
+    state.add_text_segment(1, pending_requests_segment);
+    state.add_text_segment(1, support_bundle_segment);
+
+    let mut builder = Window::builder("StatusBar");
+    if !config.clock_format.is_empty() {
+        let clock_format = config.clock_format.clone();
+        let width =
+            Span::raw(chrono::Local::now().format(&clock_format).to_string()).width() as u16;
+        let clock = LabelElement::new("").on_tick(move |label| {
+            let now = chrono::Local::now();
+            let time = now.format(&clock_format).to_string();
+            label.set_text(time);
+        });
+        builder = builder.widget("Clock", clock);
+        state.add_widget_segment(0, "Clock", width);
+    }
+
+    let w = builder
+        .with_state(state)
+        .with_layout(do_layout)
+        .with_render(do_render)
         .build();
 
     w.unwrap()
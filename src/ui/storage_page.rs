@@ -0,0 +1,257 @@
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, Gauge, HighlightSpacing, Padding, Row, StatefulWidget, Table,
+    },
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        device::{
+            efi::efi_current_boot_entry,
+            resources::format_size,
+            storage::{
+                block_devices, fs_usage, BlockDevice, PartitionRole, Usage, PERSIST_MOUNT_POINT,
+            },
+        },
+        model::{Model, MonitorModel},
+        sanitize::sanitize_line,
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::{Action, UiActions},
+};
+
+use super::{
+    command_palette::Command, export::TableExport, selection::KeyedSelection, traits::ISelector,
+};
+
+// partitions do not change often, statvfs on every tick is not needed
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+// above this persist is shown as running out of space
+const PERSIST_WARNING_PERCENT: u64 = 90;
+
+const HEADER: [&str; 6] = ["Device", "Size", "Role", "Label", "Mount", "Use%"];
+
+/// Disks and partitions with the roles EVE gives them, and how full persist is
+#[derive(Debug, Default)]
+pub struct StoragePage {
+    list: KeyedSelection<String>,
+    devices: Vec<BlockDevice>,
+    // GUID of the partition the firmware loaded the bootloader from
+    boot_partition: Option<String>,
+    persist: Option<Usage>,
+    refreshed: Option<Instant>,
+}
+
+impl StoragePage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn refresh(&mut self) {
+        self.devices = block_devices();
+        self.boot_partition = efi_current_boot_entry().and_then(|entry| entry.partition);
+        self.persist = fs_usage(PERSIST_MOUNT_POINT);
+        self.refreshed = Some(Instant::now());
+    }
+
+    fn is_boot_partition(&self, device: &BlockDevice) -> bool {
+        device.guid.is_some() && device.guid == self.boot_partition
+    }
+
+    fn cells(&self, device: &BlockDevice) -> Vec<String> {
+        let role = match device.role {
+            Some(role) if self.is_boot_partition(device) => format!("{} (boot)", role),
+            Some(role) => role.to_string(),
+            None if self.is_boot_partition(device) => "boot".to_string(),
+            None => String::new(),
+        };
+        vec![
+            match device.disk {
+                Some(_) => format!("  {}", device.name),
+                None => device.name.clone(),
+            },
+            format_size(device.size),
+            role,
+            sanitize_line(&device.label),
+            device.mount_point.clone().unwrap_or_default(),
+            device
+                .usage
+                .map(|usage| format!("{}%", usage.percent()))
+                .unwrap_or_default(),
+        ]
+    }
+
+    fn render_persist(&self, model: &MonitorModel, rect: Rect, frame: &mut Frame) {
+        let theme = model.theme;
+        let Some(usage) = self.persist else {
+            frame.render_widget(
+                Line::from(format!(" {} is not mounted", PERSIST_MOUNT_POINT)).fg(theme.error),
+                rect,
+            );
+            return;
+        };
+        let percent = usage.percent();
+        let color = if percent > PERSIST_WARNING_PERCENT {
+            theme.warning
+        } else {
+            theme.accent
+        };
+        let mut label = format!(
+            "{} of {} used",
+            format_size(usage.used),
+            format_size(usage.total)
+        );
+        if percent > PERSIST_WARNING_PERCENT {
+            label.push_str(", persist is almost full");
+        }
+        let gauge = Gauge::default()
+            .block(
+                Block::default()
+                    .title(" Persist ")
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::TOP)
+                    .border_type(BorderType::Plain)
+                    .padding(Padding::horizontal(1)),
+            )
+            .gauge_style(Style::new().fg(color).bg(theme.surface))
+            .percent(percent.min(100) as u16)
+            .label(label);
+        frame.render_widget(gauge, rect);
+    }
+}
+
+impl IPresenter for StoragePage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        if self.refreshed.is_none() {
+            self.refresh();
+        }
+        let model = model.borrow();
+        let theme = model.theme;
+
+        let [table_rect, persist_rect, help_rect] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+
+        self.list
+            .set_keys(self.devices.iter().map(|d| d.name.clone()).collect());
+        let mut rows: Vec<Row> = self
+            .devices
+            .iter()
+            .map(|device| {
+                let row = Row::new(self.cells(device));
+                match device.role {
+                    _ if self.is_boot_partition(device) => row.fg(theme.accent).bold(),
+                    Some(PartitionRole::Persist)
+                        if device
+                            .usage
+                            .is_some_and(|usage| usage.percent() > PERSIST_WARNING_PERCENT) =>
+                    {
+                        row.fg(theme.warning)
+                    }
+                    Some(_) => row.fg(theme.text),
+                    None => row.fg(theme.muted),
+                }
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push(Row::new(vec!["No block devices found"]).fg(theme.muted));
+        }
+        let block = Block::default()
+            .title(" Block devices ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        self.list.set_table_area(block.inner(table_rect), 1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(14),
+                Constraint::Length(16),
+                Constraint::Fill(1),
+                Constraint::Length(5),
+            ],
+        )
+        .block(block)
+        .header(Row::new(HEADER.to_vec()).bold())
+        .row_highlight_style(Style::new().bg(theme.selection))
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_rect, frame.buffer_mut(), &mut self.list.state);
+
+        self.render_persist(&model, persist_rect, frame);
+        frame.render_widget(
+            Line::from(
+                " ▲▼ select, 'r' to refresh, 'v' for EFI variables, 'b' to edit the boot order. The partition EVE booted from is highlighted",
+            )
+                .fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for StoragePage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+                return None;
+            }
+            Event::Tick => {
+                if self
+                    .refreshed
+                    .is_none_or(|refreshed| refreshed.elapsed() >= REFRESH_INTERVAL)
+                {
+                    self.refresh();
+                }
+                return None;
+            }
+            _ => return None,
+        };
+        match key.code {
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down => self.list.select_next(),
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.list.select_first(),
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.list.select_last(),
+            KeyCode::Char('r') => self.refresh(),
+            KeyCode::Char('v') => return Some(Action::new("storage", UiActions::ShowEfiVariables)),
+            KeyCode::Char('b') => return Some(Action::new("storage", UiActions::EditBootOrder)),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for StoragePage {
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::new("Inspect EFI variables", "v", UiActions::ShowEfiVariables),
+            Command::new("Edit the boot order", "b", UiActions::EditBootOrder),
+        ]
+    }
+
+    fn export(&self, _model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new("storage", HEADER.to_vec());
+        for device in self.devices.iter() {
+            export.add_row(self.cells(device));
+        }
+        Some(export)
+    }
+}
@@ -1,31 +1,179 @@
 use std::rc::Rc;
 
+use chrono::Local;
 use crossterm::event::{KeyCode, KeyModifiers};
 use log::{debug, info};
 use ratatui::{
     layout::{Constraint, Layout},
     prelude::Rect,
-    style::{Color, Style},
+    style::{Style, Stylize},
     text::{Line, Span, Text},
     Frame,
 };
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, IntoEnumIterator};
 
 use crate::{
     events::Event,
-    model::model::{Model, OnboardingStatus, VaultStatus},
+    ipc::eve_types::AttestState,
+    model::app_health::AppHealth,
+    model::attestation::AttestationEvent,
+    model::device::config_partition::ConfigPartitionState,
+    model::device::identity::CertInfo,
+    model::model::{Model, MonitorModel, VaultStatus},
+    model::onboarding::OnboardingState,
+    model::reachability::{short_duration, Reachability},
+    qrcode::QrCode,
     traits::{IEventHandler, IPresenter, IWindow},
+    ui::about::eve_release,
     ui::action::{Action, UiActions},
+    ui::command_palette::Command,
+    ui::export::TableExport,
+    ui::resources_page::usage_gauge,
+    ui::theme::Theme,
+    ui::widgets::qrcode::QrCodeWidget,
 };
 
-pub struct SummaryPage {}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryPanel {
+    Server,
+    Resources,
+    Connectivity,
+    #[strum(to_string = "Controller reachability")]
+    Reachability,
+    #[strum(to_string = "ZFS pools")]
+    Zfs,
+    Onboarding,
+    #[strum(to_string = "App summary")]
+    AppSummary,
+    Identity,
+    Vault,
+    Attestation,
+    #[strum(to_string = "Device QR code")]
+    DeviceQr,
+}
+
+impl SummaryPanel {
+    /// All panels in the default order
+    pub fn defaults() -> Vec<SummaryPanel> {
+        // the QR code takes half of the page, it is shown on demand
+        SummaryPanel::iter()
+            .filter(|panel| *panel != SummaryPanel::DeviceQr)
+            .collect()
+    }
+
+    // None if the panel takes what is left
+    fn height(&self) -> Option<u16> {
+        match self {
+            SummaryPanel::Server => Some(3),
+            // one line of gauges
+            SummaryPanel::Resources => Some(3),
+            // the score and the worst reasons
+            SummaryPanel::Connectivity => Some(6),
+            // the strip and the current state
+            SummaryPanel::Reachability => Some(4),
+            // a pool and its bad vdevs
+            SummaryPanel::Zfs => Some(5),
+            SummaryPanel::Onboarding | SummaryPanel::AppSummary => Some(6),
+            SummaryPanel::Identity => Some(9),
+            SummaryPanel::Vault | SummaryPanel::Attestation => None,
+            // a version 7 code with the borders
+            SummaryPanel::DeviceQr => Some(27),
+        }
+    }
+
+    // half-width panels share a row with the next half-width one
+    fn is_half_width(&self) -> bool {
+        !matches!(
+            self,
+            SummaryPanel::Server
+                | SummaryPanel::Resources
+                | SummaryPanel::Connectivity
+                | SummaryPanel::Reachability
+                | SummaryPanel::Zfs
+                | SummaryPanel::Identity
+                | SummaryPanel::DeviceQr
+        )
+    }
+
+    fn render(&self, model: &Rc<Model>, frame: &mut Frame<'_>, rect: Rect) {
+        match self {
+            SummaryPanel::Server => render_server(model, frame, rect),
+            SummaryPanel::Resources => render_resources(model, frame, rect),
+            SummaryPanel::Connectivity => render_connectivity(model, frame, rect),
+            SummaryPanel::Reachability => render_reachability(model, frame, rect),
+            SummaryPanel::Zfs => render_zfs_pools(model, frame, rect),
+            SummaryPanel::Onboarding => render_onboarding_status(model, frame, rect),
+            SummaryPanel::AppSummary => render_app_summary(model, frame, rect),
+            SummaryPanel::Identity => render_identity(model, frame, rect),
+            SummaryPanel::Vault => render_vault_status(model, frame, rect),
+            SummaryPanel::Attestation => render_attestation(model, frame, rect),
+            SummaryPanel::DeviceQr => render_device_qr(model, frame, rect),
+        }
+    }
+}
+
+// group the panels into rows of the page
+fn panel_rows(panels: &[SummaryPanel]) -> Vec<Vec<SummaryPanel>> {
+    let mut rows: Vec<Vec<SummaryPanel>> = Vec::new();
+    for panel in panels {
+        match rows.last_mut() {
+            Some(row) if panel.is_half_width() && row.len() == 1 && row[0].is_half_width() => {
+                row.push(*panel)
+            }
+            _ => rows.push(vec![*panel]),
+        }
+    }
+    rows
+}
+
+pub struct SummaryPage {
+    // visible panels in the order they are shown
+    panels: Vec<SummaryPanel>,
+}
 
 impl SummaryPage {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(panels: Vec<SummaryPanel>) -> Self {
+        Self { panels }
     }
 }
 
-impl IWindow for SummaryPage {}
+impl IWindow for SummaryPage {
+    fn commands(&self) -> Vec<Command> {
+        vec![
+            Command::new("Change server", "ctrl+s", UiActions::ChangeServer),
+            Command::new(
+                "Collect a support bundle",
+                "ctrl+b",
+                UiActions::SupportBundle,
+            ),
+            Command::new(
+                "Create a support bundle without EVE",
+                "ctrl+l",
+                UiActions::CreateBundle,
+            ),
+            Command::new("Retry attestation", "ctrl+t", UiActions::RetryAttestation),
+            Command::new("View grub.cfg", "ctrl+g", UiActions::ViewGrubCfg),
+            Command::new(
+                "Choose summary panels",
+                "ctrl+o",
+                UiActions::SummarySettings,
+            ),
+        ]
+    }
+
+    // the connectivity score for scripts and bug reports
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let connectivity = model.connectivity();
+        let mut export = TableExport::new("connectivity", vec!["Metric", "Value"]);
+        export.add_row(vec!["score".to_string(), connectivity.score.to_string()]);
+        for reason in connectivity.reasons {
+            export.add_row(vec!["reason".to_string(), reason]);
+        }
+        Some(export)
+    }
+}
 
 impl IEventHandler for SummaryPage {
     fn handle_event(&mut self, event: crate::events::Event) -> Option<super::action::Action> {
@@ -37,6 +185,36 @@ impl IEventHandler for SummaryPage {
                 debug!("CTRL+s: server change requested");
                 return Some(Action::new("net", UiActions::ChangeServer));
             }
+            Event::Key(key)
+                if (key.code == KeyCode::Char('b')) && (key.modifiers == KeyModifiers::CONTROL) =>
+            {
+                debug!("CTRL+b: support bundle requested");
+                return Some(Action::new("summary", UiActions::SupportBundle));
+            }
+            Event::Key(key)
+                if (key.code == KeyCode::Char('l')) && (key.modifiers == KeyModifiers::CONTROL) =>
+            {
+                debug!("CTRL+l: local support bundle requested");
+                return Some(Action::new("summary", UiActions::CreateBundle));
+            }
+            Event::Key(key)
+                if (key.code == KeyCode::Char('t')) && (key.modifiers == KeyModifiers::CONTROL) =>
+            {
+                debug!("CTRL+t: attestation retry requested");
+                return Some(Action::new("summary", UiActions::RetryAttestation));
+            }
+            Event::Key(key)
+                if (key.code == KeyCode::Char('g')) && (key.modifiers == KeyModifiers::CONTROL) =>
+            {
+                debug!("CTRL+g: grub.cfg viewer requested");
+                return Some(Action::new("summary", UiActions::ViewGrubCfg));
+            }
+            Event::Key(key)
+                if (key.code == KeyCode::Char('o')) && (key.modifiers == KeyModifiers::CONTROL) =>
+            {
+                debug!("CTRL+o: summary panel settings requested");
+                return Some(Action::new("summary", UiActions::SummarySettings));
+            }
             _ => {}
         }
         None
@@ -45,39 +223,234 @@ impl IEventHandler for SummaryPage {
 
 impl IPresenter for SummaryPage {
     fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
-        let [server, onboarding_status_and_app_sunnary_rect, vault_status_rect] =
-            Layout::vertical(vec![
-                Constraint::Length(3),
-                Constraint::Length(6),
-                Constraint::Fill(1),
-            ])
-            .areas(*area);
-
-        let [onboarding_status_rect, app_summary_rect] =
-            Layout::horizontal(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
-                .areas(onboarding_status_and_app_sunnary_rect);
-
-        let server_url = ratatui::widgets::Paragraph::new(
-            model
-                .borrow()
-                .node_status
-                .server
-                .clone()
-                .unwrap_or("N/A".to_string()),
-        )
+        if self.panels.is_empty() {
+            let hint = Line::from("All panels are hidden, press CTRL+o to choose them");
+            frame.render_widget(hint, *area);
+            return;
+        }
+        let rows = panel_rows(&self.panels);
+        let heights = rows.iter().map(|row| {
+            match row
+                .iter()
+                .map(|panel| panel.height())
+                .collect::<Option<Vec<u16>>>()
+            {
+                Some(heights) => Constraint::Length(heights.into_iter().max().unwrap_or(0)),
+                None => Constraint::Fill(1),
+            }
+        });
+        let row_rects = Layout::vertical(heights).split(*area);
+        for (row, row_rect) in rows.iter().zip(row_rects.iter()) {
+            let rects = Layout::horizontal(vec![Constraint::Percentage(50); 2]).split(*row_rect);
+            match row.as_slice() {
+                [panel] if panel.is_half_width() => panel.render(model, frame, rects[0]),
+                [panel] => panel.render(model, frame, *row_rect),
+                [left, right] => {
+                    left.render(model, frame, rects[0]);
+                    right.render(model, frame, rects[1]);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn render_server(model: &Rc<Model>, frame: &mut Frame<'_>, server: Rect) {
+    let theme = model.borrow().theme;
+    let server_url = ratatui::widgets::Paragraph::new(
+        model
+            .borrow()
+            .node_status
+            .server
+            .clone()
+            .unwrap_or("N/A".to_string()),
+    )
+    .block(
+        ratatui::widgets::Block::default()
+            .borders(ratatui::widgets::Borders::ALL)
+            .title("Server (CTRL+s to change, CTRL+b for support bundle, CTRL+o for panels)"),
+    )
+    .style(Style::default().fg(theme.text));
+    frame.render_widget(server_url, server);
+}
+
+fn render_resources(model: &Rc<Model>, frame: &mut Frame<'_>, rect: Rect) {
+    let model = model.borrow();
+    let theme = model.theme;
+    let resources = &model.resources;
+    let block = ratatui::widgets::Block::default()
+        .borders(ratatui::widgets::Borders::ALL)
+        .title("Resources")
+        .style(Style::default().fg(theme.text));
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+    let [cpu_rect, memory_rect, load_rect] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ])
+    .spacing(2)
+    .areas(inner);
+    let cpu = resources.cpu_percent().unwrap_or(0);
+    frame.render_widget(
+        usage_gauge(format!("CPU {:>3}%", cpu), cpu, &theme),
+        cpu_rect,
+    );
+    let memory = resources.meminfo.map(|m| m.used_percent()).unwrap_or(0);
+    frame.render_widget(
+        usage_gauge(format!("Mem {:>3}%", memory), memory, &theme),
+        memory_rect,
+    );
+    let load = match resources.load {
+        Some(load) => format!("Load {:.2} {:.2} {:.2}", load.one, load.five, load.fifteen),
+        None => "Load -".to_string(),
+    };
+    frame.render_widget(Line::from(load), load_rect);
+}
+
+fn render_connectivity(model: &Rc<Model>, frame: &mut Frame<'_>, rect: Rect) {
+    let theme = model.borrow().theme;
+    let connectivity = model.borrow().connectivity();
+    let color = match connectivity.score {
+        80.. => theme.ok,
+        50.. => theme.warning,
+        _ => theme.error,
+    };
+    // leave a line for the score
+    let room = rect.height.saturating_sub(3) as usize;
+    let mut lines = vec![Line::from(vec![
+        Span::raw("Score: "),
+        Span::styled(
+            format!("{}/100", connectivity.score),
+            Style::default().fg(color).bold(),
+        ),
+    ])];
+    if connectivity.reasons.is_empty() {
+        lines.push(Line::from("Ports, port configuration and controller are fine").fg(theme.muted));
+    }
+    let hidden = connectivity.reasons.len().saturating_sub(room);
+    for reason in connectivity.reasons.iter().take(room) {
+        lines.push(Line::from(format!("- {}", reason)));
+    }
+    if hidden > 0 {
+        if let Some(last) = lines.last_mut() {
+            *last = Line::from(format!(
+                "... and {} more, export the page to see all",
+                hidden + 1
+            ));
+        }
+    }
+    let paragraph = ratatui::widgets::Paragraph::new(Text::from(lines))
         .block(
             ratatui::widgets::Block::default()
                 .borders(ratatui::widgets::Borders::ALL)
-                .title("Server (CTRL+s to change)"),
+                .title("Connectivity"),
         )
-        .style(ratatui::style::Style::default().fg(ratatui::style::Color::White));
-        frame.render_widget(server_url, server);
+        .style(Style::default().fg(theme.text));
+    frame.render_widget(paragraph, rect);
+}
 
-        render_onboarding_status(model, frame, onboarding_status_rect);
-        render_app_summary(model, frame, app_summary_rect);
+fn reachability_color(state: Reachability, theme: &Theme) -> ratatui::style::Color {
+    match state {
+        Reachability::Unknown => theme.muted,
+        Reachability::Up => theme.ok,
+        Reachability::Degraded => theme.warning,
+        Reachability::Down => theme.error,
+    }
+}
+
+fn render_reachability(model: &Rc<Model>, frame: &mut Frame<'_>, rect: Rect) {
+    let model = model.borrow();
+    let theme = model.theme;
+    let history = &model.reachability;
+    let block = ratatui::widgets::Block::default()
+        .borders(ratatui::widgets::Borders::ALL)
+        .title(format!(
+            "Controller reachability (last {})",
+            short_duration(history.window())
+        ))
+        .style(Style::default().fg(theme.text));
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+    let [strip_rect, status_rect] =
+        Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(inner);
+
+    // one column per minute while it fits, else the worst of several, newest on the right
+    let states: Vec<Reachability> = history.slots().map(|s| s.state()).collect();
+    let width = strip_rect.width.max(1) as usize;
+    let group = states.len().div_ceil(width).max(1);
+    let columns: Vec<Reachability> = states
+        .rchunks(group)
+        .rev()
+        .map(|chunk| chunk.iter().copied().max().unwrap_or(Reachability::Unknown))
+        .collect();
+    let mut spans = vec![Span::raw(" ".repeat(width.saturating_sub(columns.len())))];
+    spans.extend(columns.into_iter().map(|state| match state {
+        Reachability::Unknown => Span::styled("·", Style::default().fg(theme.muted)),
+        _ => Span::styled("█", Style::default().fg(reachability_color(state, &theme))),
+    }));
+    frame.render_widget(Line::from(spans), strip_rect);
+
+    let current = history.current();
+    let mut status = vec![Span::styled(
+        match history.duration(std::time::Instant::now()) {
+            Some(duration) => format!("{} for {}", current, short_duration(duration)),
+            None => current.to_string(),
+        },
+        Style::default()
+            .fg(reachability_color(current, &theme))
+            .bold(),
+    )];
+    if let Some(availability) = history.availability() {
+        status.push(Span::raw(format!("  available {:.1}%", availability)));
+    }
+    status.push(Span::raw(format!("  {} changes", history.changes())));
+    frame.render_widget(Line::from(status), status_rect);
+}
 
-        render_vault_status(model, frame, vault_status_rect);
+fn render_zfs_pools(model: &Rc<Model>, frame: &mut Frame<'_>, rect: Rect) {
+    let model = model.borrow();
+    let theme = model.theme;
+    let mut lines = Vec::new();
+    for pool in model.zfs.pools.iter() {
+        let color = if pool.is_healthy() {
+            theme.ok
+        } else {
+            theme.error
+        };
+        let mut spans = vec![
+            Span::raw(format!("{}: ", pool.name)),
+            Span::styled(pool.state.to_string(), Style::default().fg(color).bold()),
+        ];
+        if !pool.status.is_empty() {
+            spans.push(Span::raw(format!("  {}", pool.status)).fg(theme.muted));
+        }
+        lines.push(Line::from(spans));
+        for vdev in pool.unhealthy_vdevs() {
+            let mut text = format!("- {} {}", vdev.name, vdev.state);
+            if !vdev.note.is_empty() {
+                text.push_str(&format!(", {}", vdev.note));
+            }
+            lines.push(Line::from(text).fg(theme.error));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Line::from("No ZFS pools, persist is not on ZFS").fg(theme.muted));
     }
+    let border = if model.zfs.pools.iter().all(|pool| pool.is_healthy()) {
+        theme.text
+    } else {
+        theme.error
+    };
+    let paragraph = ratatui::widgets::Paragraph::new(Text::from(lines))
+        .block(
+            ratatui::widgets::Block::default()
+                .borders(ratatui::widgets::Borders::ALL)
+                .border_style(Style::default().fg(border))
+                .title("ZFS pools"),
+        )
+        .style(Style::default().fg(theme.text));
+    frame.render_widget(paragraph, rect);
 }
 
 fn render_onboarding_status(
@@ -85,64 +458,51 @@ fn render_onboarding_status(
     frame: &mut Frame<'_>,
     onboarding_status_rect: Rect,
 ) {
+    let theme = model.borrow().theme;
     let onboarding_status = model.borrow().node_status.onboarding_status.clone();
     let mut text = Vec::new();
     let mut spans = vec![];
-    spans.push(Span::styled("status: ", Style::default().fg(Color::White)));
-    spans.push(match onboarding_status {
-        OnboardingStatus::Unknown => Span::styled("Unknown", Style::default().fg(Color::Yellow)),
-        OnboardingStatus::Onboarding => {
-            Span::styled("Onboarding...", Style::default().fg(Color::Yellow))
+    spans.push(Span::styled("status: ", Style::default().fg(theme.text)));
+    spans.push(match onboarding_status.state() {
+        OnboardingState::Unknown => Span::styled("Unknown", Style::default().fg(theme.warning)),
+        OnboardingState::Onboarding => {
+            Span::styled("Onboarding...", Style::default().fg(theme.warning))
         }
-        OnboardingStatus::Onboarded(_) => {
-            Span::styled("Onboarded", Style::default().fg(Color::Green))
-        }
-        OnboardingStatus::Error(_) => Span::styled("Onboarded", Style::default().fg(Color::Red)),
+        OnboardingState::Onboarded(_) => Span::styled("Onboarded", Style::default().fg(theme.ok)),
+        OnboardingState::Error(_) => Span::styled("Error", Style::default().fg(theme.error)),
     });
+    if let Some(since) = onboarding_status.since() {
+        spans.push(Span::styled(
+            format!(" since {}", since.format("%Y-%m-%d %H:%M:%S")),
+            Style::default().fg(theme.muted),
+        ));
+    }
 
     text.push(Line::from(spans));
 
-    match onboarding_status {
-        OnboardingStatus::Unknown => {
-            text.push(Line::from(vec![
-                Span::styled("GUID: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Yellow)),
-            ]));
-            text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Green)),
-            ]));
-        }
-        OnboardingStatus::Onboarding => {
-            text.push(Line::from(vec![
-                Span::styled("GUID: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Yellow)),
-            ]));
-            text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Green)),
-            ]));
-        }
-        OnboardingStatus::Onboarded(guid) => {
-            text.push(Line::from(vec![
-                Span::styled("GUID: ", Style::default().fg(Color::White)),
-                Span::styled(format!("{}", guid), Style::default().fg(Color::White)),
-            ]));
-            text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Green)),
-            ]));
-        }
-        OnboardingStatus::Error(err) => {
-            text.push(Line::from(vec![
-                Span::styled("GUID: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Yellow)),
-            ]));
-            text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::White)),
-                Span::styled(err, Style::default().fg(Color::Red)),
-            ]));
-        }
+    text.push(Line::from(vec![
+        Span::styled("GUID: ", Style::default().fg(theme.text)),
+        match onboarding_status.device_uuid() {
+            Some(guid) => Span::styled(guid.to_string(), Style::default().fg(theme.text)),
+            None => Span::styled("N/A", Style::default().fg(theme.warning)),
+        },
+    ]));
+    text.push(Line::from(vec![
+        Span::styled("Error: ", Style::default().fg(theme.text)),
+        match onboarding_status.blocking_error() {
+            Some(err) => Span::styled(err.to_string(), Style::default().fg(theme.error)),
+            None => Span::styled("N/A", Style::default().fg(theme.ok)),
+        },
+    ]));
+
+    if model.borrow().maintenance_mode {
+        text.push(Line::from(vec![
+            Span::styled("Maintenance mode: ", Style::default().fg(theme.text)),
+            Span::styled(
+                "server changes are applied after it is cleared",
+                Style::default().fg(theme.warning),
+            ),
+        ]));
     }
 
     // let status = model.borrow().node_status.onboarding_status.clone();
@@ -153,40 +513,41 @@ fn render_onboarding_status(
                 .borders(ratatui::widgets::Borders::ALL)
                 .title("Onboarding status"),
         )
-        .style(ratatui::style::Style::default().fg(ratatui::style::Color::White));
+        .style(Style::default().fg(theme.text));
     frame.render_widget(onboarding_status, onboarding_status_rect);
 }
 
 fn render_app_summary(model: &Rc<Model>, frame: &mut Frame<'_>, app_summary_rect: Rect) {
+    let theme = model.borrow().theme;
+    let counts = model.borrow().app_counts();
+    let health_color = match counts.health(&model.borrow().app_thresholds) {
+        AppHealth::Ok => theme.ok,
+        AppHealth::Warning => theme.warning,
+        AppHealth::Error => theme.error,
+    };
     let apps = &model.borrow().node_status.app_summary;
 
     let mut app_summary_text = vec![];
-    app_summary_text.push(Line::from(vec![
-        Span::raw("Running:  "),
-        Span::styled(
-            format!("{}", apps.total_running),
-            Style::default().fg(Color::Green),
-        ),
-    ]));
+    app_summary_text.push(Line::from(counts.running_text()).fg(health_color));
     app_summary_text.push(Line::from(vec![
         Span::raw("Starting: "),
         Span::styled(
             format!("{}", apps.total_starting),
-            Style::default().fg(Color::Green),
+            Style::default().fg(theme.ok),
         ),
     ]));
     app_summary_text.push(Line::from(vec![
         Span::raw("Stopping: "),
         Span::styled(
             format!("{}", apps.total_stopping),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.warning),
         ),
     ]));
     app_summary_text.push(Line::from(vec![
         Span::raw("In error: "),
         Span::styled(
             format!("{}", apps.total_error),
-            Style::default().fg(Color::Red),
+            Style::default().fg(theme.error),
         ),
     ]));
     let app_summary = ratatui::widgets::Paragraph::new(Text::from(app_summary_text))
@@ -195,22 +556,142 @@ fn render_app_summary(model: &Rc<Model>, frame: &mut Frame<'_>, app_summary_rect
                 .borders(ratatui::widgets::Borders::ALL)
                 .title("App summary"),
         )
-        .style(ratatui::style::Style::default().fg(ratatui::style::Color::White));
+        .style(Style::default().fg(theme.text));
     frame.render_widget(app_summary, app_summary_rect);
 }
 
+fn cert_lines<'a>(label: &'a str, cert: Option<&CertInfo>, theme: &Theme) -> Vec<Line<'a>> {
+    let Some(cert) = cert else {
+        return vec![Line::from(vec![
+            Span::styled(label, Style::default().fg(theme.text)),
+            Span::styled("N/A", Style::default().fg(theme.warning)),
+        ])];
+    };
+    let expires = match cert.not_after {
+        Some(not_after) if cert.is_expired() => Span::styled(
+            format!("{} (expired)", not_after.format("%Y-%m-%d")),
+            Style::default().fg(theme.error),
+        ),
+        Some(not_after) => Span::styled(
+            not_after.format("%Y-%m-%d").to_string(),
+            Style::default().fg(theme.ok),
+        ),
+        None => Span::styled("N/A", Style::default().fg(theme.warning)),
+    };
+    vec![
+        Line::from(vec![
+            Span::styled(label, Style::default().fg(theme.text)),
+            Span::styled(cert.fingerprint.clone(), Style::default().fg(theme.accent)),
+        ]),
+        Line::from(vec![
+            Span::styled("  Subject CN: ", Style::default().fg(theme.text)),
+            Span::raw(cert.subject.clone().unwrap_or("N/A".to_string())),
+            Span::styled("  Expires: ", Style::default().fg(theme.text)),
+            expires,
+        ]),
+    ]
+}
+
+fn render_identity(model: &Rc<Model>, frame: &mut Frame<'_>, identity_rect: Rect) {
+    let theme = model.borrow().theme;
+    let identity = &model.borrow().identity;
+    let mut text = Vec::new();
+
+    text.extend(cert_lines(
+        "Device cert SHA-256: ",
+        identity.device_cert.as_ref(),
+        &theme,
+    ));
+    text.push(Line::from(vec![
+        Span::styled("  Key: ", Style::default().fg(theme.text)),
+        match identity.tpm_backed {
+            Some(true) => Span::styled("TPM-backed", Style::default().fg(theme.ok)),
+            Some(false) => Span::styled("Software", Style::default().fg(theme.warning)),
+            None => Span::styled("N/A", Style::default().fg(theme.warning)),
+        },
+    ]));
+    text.push(Line::from(""));
+    text.extend(cert_lines(
+        "Onboarding cert SHA-256: ",
+        identity.onboard_cert.as_ref(),
+        &theme,
+    ));
+
+    let identity = ratatui::widgets::Paragraph::new(Text::from(text))
+        .block(
+            ratatui::widgets::Block::default()
+                .borders(ratatui::widgets::Borders::ALL)
+                .title("Device identity"),
+        )
+        .style(Style::default().fg(theme.text));
+    frame.render_widget(identity, identity_rect);
+}
+
+/// What the QR code holds, one "key: value" per line
+fn device_qr_text(model: &MonitorModel) -> String {
+    let na = || "N/A".to_string();
+    format!(
+        "uuid: {}\nserver: {}\neve: {}\nmonitor: {}",
+        model
+            .node_status
+            .onboarding_status
+            .device_uuid()
+            .map_or_else(na, |uuid| uuid.to_string()),
+        model.node_status.server.clone().unwrap_or_else(na),
+        eve_release().unwrap_or_else(na),
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+fn render_device_qr(model: &Rc<Model>, frame: &mut Frame<'_>, rect: Rect) {
+    let model = model.borrow();
+    let theme = model.theme;
+    let text = device_qr_text(&model);
+    let block = ratatui::widgets::Block::default()
+        .borders(ratatui::widgets::Borders::ALL)
+        .title("Device QR code");
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+
+    let code = match QrCode::encode(text.as_bytes()) {
+        Ok(code) => code,
+        Err(e) => {
+            frame.render_widget(Line::from(e.to_string()).fg(theme.error), inner);
+            return;
+        }
+    };
+    let widget = QrCodeWidget::new(&code);
+    let (width, _) = widget.size();
+    let [code_rect, text_rect] =
+        Layout::horizontal([Constraint::Length(width), Constraint::Fill(1)])
+            .spacing(2)
+            .areas(inner);
+    frame.render_widget(widget, code_rect);
+    let mut lines: Vec<Line> = text
+        .lines()
+        .map(|line| Line::from(line.to_string()))
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from("Scan the code instead of retyping the identifiers").fg(theme.muted));
+    frame.render_widget(
+        ratatui::widgets::Paragraph::new(Text::from(lines)).style(Style::default().fg(theme.text)),
+        text_rect,
+    );
+}
+
 fn render_vault_status(model: &Rc<Model>, frame: &mut Frame<'_>, onboarding_status_rect: Rect) {
+    let theme = model.borrow().theme;
     let vault_status = &model.borrow().vault_status;
     let mut text = Vec::new();
     let mut spans = vec![];
-    spans.push(Span::styled("Status: ", Style::default().fg(Color::White)));
+    spans.push(Span::styled("Status: ", Style::default().fg(theme.text)));
     spans.push(match vault_status {
-        VaultStatus::Unknown => Span::styled("Unknown", Style::default().fg(Color::Yellow)),
+        VaultStatus::Unknown => Span::styled("Unknown", Style::default().fg(theme.warning)),
         VaultStatus::EncryptionDisabled(_, _) => {
-            Span::styled("Encryption disabled", Style::default().fg(Color::Yellow))
+            Span::styled("Encryption disabled", Style::default().fg(theme.warning))
         }
-        VaultStatus::Unlocked(_) => Span::styled("Unlocked", Style::default().fg(Color::Green)),
-        VaultStatus::Locked(_, _) => Span::styled("Locked", Style::default().fg(Color::Red)),
+        VaultStatus::Unlocked(_) => Span::styled("Unlocked", Style::default().fg(theme.ok)),
+        VaultStatus::Locked(_, _) => Span::styled("Locked", Style::default().fg(theme.error)),
     });
 
     text.push(Line::from(spans));
@@ -218,60 +699,203 @@ fn render_vault_status(model: &Rc<Model>, frame: &mut Frame<'_>, onboarding_stat
     match vault_status {
         VaultStatus::Unknown => {
             text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Green)),
+                Span::styled("Error: ", Style::default().fg(theme.text)),
+                Span::styled("N/A", Style::default().fg(theme.ok)),
             ]));
         }
         VaultStatus::EncryptionDisabled(reason, tpm_used) => {
             text.push(Line::from(vec![
-                Span::styled("TPM used: ", Style::default().fg(Color::White)),
+                Span::styled("TPM used: ", Style::default().fg(theme.text)),
                 if *tpm_used {
-                    Span::styled("Yes", Style::default().fg(Color::Green))
+                    Span::styled("Yes", Style::default().fg(theme.ok))
                 } else {
-                    Span::styled("No", Style::default().fg(Color::Red))
+                    Span::styled("No", Style::default().fg(theme.error))
                 },
             ]));
             text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::Red)),
-                Span::styled(&reason.error, Style::default().fg(Color::White)),
+                Span::styled("Error: ", Style::default().fg(theme.error)),
+                Span::styled(&reason.error, Style::default().fg(theme.text)),
             ]));
         }
         VaultStatus::Unlocked(tpm_used) => {
             text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::White)),
-                Span::styled("N/A", Style::default().fg(Color::Green)),
+                Span::styled("Error: ", Style::default().fg(theme.text)),
+                Span::styled("N/A", Style::default().fg(theme.ok)),
             ]));
             text.push(Line::from(vec![
-                Span::styled("TPM used: ", Style::default().fg(Color::White)),
+                Span::styled("TPM used: ", Style::default().fg(theme.text)),
                 if *tpm_used {
-                    Span::styled("Yes", Style::default().fg(Color::Green))
+                    Span::styled("Yes", Style::default().fg(theme.ok))
                 } else {
-                    Span::styled("No", Style::default().fg(Color::Red))
+                    Span::styled("No", Style::default().fg(theme.error))
                 },
             ]));
         }
         VaultStatus::Locked(err, pcr) => {
             text.push(Line::from(vec![
-                Span::styled("Error: ", Style::default().fg(Color::Red)),
-                Span::styled(&err.error, Style::default().fg(Color::White)),
+                Span::styled("Error: ", Style::default().fg(theme.error)),
+                Span::styled(&err.error, Style::default().fg(theme.text)),
             ]));
             text.push(Line::from(vec![
-                Span::styled("Affected PCRs: ", Style::default().fg(Color::White)),
+                Span::styled("Affected PCRs: ", Style::default().fg(theme.text)),
                 if let Some(pcr) = pcr {
-                    Span::styled(format!("{:?}", pcr), Style::default().fg(Color::Green))
+                    Span::styled(format!("{:?}", pcr), Style::default().fg(theme.ok))
                 } else {
-                    Span::styled("N/A", Style::default().fg(Color::Yellow))
+                    Span::styled("N/A", Style::default().fg(theme.warning))
                 },
             ]));
         }
     }
 
+    // remediation of a locked vault often means editing files in /config
+    text.push(Line::from(vec![
+        Span::styled("/config: ", Style::default().fg(theme.text)),
+        match &model.borrow().config_partition.state {
+            ConfigPartitionState::Unknown => {
+                Span::styled("Unknown", Style::default().fg(theme.warning))
+            }
+            ConfigPartitionState::NotMounted => {
+                Span::styled("Not mounted", Style::default().fg(theme.error))
+            }
+            ConfigPartitionState::Mounted {
+                device,
+                read_only: true,
+                ..
+            } => Span::styled(
+                format!("{} mounted read-only", device),
+                Style::default().fg(theme.error),
+            ),
+            ConfigPartitionState::Mounted {
+                device,
+                writable: false,
+                ..
+            } => Span::styled(
+                format!("{} not writable by the monitor", device),
+                Style::default().fg(theme.warning),
+            ),
+            ConfigPartitionState::Mounted { device, .. } => Span::styled(
+                format!("{} writable", device),
+                Style::default().fg(theme.ok),
+            ),
+        },
+    ]));
+
+    if matches!(vault_status, VaultStatus::Locked(_, _))
+        && !model.borrow().config_partition.state.is_usable()
+    {
+        text.push(Line::from(vec![
+            Span::styled("Tip: ", Style::default().fg(theme.text)),
+            Span::styled(
+                "changes in /config e.g. grub.cfg cannot be reverted from this console",
+                Style::default().fg(theme.warning),
+            ),
+        ]));
+    }
+
+    // EVE enters maintenance mode when it cannot unlock the vault
+    if model.borrow().maintenance_mode {
+        let tip = match vault_status {
+            VaultStatus::Locked(_, _) => {
+                "Device is in maintenance mode until the vault is unlocked, applications stay stopped"
+            }
+            _ => "Device is in maintenance mode, configuration changes are not applied",
+        };
+        text.push(Line::from(vec![
+            Span::styled("Tip: ", Style::default().fg(theme.text)),
+            Span::styled(tip, Style::default().fg(theme.warning)),
+        ]));
+    }
+
     let vault_status = ratatui::widgets::Paragraph::new(Text::from(text))
         .block(
             ratatui::widgets::Block::default()
                 .borders(ratatui::widgets::Borders::ALL)
-                .title("Vault status"),
+                .title("Vault status (CTRL+g to view grub.cfg)"),
         )
-        .style(ratatui::style::Style::default().fg(ratatui::style::Color::White));
+        .style(Style::default().fg(theme.text));
     frame.render_widget(vault_status, onboarding_status_rect);
 }
+
+fn render_attestation(model: &Rc<Model>, frame: &mut Frame<'_>, attestation_rect: Rect) {
+    let theme = model.borrow().theme;
+    let attestation = &model.borrow().attestation;
+    let mut text = Vec::new();
+    text.push(Line::from(vec![
+        Span::styled("State: ", Style::default().fg(theme.text)),
+        match attestation.state() {
+            None => Span::styled("Unknown", Style::default().fg(theme.warning)),
+            Some(AttestState::StateComplete) => {
+                Span::styled("Complete", Style::default().fg(theme.ok))
+            }
+            Some(state) if attestation.is_stuck(Local::now()) => Span::styled(
+                format!("{:?} (stuck)", state),
+                Style::default().fg(theme.error),
+            ),
+            Some(state) => Span::styled(format!("{:?}", state), Style::default().fg(theme.warning)),
+        },
+    ]));
+
+    // newest first
+    for entry in attestation.entries().rev() {
+        let time = Span::styled(
+            format!("{} ", entry.time.format("%H:%M:%S")),
+            Style::default().fg(theme.muted),
+        );
+        text.push(match &entry.event {
+            AttestationEvent::State { state, error } if error.is_empty() => {
+                Line::from(vec![time, Span::raw(format!("{:?}", state))])
+            }
+            AttestationEvent::State { state, error } => Line::from(vec![
+                time,
+                Span::raw(format!("{:?}: ", state)),
+                Span::styled(error.clone(), Style::default().fg(theme.error)),
+            ]),
+            AttestationEvent::RetryRequested => Line::from(vec![
+                time,
+                Span::styled("Retry requested", Style::default().fg(theme.accent)),
+            ]),
+        });
+    }
+
+    let attestation = ratatui::widgets::Paragraph::new(Text::from(text))
+        .block(
+            ratatui::widgets::Block::default()
+                .borders(ratatui::widgets::Borders::ALL)
+                .title("Attestation (CTRL+t to retry)"),
+        )
+        .style(Style::default().fg(theme.text));
+    frame.render_widget(attestation, attestation_rect);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panel_rows() {
+        use SummaryPanel::*;
+        assert_eq!(
+            panel_rows(&SummaryPanel::defaults()),
+            vec![
+                vec![Server],
+                vec![Resources],
+                vec![Connectivity],
+                vec![Reachability],
+                vec![Zfs],
+                vec![Onboarding, AppSummary],
+                vec![Identity],
+                vec![Vault, Attestation]
+            ]
+        );
+        // a half-width panel alone between full-width ones keeps its row
+        assert_eq!(
+            panel_rows(&[Vault, Identity, Attestation, Onboarding, AppSummary]),
+            vec![
+                vec![Vault],
+                vec![Identity],
+                vec![Attestation, Onboarding],
+                vec![AppSummary]
+            ]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use strum::IntoEnumIterator;
+
+use crate::{model::model::Model, traits::IWindow, ui::action::UiActions};
+
+use super::{action::Action, summary_page::SummaryPanel, window::Window};
+
+struct SummarySettingsState {
+    // all panels in the order they are shown and whether they are visible
+    panels: Vec<(SummaryPanel, bool)>,
+    selected: usize,
+}
+
+fn all_panels(visible: &[SummaryPanel]) -> Vec<(SummaryPanel, bool)> {
+    let mut panels: Vec<(SummaryPanel, bool)> = visible.iter().map(|p| (*p, true)).collect();
+    // hidden ones go last
+    panels.extend(
+        SummaryPanel::iter()
+            .filter(|p| !visible.contains(p))
+            .map(|p| (p, false)),
+    );
+    panels
+}
+
+fn do_layout(w: &mut Window<SummarySettingsState>, rect: &Rect, _model: &Rc<Model>) {
+    // panels, a blank line and the help + borders
+    let height = w.state.panels.len() as u16 + 5;
+    let frame_rect = crate::ui::tools::centered_rect_fixed(50, height, *rect);
+    w.update_layout("frame", frame_rect);
+}
+
+fn do_render(
+    w: &mut Window<SummarySettingsState>,
+    _rect: &Rect,
+    frame: &mut Frame<'_>,
+    model: &Rc<Model>,
+) {
+    let theme = model.borrow().theme;
+    let frame_rect = w.get_layout("frame");
+    frame.render_widget(Clear {}, frame_rect);
+
+    let state = &w.state;
+    let mut lines: Vec<Line> = state
+        .panels
+        .iter()
+        .enumerate()
+        .map(|(i, (panel, visible))| {
+            let line = Line::from(format!(" [{}] {}", if *visible { "x" } else { " " }, panel));
+            if i == state.selected {
+                line.reversed()
+            } else {
+                line
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(" Space show/hide, Shift+▲▼ move, 'r' reset").fg(theme.muted));
+    lines.push(Line::from(" Enter to save, Esc to cancel").fg(theme.muted));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.surface))
+        .title(w.name.clone());
+    frame.render_widget(Paragraph::new(Text::from(lines)).block(block), frame_rect);
+}
+
+fn on_key_event(w: &mut Window<SummarySettingsState>, key: KeyEvent) -> Option<Action> {
+    let state = &mut w.state;
+    let last = state.panels.len() - 1;
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    match key.code {
+        KeyCode::Esc => return Some(Action::new(&w.name, UiActions::DismissDialog)),
+        KeyCode::Enter => {
+            let visible = state
+                .panels
+                .iter()
+                .filter(|(_, visible)| *visible)
+                .map(|(panel, _)| *panel)
+                .collect();
+            return Some(Action::new(&w.name, UiActions::SetSummaryPanels(visible)));
+        }
+        KeyCode::Up if shift && state.selected > 0 => {
+            state.panels.swap(state.selected, state.selected - 1);
+            state.selected -= 1;
+        }
+        KeyCode::Down if shift && state.selected < last => {
+            state.panels.swap(state.selected, state.selected + 1);
+            state.selected += 1;
+        }
+        KeyCode::Up => state.selected = state.selected.saturating_sub(1),
+        KeyCode::Down => state.selected = (state.selected + 1).min(last),
+        KeyCode::Char(' ') => state.panels[state.selected].1 ^= true,
+        KeyCode::Char('r') => {
+            state.panels = all_panels(&SummaryPanel::defaults());
+            state.selected = 0;
+        }
+        _ => return None,
+    }
+    Some(Action::new(&w.name, UiActions::Redraw))
+}
+
+/// Choose and order the panels of the summary page
+pub fn create_summary_settings(visible: &[SummaryPanel]) -> impl IWindow {
+    Window::builder("Summary panels")
+        .with_layout(do_layout)
+        .with_render(do_render)
+        .with_on_key_event(on_key_event)
+        .with_state(SummarySettingsState {
+            panels: all_panels(visible),
+            selected: 0,
+        })
+        .build()
+        .unwrap()
+}
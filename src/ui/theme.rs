@@ -0,0 +1,148 @@
+// Colors of the UI by meaning. Pages take them from MonitorModel::theme instead
+// of naming colors so the palette can be switched for consoles where the
+// default one is hard to read
+use std::{collections::BTreeMap, str::FromStr};
+
+use log::warn;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Display, EnumIter)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub text: Color,
+    // hints and secondary information
+    pub muted: Color,
+    pub ok: Color,
+    pub warning: Color,
+    pub error: Color,
+    // values to look at e.g. fingerprints
+    pub accent: Color,
+    // behind the pages, Reset keeps the terminal background
+    pub background: Color,
+    // behind dialogs, tabs and the status bar
+    pub surface: Color,
+    // background of the selected row
+    pub selection: Color,
+    pub border: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            text: Color::White,
+            muted: Color::DarkGray,
+            ok: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            accent: Color::Cyan,
+            background: Color::Reset,
+            surface: Color::Black,
+            selection: Color::DarkGray,
+            border: Color::White,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            text: Color::Black,
+            muted: Color::Gray,
+            ok: Color::Rgb(0, 128, 0),
+            // yellow is unreadable on white
+            warning: Color::Rgb(175, 95, 0),
+            error: Color::Rgb(192, 0, 0),
+            accent: Color::Blue,
+            background: Color::White,
+            surface: Color::Rgb(230, 230, 230),
+            selection: Color::Rgb(200, 200, 200),
+            border: Color::Black,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Color::White,
+            muted: Color::Gray,
+            ok: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            accent: Color::LightCyan,
+            background: Color::Black,
+            surface: Color::Black,
+            selection: Color::Blue,
+            border: Color::White,
+        }
+    }
+
+    /// The palette with the colors in `overrides` replaced. Keys are the field
+    /// names, values are color names or "#rrggbb"
+    pub fn new(name: ThemeName, overrides: &BTreeMap<String, String>) -> Self {
+        let mut theme = match name {
+            ThemeName::Dark => Self::dark(),
+            ThemeName::Light => Self::light(),
+            ThemeName::HighContrast => Self::high_contrast(),
+        };
+        for (key, value) in overrides {
+            let Ok(color) = Color::from_str(value) else {
+                warn!("Invalid color '{}' for theme color {}", value, key);
+                continue;
+            };
+            let field = match key.as_str() {
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "ok" => &mut theme.ok,
+                "warning" => &mut theme.warning,
+                "error" => &mut theme.error,
+                "accent" => &mut theme.accent,
+                "background" => &mut theme.background,
+                "surface" => &mut theme.surface,
+                "selection" => &mut theme.selection,
+                "border" => &mut theme.border,
+                _ => {
+                    warn!("Unknown theme color {}", key);
+                    continue;
+                }
+            };
+            *field = color;
+        }
+        theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides() {
+        let overrides: BTreeMap<String, String> = [
+            ("error", "#ff8000"),
+            ("accent", "magenta"),
+            ("ok", "not a color"),
+            ("sparkle", "red"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let theme = Theme::new(ThemeName::Light, &overrides);
+        assert_eq!(theme.error, Color::Rgb(255, 128, 0));
+        assert_eq!(theme.accent, Color::Magenta);
+        assert_eq!(theme.ok, Theme::light().ok);
+    }
+}
@@ -0,0 +1,149 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    config::AlertsConfig,
+    model::alerts::{Alert, AlertSeverity},
+};
+
+use super::theme::Theme;
+
+const TOAST_WIDTH: u16 = 60;
+// the border and two lines of text
+const TOAST_HEIGHT: u16 = 4;
+// older toasts make room for new ones
+const MAX_TOASTS: usize = 3;
+
+/// New alerts popping up in the corner of the screen for a few seconds,
+/// over whatever tab is shown
+#[derive(Debug)]
+pub struct Toasts {
+    severity: AlertSeverity,
+    // None if toasts are disabled
+    duration: Option<Duration>,
+    last_id: u64,
+    shown: Vec<(Alert, Instant)>,
+}
+
+impl Toasts {
+    pub fn new(config: &AlertsConfig) -> Self {
+        Self {
+            severity: config.toast_severity,
+            duration: (config.toast_seconds > 0).then(|| Duration::from_secs(config.toast_seconds)),
+            last_id: 0,
+            shown: Vec::new(),
+        }
+    }
+
+    /// Pick up alerts raised since the last call and drop expired toasts and
+    /// the ones the user has acknowledged
+    pub fn update(&mut self, alerts: &[Alert], now: Instant) {
+        let Some(duration) = self.duration else {
+            return;
+        };
+        for alert in alerts.iter().filter(|alert| alert.id > self.last_id) {
+            if alert.severity >= self.severity && !alert.acknowledged {
+                self.shown.push((alert.clone(), now));
+            }
+        }
+        self.last_id = alerts.last().map_or(self.last_id, |alert| alert.id);
+        self.shown.retain(|(toast, shown_at)| {
+            now.duration_since(*shown_at) < duration
+                && alerts
+                    .iter()
+                    .any(|alert| alert.id == toast.id && !alert.acknowledged)
+        });
+        let excess = self.shown.len().saturating_sub(MAX_TOASTS);
+        self.shown.drain(..excess);
+    }
+
+    /// Newest at the bottom right of `area`
+    pub fn render(&self, area: Rect, frame: &mut Frame, theme: &Theme) {
+        let width = TOAST_WIDTH.min(area.width);
+        let mut bottom = area.bottom();
+        for (alert, _) in self.shown.iter().rev() {
+            if bottom < area.y + TOAST_HEIGHT {
+                break;
+            }
+            bottom -= TOAST_HEIGHT;
+            let rect = Rect::new(area.right() - width, bottom, width, TOAST_HEIGHT);
+            let color = match alert.severity {
+                AlertSeverity::Info => theme.accent,
+                AlertSeverity::Warning => theme.warning,
+                AlertSeverity::Critical => theme.error,
+            };
+            let block = Block::default()
+                .title(format!(
+                    " {} {} {} ",
+                    alert.time.format("%H:%M:%S"),
+                    alert.severity,
+                    alert.source
+                ))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::new().fg(color));
+            frame.render_widget(Clear, rect);
+            frame.render_widget(
+                Paragraph::new(Line::from(alert.text.clone()))
+                    .block(block)
+                    .wrap(Wrap { trim: true })
+                    .style(Style::new().fg(theme.text).bg(theme.surface))
+                    .bold(),
+                rect,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Local;
+
+    use super::*;
+
+    fn alert(id: u64, severity: AlertSeverity) -> Alert {
+        Alert {
+            id,
+            time: Local::now(),
+            severity,
+            source: "test".to_string(),
+            text: format!("alert {}", id),
+            #[cfg(feature = "dmesg")]
+            link: None,
+            acknowledged: false,
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut toasts = Toasts::new(&AlertsConfig::default());
+        let now = Instant::now();
+        let mut alerts = vec![
+            alert(1, AlertSeverity::Info),
+            alert(2, AlertSeverity::Warning),
+            alert(3, AlertSeverity::Critical),
+        ];
+        toasts.update(&alerts, now);
+        let ids = |toasts: &Toasts| toasts.shown.iter().map(|(a, _)| a.id).collect::<Vec<_>>();
+        // info is below the default severity
+        assert_eq!(ids(&toasts), vec![2, 3]);
+
+        // shown once only
+        alerts[1].acknowledged = true;
+        toasts.update(&alerts, now);
+        assert_eq!(ids(&toasts), vec![3]);
+
+        alerts.push(alert(4, AlertSeverity::Warning));
+        toasts.update(&alerts, now + Duration::from_secs(3));
+        assert_eq!(ids(&toasts), vec![3, 4]);
+        toasts.update(&alerts, now + Duration::from_secs(6));
+        assert_eq!(ids(&toasts), vec![4]);
+    }
+}
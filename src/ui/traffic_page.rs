@@ -0,0 +1,196 @@
+use std::rc::Rc;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, BorderType, Borders, HighlightSpacing, Padding, Row, Sparkline, StatefulWidget,
+        Table,
+    },
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        device::traffic::{format_rate, IfaceTraffic},
+        model::{Model, MonitorModel},
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::Action,
+};
+
+use super::{export::TableExport, selection::KeyedSelection, theme::Theme, traits::ISelector};
+
+const HEADER: [&str; 7] = [
+    "Interface",
+    "RX",
+    "TX",
+    "RX total",
+    "TX total",
+    "Errors",
+    "Dropped",
+];
+
+/// Live RX/TX rates per interface with a graph of the selected one
+#[derive(Debug, Default)]
+pub struct TrafficPage {
+    list: KeyedSelection<String>,
+}
+
+impl TrafficPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn cells(name: &str, iface: &IfaceTraffic) -> Vec<String> {
+    let c = &iface.counters;
+    vec![
+        name.to_string(),
+        format_rate(iface.rx_rate()),
+        format_rate(iface.tx_rate()),
+        c.rx_bytes.to_string(),
+        c.tx_bytes.to_string(),
+        (c.rx_errors + c.tx_errors).to_string(),
+        (c.rx_dropped + c.tx_dropped).to_string(),
+    ]
+}
+
+fn render_graph(
+    title: String,
+    rates: Vec<u64>,
+    color: Color,
+    rect: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::TOP)
+        .border_type(BorderType::Plain)
+        .border_style(Style::default().fg(theme.border));
+    // the newest rate is at the right edge
+    let width = rect.width as usize;
+    let rates = &rates[rates.len().saturating_sub(width)..];
+    let sparkline = Sparkline::default().block(block).data(rates).fg(color);
+    frame.render_widget(sparkline, rect);
+}
+
+impl IPresenter for TrafficPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        let model = model.borrow();
+        let theme = model.theme;
+        let traffic = &model.traffic;
+
+        // header, a row per interface and the table borders
+        let table_height = (traffic.ifaces.len() as u16 + 3).min(area.height / 2);
+        let [table_rect, rx_rect, tx_rect, help_rect] = Layout::vertical([
+            Constraint::Length(table_height),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+
+        self.list.set_keys(traffic.ifaces.keys().cloned().collect());
+        let rows: Vec<Row> = traffic
+            .ifaces
+            .iter()
+            .map(|(name, iface)| Row::new(cells(name, iface)))
+            .collect();
+        let block = Block::default()
+            .title(" Traffic ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        self.list.set_table_area(block.inner(table_rect), 1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(16),
+                Constraint::Length(12),
+                Constraint::Length(12),
+                Constraint::Length(16),
+                Constraint::Length(16),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .block(block)
+        .header(Row::new(HEADER.to_vec()).bold())
+        .row_highlight_style(Style::new().bg(theme.selection))
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_rect, frame.buffer_mut(), &mut self.list.state);
+
+        // graph the first interface until one is selected
+        let shown = self
+            .list
+            .selected()
+            .and_then(|name| traffic.ifaces.get_key_value(&name))
+            .or_else(|| traffic.ifaces.iter().next());
+        if let Some((name, iface)) = shown {
+            render_graph(
+                format!(" {} RX {} ", name, format_rate(iface.rx_rate())),
+                iface.rx_rates.iter().copied().collect(),
+                theme.ok,
+                rx_rect,
+                frame,
+                &theme,
+            );
+            render_graph(
+                format!(" {} TX {} ", name, format_rate(iface.tx_rate())),
+                iface.tx_rates.iter().copied().collect(),
+                theme.accent,
+                tx_rect,
+                frame,
+                &theme,
+            );
+        }
+
+        let seconds = traffic.window() as u64 * model.settings.tick_ms / 1000;
+        frame.render_widget(
+            Line::from(format!(
+                " ▲▼ select the interface to graph. Graphs show the last {}s",
+                seconds
+            ))
+            .fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for TrafficPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+                return None;
+            }
+            _ => return None,
+        };
+        match key.code {
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down => self.list.select_next(),
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.list.select_first(),
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.list.select_last(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for TrafficPage {
+    fn export(&self, model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new("traffic", HEADER.to_vec());
+        for (name, iface) in model.traffic.ifaces.iter() {
+            export.add_row(cells(name, iface));
+        }
+        Some(export)
+    }
+}
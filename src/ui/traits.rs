@@ -1,12 +1,9 @@
-use ratatui::style::Style;
-
-pub trait IntoRatatuiStyle {
-    fn style(&self) -> Style;
-}
 pub trait ISelector {
+    // identifies the selected item, e.g. an interface name or an app UUID
+    type Key;
     fn select_next(&mut self);
     fn select_previous(&mut self);
     fn select_first(&mut self);
     fn select_last(&mut self);
-    fn selected(&self) -> Option<String>;
+    fn selected(&self) -> Option<Self::Key>;
 }
@@ -1,48 +1,89 @@
 use crate::{
     model::device::network::NetworkInterfaceStatus,
     traits::{IPresenter, IWindow},
-    ui::{input_dialog::create_input_dialog, ipdialog::create_ip_dialog},
+    ui::{
+        input_dialog::{create_input_dialog, create_validated_input_dialog, validate_server_url},
+        ipdialog::create_ip_dialog,
+    },
 };
+use chrono::Local;
 use core::fmt::Debug;
-use crossterm::event::{KeyCode, KeyModifiers};
-use log::debug;
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+use log::{debug, warn};
 use ratatui::{
+    layout::Rect,
     layout::{
+        Alignment,
         Constraint::{Fill, Length},
         Layout,
     },
-    style::{Color, Modifier, Stylize},
+    style::{Color, Modifier, Style, Stylize},
     text::Line,
     widgets::{Block, Clear, Paragraph, Tabs, Widget},
+    Frame,
 };
-use std::rc::Rc;
+use std::{collections::HashSet, ops::Range, path::PathBuf, rc::Rc};
 use strum::{Display, EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
+    actions::MonActions,
+    config::MonitorConfig,
+    error::MonitorError,
     events::Event,
-    model::{device::dmesg::DmesgViewer, model::Model},
+    ipc::eve_types::{ConsoleHandoffState, ConsoleHandoffStatus},
+    lock::LockScreen,
+    model::model::{Model, MonitorModel},
+    selftest::SelfTestResult,
     terminal::TerminalWrapper,
     traits::IEventHandler,
     ui::action::UiActions,
 };
 
 use super::{
+    about::about_text,
     action::Action,
+    alerts_page::AlertsPage,
     app_page::ApplicationsPage,
+    audit_page::AuditPage,
+    cellular_page::CellularPage,
+    command_palette::{create_command_palette, Command},
+    debug_page::DebugPage,
+    export::{ExportFormat, TableExport},
+    keymap::{KeyCapture, KeyChords},
     layer_stack::LayerStack,
     networkpage::create_network_page,
+    resources_page::ResourcesPage,
+    selftest_splash::SelfTestSplash,
+    settings_page::SettingsPage,
+    snapshot_compare::create_snapshot_compare,
     statusbar::{create_status_bar, StatusBarState},
-    summary_page::SummaryPage,
+    summary_page::{SummaryPage, SummaryPanel},
+    theme::Theme,
+    toasts::Toasts,
+    tools::centered_rect_fixed,
+    traffic_page::TrafficPage,
+    widgets::tab::tab_title_rects,
     window::Window,
 };
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "home-page"))]
+use super::homepage::create_disabled_home_page;
+#[cfg(any(debug_assertions, feature = "home-page"))]
 use super::homepage::HomePage;
+#[cfg(feature = "efi")]
+use super::{storage_page::StoragePage, usb_page::UsbPage};
+#[cfg(feature = "dmesg")]
+use crate::model::device::dmesg::DmesgViewer;
 
 use std::result::Result::Ok;
 
 use anyhow::Result;
+use uuid::Uuid;
+
+const TABS_DIVIDER: &str = " ";
+const TABS_MORE_LEFT: &str = "◄ ";
+const TABS_MORE_RIGHT: &str = " ►";
 
 pub struct Ui {
     pub terminal: TerminalWrapper,
@@ -51,17 +92,38 @@ pub struct Ui {
     pub selected_tab: UiTabs,
     pub status_bar: Window<StatusBarState>,
     first_frame: bool,
+    chords: KeyChords,
+    key_capture: Option<KeyCapture>,
+    summary_panels: Vec<SummaryPanel>,
+    // where the tab titles and the "more" indicators were drawn, for clicks
+    tab_hits: Vec<(Rect, UiTabs)>,
+    toasts: Toasts,
+    // over every tab until dismissed, it belongs to none of them
+    splash: Option<SelfTestSplash>,
 }
 
-#[derive(Default, Copy, Clone, Display, EnumIter, Debug, FromRepr, EnumCount)]
+#[derive(Default, Copy, Clone, PartialEq, Display, EnumIter, Debug, FromRepr, EnumCount)]
 pub enum UiTabs {
     #[default]
     Summary,
-    #[cfg(debug_assertions)]
+    #[cfg(any(debug_assertions, feature = "home-page"))]
     Home,
     Network,
+    Traffic,
+    Cellular,
+    #[cfg(feature = "efi")]
+    #[strum(to_string = "USB")]
+    Usb,
+    #[cfg(feature = "efi")]
+    Storage,
+    Resources,
     Applications,
+    Alerts,
+    Audit,
+    #[cfg(feature = "dmesg")]
     Dmesg,
+    Debug,
+    Settings,
 }
 
 impl Debug for Ui {
@@ -71,46 +133,87 @@ impl Debug for Ui {
 }
 
 impl Ui {
-    pub fn new(action_tx: UnboundedSender<Action>, terminal: TerminalWrapper) -> Result<Self> {
+    pub fn new(
+        action_tx: UnboundedSender<Action>,
+        terminal: TerminalWrapper,
+        config: &MonitorConfig,
+    ) -> Result<Self> {
         Ok(Self {
             terminal,
             action_tx,
             views: vec![LayerStack::new(); UiTabs::COUNT],
             selected_tab: UiTabs::default(),
-            status_bar: create_status_bar(),
+            status_bar: create_status_bar(config),
             first_frame: true,
+            chords: KeyChords::new(&config.keymap),
+            key_capture: None,
+            summary_panels: config.summary_panels.clone(),
+            tab_hits: Vec::new(),
+            toasts: Toasts::new(&config.alerts),
+            splash: None,
         })
     }
 
-    fn tabs() -> Tabs<'static> {
-        let tab_titles = UiTabs::iter().map(UiTabs::to_tab_title);
-        let block = Block::new().title(" Use ctrl + ◄ ► to change tab");
-        Tabs::new(tab_titles)
-            .block(block)
+    fn tabs_block() -> Block<'static> {
+        Block::new().title(" Use ctrl + ◄ ► or alt + 1..9 to change tab, ctrl + p for all commands")
+    }
+
+    fn chord_block() -> Block<'static> {
+        Block::new()
+            .title(" Waiting for the second key of a chord...")
+            .bold()
+    }
+
+    // returns the tabs that fit into `width` and the range of visible tab indexes.
+    // The selected tab is always visible
+    fn tabs(selected: UiTabs, width: u16, theme: &Theme) -> (Tabs<'static>, Range<usize>) {
+        let titles: Vec<Line<'static>> = UiTabs::iter().map(|t| t.to_tab_title(theme)).collect();
+        let visible = visible_tabs_range(&titles, selected as usize, width);
+        let tabs = Tabs::new(titles[visible.clone()].to_vec())
             .highlight_style(Modifier::REVERSED)
-            .divider(" ")
+            .divider(TABS_DIVIDER)
             .padding("", "")
+            .select(selected as usize - visible.start);
+        (tabs, visible)
     }
 
-    pub fn init(&mut self) {
-        self.views[UiTabs::Summary as usize].push(Box::new(SummaryPage::new()));
-        #[cfg(debug_assertions)]
+    pub fn init(&mut self, low_memory: bool) {
+        self.views[UiTabs::Summary as usize]
+            .push(Box::new(SummaryPage::new(self.summary_panels.clone())));
+        #[cfg(any(debug_assertions, feature = "home-page"))]
         {
-            self.views[UiTabs::Home as usize].push(Box::new(HomePage::new()));
+            // HomePage spawns lsusb/lspci and keeps their output around
+            if low_memory {
+                self.views[UiTabs::Home as usize].push(Box::new(create_disabled_home_page()));
+            } else {
+                self.views[UiTabs::Home as usize].push(Box::new(HomePage::new()));
+            }
         }
+        #[cfg(not(any(debug_assertions, feature = "home-page")))]
+        let _ = low_memory;
 
         self.views[UiTabs::Network as usize].push(Box::new(create_network_page()));
+        self.views[UiTabs::Traffic as usize].push(Box::new(TrafficPage::new()));
+        self.views[UiTabs::Cellular as usize].push(Box::new(CellularPage::new()));
+        #[cfg(feature = "efi")]
+        self.views[UiTabs::Usb as usize].push(Box::new(UsbPage::new()));
+        #[cfg(feature = "efi")]
+        self.views[UiTabs::Storage as usize].push(Box::new(StoragePage::new()));
+        self.views[UiTabs::Resources as usize].push(Box::new(ResourcesPage::new()));
 
         self.views[UiTabs::Applications as usize].push(Box::new(ApplicationsPage::new()));
+        self.views[UiTabs::Alerts as usize].push(Box::new(AlertsPage::new()));
+        self.views[UiTabs::Audit as usize].push(Box::new(AuditPage::new()));
+        #[cfg(feature = "dmesg")]
         self.views[UiTabs::Dmesg as usize].push(Box::new(DmesgViewer::new()));
+        self.views[UiTabs::Debug as usize].push(Box::new(DebugPage::new()));
+        self.views[UiTabs::Settings as usize].push(Box::new(SettingsPage::new()));
     }
 
-    pub fn draw(&mut self, model: Rc<Model>) {
+    pub fn draw(&mut self, model: Rc<Model>) -> Result<(), MonitorError> {
         let screen_layout = Layout::vertical([Length(3), Fill(0), Length(3)]);
-        let tabs_widget = Ui::tabs();
 
-        //TODO: handle terminal event
-        let _ = self.terminal.draw(|frame| {
+        let frame_result = self.terminal.draw(|frame| {
             let area = frame.area();
             let [top_bar_rect, body_rect, statusbar_rect] = screen_layout.areas(area);
 
@@ -118,6 +221,16 @@ impl Ui {
                 self.first_frame = false;
                 frame.render_widget(Clear, area);
             }
+            let theme = model.borrow().theme;
+            frame.render_widget(
+                Block::new().style(Style::default().fg(theme.text).bg(theme.background)),
+                area,
+            );
+            // nothing of the device is shown while locked
+            if let Some(screen) = model.borrow().lock_screen.as_ref() {
+                render_lock_screen(screen, area, frame, &theme);
+                return;
+            }
 
             // these are evaluated statically during build time
             let git_version = option_env!("GIT_VERSION").unwrap_or("Git version: N/A");
@@ -125,12 +238,63 @@ impl Ui {
             let [tabs_rect, version_rect] =
                 Layout::horizontal([Fill(0), Length(git_version.len() as u16)]).areas(top_bar_rect);
 
-            let version_widget = Paragraph::new(git_version).fg(Color::DarkGray);
+            let version_widget = Paragraph::new(git_version).fg(theme.muted);
             frame.render_widget(version_widget, version_rect);
 
-            tabs_widget
-                .select(self.selected_tab as usize)
-                .render(tabs_rect, frame.buffer_mut());
+            // show that the next key completes a chord instead of the usual hint
+            let tabs_block = if self.chords.is_pending() {
+                Ui::chord_block()
+            } else {
+                Ui::tabs_block()
+            };
+            let tabs_inner_rect = tabs_block.inner(tabs_rect);
+            tabs_block.render(tabs_rect, frame.buffer_mut());
+
+            // leave room for the "more" indicators on both sides
+            let indicator_width = TABS_MORE_LEFT.chars().count() as u16;
+            let [more_left_rect, tabs_list_rect, more_right_rect] =
+                Layout::horizontal([Length(indicator_width), Fill(0), Length(indicator_width)])
+                    .areas(tabs_inner_rect);
+
+            let (tabs_widget, visible) = Ui::tabs(self.selected_tab, tabs_list_rect.width, &theme);
+            tabs_widget.render(tabs_list_rect, frame.buffer_mut());
+            if visible.start > 0 {
+                Paragraph::new(TABS_MORE_LEFT).render(more_left_rect, frame.buffer_mut());
+            }
+            if visible.end < UiTabs::COUNT {
+                Paragraph::new(TABS_MORE_RIGHT).render(more_right_rect, frame.buffer_mut());
+            }
+            // a click on an indicator shows the next hidden tab
+            let widths: Vec<u16> = UiTabs::iter()
+                .map(|t| t.to_tab_title(&theme).width() as u16)
+                .collect();
+            self.tab_hits = tab_title_rects(
+                tabs_list_rect,
+                &widths[visible.clone()],
+                TABS_DIVIDER.chars().count() as u16,
+            )
+            .into_iter()
+            .zip(UiTabs::iter().skip(visible.start))
+            .chain(
+                visible
+                    .start
+                    .checked_sub(1)
+                    .and_then(UiTabs::from_repr)
+                    .map(|tab| (more_left_rect, tab)),
+            )
+            .chain(UiTabs::from_repr(visible.end).map(|tab| (more_right_rect, tab)))
+            .collect();
+
+            // warn the user before the console is taken over by a guest
+            let body_rect = match model.borrow().console_handoff.as_ref() {
+                Some(handoff) if handoff.state == ConsoleHandoffState::Pending => {
+                    let [banner_rect, body_rect] =
+                        Layout::vertical([Length(4), Fill(0)]).areas(body_rect);
+                    render_handoff_banner(handoff, banner_rect, frame, &theme);
+                    body_rect
+                }
+                _ => body_rect,
+            };
 
             // redraw from the bottom up
             let stack = &mut self.views[self.selected_tab as usize];
@@ -138,10 +302,24 @@ impl Ui {
             for (index, layer) in stack.iter_mut().enumerate() {
                 layer.render(&body_rect, frame, &model, index == last_index);
             }
+            if let Some(capture) = self.key_capture.as_ref() {
+                capture.render(&body_rect, frame, &theme);
+            }
+            if let Some(splash) = self.splash.as_mut() {
+                splash.render(&body_rect, frame, &model, true);
+            }
+            // over any layer of any tab
+            self.toasts
+                .update(&model.borrow().alerts, std::time::Instant::now());
+            self.toasts.render(body_rect, frame, &theme);
             // render status bar
             self.status_bar
                 .render(&statusbar_rect, frame, &model, false);
         });
+        frame_result.map_err(|e| {
+            MonitorError::Render(anyhow::Error::new(e).context("Cannot draw frame"))
+        })?;
+        Ok(())
     }
 
     fn invalidate(&mut self) {
@@ -155,6 +333,35 @@ impl Ui {
             debug!("Ui handle_event {:?}", event);
         }
 
+        // the capture window gets all keys until Esc so global shortcuts can be captured too
+        let event = match (event, self.key_capture.as_mut()) {
+            (Event::Key(key), Some(capture)) => {
+                if key.code == KeyCode::Esc {
+                    self.key_capture = None;
+                } else {
+                    capture.record(key);
+                }
+                self.invalidate();
+                return None;
+            }
+            (Event::Key(key), None) => Event::Key(self.chords.translate(key)?),
+            (event, _) => event,
+        };
+
+        // the splash takes the first key and closes itself on time, whatever tab is shown.
+        // Clicks and pastes would reach the page hidden under it
+        if let Some(splash) = self.splash.as_mut() {
+            if matches!(event, Event::Key(_) | Event::Tick)
+                && splash.handle_event(event.clone()).is_some()
+            {
+                self.splash = None;
+                self.invalidate();
+            }
+            if matches!(event, Event::Key(_) | Event::Mouse(_) | Event::Paste(_)) {
+                return None;
+            }
+        }
+
         match event {
             // only for debugging purposes
             Event::Key(key)
@@ -176,14 +383,12 @@ impl Ui {
                 debug!("CTRL+r: manual Redraw requested");
                 self.invalidate();
             }
-            // For debugging purposes
+            // commands of all pages in one list, available on every page
             Event::Key(key)
-                if (key.code == KeyCode::Char('p'))
-                    && (key.modifiers == KeyModifiers::CONTROL)
-                    && cfg!(debug_assertions) =>
+                if key.code == KeyCode::Char('p') && key.modifiers == KeyModifiers::CONTROL =>
             {
-                debug!("CTRL+p: manual layer.pop() requested");
-                self.pop_layer();
+                debug!("CTRL+p: command palette requested");
+                self.show_command_palette();
             }
 
             // For debugging purposes
@@ -196,30 +401,61 @@ impl Ui {
                 panic!("Manual panic requested");
             }
 
+            // direct jump to a tab
+            Event::Key(key)
+                if key.modifiers == KeyModifiers::ALT
+                    && matches!(key.code, KeyCode::Char('1'..='9')) =>
+            {
+                if let KeyCode::Char(c) = key.code {
+                    let index = c as usize - '1' as usize;
+                    if let Some(tab) = UiTabs::from_repr(index) {
+                        debug!("ALT+{}: switching to tab {}", c, tab);
+                        self.selected_tab = tab;
+                    }
+                }
+            }
+
+            // build information for bug reports, available on every page
+            Event::Key(key) if key.code == KeyCode::F(1) => {
+                debug!("F1: about dialog requested");
+                self.message_box("About", &about_text());
+            }
+
+            // compare the live state with a snapshot, available on every page
+            Event::Key(key)
+                if key.modifiers == KeyModifiers::ALT && key.code == KeyCode::Char('s') =>
+            {
+                debug!("ALT+s: snapshot requested");
+                self.show_snapshot_dialog();
+            }
+            Event::Key(key)
+                if key.modifiers == KeyModifiers::ALT && key.code == KeyCode::Char('d') =>
+            {
+                debug!("ALT+d: snapshot compare requested");
+                self.push_layer(create_snapshot_compare());
+            }
+
+            // export the current page. Alt+C for CSV, Alt+J for JSON
+            Event::Key(key)
+                if key.modifiers == KeyModifiers::ALT
+                    && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('j')) =>
+            {
+                let format = if key.code == KeyCode::Char('c') {
+                    ExportFormat::Csv
+                } else {
+                    ExportFormat::Json
+                };
+                return Some(Action::new("user", UiActions::ExportView(format)));
+            }
+
             // forward all other key events to the top layer
             Event::Key(key) => {
                 if let Some(action) = self.views[self.selected_tab as usize]
                     .last_mut()?
                     .handle_event(Event::Key(key))
                 {
-                    match action.action {
-                        UiActions::DismissDialog => {
-                            self.pop_layer();
-                        }
-
-                        UiActions::ButtonClicked(name) => match name.as_str() {
-                            "Ok" => {
-                                self.pop_layer();
-                            }
-                            "Cancel" => {
-                                self.pop_layer();
-                            }
-                            _ => {}
-                        },
-
-                        _ => {
-                            return Some(action);
-                        }
+                    if let Some(action) = self.handle_layer_action(action) {
+                        return Some(action);
                     }
                 }
 
@@ -233,6 +469,31 @@ impl Ui {
                     self.selected_tab = self.selected_tab.next();
                 }
             }
+            // clicks on the tab bar switch tabs, the rest goes to the top layer
+            Event::Mouse(_) if self.key_capture.is_some() => {}
+            Event::Mouse(mouse) => {
+                if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                    if let Some((_, tab)) = self
+                        .tab_hits
+                        .iter()
+                        .find(|(rect, _)| rect.contains((mouse.column, mouse.row).into()))
+                    {
+                        debug!("Mouse: switching to tab {}", tab);
+                        self.selected_tab = *tab;
+                        return None;
+                    }
+                }
+                let action = self.views[self.selected_tab as usize]
+                    .last_mut()?
+                    .handle_event(Event::Mouse(mouse))?;
+                return self.handle_layer_action(action);
+            }
+            // pasted text goes to the focused input of the top layer
+            Event::Paste(text) => {
+                return self.views[self.selected_tab as usize]
+                    .last_mut()?
+                    .handle_event(Event::Paste(text));
+            }
             Event::Tick => {
                 // forward tick event to all layers. Collect actions
                 for layer in self.views[self.selected_tab as usize].iter_mut() {
@@ -243,14 +504,147 @@ impl Ui {
                 // and to the status bar
                 self.status_bar.handle_event(Event::Tick);
             }
-            _ => {
-                debug!("Unhandled event: {:?}", event);
+            Event::TerminalResize(w, h) => {
+                // serial consoles may keep artifacts from the old geometry on screen
+                // so resize the buffers and force a full clear before the next frame
+                if let Err(e) = self.terminal.autoresize() {
+                    warn!("Failed to resize terminal to {}x{}: {}", w, h, e);
+                }
+                self.invalidate_screen();
+                // let all layers on all tabs recompute page sizes and clamp selections
+                for stack in self.views.iter_mut() {
+                    for layer in stack.iter_mut() {
+                        layer.handle_event(Event::TerminalResize(w, h));
+                    }
+                }
             }
         }
 
         None
     }
 
+    // actions of the top layer the UI handles itself, the rest goes to the application
+    fn handle_layer_action(&mut self, action: Action) -> Option<Action> {
+        match action.action {
+            UiActions::StartKeyCapture => {
+                self.key_capture = Some(KeyCapture::new());
+            }
+            UiActions::DismissDialog => {
+                self.pop_layer();
+            }
+
+            UiActions::ButtonClicked(name) => match name.as_str() {
+                "Ok" => {
+                    self.pop_layer();
+                }
+                "Cancel" => {
+                    self.pop_layer();
+                }
+                _ => {}
+            },
+
+            UiActions::RunCommand(command) => {
+                // the palette is on top
+                self.pop_layer();
+                if let Some(tab) = command.tab {
+                    self.selected_tab = tab;
+                }
+                return self.handle_layer_action(Action::new(action.source, command.action));
+            }
+            UiActions::ShowTab(tab) => self.selected_tab = tab,
+            UiActions::ShowAbout => self.message_box("About", &about_text()),
+            UiActions::SnapshotDialog => self.show_snapshot_dialog(),
+            UiActions::CompareSnapshots => self.push_layer(create_snapshot_compare()),
+
+            _ => {
+                return Some(action);
+            }
+        }
+        None
+    }
+
+    fn show_snapshot_dialog(&mut self) {
+        let name = format!("snapshot {}", Local::now().format("%H:%M:%S"));
+        let d = create_input_dialog(
+            "Take snapshot",
+            "Name",
+            &name,
+            "before the cable change",
+            UiActions::TakeSnapshot,
+        );
+        self.push_layer(d);
+    }
+
+    // shortcuts that work on every page
+    fn global_commands() -> Vec<Command> {
+        let mut commands: Vec<Command> = UiTabs::iter()
+            .map(|tab| {
+                let number = tab as usize + 1;
+                let keys = if number <= 9 {
+                    format!("alt+{}", number)
+                } else {
+                    String::new()
+                };
+                Command::new(format!("Go to {}", tab), keys, UiActions::ShowTab(tab))
+            })
+            .collect();
+        commands.extend([
+            Command::new(
+                "Export the view as CSV",
+                "alt+c",
+                UiActions::ExportView(ExportFormat::Csv),
+            ),
+            Command::new(
+                "Export the view as JSON",
+                "alt+j",
+                UiActions::ExportView(ExportFormat::Json),
+            ),
+            Command::new("Take a snapshot", "alt+s", UiActions::SnapshotDialog),
+            Command::new(
+                "Compare with a snapshot",
+                "alt+d",
+                UiActions::CompareSnapshots,
+            ),
+            Command::new("About", "F1", UiActions::ShowAbout),
+        ]);
+        commands
+    }
+
+    /// The commands of the window on top, of the pages of the other tabs and
+    /// the global ones
+    fn show_command_palette(&mut self) {
+        let mut commands = self.views[self.selected_tab as usize]
+            .last()
+            .map(|layer| layer.commands())
+            .unwrap_or_default();
+        for tab in UiTabs::iter().filter(|tab| *tab != self.selected_tab) {
+            let Some(page) = self.views[tab as usize].first() else {
+                continue;
+            };
+            commands.extend(page.commands().into_iter().map(|command| Command {
+                title: format!("{}: {}", tab, command.title),
+                tab: Some(tab),
+                ..command
+            }));
+        }
+        commands.extend(Self::global_commands());
+        self.push_layer(create_command_palette(commands));
+    }
+
+    /// Content of the topmost page of the current tab if it supports export
+    pub fn export_current_view(&self, model: &MonitorModel) -> Option<TableExport> {
+        self.views[self.selected_tab as usize].last()?.export(model)
+    }
+
+    /// Clear the screen and redraw everything on the next frame e.g. when the console
+    /// comes back from a guest VM
+    pub fn invalidate_screen(&mut self) {
+        if let Err(e) = self.terminal.clear() {
+            warn!("Failed to clear terminal: {}", e);
+        }
+        self.first_frame = true;
+    }
+
     fn push_layer(&mut self, d: impl IWindow + 'static) {
         self.views[self.selected_tab as usize].push(Box::new(d))
     }
@@ -265,25 +659,191 @@ impl Ui {
     }
 
     pub fn show_server_url_dialog(&mut self, url: &str) {
-        let d = create_input_dialog(
+        let d = create_validated_input_dialog(
             "Change server URL",
             "Server URL",
             url,
             "https://prod.zedcontrol.zededa.net",
+            validate_server_url,
+            |url| UiActions::AppAction(MonActions::ServerUpdated(url)),
         );
         self.push_layer(d);
     }
 
+    pub fn show_self_test_results(&mut self, results: Vec<SelfTestResult>) {
+        self.splash = Some(SelfTestSplash::new(results));
+    }
+
+    pub fn confirm_dialog(&mut self, title: &str, message: &str, on_yes: UiActions) {
+        let d = super::confirm_dialog::create_confirm_dialog(title, message, on_yes);
+        self.push_layer(d);
+    }
+
     pub fn message_box(&mut self, title: &str, message: &str) {
         let d = super::message_box::create_message_box(title, message);
         self.push_layer(d);
     }
+
+    pub fn set_key_chords(&mut self, enabled: bool) {
+        self.chords.set_enabled(enabled);
+    }
+
+    pub fn show_summary_settings(&mut self) {
+        let d = super::summary_settings::create_summary_settings(&self.summary_panels);
+        self.push_layer(d);
+    }
+
+    pub fn set_summary_panels(&mut self, panels: Vec<SummaryPanel>) {
+        self.views[UiTabs::Summary as usize].set_base(Box::new(SummaryPage::new(panels.clone())));
+        self.summary_panels = panels;
+    }
+
+    pub fn show_app_details(&mut self, uuid: Uuid) {
+        let d = super::app_details::create_app_details(uuid);
+        self.push_layer(d);
+    }
+
+    pub fn show_diagnostics(&mut self, iface: &str) {
+        let d = super::diagnostics_dialog::create_diagnostics_dialog(iface);
+        self.push_layer(d);
+    }
+
+    pub fn show_bundle_dialog(&mut self) {
+        let d = super::bundle_dialog::create_bundle_dialog();
+        self.push_layer(d);
+    }
+
+    #[cfg(feature = "efi")]
+    pub fn show_boot_order_editor(&mut self) {
+        let d = super::boot_order::create_boot_order_editor();
+        self.push_layer(d);
+    }
+
+    #[cfg(feature = "efi")]
+    pub fn show_efi_variables(&mut self) {
+        let d = super::efi_variables::create_efi_variables();
+        self.push_layer(d);
+    }
+
+    pub fn show_dpc_history(&mut self) {
+        let d = super::dpc_history::create_dpc_history();
+        self.push_layer(d);
+    }
+
+    pub fn show_app_logs(&mut self) {
+        let d = super::app_logs_viewer::create_app_logs_viewer();
+        self.push_layer(d);
+    }
+
+    pub fn show_file_browser(
+        &mut self,
+        extensions: &'static [&'static str],
+        on_select: fn(PathBuf) -> UiActions,
+    ) {
+        let d = super::file_browser::create_file_browser(extensions, on_select);
+        self.push_layer(d);
+    }
+
+    /// Pass the result of e.g. a file dialog to the window on top
+    pub fn send_to_top_layer(&mut self, source: &str, action: UiActions) {
+        let Some(layer) = self.views[self.selected_tab as usize].last_mut() else {
+            return;
+        };
+        if let Some(action) = layer.on_child_action(source.to_string(), action) {
+            self.action_tx.send(action).unwrap();
+        }
+        self.invalidate();
+    }
+
+    pub fn show_monitor_log(&mut self, capacity: usize) {
+        let d = super::monitor_log_viewer::MonitorLogViewer::new(capacity);
+        self.push_layer(d);
+    }
+
+    pub fn show_file_viewer(&mut self, path: &str, content: &str, highlight: HashSet<usize>) {
+        let d = super::file_viewer::create_file_viewer(path, content, highlight);
+        self.push_layer(d);
+    }
+}
+
+// find as many tabs around the selected one as fit into `width`
+fn render_handoff_banner(
+    handoff: &ConsoleHandoffStatus,
+    rect: Rect,
+    frame: &mut Frame,
+    theme: &Theme,
+) {
+    let text = vec![
+        Line::from(format!(
+            "The console will be passed to application '{}' in {}s",
+            handoff.app_name, handoff.seconds_left
+        ))
+        .bold(),
+        Line::from("The monitor comes back when the application stops and releases the GPU"),
+    ];
+    let banner = Paragraph::new(text)
+        .block(Block::bordered().title(" Console handoff "))
+        .style(Style::default().fg(Color::White).bg(theme.error));
+    frame.render_widget(banner, rect);
+}
+
+fn render_lock_screen(screen: &LockScreen, area: Rect, frame: &mut Frame, theme: &Theme) {
+    let rect = centered_rect_fixed(50, 7, area);
+    let mut text = vec![
+        Line::from("The monitor is locked").bold(),
+        Line::from(""),
+        Line::from(format!("Passcode: {}", "*".repeat(screen.typed))),
+    ];
+    if let Some(message) = &screen.message {
+        text.push(Line::from(message.clone()).fg(theme.error));
+    }
+    let lock = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .block(
+            Block::bordered()
+                .border_style(Style::default().fg(theme.border))
+                .title(" Locked, type the passcode and press Enter "),
+        )
+        .style(Style::default().fg(theme.text).bg(theme.surface));
+    frame.render_widget(lock, rect);
+}
+
+fn visible_tabs_range(titles: &[Line], selected: usize, width: u16) -> Range<usize> {
+    let divider_width = TABS_DIVIDER.chars().count();
+    let mut visible = selected..selected + 1;
+    let mut used = titles[selected].width();
+    loop {
+        let mut grown = false;
+        if visible.end < titles.len()
+            && used + divider_width + titles[visible.end].width() <= width as usize
+        {
+            used += divider_width + titles[visible.end].width();
+            visible.end += 1;
+            grown = true;
+        }
+        if visible.start > 0
+            && used + divider_width + titles[visible.start - 1].width() <= width as usize
+        {
+            used += divider_width + titles[visible.start - 1].width();
+            visible.start -= 1;
+            grown = true;
+        }
+        if !grown {
+            break visible;
+        }
+    }
 }
 
 impl UiTabs {
-    fn to_tab_title(self) -> Line<'static> {
+    fn to_tab_title(self, theme: &Theme) -> Line<'static> {
         let text = self.to_string();
-        format!(" {text} ").bg(Color::Black).into()
+        // tab numbers match alt + 1..9 shortcuts
+        let number = self as usize + 1;
+        if number <= 9 {
+            format!(" {number}:{text} ").bg(theme.surface).into()
+        } else {
+            format!(" {text} ").bg(theme.surface).into()
+        }
     }
 
     /// Get the previous tab, if there is no previous tab return the current tab.
@@ -300,3 +860,62 @@ impl UiTabs {
         Self::from_repr(next_index).unwrap_or(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, MouseEvent};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_splash_leaves_pages_alone() {
+        let (action_tx, _action_rx) = mpsc::unbounded_channel();
+        let terminal = TerminalWrapper::headless(120, 40).unwrap();
+        let mut ui = Ui::new(action_tx, terminal, &MonitorConfig::default()).unwrap();
+        ui.init(true);
+        ui.show_self_test_results(vec![]);
+        ui.selected_tab = UiTabs::Network;
+
+        // a click on the tab bar does not switch tabs under the splash
+        let model = Rc::new(std::cell::RefCell::new(MonitorModel::default()));
+        ui.draw(model).unwrap();
+        let (rect, tab) = ui.tab_hits[0];
+        assert_ne!(tab, UiTabs::Network);
+        ui.handle_event(Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: rect.x,
+            row: rect.y,
+            modifiers: KeyModifiers::NONE,
+        }));
+        ui.handle_event(Event::Paste("text".to_string()));
+        assert_eq!(ui.selected_tab, UiTabs::Network);
+        assert!(ui.splash.is_some());
+
+        // the key closes the splash and does nothing else
+        ui.handle_event(Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+        ui.handle_event(Event::Tick);
+        assert!(ui.splash.is_none());
+        assert!(ui.views.iter().all(|stack| stack.len() == 1));
+    }
+
+    #[cfg(any(debug_assertions, feature = "home-page"))]
+    #[test]
+    fn test_low_memory_home_page() {
+        let (action_tx, _action_rx) = mpsc::unbounded_channel();
+        let terminal = TerminalWrapper::headless(120, 40).unwrap();
+        let mut ui = Ui::new(action_tx, terminal, &MonitorConfig::default()).unwrap();
+        ui.init(true);
+        ui.selected_tab = UiTabs::Home;
+        let model = Rc::new(std::cell::RefCell::new(MonitorModel::default()));
+        ui.draw(model).unwrap();
+        let screen: String = ui
+            .terminal
+            .buffer()
+            .unwrap()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Device summary is disabled in low-memory mode"));
+    }
+}
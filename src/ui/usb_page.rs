@@ -0,0 +1,284 @@
+use std::{
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::{
+        Block, BorderType, Borders, HighlightSpacing, Padding, Paragraph, Row, StatefulWidget,
+        Table,
+    },
+    Frame,
+};
+
+use crate::{
+    events::Event,
+    model::{
+        device::{
+            efi::{efi_boot_current, efi_boot_entries, EfiBootEntry},
+            usb::{usb_class_name, usb_devices, UsbBootPath, UsbDevice},
+        },
+        model::{Model, MonitorModel, VaultStatus},
+        sanitize::sanitize_line,
+    },
+    traits::{IEventHandler, IPresenter, IWindow},
+    ui::action::Action,
+};
+
+use super::{export::TableExport, selection::KeyedSelection, theme::Theme, traits::ISelector};
+
+// devices come and go, sysfs is cheap to read
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+const HEADER: [&str; 6] = ["Device", "ID", "Class", "Product", "Serial", "Boot entry"];
+
+/// Connected USB devices and the EFI boot entries that point at them. A
+/// system started from a USB entry measures differently into the TPM, which
+/// keeps the vault locked
+#[derive(Debug, Default)]
+pub struct UsbPage {
+    list: KeyedSelection<String>,
+    devices: Vec<UsbDevice>,
+    boot_entries: Vec<EfiBootEntry>,
+    boot_current: Option<u16>,
+    refreshed: Option<Instant>,
+}
+
+impl UsbPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn refresh(&mut self) {
+        self.devices = usb_devices();
+        self.boot_entries = efi_boot_entries();
+        self.boot_current = efi_boot_current();
+        self.refreshed = Some(Instant::now());
+    }
+
+    // boot entries that name the device, e.g. "Boot0003"
+    fn boot_matches(&self, device: &UsbDevice) -> String {
+        self.boot_entries
+            .iter()
+            .filter(|entry| entry.usb.as_ref().is_some_and(|usb| usb.matches(device)))
+            .map(|entry| format!("Boot{:04X}", entry.number))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn cells(&self, device: &UsbDevice) -> Vec<String> {
+        let product = [&device.manufacturer, &device.product]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| sanitize_line(s))
+            .collect::<Vec<_>>()
+            .join(" ");
+        vec![
+            device.name.clone(),
+            format!("{:04x}:{:04x}", device.vendor_id, device.product_id),
+            usb_class_name(device.class).to_string(),
+            product,
+            sanitize_line(&device.serial),
+            self.boot_matches(device),
+        ]
+    }
+
+    fn boot_entry_line(&self, entry: &EfiBootEntry, theme: &Theme) -> Line<'static> {
+        let current = self.boot_current == Some(entry.number);
+        let target = match &entry.usb {
+            None => "not USB".to_string(),
+            Some(UsbBootPath::Ports(ports)) => format!(
+                "USB port {}, the device cannot be told",
+                ports
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(".")
+            ),
+            Some(UsbBootPath::Device {
+                vendor_id,
+                product_id,
+                ..
+            }) => {
+                let connected = self
+                    .devices
+                    .iter()
+                    .any(|d| entry.usb.as_ref().is_some_and(|usb| usb.matches(d)));
+                format!(
+                    "USB {:04x}:{:04x}, {}",
+                    vendor_id,
+                    product_id,
+                    if connected {
+                        "connected"
+                    } else {
+                        "not connected"
+                    }
+                )
+            }
+        };
+        let line = Line::from(format!(
+            "{} Boot{:04X} {:<32} {}{}",
+            if current { "▶" } else { " " },
+            entry.number,
+            sanitize_line(&entry.description),
+            target,
+            if entry.active { "" } else { ", inactive" }
+        ));
+        match (current, &entry.usb) {
+            (true, Some(_)) => line.fg(theme.warning).bold(),
+            (true, None) => line.fg(theme.text).bold(),
+            (false, _) => line.fg(theme.text),
+        }
+    }
+
+    fn render_boot_entries(&self, model: &MonitorModel, rect: Rect, frame: &mut Frame) {
+        let theme = model.theme;
+        let mut lines: Vec<Line> = self
+            .boot_entries
+            .iter()
+            .map(|entry| self.boot_entry_line(entry, &theme))
+            .collect();
+        if lines.is_empty() {
+            lines.push(
+                Line::from("No EFI boot entries, the system may boot in legacy mode")
+                    .fg(theme.muted),
+            );
+        }
+        let booted_from_usb = self
+            .boot_entries
+            .iter()
+            .any(|entry| self.boot_current == Some(entry.number) && entry.usb.is_some());
+        if booted_from_usb && matches!(model.vault_status, VaultStatus::Locked(_, _)) {
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(
+                    "The system booted from USB and the vault is locked. Booting from another \
+                     device changes the TPM measurements, boot from the disk to unlock it",
+                )
+                .fg(theme.error),
+            );
+        }
+        let block = Block::default()
+            .title(" EFI boot entries ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Paragraph::new(Text::from(lines)).block(block), rect);
+    }
+}
+
+impl IPresenter for UsbPage {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, model: &Rc<Model>, _focused: bool) {
+        if self.refreshed.is_none() {
+            self.refresh();
+        }
+        let model = model.borrow();
+        let theme = model.theme;
+
+        // the entries, a blank line and the vault hint
+        let boot_height = (self.boot_entries.len().max(1) as u16 + 4).min(area.height / 2);
+        let [table_rect, boot_rect, help_rect] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(boot_height),
+            Constraint::Length(1),
+        ])
+        .areas(*area);
+
+        self.list
+            .set_keys(self.devices.iter().map(|d| d.name.clone()).collect());
+        let mut rows: Vec<Row> = self
+            .devices
+            .iter()
+            .map(|device| {
+                let row = Row::new(self.cells(device));
+                if self.boot_matches(device).is_empty() {
+                    row
+                } else {
+                    row.fg(theme.warning)
+                }
+            })
+            .collect();
+        if rows.is_empty() {
+            rows.push(Row::new(vec!["No USB devices are connected"]).fg(theme.muted));
+        }
+        let block = Block::default()
+            .title(" USB devices ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::TOP)
+            .border_type(BorderType::Plain)
+            .padding(Padding::horizontal(1));
+        self.list.set_table_area(block.inner(table_rect), 1);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(16),
+                Constraint::Fill(1),
+                Constraint::Length(20),
+                Constraint::Length(20),
+            ],
+        )
+        .block(block)
+        .header(Row::new(HEADER.to_vec()).bold())
+        .row_highlight_style(Style::new().bg(theme.selection))
+        .highlight_symbol(" █ ")
+        .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(table, table_rect, frame.buffer_mut(), &mut self.list.state);
+
+        self.render_boot_entries(&model, boot_rect, frame);
+        frame.render_widget(
+            Line::from(
+                " ▲▼ select, 'r' to refresh. Devices a boot entry points at are highlighted",
+            )
+            .fg(theme.muted),
+            help_rect,
+        );
+    }
+}
+
+impl IEventHandler for UsbPage {
+    fn handle_event(&mut self, event: Event) -> Option<Action> {
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                self.list.handle_mouse(mouse);
+                return None;
+            }
+            Event::Tick => {
+                if self
+                    .refreshed
+                    .is_none_or(|refreshed| refreshed.elapsed() >= REFRESH_INTERVAL)
+                {
+                    self.refresh();
+                }
+                return None;
+            }
+            _ => return None,
+        };
+        match key.code {
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down => self.list.select_next(),
+            KeyCode::Home if key.modifiers == KeyModifiers::CONTROL => self.list.select_first(),
+            KeyCode::End if key.modifiers == KeyModifiers::CONTROL => self.list.select_last(),
+            KeyCode::Char('r') => self.refresh(),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl IWindow for UsbPage {
+    fn export(&self, _model: &MonitorModel) -> Option<TableExport> {
+        let mut export = TableExport::new("usb", HEADER.to_vec());
+        for device in self.devices.iter() {
+            export.add_row(self.cells(device));
+        }
+        Some(export)
+    }
+}
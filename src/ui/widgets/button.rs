@@ -1,4 +1,4 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use log::{info, trace};
 use ratatui::{
     layout::Alignment,
@@ -102,6 +102,15 @@ impl IElementEventHandler for ButtonElement {
             _ => None,
         }
     }
+
+    fn handle_mouse_event(&mut self, event: MouseEvent, _area: Rect) -> Option<UiActions> {
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                Some(UiActions::ButtonClicked(self.label.clone()))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl IWidget for ButtonElement {}
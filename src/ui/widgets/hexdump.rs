@@ -0,0 +1,160 @@
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Text},
+    widgets::{Paragraph, WidgetRef},
+    Frame,
+};
+
+use crate::{
+    traits::{IElementEventHandler, IWidget, IWidgetPresenter},
+    ui::action::UiActions,
+};
+
+// bytes per row, 'w' switches to the next
+const WIDTHS: [usize; 3] = [8, 16, 32];
+
+/// Offset, hex and ASCII columns of a binary blob
+pub struct HexDumpElement {
+    data: Vec<u8>,
+    width: usize,
+    // first row shown
+    top: usize,
+    // rows that fit, known after the first render
+    rows: usize,
+}
+
+impl IWidget for HexDumpElement {}
+
+/// "00000010  de ad be ef 00 01 02 03  04 05 06 07 08 09 0a 0b  |....abcd........|"
+pub fn hexdump_line(data: &[u8], offset: usize, width: usize) -> String {
+    let bytes = data.get(offset..).unwrap_or_default();
+    let bytes = &bytes[..bytes.len().min(width)];
+    let mut line = format!("{:08x} ", offset);
+    for i in 0..width {
+        // an extra space every 8 bytes
+        if i % 8 == 0 {
+            line.push(' ');
+        }
+        match bytes.get(i) {
+            Some(b) => line.push_str(&format!("{:02x} ", b)),
+            None => line.push_str("   "),
+        }
+    }
+    line.push_str(" |");
+    line.extend(bytes.iter().map(|b| {
+        if b.is_ascii_graphic() || *b == b' ' {
+            *b as char
+        } else {
+            '.'
+        }
+    }));
+    line.push('|');
+    line
+}
+
+impl HexDumpElement {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            width: 16,
+            top: 0,
+            rows: 1,
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        self.data.len().div_ceil(self.width)
+    }
+
+    fn scroll_to(&mut self, top: usize) {
+        self.top = top.min(self.row_count().saturating_sub(self.rows));
+    }
+
+    fn next_width(&mut self) {
+        // keep the byte at the top in view
+        let offset = self.top * self.width;
+        let index = WIDTHS.iter().position(|w| *w == self.width).unwrap_or(0);
+        self.width = WIDTHS[(index + 1) % WIDTHS.len()];
+        self.scroll_to(offset / self.width);
+    }
+}
+
+impl IElementEventHandler for HexDumpElement {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Option<UiActions> {
+        match key.code {
+            KeyCode::Up => self.scroll_to(self.top.saturating_sub(1)),
+            KeyCode::Down => self.scroll_to(self.top + 1),
+            KeyCode::PageUp => self.scroll_to(self.top.saturating_sub(self.rows)),
+            KeyCode::PageDown => self.scroll_to(self.top + self.rows),
+            KeyCode::Home => self.scroll_to(0),
+            KeyCode::End => self.scroll_to(usize::MAX),
+            KeyCode::Char('w') => self.next_width(),
+            _ => return None,
+        }
+        Some(UiActions::Redraw)
+    }
+
+    fn handle_mouse_event(&mut self, event: MouseEvent, _area: Rect) -> Option<UiActions> {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.scroll_to(self.top.saturating_sub(3)),
+            MouseEventKind::ScrollDown => self.scroll_to(self.top + 3),
+            _ => return None,
+        }
+        Some(UiActions::Redraw)
+    }
+}
+
+impl IWidgetPresenter for HexDumpElement {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, focused: bool) {
+        let [dump_rect, status_rect] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(*area);
+        self.rows = (dump_rect.height as usize).max(1);
+        self.scroll_to(self.top);
+
+        let lines: Vec<Line> = (self.top..self.row_count())
+            .take(self.rows)
+            .map(|row| Line::from(hexdump_line(&self.data, row * self.width, self.width)))
+            .collect();
+        let style = if focused {
+            Style::default().fg(Color::White)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        Paragraph::new(Text::from(lines))
+            .style(style)
+            .render_ref(dump_rect, frame.buffer_mut());
+
+        let status = format!(
+            "{} bytes, rows {}-{} of {}, {} bytes per row ('w' to change)",
+            self.data.len(),
+            (self.top + 1).min(self.row_count()),
+            (self.top + self.rows).min(self.row_count()),
+            self.row_count(),
+            self.width
+        );
+        Paragraph::new(status)
+            .style(Style::default().fg(Color::DarkGray))
+            .render_ref(status_rect, frame.buffer_mut());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hexdump_line() {
+        let data: Vec<u8> = (0x3e..0x50).collect();
+        assert_eq!(
+            hexdump_line(&data, 0, 8),
+            "00000000  3e 3f 40 41 42 43 44 45  |>?@ABCDE|"
+        );
+        // the last row is padded so the ASCII column stays aligned
+        assert_eq!(
+            hexdump_line(&data, 16, 16),
+            "00000010  4e 4f                                             |NO|"
+        );
+    }
+}
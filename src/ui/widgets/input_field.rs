@@ -304,6 +304,39 @@ impl IElementEventHandler for InputFieldElement {
         }
         None
     }
+
+    fn handle_paste(&mut self, text: &str) -> Option<UiActions> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let value = self.value.as_mut()?;
+        let mut pasted = 0;
+        // line breaks of a copied URL are not part of it
+        for c in text.chars().filter(|c| !c.is_control()) {
+            let Some(c) = self.on_char.as_mut().and_then(|f| f(&c)) else {
+                continue;
+            };
+            if self.input_mode == InputMode::Overwrite && self.input_position < value.len() {
+                value.remove(self.input_position);
+            }
+            value.insert(self.input_position, c);
+            self.input_position += 1;
+            pasted += 1;
+        }
+        if pasted == 0 {
+            return None;
+        }
+        // scroll so the cursor stays inside the field
+        let width = self.text_area.width.saturating_sub(1);
+        let position = self.input_position as u16;
+        if position > self.scroll_left + width {
+            self.scroll_left = position - width;
+        }
+        self.cursor_position = position - self.scroll_left;
+        Some(UiActions::Input {
+            text: value.clone(),
+        })
+    }
 }
 
 impl IWidgetPresenter for InputFieldElement {
@@ -0,0 +1,256 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use log::trace;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+use crate::{
+    traits::{IElementEventHandler, IWidget, IWidgetPresenter},
+    ui::action::UiActions,
+};
+
+// returns the entry as it is stored or why it is refused
+pub type Validator = dyn Fn(&str) -> Result<String, String>;
+
+/// List of short entries, e.g. the addresses of a port. Up/Down select an
+/// entry, Delete removes it and Enter takes it back to the input line for
+/// editing. Enter on the input line adds its text to the list
+pub struct ListEditorElement {
+    caption: String,
+    items: Vec<String>,
+    // items.len() is the input line
+    selected: usize,
+    input: String,
+    validator: Option<Box<Validator>>,
+    error: Option<String>,
+    text_hint: Option<String>,
+}
+
+impl IWidget for ListEditorElement {}
+
+impl ListEditorElement {
+    pub fn new<S: Into<String>>(caption: S, items: Vec<String>) -> Self {
+        Self {
+            caption: caption.into(),
+            selected: items.len(),
+            items,
+            input: String::new(),
+            validator: None,
+            error: None,
+            text_hint: None,
+        }
+    }
+
+    pub fn with_validator<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> Result<String, String> + 'static,
+    {
+        self.validator = Some(Box::new(f));
+        self
+    }
+
+    pub fn with_text_hint<S: Into<String>>(mut self, text_hint: S) -> Self {
+        self.text_hint = Some(text_hint.into());
+        self
+    }
+
+    fn on_input_line(&self) -> bool {
+        self.selected == self.items.len()
+    }
+
+    fn changed(&self) -> Option<UiActions> {
+        Some(UiActions::ListChanged {
+            items: self.items.clone(),
+        })
+    }
+
+    fn add(&mut self) -> Option<UiActions> {
+        let text = self.input.trim();
+        if text.is_empty() {
+            return None;
+        }
+        let entry = match self
+            .validator
+            .as_ref()
+            .map_or(Ok(text.to_string()), |f| f(text))
+        {
+            Ok(entry) => entry,
+            Err(e) => {
+                self.error = Some(e);
+                return Some(UiActions::Redraw);
+            }
+        };
+        if self.items.contains(&entry) {
+            self.error = Some(format!("{} is in the list already", entry));
+            return Some(UiActions::Redraw);
+        }
+        self.items.push(entry);
+        self.input.clear();
+        self.selected = self.items.len();
+        self.changed()
+    }
+
+    fn remove(&mut self) -> Option<String> {
+        if self.on_input_line() {
+            return None;
+        }
+        Some(self.items.remove(self.selected))
+    }
+}
+
+impl IElementEventHandler for ListEditorElement {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Option<UiActions> {
+        trace!("list editor {} handling key {:?}", self.caption, key.code);
+        self.error = None;
+        match key.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(self.items.len()),
+            KeyCode::Char(c) => {
+                self.selected = self.items.len();
+                self.input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.selected = self.items.len();
+                self.input.pop();
+            }
+            KeyCode::Delete => {
+                self.remove()?;
+                return self.changed();
+            }
+            KeyCode::Enter if self.on_input_line() => return self.add(),
+            KeyCode::Enter => {
+                // an entry being edited is lost if the input line had text
+                if !self.input.trim().is_empty() {
+                    self.error = Some("Add or clear the entry being edited first".to_string());
+                    return Some(UiActions::Redraw);
+                }
+                self.input = self.remove()?;
+                self.selected = self.items.len();
+                return self.changed();
+            }
+            _ => return None,
+        }
+        Some(UiActions::Redraw)
+    }
+
+    fn handle_paste(&mut self, text: &str) -> Option<UiActions> {
+        self.selected = self.items.len();
+        self.input.extend(text.chars().filter(|c| !c.is_control()));
+        Some(UiActions::Redraw)
+    }
+}
+
+impl IWidgetPresenter for ListEditorElement {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, focused: bool) {
+        let style = if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let mut block = Block::new()
+            .border_type(BorderType::Plain)
+            .borders(Borders::ALL)
+            .border_style(style)
+            .style(Style::default().bg(Color::Black))
+            .title(self.caption.as_str());
+        if let Some(error) = &self.error {
+            block = block.title_bottom(Line::from(format!(" {} ", error)).fg(Color::Red));
+        } else if focused {
+            block = block.title_bottom(" Enter add/edit, Del remove ");
+        }
+        let inner = block.inner(*area);
+
+        let mut lines: Vec<Line> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let line = Line::from(item.as_str()).fg(Color::White);
+                if focused && i == self.selected {
+                    line.add_modifier(Modifier::REVERSED)
+                } else {
+                    line
+                }
+            })
+            .collect();
+        match (&self.text_hint, self.input.is_empty()) {
+            (Some(hint), true) => lines.push(Line::from(hint.as_str()).fg(Color::DarkGray)),
+            _ => lines.push(Line::from(self.input.as_str()).fg(Color::White)),
+        }
+
+        // keep the input line visible when the list is longer than the area
+        let scroll = (lines.len() as u16).saturating_sub(inner.height);
+        frame.render_widget(
+            Paragraph::new(Text::from(lines))
+                .scroll((scroll, 0))
+                .block(block),
+            *area,
+        );
+
+        if focused && self.on_input_line() {
+            let x =
+                inner.x + (self.input.chars().count() as u16).min(inner.width.saturating_sub(1));
+            let y = inner.y + (self.items.len() as u16).saturating_sub(scroll);
+            frame.set_cursor_position((x, y.min(inner.bottom().saturating_sub(1))));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(editor: &mut ListEditorElement, code: KeyCode) -> Option<UiActions> {
+        editor.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(editor: &mut ListEditorElement, text: &str) -> Option<UiActions> {
+        text.chars().for_each(|c| {
+            key(editor, KeyCode::Char(c));
+        });
+        key(editor, KeyCode::Enter)
+    }
+
+    #[test]
+    fn test_list_editor() {
+        let mut editor =
+            ListEditorElement::new("Numbers", vec!["1".to_string()]).with_validator(|s| {
+                s.parse::<u8>()
+                    .map(|n| n.to_string())
+                    .map_err(|e| e.to_string())
+            });
+
+        let items = |items: &[&str]| {
+            Some(UiActions::ListChanged {
+                items: items.iter().map(|s| s.to_string()).collect(),
+            })
+        };
+        assert_eq!(type_text(&mut editor, "02"), items(&["1", "2"]));
+        // refused entries stay on the input line
+        assert_eq!(type_text(&mut editor, "300"), Some(UiActions::Redraw));
+        assert!(editor.error.is_some());
+        assert_eq!(editor.input, "300");
+        editor.input.clear();
+        assert_eq!(type_text(&mut editor, "1"), Some(UiActions::Redraw));
+        editor.input.clear();
+
+        // edit the first entry
+        key(&mut editor, KeyCode::Up);
+        key(&mut editor, KeyCode::Up);
+        assert_eq!(key(&mut editor, KeyCode::Enter), items(&["2"]));
+        key(&mut editor, KeyCode::Backspace);
+        assert_eq!(type_text(&mut editor, "3"), items(&["2", "3"]));
+
+        key(&mut editor, KeyCode::Up);
+        assert_eq!(key(&mut editor, KeyCode::Delete), items(&["2"]));
+        // nothing to remove on the input line
+        key(&mut editor, KeyCode::Down);
+        assert_eq!(key(&mut editor, KeyCode::Delete), None);
+    }
+}
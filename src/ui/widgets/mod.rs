@@ -1,6 +1,12 @@
 pub mod button;
+#[cfg(feature = "efi")]
+pub mod hexdump;
 pub mod input_field;
 pub mod label;
+pub mod list_editor;
+pub mod qrcode;
 pub mod radiogroup;
+#[cfg(feature = "efi")]
+pub mod reorder_list;
 pub mod spin_box;
 pub mod tab;
@@ -0,0 +1,61 @@
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+
+use crate::qrcode::QrCode;
+
+// light modules around the code. The standard asks for 4, phones cope with less
+const QUIET_ZONE: usize = 2;
+
+/// A QR code drawn with half blocks, two rows of modules per line. The colors
+/// are fixed, dark themes would invert the code otherwise
+pub struct QrCodeWidget<'a> {
+    code: &'a QrCode,
+}
+
+impl<'a> QrCodeWidget<'a> {
+    pub fn new(code: &'a QrCode) -> Self {
+        Self { code }
+    }
+
+    /// Columns and lines the code takes with its quiet zone
+    pub fn size(&self) -> (u16, u16) {
+        let modules = self.code.size() + 2 * QUIET_ZONE;
+        (modules as u16, modules.div_ceil(2) as u16)
+    }
+
+    fn color(&self, x: usize, y: usize) -> Color {
+        // the quiet zone is outside of the code
+        let dark =
+            x >= QUIET_ZONE && y >= QUIET_ZONE && self.code.module(x - QUIET_ZONE, y - QUIET_ZONE);
+        if dark {
+            Color::Black
+        } else {
+            Color::White
+        }
+    }
+}
+
+impl Widget for QrCodeWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (width, height) = self.size();
+        // a cut code cannot be scanned
+        if width > area.width || height > area.height {
+            buf.set_string(
+                area.x,
+                area.y,
+                "Not enough space for the QR code",
+                Color::Gray,
+            );
+            return;
+        }
+        for row in 0..height {
+            for column in 0..width {
+                let (x, y) = (column as usize, row as usize * 2);
+                if let Some(cell) = buf.cell_mut((area.x + column, area.y + row)) {
+                    cell.set_symbol("▀")
+                        .set_fg(self.color(x, y))
+                        .set_bg(self.color(x, y + 1));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+use crate::{
+    traits::{IElementEventHandler, IWidget, IWidgetPresenter},
+    ui::action::UiActions,
+};
+
+/// Entries that can be moved and switched off, e.g. the boot order. Up/Down
+/// select an entry, Shift+Up/Down or +/- move it and Space switches it on or off
+pub struct ReorderListElement {
+    caption: String,
+    labels: Vec<String>,
+    // indexes into labels in the current order
+    order: Vec<usize>,
+    // by index into labels
+    enabled: Vec<bool>,
+    selected: usize,
+}
+
+impl IWidget for ReorderListElement {}
+
+impl ReorderListElement {
+    pub fn new<S: Into<String>>(caption: S, items: Vec<(String, bool)>) -> Self {
+        let (labels, enabled): (Vec<String>, Vec<bool>) = items.into_iter().unzip();
+        Self {
+            caption: caption.into(),
+            order: (0..labels.len()).collect(),
+            labels,
+            enabled,
+            selected: 0,
+        }
+    }
+
+    fn changed(&self) -> Option<UiActions> {
+        Some(UiActions::OrderChanged {
+            order: self.order.clone(),
+            enabled: self.enabled.clone(),
+        })
+    }
+
+    // None at the top or the bottom
+    fn move_selected(&mut self, up: bool) -> Option<UiActions> {
+        let target = if up {
+            self.selected.checked_sub(1)?
+        } else {
+            Some(self.selected + 1).filter(|i| *i < self.order.len())?
+        };
+        self.order.swap(self.selected, target);
+        self.selected = target;
+        self.changed()
+    }
+}
+
+impl IElementEventHandler for ReorderListElement {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Option<UiActions> {
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Up if shift => return self.move_selected(true),
+            KeyCode::Down if shift => return self.move_selected(false),
+            KeyCode::Char('-') => return self.move_selected(true),
+            KeyCode::Char('+') => return self.move_selected(false),
+            KeyCode::Char(' ') => {
+                let index = *self.order.get(self.selected)?;
+                self.enabled[index] = !self.enabled[index];
+                return self.changed();
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.order.len().saturating_sub(1))
+            }
+            _ => return None,
+        }
+        Some(UiActions::Redraw)
+    }
+}
+
+impl IWidgetPresenter for ReorderListElement {
+    fn render(&mut self, area: &Rect, frame: &mut Frame<'_>, focused: bool) {
+        let style = if focused {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let mut block = Block::new()
+            .border_type(BorderType::Plain)
+            .borders(Borders::ALL)
+            .border_style(style)
+            .style(Style::default().bg(Color::Black))
+            .title(self.caption.as_str());
+        if focused {
+            block = block.title_bottom(" Shift+▲▼ or +/- move, Space on/off ");
+        }
+        let inner = block.inner(*area);
+
+        let lines: Vec<Line> = self
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, index)| {
+                let (mark, color) = if self.enabled[*index] {
+                    ("[x]", Color::White)
+                } else {
+                    ("[ ]", Color::DarkGray)
+                };
+                let line = Line::from(format!("{} {}", mark, self.labels[*index])).fg(color);
+                if focused && i == self.selected {
+                    line.add_modifier(Modifier::REVERSED)
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        // keep the selected entry visible
+        let scroll = (self.selected as u16 + 1).saturating_sub(inner.height);
+        frame.render_widget(
+            Paragraph::new(Text::from(lines))
+                .scroll((scroll, 0))
+                .block(block),
+            *area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(list: &mut ReorderListElement, code: KeyCode) -> Option<UiActions> {
+        list.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_reorder_list() {
+        let mut list = ReorderListElement::new(
+            "Boot order",
+            vec![
+                ("a".to_string(), true),
+                ("b".to_string(), true),
+                ("c".to_string(), false),
+            ],
+        );
+        // the first entry cannot move up
+        assert_eq!(key(&mut list, KeyCode::Char('-')), None);
+        assert_eq!(
+            key(&mut list, KeyCode::Char('+')),
+            Some(UiActions::OrderChanged {
+                order: vec![1, 0, 2],
+                enabled: vec![true, true, false],
+            })
+        );
+        key(&mut list, KeyCode::Down);
+        assert_eq!(
+            list.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT)),
+            Some(UiActions::OrderChanged {
+                order: vec![1, 2, 0],
+                enabled: vec![true, true, false],
+            })
+        );
+        assert_eq!(
+            key(&mut list, KeyCode::Char(' ')),
+            Some(UiActions::OrderChanged {
+                order: vec![1, 2, 0],
+                enabled: vec![true, true, true],
+            })
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use log::debug;
 use ratatui::{
+    layout::Rect,
     style::Modifier,
     widgets::{Block, Tabs},
 };
@@ -30,6 +31,30 @@ impl TabElement {
     }
 }
 
+/// Areas of tab titles of `widths` as ratatui renders them into `area` with a
+/// divider of `divider_width` and no padding. Titles that don't fit are left out
+pub fn tab_title_rects(area: Rect, widths: &[u16], divider_width: u16) -> Vec<Rect> {
+    let mut x = area.x;
+    let mut rects = Vec::new();
+    for width in widths {
+        if x >= area.right() {
+            break;
+        }
+        let width = (*width).min(area.right() - x);
+        rects.push(Rect::new(x, area.y, width, 1.min(area.height)));
+        x = x.saturating_add(width + divider_width);
+    }
+    rects
+}
+
+impl TabElement {
+    fn block(&self) -> Option<Block<'static>> {
+        self.caption
+            .as_ref()
+            .map(|caption| Block::new().title(caption.clone()))
+    }
+}
+
 impl IWidget for TabElement {}
 impl IWidgetPresenter for TabElement {
     fn render(
@@ -48,8 +73,7 @@ impl IWidgetPresenter for TabElement {
             .padding("", "")
             .select(self.ft.get_focused_index());
 
-        if let Some(caption) = &self.caption {
-            let block = Block::new().title(caption.clone());
+        if let Some(block) = self.block() {
             widget = widget.block(block);
         }
 
@@ -78,4 +102,39 @@ impl IElementEventHandler for TabElement {
         };
         action
     }
+
+    fn handle_mouse_event(&mut self, event: MouseEvent, area: Rect) -> Option<UiActions> {
+        if event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return None;
+        }
+        let area = self.block().map_or(area, |block| block.inner(area));
+        let widths: Vec<u16> = self.tabs.iter().map(|t| t.chars().count() as u16).collect();
+        let index = tab_title_rects(area, &widths, 1)
+            .iter()
+            .position(|rect| rect.contains((event.column, event.row).into()))?;
+        let current = self.ft.get_focused_view()?;
+        self.ft.set_focused_index(index);
+        let new = self.ft.get_focused_view()?;
+        (current != new).then_some(UiActions::TabChanged(current, new))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_title_rects() {
+        let area = Rect::new(2, 1, 12, 2);
+        assert_eq!(
+            tab_title_rects(area, &[3, 4, 5], 1),
+            vec![
+                Rect::new(2, 1, 3, 1),
+                Rect::new(6, 1, 4, 1),
+                // cut at the right edge
+                Rect::new(11, 1, 3, 1),
+            ]
+        );
+        assert_eq!(tab_title_rects(area, &[12, 2], 1).len(), 1);
+    }
 }
@@ -4,7 +4,7 @@ use std::borrow::BorrowMut;
 use std::collections::HashMap;
 use std::{fmt::Debug, rc::Rc};
 
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 use indexmap::IndexMap;
 use log::{debug, trace};
 use ratatui::layout::Rect;
@@ -259,6 +259,31 @@ impl<D> Window<D> {
         Some(Action::new(focused_view, action))
     }
 
+    pub fn handle_paste_in_focused_view(&mut self, text: &str) -> Option<Action> {
+        let focused_view = self.ft.get_focused_view()?;
+        let widget = self.widgets.get_mut(&focused_view)?;
+        let action = widget.handle_paste(text)?;
+        Some(Action::new(focused_view, action))
+    }
+
+    // the widget under the pointer gets the focus and the event. Widgets are
+    // rendered in order so the last one wins where they overlap
+    pub fn handle_mouse_event_in_widget(&mut self, event: MouseEvent) -> Option<Action> {
+        let (name, area) = self.widgets.keys().rev().find_map(|name| {
+            let area = self.layout.get(name)?;
+            area.contains((event.column, event.row).into())
+                .then(|| (name.clone(), *area))
+        })?;
+        let focus_changed = self.widgets.get(&name)?.can_focus()
+            && self.ft.get_focused_view().as_ref() != Some(&name)
+            && self.ft.focus_view(&name);
+        match self.widgets.get_mut(&name)?.handle_mouse_event(event, area) {
+            Some(action) => Some(Action::new(name, action)),
+            None if focus_changed => Some(Action::new(self.name.clone(), UiActions::Redraw)),
+            None => None,
+        }
+    }
+
     pub fn handle_child_ui_action(&mut self, action: Action) -> Option<Action> {
         let on_child_ui_action = self.on_child_ui_action.clone()?;
         let action = on_child_ui_action(self, &action.source, &action.action)?;
@@ -279,7 +304,11 @@ impl<D> Window<D> {
     }
 }
 
-impl<D> IWindow for Window<D> {}
+impl<D> IWindow for Window<D> {
+    fn on_child_action(&mut self, source: String, action: UiActions) -> Option<Action> {
+        self.handle_child_ui_action(Action::new(source, action))
+    }
+}
 
 impl<D> IEventHandler for Window<D> {
     fn handle_event(&mut self, event: events::Event) -> Option<Action> {
@@ -351,6 +380,20 @@ impl<D> IEventHandler for Window<D> {
                 );
                 return Some(Action::new(self.name.clone(), UiActions::Redraw));
             }
+            events::Event::Paste(text) => {
+                let next_action = self.handle_paste_in_focused_view(&text)?;
+                if self.on_child_ui_action.is_some() {
+                    return self.handle_child_ui_action(next_action);
+                }
+                return Some(next_action);
+            }
+            events::Event::Mouse(mouse) => {
+                let next_action = self.handle_mouse_event_in_widget(mouse)?;
+                if self.on_child_ui_action.is_some() && next_action.source != self.name {
+                    return self.handle_child_ui_action(next_action);
+                }
+                return Some(next_action);
+            }
             _ => {}
         }
         None